@@ -42,7 +42,7 @@ fn main() {
     let mut as_str_arms = String::new();
     let mut all_array = String::new();
 
-    for (_i, id) in vendor_ids.iter().enumerate() {
+    for id in vendor_ids.iter() {
         let id = id.trim();
         if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
             continue;
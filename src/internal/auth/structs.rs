@@ -1 +1,4 @@
-pub mod webdav_auth;
\ No newline at end of file
+pub(crate) mod digest_auth;
+pub mod request_options;
+pub mod server_quirks;
+pub mod webdav_auth;
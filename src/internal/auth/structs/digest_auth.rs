@@ -0,0 +1,397 @@
+//! HTTP Digest 认证（RFC 7616）：解析 `WWW-Authenticate: Digest` 质询，
+//! 并为每个请求计算 `Authorization`。
+
+use std::fmt;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reqwest::header::{
+    AUTHORIZATION, HeaderMap, HeaderValue, WWW_AUTHENTICATE,
+};
+use md5::Md5;
+use reqwest::{Method, Request, RequestBuilder, Response, StatusCode};
+use sha2::{Digest, Sha256};
+
+/// 质询中的摘要算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DigestAlgorithm {
+    Md5,
+    Md5Sess,
+    Sha256,
+    Sha256Sess,
+}
+
+impl DigestAlgorithm {
+    /// 解析 `algorithm` 参数，不支持的算法（如 SHA-512-256）返回 `None`
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_uppercase().as_str() {
+            "MD5" => Some(Self::Md5),
+            "MD5-SESS" => Some(Self::Md5Sess),
+            "SHA-256" => Some(Self::Sha256),
+            "SHA-256-SESS" => Some(Self::Sha256Sess),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Md5Sess => "MD5-sess",
+            Self::Sha256 => "SHA-256",
+            Self::Sha256Sess => "SHA-256-sess",
+        }
+    }
+
+    fn is_session(self) -> bool {
+        matches!(self, Self::Md5Sess | Self::Sha256Sess)
+    }
+
+    /// 同时收到多个质询时优先使用更强的算法
+    fn strength(self) -> u8 {
+        match self {
+            Self::Md5 | Self::Md5Sess => 0,
+            Self::Sha256 | Self::Sha256Sess => 1,
+        }
+    }
+
+    fn hash(self, data: &str) -> String {
+        match self {
+            Self::Md5 | Self::Md5Sess => {
+                format!("{:x}", Md5::digest(data.as_bytes()))
+            }
+            Self::Sha256 | Self::Sha256Sess => {
+                format!("{:x}", Sha256::digest(data.as_bytes()))
+            }
+        }
+    }
+}
+
+/// 服务器的 Digest 质询
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DigestChallenge {
+    pub realm: String,
+    pub nonce: String,
+    pub opaque: Option<String>,
+    pub algorithm: DigestAlgorithm,
+    /// 服务器提供了 `qop=auth`；为 `false` 时按 RFC 2069 的旧格式计算
+    pub qop_auth: bool,
+    /// 用户名以 `H(username:realm)` 的形式发送
+    pub userhash: bool,
+}
+
+impl DigestChallenge {
+    /// 解析单个 `WWW-Authenticate` 值中的 Digest 质询
+    ///
+    /// 同一个值里可以有多个认证方案（如 `Basic realm="x", Digest ...`）。
+    /// 没有 Digest 质询、缺少 `nonce`、算法不支持或只提供 `qop=auth-int`
+    /// 时返回 `None`。
+    pub(crate) fn parse(header: &str) -> Option<Self> {
+        let lower = header.to_ascii_lowercase();
+        let (start, _) =
+            lower.match_indices("digest ").find(|(i, _)| {
+                *i == 0 || lower[..*i].trim_end().ends_with(',')
+            })?;
+
+        let mut realm = String::new();
+        let mut nonce = None;
+        let mut opaque = None;
+        let mut algorithm = DigestAlgorithm::Md5;
+        let mut qop = None;
+        let mut userhash = false;
+        for (key, value) in
+            parse_params(&header[start + "digest ".len()..])
+        {
+            match key.as_str() {
+                "realm" => realm = value,
+                "nonce" => nonce = Some(value),
+                "opaque" => opaque = Some(value),
+                "algorithm" => algorithm = DigestAlgorithm::parse(&value)?,
+                "qop" => qop = Some(value),
+                "userhash" => {
+                    userhash = value.eq_ignore_ascii_case("true")
+                }
+                _ => {}
+            }
+        }
+
+        let qop_auth = match qop {
+            Some(qop) => {
+                if !qop
+                    .split(',')
+                    .any(|q| q.trim().eq_ignore_ascii_case("auth"))
+                {
+                    return None;
+                }
+                true
+            }
+            None => false,
+        };
+
+        Some(Self {
+            realm,
+            nonce: nonce?,
+            opaque,
+            algorithm,
+            qop_auth,
+            userhash,
+        })
+    }
+
+    /// 从响应头的所有 `WWW-Authenticate` 中选出最强的可用质询
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        headers
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(Self::parse)
+            .max_by_key(|challenge| challenge.algorithm.strength())
+    }
+}
+
+/// 逐个取出 `key=value` / `key="value"`，遇到下一个认证方案时停止
+fn parse_params(input: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = input;
+
+    loop {
+        rest = rest.trim_start_matches([' ', '\t', ',']);
+        let Some(eq) = rest.find('=') else { break };
+        let key = rest[..eq].trim();
+        // `Basic realm=...` 之类：键中带空格说明是下一个认证方案
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            break;
+        }
+        rest = rest[eq + 1..].trim_start();
+
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let mut value = String::new();
+            let mut escaped = false;
+            let mut end = quoted.len();
+            for (i, c) in quoted.char_indices() {
+                if escaped {
+                    value.push(c);
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    end = i + 1;
+                    break;
+                } else {
+                    value.push(c);
+                }
+            }
+            rest = &quoted[end..];
+            value
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            let value = rest[..end].trim().to_string();
+            rest = &rest[end..];
+            value
+        };
+
+        params.push((key.to_ascii_lowercase(), value));
+    }
+
+    params
+}
+
+/// Digest 认证的凭据与最近一次质询，由同一个 WebdavAuth 的所有 clone 共享
+pub(crate) struct DigestAuth {
+    username: String,
+    password: String,
+    challenge: Mutex<Option<DigestChallenge>>,
+    /// 当前 nonce 已使用的次数（`nc`）
+    nonce_count: AtomicU32,
+}
+
+/// 防止debug泄漏密码
+impl fmt::Debug for DigestAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestAuth")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DigestAuth {
+    pub(crate) fn new(username: &str, password: &str) -> Self {
+        Self {
+            username: username.to_string(),
+            password: password.to_string(),
+            challenge: Mutex::new(None),
+            nonce_count: AtomicU32::new(0),
+        }
+    }
+
+    /// 是否已经收到过质询
+    pub(crate) fn has_challenge(&self) -> bool {
+        self.challenge.lock().is_ok_and(|challenge| challenge.is_some())
+    }
+
+    /// 记录新的质询；nonce 变化时重新从 `nc=00000001` 计数
+    pub(crate) fn update(&self, challenge: DigestChallenge) {
+        let Ok(mut current) = self.challenge.lock() else { return };
+        let nonce_changed = current
+            .as_ref()
+            .is_none_or(|current| current.nonce != challenge.nonce);
+        if nonce_changed {
+            self.nonce_count.store(0, Ordering::SeqCst);
+        }
+        *current = Some(challenge);
+    }
+
+    /// 按最近一次质询为请求设置 `Authorization`；还没有质询时不做任何事
+    pub(crate) fn authorize(&self, request: &mut Request) {
+        let Some(challenge) =
+            self.challenge.lock().ok().and_then(|c| c.clone())
+        else {
+            return;
+        };
+
+        let url = request.url();
+        let uri = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let nc = self.nonce_count.fetch_add(1, Ordering::SeqCst) + 1;
+        let header = self.authorization(
+            &challenge,
+            request.method(),
+            &uri,
+            nc,
+            &new_cnonce(nc),
+        );
+
+        if let Ok(value) = HeaderValue::from_bytes(header.as_bytes()) {
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+    }
+
+    /// 计算 `Authorization` 的值（RFC 7616 第 3.4 节）
+    pub(crate) fn authorization(
+        &self,
+        challenge: &DigestChallenge,
+        method: &Method,
+        uri: &str,
+        nc: u32,
+        cnonce: &str,
+    ) -> String {
+        let algorithm = challenge.algorithm;
+        let nonce = &challenge.nonce;
+
+        let mut ha1 = algorithm.hash(&format!(
+            "{}:{}:{}",
+            self.username, challenge.realm, self.password
+        ));
+        if algorithm.is_session() {
+            ha1 = algorithm.hash(&format!("{}:{}:{}", ha1, nonce, cnonce));
+        }
+        let ha2 = algorithm.hash(&format!("{}:{}", method, uri));
+
+        let response = if challenge.qop_auth {
+            algorithm.hash(&format!(
+                "{}:{}:{:08x}:{}:auth:{}",
+                ha1, nonce, nc, cnonce, ha2
+            ))
+        } else {
+            algorithm.hash(&format!("{}:{}:{}", ha1, nonce, ha2))
+        };
+
+        let username = if challenge.userhash {
+            algorithm
+                .hash(&format!("{}:{}", self.username, challenge.realm))
+        } else {
+            self.username.clone()
+        };
+
+        let mut header = format!(
+            r#"Digest username="{}", realm="{}", nonce="{}", uri="{}", algorithm={}, response="{}""#,
+            quote(&username),
+            quote(&challenge.realm),
+            quote(nonce),
+            quote(uri),
+            algorithm.name(),
+            response
+        );
+        if challenge.qop_auth {
+            header.push_str(&format!(
+                r#", qop=auth, nc={:08x}, cnonce="{}""#,
+                nc, cnonce
+            ));
+        }
+        if let Some(opaque) = &challenge.opaque {
+            header.push_str(&format!(r#", opaque="{}""#, quote(opaque)));
+        }
+        if challenge.userhash {
+            header.push_str(", userhash=true");
+        }
+        header
+    }
+}
+
+/// 引号字符串中的 `\` 与 `"` 需要转义
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// 客户端随机数：时间、计数与进程号的 SHA-256，取前 32 位十六进制
+fn new_cnonce(nc: u32) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    hasher.update(nanos.to_le_bytes());
+    hasher.update(nc.to_le_bytes());
+    hasher.update(std::process::id().to_le_bytes());
+    let mut cnonce = format!("{:x}", hasher.finalize());
+    cnonce.truncate(32);
+    cnonce
+}
+
+/// 发送请求；`digest` 为 `Some` 时按 Digest 认证处理
+///
+/// - 已有质询时直接带上 `Authorization`
+/// - 收到带 Digest 质询的 401（首次请求或 nonce 过期）时记录质询并重发一次
+/// - 请求体是流、无法重发时，先用 OPTIONS 取得质询
+pub(crate) async fn send_with_digest(
+    digest: Option<&DigestAuth>,
+    request: RequestBuilder,
+) -> reqwest::Result<Response> {
+    let Some(digest) = digest else {
+        return request.send().await;
+    };
+
+    let (client, request) = request.build_split();
+    let mut request = request?;
+
+    if !digest.has_challenge() && request.try_clone().is_none() {
+        let probe = client
+            .request(Method::OPTIONS, request.url().clone())
+            .send()
+            .await?;
+        if let Some(challenge) =
+            DigestChallenge::from_headers(probe.headers())
+        {
+            digest.update(challenge);
+        }
+    }
+
+    digest.authorize(&mut request);
+    let retry = request.try_clone();
+    let res = client.execute(request).await?;
+    if res.status() != StatusCode::UNAUTHORIZED {
+        return Ok(res);
+    }
+
+    let (Some(challenge), Some(mut retry)) =
+        (DigestChallenge::from_headers(res.headers()), retry)
+    else {
+        return Ok(res);
+    };
+    digest.update(challenge);
+    digest.authorize(&mut retry);
+    client.execute(retry).await
+}
@@ -0,0 +1,81 @@
+//! 附加在 WebdavAuth 上、影响每个 WebDAV 请求的选项。
+
+use std::time::Duration;
+
+use super::server_quirks::ServerQuirks;
+
+/// [`RequestOptions::read_timeout`] 与 [`RequestOptions::request_timeout`]
+/// 都未设置时读取响应体的超时时间
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// WebDAV 请求选项，由 [`WebdavAuth`](super::webdav_auth::WebdavAuth)
+/// 的 builder 方法设置，随认证一起 clone 传递。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// PROPFIND 时发送 `Prefer: return=minimal` 与 `Brief: t`，
+    /// 让服务器省略 404 的 propstat
+    pub prefer_minimal: bool,
+    /// 读取响应体（如 PROPFIND 的 XML）的超时时间，为 `None` 时使用
+    /// `request_timeout`，两者都未设置时使用 [`DEFAULT_READ_TIMEOUT`]
+    pub read_timeout: Option<Duration>,
+    /// 手动指定的兼容性处理，为 `None` 时按 `Server` 头自动识别
+    pub quirks: Option<ServerQuirks>,
+    /// `exists`/`stat` 优先使用的方法，失败时改用另一种
+    pub stat_method: StatMethod,
+    /// 建立连接（含 TLS 握手）的超时时间，`None` 表示不限制
+    pub connect_timeout: Option<Duration>,
+    /// 单个 WebDAV 请求从发出到读完响应的总时长上限，`None` 表示不限制；
+    /// 下载不受此限制，见 [`WebdavAuth::with_timeout`](super::webdav_auth::WebdavAuth::with_timeout)
+    pub request_timeout: Option<Duration>,
+    /// 与服务器通信使用的 HTTP 协议版本，默认只用 HTTP/1.1
+    pub http_protocol: HttpProtocol,
+    /// 连接池中每个主机最多保留的空闲连接数，`None` 时使用 reqwest 的默认值
+    /// （不限制）
+    pub pool_max_idle_per_host: Option<usize>,
+    /// 空闲连接在连接池中保留的时长，`None` 时使用 reqwest 的默认值（90 秒）
+    pub pool_idle_timeout: Option<Duration>,
+}
+
+/// HTTP 协议版本的选择，由
+/// [`WebdavAuth::prefer_http2`](super::webdav_auth::WebdavAuth::prefer_http2)
+/// 设置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HttpProtocol {
+    /// 只使用 HTTP/1.1（默认），并发请求分布在多个连接上
+    #[default]
+    Http1Only,
+    /// HTTPS 时通过 ALPN 协商，服务器支持时使用 HTTP/2，否则回退到
+    /// HTTP/1.1；明文 HTTP 没有协商过程，始终使用 HTTP/1.1
+    PreferHttp2,
+    /// 不经协商直接以 HTTP/2 连接（prior knowledge，明文 HTTP 即 h2c），
+    /// 服务器不支持 HTTP/2 时请求直接失败，不会回退
+    Http2PriorKnowledge,
+}
+
+/// 查询单个资源是否存在及其属性时使用的方法
+///
+/// 有的服务器对目录的 HEAD 返回 404，PROPFIND 却正常；也有服务器反过来。
+/// 无论选哪一种，优先的方法返回 400/404/405/501 等状态时都会再用另一种试一次。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatMethod {
+    /// 优先 HEAD
+    Head,
+    /// 优先 PROPFIND（`Depth: 0`）
+    Propfind,
+    /// 自动选择（默认）：先用 HEAD；OPTIONS 的 `Allow` 头只声明了其中一种，
+    /// 或某次改用另一种后成功时，之后优先使用那一种
+    #[default]
+    Auto,
+}
+
+impl RequestOptions {
+    /// 实际生效的响应体读取超时
+    ///
+    /// 只设置了请求超时时，读取响应体也以它为上限，
+    /// 而不是在请求超时更长时提前被默认的 60 秒截断
+    pub fn effective_read_timeout(&self) -> Duration {
+        self.read_timeout
+            .or(self.request_timeout)
+            .unwrap_or(DEFAULT_READ_TIMEOUT)
+    }
+}
@@ -0,0 +1,42 @@
+//! 按服务器类型自动应用的兼容性处理。
+
+/// 已知服务器的兼容性处理
+///
+/// 默认在第一次 PROPFIND 时根据响应的 `Server` 头从内置表中选择
+/// （见 [`ServerQuirks::detect`]），也可以通过
+/// [`WebdavAuth::quirks`](super::webdav_auth::WebdavAuth::quirks) 手动指定，
+/// 手动指定后不再自动识别。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerQuirks {
+    /// MKCOL 的目录 URL 总是补上尾部斜杠
+    pub force_trailing_slash: bool,
+    /// 服务器禁用了 `Depth: infinity`，此类请求直接返回
+    /// `WebDavError::Unsupported`，请改用逐层的递归列举
+    pub no_depth_infinity: bool,
+    /// PROPFIND 时发送的 `Accept` 头，`None` 时为 `application/xml`
+    pub accept: Option<String>,
+}
+
+impl ServerQuirks {
+    /// 根据 `Server` 响应头选择内置的兼容性处理，未知服务器返回默认值
+    ///
+    /// - IIS：默认不允许 `Depth: infinity`，创建目录要求尾部斜杠
+    /// - Apache mod_dav：`DavDepthInfinity` 默认关闭
+    pub fn detect(server: &str) -> Self {
+        let server = server.to_ascii_lowercase();
+
+        if server.contains("microsoft-iis") {
+            return Self {
+                force_trailing_slash: true,
+                no_depth_infinity: true,
+                ..Default::default()
+            };
+        }
+
+        if server.starts_with("apache") {
+            return Self { no_depth_infinity: true, ..Default::default() };
+        }
+
+        Self::default()
+    }
+}
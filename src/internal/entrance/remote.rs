@@ -1,14 +1,37 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures_util::StreamExt;
 use futures_util::future::join_all;
+use futures_util::stream;
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::{RequestBuilder, StatusCode};
 
 use crate::{
     auth::structs::webdav_auth::WebdavAuth,
-    remote_file::RemoteFile,
+    internal::remote_file::structs::listing_cache::CacheLookup,
+    remote_file::{
+        CancelToken, DedupKey, DownloadBatch, DownloadError,
+        DownloadResult, FolderView, ListingCache, PartialListing,
+        RecursionPolicy, RemoteFile, RemoteFileData,
+    },
     webdav::{
-        enums::Depth, functions::get_folders_raw_data,
-        structs::MultiStatus,
+        enums::Depth,
+        errors::WebDavError,
+        functions::{
+            collection_url, copy_resource, delete, delete_with_lock,
+            get_folders_raw_data, get_folders_raw_data_if_none_match,
+            get_quota, head, lock, lock_if_header, mkcol, move_resource,
+            normalize_webdav_path, proppatch, unlock,
+        },
+        structs::{LockInfo, MultiStatus, Quota, RemoteHead},
+        traits::ToRemoteFileData,
     },
 };
 
+/// 递归列举时同时进行的 PROPFIND 请求数
+const RECURSIVE_LISTING_CONCURRENCY: usize = 4;
+
 fn format_url_path(
     webdav_auth: &WebdavAuth,
     path: &str,
@@ -31,7 +54,413 @@ fn format_url_path(
     Ok(joined_url.to_string())
 }
 
-type WebDavTaskResult = Vec<Result<MultiStatus, String>>;
+type WebDavTaskResult = Vec<Result<(String, MultiStatus), String>>;
+
+/// 上传文件（PUT），已存在时覆盖
+///
+/// `body` 可以是 `Vec<u8>`、`String`、`bytes::Bytes` 等任何能转换为
+/// [`reqwest::Body`] 的类型；大文件请使用 [`upload_remote_file_stream`]。
+///
+/// - 201 Created（新建）与 204 No Content（覆盖）等 2xx 均视为成功
+/// - 409 Conflict 表示父目录不存在，返回明确的错误（可先调用
+///   `ensure_collection_path` 创建）
+/// - 507 表示远程空间不足，其余非 2xx 状态返回状态码与响应体
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn upload_remote_file(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    body: impl Into<reqwest::Body>,
+) -> Result<(), String> {
+    let url = format_url_path(webdav_auth, relative_url)?;
+    put(webdav_auth, webdav_auth.client.put(&url).body(body), &url).await
+}
+
+/// 上传被自己锁定的文件（PUT），携带 `If: (<lock_token>)`
+///
+/// `lock_token` 为 [`lock_remote`] 返回的 [`LockInfo::token`]；
+/// 不带令牌写入被锁定的资源时服务器返回 423 Locked。
+/// 其余行为与 [`upload_remote_file`] 相同。
+pub async fn upload_remote_file_locked(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    body: impl Into<reqwest::Body>,
+    lock_token: &str,
+) -> Result<(), String> {
+    let url = format_url_path(webdav_auth, relative_url)?;
+    let request = webdav_auth
+        .client
+        .put(&url)
+        .header("If", lock_if_header(lock_token))
+        .body(body);
+    put(webdav_auth, request, &url).await
+}
+
+/// 流式上传本地文件（PUT），边读边发，不会把文件整体读入内存
+///
+/// 按文件当前大小发送 `Content-Length`（不少服务器拒绝分块传输编码的 PUT），
+/// 其余行为与 [`upload_remote_file`] 相同。
+pub async fn upload_remote_file_stream(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    file: tokio::fs::File,
+) -> Result<(), String> {
+    let url = format_url_path(webdav_auth, relative_url)?;
+    let len = file.metadata().await.map_err(|e| e.to_string())?.len();
+
+    let request = webdav_auth
+        .client
+        .put(&url)
+        .header(CONTENT_LENGTH, len)
+        .body(reqwest::Body::from(file));
+    put(webdav_auth, request, &url).await
+}
+
+/// 创建单个远程目录（MKCOL）
+///
+/// MKCOL 不会递归创建，父目录必须已经存在；需要 `mkdir -p` 时请使用
+/// `ensure_collection_path`。请求的 URL 总是带尾部斜杠。
+///
+/// - 201 Created 等 2xx 视为成功
+/// - 目录已存在（405）时返回以 `资源已存在` 开头的错误，
+///   调用方可以据此判断并忽略
+/// - 父目录不存在（409）时返回以 `父目录不存在` 开头的错误
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn create_remote_dir(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+) -> Result<(), String> {
+    let url = collection_url(&format_url_path(webdav_auth, relative_url)?);
+
+    mkcol(webdav_auth, &url).await.map_err(|e| match e {
+        WebDavError::Status { status: 409, .. } => format!(
+            "父目录不存在（409 Conflict），MKCOL 不会创建中间目录: {}",
+            url
+        ),
+        e => e.to_string(),
+    })
+}
+
+/// 删除远程文件或目录（DELETE）
+///
+/// **删除目录是递归的**：目录及其下所有文件和子目录都会被删除，且无法撤销。
+///
+/// 路径没有尾部斜杠时先发送一次 `Depth: 0` 的 PROPFIND（HEAD 无法区分文件与
+/// 目录），是目录则补上尾部斜杠再删除，避免部分服务器对目录的无斜杠地址
+/// 返回重定向或拒绝删除。
+///
+/// - 200 OK 与 204 No Content 等 2xx 视为成功
+/// - 资源不存在（404）时返回以 `资源不存在` 开头的错误（对应
+///   [`WebDavError::NotFound`]），调用方可以据此区分“已经不存在”与真正的失败
+/// - 207 Multi-Status 表示目录中部分资源删除失败，与其余非 2xx 状态一样
+///   返回状态码与响应体
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn delete_remote(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+) -> Result<(), String> {
+    delete_remote_inner(webdav_auth, relative_url, None).await
+}
+
+/// 删除被自己锁定的文件或目录（DELETE），携带 `If: (<lock_token>)`
+///
+/// 其余行为与 [`delete_remote`] 相同。
+pub async fn delete_remote_locked(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    lock_token: &str,
+) -> Result<(), String> {
+    delete_remote_inner(webdav_auth, relative_url, Some(lock_token)).await
+}
+
+async fn delete_remote_inner(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    lock_token: Option<&str>,
+) -> Result<(), String> {
+    let mut url = format_url_path(webdav_auth, relative_url)?;
+
+    if !url.ends_with('/') {
+        match get_folders_raw_data(webdav_auth, &url, &Depth::Zero).await {
+            Ok(multi_status) => {
+                let is_dir = multi_status
+                    .to_all_remote_file_data(&webdav_auth.base_url)
+                    .first()
+                    .is_some_and(|data| data.is_dir);
+                if is_dir {
+                    url = collection_url(&url);
+                }
+            }
+            Err(WebDavError::Status { status: 404, .. }) => {
+                return Err(WebDavError::NotFound(url).to_string());
+            }
+            // 查询失败时按原地址删除，由 DELETE 的结果决定
+            Err(_) => {}
+        }
+    }
+
+    delete_with_lock(webdav_auth, &url, lock_token).await.map_err(|e| {
+        match e {
+            WebDavError::Status { status: 404, .. } => {
+                WebDavError::NotFound(url.clone()).to_string()
+            }
+            e => e.to_string(),
+        }
+    })
+}
+
+/// 移动（重命名）远程文件或目录（MOVE）
+///
+/// `from_relative_url` 与 `to_relative_url` 都经过与其他入口函数相同的解析与
+/// base_url 范围检查，目标地址以编码后的绝对 URL 放入 `Destination` 请求头
+/// （空格、中文等会被百分号编码）。
+///
+/// - `overwrite` 为 `false` 时发送 `Overwrite: F`，目标已存在时返回
+///   [`WebDavError::DestinationExists`]（服务器的 412 Precondition Failed）
+/// - 路径解析失败返回 [`WebDavError::InvalidRequest`]，不会发出请求
+/// - 其余失败见 [`move_resource`]
+/// - 注意：两个路径都是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn move_remote(
+    webdav_auth: &WebdavAuth,
+    from_relative_url: &str,
+    to_relative_url: &str,
+    overwrite: bool,
+) -> Result<(), WebDavError> {
+    let (from, to) =
+        transfer_urls(webdav_auth, from_relative_url, to_relative_url)?;
+    move_resource(webdav_auth, &from, &to, overwrite).await
+}
+
+/// 复制远程文件或目录（COPY），目录连同其下内容整体复制
+///
+/// 参数与错误处理同 [`move_remote`]，其余失败见 [`copy_resource`]。
+pub async fn copy_remote(
+    webdav_auth: &WebdavAuth,
+    from_relative_url: &str,
+    to_relative_url: &str,
+    overwrite: bool,
+) -> Result<(), WebDavError> {
+    let (from, to) =
+        transfer_urls(webdav_auth, from_relative_url, to_relative_url)?;
+    copy_resource(webdav_auth, &from, &to, overwrite).await
+}
+
+/// 解析 MOVE/COPY 的源地址与目标地址
+fn transfer_urls(
+    webdav_auth: &WebdavAuth,
+    from_relative_url: &str,
+    to_relative_url: &str,
+) -> Result<(String, String), WebDavError> {
+    let resolve = |path| {
+        format_url_path(webdav_auth, path).map_err(|e| {
+            WebDavError::InvalidRequest(format!("{}: {}", e, path))
+        })
+    };
+    Ok((resolve(from_relative_url)?, resolve(to_relative_url)?))
+}
+
+/// 设置远程文件或目录的自定义属性（PROPPATCH）
+///
+/// 属性以 `{namespace}name` 标识，`value` 按文本写入（自动转义）。
+///
+/// - 路径解析失败返回 [`WebDavError::InvalidRequest`]，不会发出请求
+/// - 服务器拒绝该属性（multistatus 中的 propstat 不是 2xx）时返回
+///   [`WebDavError::PropertyFailed`]，带上服务器给出的状态行
+/// - 其余失败见 [`proppatch`]
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn set_property(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    namespace: &str,
+    name: &str,
+    value: &str,
+) -> Result<(), WebDavError> {
+    let url = format_url_path(webdav_auth, relative_url).map_err(|e| {
+        WebDavError::InvalidRequest(format!("{}: {}", e, relative_url))
+    })?;
+    proppatch(webdav_auth, &url, namespace, name, value).await
+}
+
+/// 用一次 HEAD 查询远程文件的大小、类型、ETag 与修改时间，不下载内容
+///
+/// 比 PROPFIND 轻量，适合下载前判断文件是否存在、决定单线程还是分片下载
+/// （见 [`RemoteHead::accepts_ranges`]）。
+///
+/// - 资源不存在（404）时返回以 `资源不存在` 开头的错误
+/// - 服务器不支持 HEAD（405/501）时返回说明错误，此时可以改用
+///   [`stat`](crate::webdav::functions::stat)，它会自动回退到 PROPFIND
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn head_remote(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+) -> Result<RemoteHead, String> {
+    let url = format_url_path(webdav_auth, relative_url)?;
+
+    head(webdav_auth, &url).await.map_err(|e| match e {
+        WebDavError::Status { status: 404, .. } => {
+            WebDavError::NotFound(url.clone()).to_string()
+        }
+        WebDavError::Status { status, .. }
+            if matches!(status, 405 | 501) =>
+        {
+            format!(
+                "服务器不支持 HEAD（{}），请改用 stat（会自动回退到 \
+                 PROPFIND）: {}",
+                status, url
+            )
+        }
+        e => e.to_string(),
+    })
+}
+
+/// 查询远程目录所在空间的配额（已用 / 可用字节数）
+///
+/// 发送只请求 `quota-used-bytes` 与 `quota-available-bytes` 的 `Depth: 0`
+/// PROPFIND（这两个属性不会出现在 `allprop` 中）。服务器不支持配额或
+/// 报告为不限额时返回以 `服务器不支持` 开头的错误。
+///
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn get_remote_quota(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+) -> Result<Quota, String> {
+    let url = format_url_path(webdav_auth, relative_url)?;
+    get_quota(webdav_auth, &url).await.map_err(|e| e.to_string())
+}
+
+/// 对远程文件加独占写锁（LOCK），返回锁令牌与服务器给出的有效期
+///
+/// - `timeout_secs` 为请求的有效期，实际以 [`LockInfo::timeout`] 为准，
+///   需要长时间持有时请自行续期或使用
+///   [`LockGuard`](crate::webdav::structs::LockGuard)
+/// - 资源已被他人锁定时返回带 423 状态码的错误
+/// - 之后的写入请使用 [`upload_remote_file_locked`] / [`delete_remote_locked`]，
+///   用完调用 [`unlock_remote`] 释放
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn lock_remote(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    timeout_secs: u64,
+) -> Result<LockInfo, String> {
+    let url = format_url_path(webdav_auth, relative_url)?;
+    lock(webdav_auth, &url, timeout_secs).await.map_err(|e| e.to_string())
+}
+
+/// 释放 [`lock_remote`] 得到的锁（UNLOCK）
+///
+/// 令牌不匹配或锁已过期时返回状态码与响应体。
+pub async fn unlock_remote(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    lock_token: &str,
+) -> Result<(), String> {
+    let url = format_url_path(webdav_auth, relative_url)?;
+    unlock(webdav_auth, &url, lock_token).await.map_err(|e| e.to_string())
+}
+
+/// 发送 PUT 并检查状态
+async fn put(
+    webdav_auth: &WebdavAuth,
+    request: RequestBuilder,
+    url: &str,
+) -> Result<(), String> {
+    let res =
+        webdav_auth.send(request).await.map_err(|e| e.to_string())?;
+
+    let status = res.status();
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let body = res.text().await.unwrap_or_default();
+
+    if status == StatusCode::CONFLICT {
+        return Err(format!("父目录不存在（409 Conflict）: {}", url));
+    }
+
+    Err(WebDavError::from_write_status(url, status, body).to_string())
+}
+
+/// 批量删除远程文件或目录
+///
+/// 最多同时发出 `concurrency` 个 DELETE 请求（为 0 时按 1 处理），
+/// 单个路径失败不会中断其余路径。返回值与 `relative_urls` 一一对应、顺序相同，
+/// 元素为 (传入的路径, 删除结果)。
+///
+/// - 注意：relative_urls是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn delete_many(
+    webdav_auth: &WebdavAuth,
+    relative_urls: &[&str],
+    concurrency: usize,
+) -> Vec<(String, Result<(), WebDavError>)> {
+    stream::iter(relative_urls.iter().map(|path| async move {
+        let result = match format_url_path(webdav_auth, path) {
+            Ok(url) => delete(webdav_auth, &url).await,
+            Err(e) => Err(WebDavError::InvalidRequest(e)),
+        };
+        (path.to_string(), result)
+    }))
+    .buffered(concurrency.max(1))
+    .collect()
+    .await
+}
+
+/// 批量 GET 大量小文件到内存，最多同时进行 `max_in_flight` 个请求
+/// （为 0 时按 1 处理）
+///
+/// 每个文件只发一次 GET 并整体读取响应体，不经过下载器的进度、分片与控制命令，
+/// 省去逐个构建下载器的开销。所有请求共用 `files` 中认证携带的 HTTP 客户端。
+///
+/// - 本函数只限制并发数，不检测也不要求 HTTP/2：请求如何分布到连接上由
+///   客户端的协议设置决定。默认的 HTTP/1.1 下分布在连接池中多个复用的
+///   keep-alive 连接上；认证开启 [`WebdavAuth::prefer_http2`] 且协商到
+///   HTTP/2 时才会在单个连接上多路复用
+/// - 返回值与 `files` 一一对应、顺序相同，元素为 (相对路径, 文件内容)；
+///   非 2xx 状态返回 [`DownloadError::Request`]，单个文件失败不影响其余文件
+/// - 目录返回 [`DownloadError::IsDir`]
+pub async fn get_many(
+    files: &[RemoteFile],
+    max_in_flight: usize,
+) -> Vec<(String, Result<Vec<u8>, DownloadError>)> {
+    stream::iter(files.iter().map(|file| async move {
+        let path = file.data.relative_root_path.clone();
+        if file.data.is_dir {
+            return (path, Err(DownloadError::IsDir));
+        }
+
+        let result = async {
+            let auth = &file.webdav_auth;
+            let res = auth
+                .send(auth.client.get(&file.data.absolute_path))
+                .await?
+                .error_for_status()?;
+            Ok(res.bytes().await?.to_vec())
+        }
+        .await;
+        (path, result)
+    }))
+    .buffered(max_in_flight.max(1))
+    .collect()
+    .await
+}
+
+/// 用 `webdav_auth` 批量下载文件到内存，同时进行的请求总数不超过
+/// `global_max_concurrency`（为 0 时按 1 处理）
+///
+/// 每个文件使用默认配置的下载器（单线程、输出到内存），按 [`DownloadBatch`]
+/// 共享全局并发额度。返回值与 `files` 一一对应、顺序相同，单个文件失败不会
+/// 中断其余文件。需要为每个文件单独配置下载器，或在下载过程中暂停、取消
+/// 某个文件时，请直接使用 [`DownloadBatch`]。
+pub async fn download_many(
+    webdav_auth: &WebdavAuth,
+    files: &[RemoteFile],
+    global_max_concurrency: usize,
+) -> Vec<Result<DownloadResult, DownloadError>> {
+    let downloaders = files
+        .iter()
+        .map(|file| file.download(webdav_auth.clone()))
+        .collect();
+    DownloadBatch::new(downloaders, global_max_concurrency).send().await
+}
 
 /// 读取远程文件，并转换成领域结构体模型
 ///
@@ -61,9 +490,11 @@ pub async fn get_remote_files(
     let tasks = relative_urls.iter().map(|path| async move {
         let url = format_url_path(webdav_auth, path)?;
         let folders_raw_data =
-            get_folders_raw_data(webdav_auth, &url, &Depth::One).await?;
+            get_folders_raw_data(webdav_auth, &url, &Depth::One)
+                .await
+                .map_err(|e| e.to_string())?;
 
-        Ok(folders_raw_data)
+        Ok((url, folders_raw_data))
     });
 
     // 并发获取全部的列表
@@ -74,11 +505,12 @@ pub async fn get_remote_files(
 
     for webdav_task_result in fetched_webdav_task_results {
         match webdav_task_result {
-            Ok(multi_status) => {
+            Ok((url, multi_status)) => {
                 let from_multi_status_result =
                     RemoteFile::from_multi_status(
                         webdav_auth,
                         multi_status,
+                        &url,
                     );
 
                 match from_multi_status_result {
@@ -97,24 +529,326 @@ pub async fn get_remote_files(
     files_collection
 }
 
-/// 获取远程WebDav服务器上的文件夹树
+/// 读取目录的直接子项，优先使用 `cache`
 ///
-/// `relative_url`参数可选，未设置时默认读取webdav_auth中的base_url
+/// 返回内容与对单个目录调用 [`get_remote_files`] 相同。缓存在 ttl 内直接返回；
+/// 过期后用目录的 ETag 发送 `If-None-Match` 的 PROPFIND 重新验证，
+/// 304 时沿用缓存，否则更新缓存。见 [`ListingCache`]。
+///
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn get_remote_files_cached(
+    webdav_auth: &WebdavAuth,
+    cache: &ListingCache,
+    relative_url: &str,
+) -> Result<Vec<RemoteFile>, String> {
+    let url = collection_url(&format_url_path(webdav_auth, relative_url)?);
+
+    let etag = match cache.lookup(&url) {
+        CacheLookup::Fresh(files) => return Ok(files),
+        CacheLookup::Stale(etag) => etag,
+        CacheLookup::Miss => None,
+    };
+
+    // 过期且有 ETag：先条件请求，304 时沿用缓存
+    let mut fetched = None;
+    if let Some(etag) = etag {
+        match get_folders_raw_data_if_none_match(
+            webdav_auth,
+            &url,
+            &Depth::One,
+            &etag,
+        )
+        .await
+        .map_err(|e| e.to_string())?
+        {
+            Some(multi_status) => fetched = Some(multi_status),
+            None => {
+                if let Some(files) = cache.revalidated(&url) {
+                    return Ok(files);
+                }
+            }
+        }
+    }
+
+    let multi_status = match fetched {
+        Some(multi_status) => multi_status,
+        None => get_folders_raw_data(webdav_auth, &url, &Depth::One)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+
+    let folder_etag = folder_etag(&multi_status, webdav_auth, &url);
+    let files =
+        RemoteFile::from_multi_status(webdav_auth, multi_status, &url)?;
+
+    cache.store(&url, files.clone(), folder_etag);
+    Ok(files)
+}
+
+/// 从 multistatus 中找出目录自身，取其可用于条件请求的 ETag
+fn folder_etag(
+    multi_status: &MultiStatus,
+    webdav_auth: &WebdavAuth,
+    url: &str,
+) -> Option<String> {
+    let requested = normalize_webdav_path(url);
+    let response = multi_status
+        .responses
+        .iter()
+        .find(|r| normalize_webdav_path(&r.href) == requested)?;
+
+    MultiStatus { responses: vec![response.clone()], skipped: Vec::new() }
+        .to_all_remote_file_data(&webdav_auth.base_url)
+        .pop()?
+        .conditional_etag()
+}
+
+/// 读取一个目录自身的信息及其直接子项
+///
+/// 发送一次 Depth: 1 的 PROPFIND，按 href 识别目录自身（不依赖返回顺序），
+/// 请求前会给目录地址补上尾斜杠。
+///
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn get_folder_view(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+) -> Result<FolderView, String> {
+    let url = collection_url(&format_url_path(webdav_auth, relative_url)?);
+    let multi_status = get_folders_raw_data(webdav_auth, &url, &Depth::One)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    FolderView::from_multi_status(multi_status, &webdav_auth.base_url, &url)
+}
+
+/// 获取远程WebDav服务器上一个目录的直接子项
+///
+/// `relative_url`参数可选，未设置时默认读取webdav_auth中的base_url。
+/// 返回的条目带有大小、类型等信息，按服务器返回的顺序排列；
+/// 目录自身按 href 识别并跳过，不依赖它在响应中的位置。
 ///
 /// - 注意1：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
-/// - 注意2：只能读取一层目录
+/// - 注意2：只能读取一层目录，需要递归请使用 [`get_remote_files_recursive`]
 pub async fn get_remote_files_tree(
     webdav_auth: &WebdavAuth,
     relative_url: Option<&str>,
-) -> Result<Vec<String>, String> {
-    if let Some(relative_url) = relative_url {
-        let a = get_folders_raw_data(
-            webdav_auth,
-            relative_url,
-            &Depth::One, // 这里只读取一级，避免出现递归问题
+) -> Result<Vec<RemoteFileData>, String> {
+    let url = collection_url(&format_url_path(
+        webdav_auth,
+        relative_url.unwrap_or("./"),
+    )?);
+    let multi_status = get_folders_raw_data(
+        webdav_auth,
+        &url,
+        &Depth::One, // 这里只读取一级，避免出现递归问题
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let requested = normalize_webdav_path(&url);
+    Ok(multi_status
+        .to_all_remote_file_data(&webdav_auth.base_url)
+        .into_iter()
+        .filter(|data| {
+            normalize_webdav_path(&data.absolute_path) != requested
+        })
+        .collect())
+}
+
+/// 递归读取远程目录，返回其下所有文件与目录（不包含起始目录本身）
+///
+/// 按层（广度优先）列举，每层最多同时发出 4 个 PROPFIND 请求，
+/// 结果按层、按服务器返回的顺序排列。
+///
+/// - `policy.max_depth` 限制递归深度
+/// - `policy.follow_shares` 为 `false` 时不进入 href 不在父目录之下的共享/挂载目录
+/// - `policy.visited_dedup_by` 决定已访问目录的判定方式，防止循环链接导致无限递归
+/// - `policy.include_hidden` 与 `policy.exclude` 按名称过滤条目，被过滤的目录不会进入
+/// - 任一目录列举失败时返回错误（错误信息带有该目录 URL）
+/// - 目录 URL 在请求前会补上尾斜杠（见 [`collection_url`]），
+///   `relative_url` 写成 `"docs"` 或 `"docs/"` 效果相同
+///
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn get_remote_files_recursive(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    policy: &RecursionPolicy,
+) -> Result<Vec<RemoteFile>, String> {
+    list_recursive(webdav_auth, relative_url, policy, None)
+        .await
+        .map(|listing| listing.collected)
+}
+
+/// 递归遍历远程目录，最多进入 `max_depth` 层，返回拍平后的全部后代
+///
+/// 等同于以默认 [`RecursionPolicy`] 调用 [`get_remote_files_recursive`]，
+/// 只返回各条目的 [`RemoteFileData`]：`max_depth` 为 1 时只返回直接子项，
+/// 为 0 时返回空列表。
+///
+/// - 每层最多同时发出 4 个 PROPFIND 请求，深层目录树不会一次打开大量连接
+/// - 每层 `Depth: 1` 响应中的目录自身按 href 跳过，不会重复计入
+/// - 已访问的目录按路径去重，href 指回自身或上层（不在父目录之下）的目录
+///   只返回、不进入，服务器返回自引用的 href 时也不会死循环
+/// - 注意：root是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn walk_remote(
+    webdav_auth: &WebdavAuth,
+    root: &str,
+    max_depth: usize,
+) -> Result<Vec<RemoteFileData>, String> {
+    let policy = RecursionPolicy {
+        max_depth: Some(max_depth),
+        ..RecursionPolicy::default()
+    };
+
+    let listing = list_recursive(webdav_auth, root, &policy, None).await?;
+    Ok(listing
+        .collected
+        .into_iter()
+        .map(|file| Arc::unwrap_or_clone(file.data))
+        .collect())
+}
+
+/// 可取消的 [`get_remote_files_recursive`]
+///
+/// `cancel` 被触发后立即停止：进行中的 PROPFIND 被丢弃，
+/// 返回 `cancelled: true` 以及此前已经列举到的条目。
+/// 未被取消时结果与 [`get_remote_files_recursive`] 相同；
+/// 取消前发生的列举错误仍然返回 `Err`。
+///
+/// - 注意：relative_url是基于webdav_auth中的base_url的，所以不建议以"/"开头
+pub async fn get_remote_files_recursive_cancellable(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    policy: &RecursionPolicy,
+    cancel: &CancelToken,
+) -> Result<PartialListing, String> {
+    list_recursive(webdav_auth, relative_url, policy, Some(cancel)).await
+}
+
+/// 递归列举的实现；`cancel` 为 `None` 时不可取消
+async fn list_recursive(
+    webdav_auth: &WebdavAuth,
+    relative_url: &str,
+    policy: &RecursionPolicy,
+    cancel: Option<&CancelToken>,
+) -> Result<PartialListing, String> {
+    let mut collected = Vec::new();
+    if cancel.is_some_and(CancelToken::is_cancelled) {
+        return Ok(PartialListing { collected, cancelled: true });
+    }
+    if policy.max_depth == Some(0) {
+        return Ok(PartialListing { collected, cancelled: false });
+    }
+
+    // 起始路径与子目录都是目录，请求前统一补上尾斜杠，避免重定向
+    let root_url =
+        collection_url(&format_url_path(webdav_auth, relative_url)?);
+
+    let mut visited = HashSet::new();
+    visited.insert(normalize_webdav_path(&root_url));
+
+    // 当前层待列举的目录 URL
+    let mut frontier = vec![root_url];
+    let mut depth = 0usize;
+
+    while !frontier.is_empty() {
+        depth += 1;
+
+        let mut listings = stream::iter(
+            std::mem::take(&mut frontier).into_iter().map(|url| async move {
+                let multi_status =
+                    get_folders_raw_data(webdav_auth, &url, &Depth::One)
+                        .await
+                        .map_err(|e| format!("{}: {}", url, e))?;
+                let files = RemoteFile::from_multi_status(
+                    webdav_auth,
+                    multi_status,
+                    &url,
+                )?;
+                Ok::<_, String>((url, files))
+            }),
         )
-        .await?;
+        .buffered(RECURSIVE_LISTING_CONCURRENCY);
+
+        let can_descend = policy.max_depth.is_none_or(|max| depth < max);
+        let mut next_frontier = Vec::new();
+
+        // 逐个处理已完成的目录，取消时丢弃其余进行中的请求
+        loop {
+            let listing = tokio::select! {
+                biased;
+
+                _ = wait_cancelled(cancel) => {
+                    return Ok(PartialListing { collected, cancelled: true });
+                }
+                listing = listings.next() => match listing {
+                    Some(listing) => listing,
+                    None => break,
+                },
+            };
+            let (parent_url, files) = listing?;
+            let parent_path = normalize_webdav_path(&parent_url);
+
+            for file in files {
+                let path = normalize_webdav_path(&file.data.absolute_path);
+                // 空目录时服务器只返回目录自身，不能把它当成子项
+                if path == parent_path {
+                    continue;
+                }
+                // 过滤隐藏文件与排除项：被过滤的目录也不会进入
+                if !policy.keeps(&file.data.name) {
+                    continue;
+                }
+
+                if file.data.is_dir
+                    && can_descend
+                    && should_descend(
+                        &file,
+                        &path,
+                        &parent_path,
+                        policy,
+                        &mut visited,
+                    )
+                {
+                    next_frontier
+                        .push(collection_url(&file.data.absolute_path));
+                }
+                collected.push(file);
+            }
+        }
+
+        frontier = next_frontier;
+    }
+
+    Ok(PartialListing { collected, cancelled: false })
+}
+
+/// 等待取消；没有令牌时永远不返回
+async fn wait_cancelled(cancel: Option<&CancelToken>) {
+    match cancel {
+        Some(cancel) => cancel.cancelled().await,
+        None => std::future::pending().await,
     }
+}
+
+/// 判断是否进入某个子目录，并记录到已访问集合
+fn should_descend(
+    dir: &RemoteFile,
+    path: &str,
+    parent_path: &str,
+    policy: &RecursionPolicy,
+    visited: &mut HashSet<String>,
+) -> bool {
+    let parent_prefix = format!("{}/", parent_path.trim_end_matches('/'));
+    let is_share = !path.starts_with(&parent_prefix);
+    if is_share && !policy.follow_shares {
+        return false;
+    }
+
+    let key = match (&policy.visited_dedup_by, &dir.data.etag) {
+        (DedupKey::Etag, Some(etag)) => format!("etag:{}", etag),
+        _ => path.to_string(),
+    };
 
-    Ok(Vec::new())
+    visited.insert(key)
 }
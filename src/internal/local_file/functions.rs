@@ -0,0 +1,3 @@
+pub mod available_space;
+pub mod plan_upload;
+pub mod upload_directory_as_tar;
@@ -0,0 +1,63 @@
+use std::io;
+use std::path::Path;
+
+/// 查询 `path` 所在文件系统上当前用户可用的剩余空间（字节）
+///
+/// `path` 必须已存在；要查询一个尚未创建的文件，请传入它的父目录。
+#[cfg(unix)]
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    // SAFETY: statvfs 只写入传入的结构体，c_path 在调用期间有效
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // f_bavail 为非特权用户可用的块数，块大小以 f_frsize 为准；
+    // 两者在不同平台上分别是 u32/u64，统一转换为 u64
+    #[allow(clippy::useless_conversion)]
+    Ok(u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize)))
+}
+
+/// 查询 `path` 所在文件系统上当前用户可用的剩余空间（字节）
+///
+/// `path` 必须已存在；要查询一个尚未创建的文件，请传入它的父目录。
+#[cfg(windows)]
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> =
+        path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut free_bytes: u64 = 0;
+
+    // SAFETY: wide 以 0 结尾且在调用期间有效，不需要的输出参数传空指针
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut free_bytes,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(free_bytes)
+}
+
+/// 查询 `path` 所在文件系统上当前用户可用的剩余空间（字节）
+#[cfg(not(any(unix, windows)))]
+pub fn available_space(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "当前平台不支持查询磁盘剩余空间",
+    ))
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use percent_encoding::percent_decode_str;
+
+use crate::internal::local_file::structs::upload_plan::{
+    UploadItem, UploadPlan, UploadReason, UploadStrategy,
+};
+use crate::remote_file::RemoteFileData;
+
+/// 比较本地目录与远程列表，得出需要上传的文件与需要创建的目录
+///
+/// - `local_root`：要上传的本地目录，递归遍历（不跟随符号链接）
+/// - `remote_root`：与 `local_root` 对应的远程目录，与
+///   `RemoteFileData::relative_root_path` 同一形式（如 `/backup/photos/`）
+/// - `remote_listing`：`remote_root` 之下的递归列表，例如
+///   [`get_remote_files_recursive`](crate::get_remote_files_recursive) 的结果；
+///   不在 `remote_root` 之下的条目被忽略
+///
+/// 只做比较，不发送任何请求。远程的 href 会先做百分号解码再与本地名称比较。
+pub fn plan_upload(
+    local_root: &Path,
+    remote_root: &str,
+    remote_listing: &[RemoteFileData],
+    strategy: UploadStrategy,
+) -> io::Result<UploadPlan> {
+    let remote = index_remote(remote_root, remote_listing);
+
+    let mut plan = UploadPlan::default();
+    walk(local_root, "", &remote, strategy, &mut plan)?;
+
+    plan.create_dirs.sort();
+    plan.uploads.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    plan.conflicts.sort();
+    Ok(plan)
+}
+
+/// 远程条目按相对 `remote_root` 的解码路径（无首尾斜杠）索引
+fn index_remote<'a>(
+    remote_root: &str,
+    remote_listing: &'a [RemoteFileData],
+) -> HashMap<String, &'a RemoteFileData> {
+    let root = decode(remote_root);
+    let root = root.trim_matches('/');
+
+    remote_listing
+        .iter()
+        .filter_map(|data| {
+            let path = decode(&data.relative_root_path);
+            let path = path.trim_matches('/');
+            let relative = if root.is_empty() {
+                path
+            } else {
+                path.strip_prefix(root)?.strip_prefix('/')?
+            };
+            (!relative.is_empty()).then(|| (relative.to_string(), data))
+        })
+        .collect()
+}
+
+fn decode(path: &str) -> String {
+    percent_decode_str(path).decode_utf8_lossy().into_owned()
+}
+
+/// 递归遍历本地目录，`prefix` 为当前目录相对根目录的路径
+fn walk(
+    dir: &Path,
+    prefix: &str,
+    remote: &HashMap<String, &RemoteFileData>,
+    strategy: UploadStrategy,
+    plan: &mut UploadPlan,
+) -> io::Result<()> {
+    let mut entries =
+        fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let relative = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let file_type = entry.file_type()?;
+        let remote_entry = remote.get(&relative).copied();
+
+        if file_type.is_dir() {
+            match remote_entry {
+                Some(data) if !data.is_dir => {
+                    plan.conflicts.push(relative);
+                    continue;
+                }
+                Some(_) => {}
+                None => plan.create_dirs.push(relative.clone()),
+            }
+            walk(&entry.path(), &relative, remote, strategy, plan)?;
+        } else if file_type.is_file() {
+            let metadata = entry.metadata()?;
+            let reason = match remote_entry {
+                Some(data) if data.is_dir => {
+                    plan.conflicts.push(relative);
+                    continue;
+                }
+                Some(data) => compare(&metadata, data, strategy),
+                None => Some(UploadReason::Missing),
+            };
+            match reason {
+                Some(reason) => plan.uploads.push(UploadItem {
+                    relative_path: relative,
+                    local_path: entry.path(),
+                    size: metadata.len(),
+                    reason,
+                }),
+                None => plan.unchanged += 1,
+            }
+        }
+        // 符号链接等其他类型不处理
+    }
+
+    Ok(())
+}
+
+/// 远程已存在同名文件时，按策略判断是否需要上传
+fn compare(
+    local: &fs::Metadata,
+    remote: &RemoteFileData,
+    strategy: UploadStrategy,
+) -> Option<UploadReason> {
+    if strategy == UploadStrategy::MissingOnly {
+        return None;
+    }
+
+    if remote.size != Some(local.len()) {
+        return Some(UploadReason::SizeDiffers {
+            local: local.len(),
+            remote: remote.size,
+        });
+    }
+
+    if strategy == UploadStrategy::SizeOrNewer
+        && let (Ok(modified), Some(remote_modified)) =
+            (local.modified(), remote.last_modified)
+        && local_is_newer(modified, remote_modified.with_timezone(&Utc))
+    {
+        return Some(UploadReason::Newer);
+    }
+
+    None
+}
+
+/// 本地时间比远程晚 1 秒以上
+fn local_is_newer(local: SystemTime, remote: DateTime<Utc>) -> bool {
+    let local: DateTime<Utc> = local.into();
+    local.signed_duration_since(remote).num_seconds() > 1
+}
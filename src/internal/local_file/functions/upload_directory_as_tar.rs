@@ -0,0 +1,152 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use url::Url;
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::local_file::structs::tar_header::encoded_header_len;
+use crate::internal::local_file::structs::tar_upload::{
+    TarEntry, TarUpload,
+};
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 把本地目录打成 tar 并通过一次 PUT 上传到 `remote_path`
+///
+/// 等同于 [`prepare_tar_upload`] 后直接 [`send`](TarUpload::send)，
+/// 返回上传的字节数；需要监听进度时请分两步调用。
+pub async fn upload_directory_as_tar(
+    webdav_auth: &WebdavAuth,
+    local_dir: &Path,
+    remote_path: &str,
+) -> Result<u64, WebDavError> {
+    prepare_tar_upload(webdav_auth, local_dir, remote_path)
+        .await?
+        .send()
+        .await
+}
+
+/// 扫描本地目录，准备一个 tar 流式上传
+///
+/// - `remote_path` 基于 webdav_auth 中的 base_url，例如 `backup/photos.tar`，
+///   父目录需要已经存在（可先调用 `ensure_collection_path`）
+/// - 归档内的路径相对 `local_dir`，不含 `local_dir` 自身的名称；
+///   目录按名称排序、先于其内容写入，符号链接等特殊文件被跳过
+/// - 扫描时记录每个文件的大小，归档总大小因此可以预先算出并作为
+///   `Content-Length` 发送；文件内容在发送时才读取，不会生成临时文件
+/// - 归档由 tokio-tar 编码为 GNU 格式：超过 100 字节的路径使用 `LongLink`
+///   扩展，超过 8GB 的文件使用 base-256 大小字段，GNU tar、bsdtar 与
+///   Python tarfile 均可读取
+pub async fn prepare_tar_upload(
+    webdav_auth: &WebdavAuth,
+    local_dir: &Path,
+    remote_path: &str,
+) -> Result<TarUpload, WebDavError> {
+    let url = remote_file_url(&webdav_auth.base_url, remote_path)?;
+
+    let root = local_dir.to_path_buf();
+    let mut entries = tokio::task::spawn_blocking(move || {
+        let mut entries = Vec::new();
+        scan(&root, "", &mut entries).map(|()| entries)
+    })
+    .await
+    .map_err(|e| WebDavError::LocalIo(io::Error::other(e)))?
+    .map_err(WebDavError::LocalIo)?;
+
+    for entry in &mut entries {
+        entry.header_len = encoded_header_len(&entry.path, entry.kind())
+            .await
+            .map_err(WebDavError::LocalIo)?;
+    }
+
+    Ok(TarUpload::new(webdav_auth, url, entries))
+}
+
+/// 在 base_url 后逐级追加路径（自动百分号编码），不保留尾部斜杠
+fn remote_file_url(
+    base_url: &Url,
+    path: &str,
+) -> Result<String, WebDavError> {
+    let components: Vec<&str> =
+        path.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+
+    if components.is_empty() || components.contains(&"..") {
+        return Err(WebDavError::InvalidRequest(format!(
+            "无效的上传路径: {}",
+            path
+        )));
+    }
+
+    let mut url = base_url.clone();
+    url.path_segments_mut()
+        .map_err(|_| {
+            WebDavError::InvalidRequest(format!(
+                "无法拼接路径: {}",
+                base_url
+            ))
+        })?
+        .pop_if_empty()
+        .extend(components);
+    Ok(url.to_string())
+}
+
+/// 递归扫描目录，`prefix` 为当前目录在归档中的路径
+fn scan(
+    dir: &Path,
+    prefix: &str,
+    entries: &mut Vec<TarEntry>,
+) -> io::Result<()> {
+    let mut children =
+        fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    children.sort_by_key(|entry| entry.file_name());
+
+    for child in children {
+        let name = child.file_name().to_string_lossy().into_owned();
+        let path = if prefix.is_empty() {
+            name
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let file_type = child.file_type()?;
+        let metadata = child.metadata()?;
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+
+        if file_type.is_dir() {
+            entries.push(TarEntry {
+                path: path.clone(),
+                mode: mode(&metadata, 0o755),
+                mtime,
+                header_len: 0,
+                file: None,
+            });
+            scan(&child.path(), &path, entries)?;
+        } else if file_type.is_file() {
+            entries.push(TarEntry {
+                path,
+                mode: mode(&metadata, 0o644),
+                mtime,
+                header_len: 0,
+                file: Some((child.path(), metadata.len())),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// 权限位：unix 上取自文件本身，其他平台使用 `default`
+#[cfg(unix)]
+fn mode(metadata: &fs::Metadata, _default: u32) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn mode(_metadata: &fs::Metadata, default: u32) -> u32 {
+    default
+}
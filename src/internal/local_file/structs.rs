@@ -0,0 +1,3 @@
+pub(crate) mod tar_header;
+pub mod tar_upload;
+pub mod upload_plan;
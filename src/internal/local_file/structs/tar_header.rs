@@ -0,0 +1,60 @@
+//! tar 条目头部：字段由本 crate 填写，编码（GNU 长文件名、超大文件的
+//! base-256 大小、校验和）交给 tokio-tar。
+
+use std::io;
+
+use tokio_tar::{Builder, EntryType, Header};
+
+/// 归档块大小
+pub(crate) const BLOCK_SIZE: u64 = 512;
+
+/// 归档条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EntryKind {
+    File,
+    Dir,
+}
+
+/// 补齐到块大小所需的字节数
+pub(crate) fn padding(size: u64) -> u64 {
+    (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE
+}
+
+/// 构造一个条目的 GNU 头部；路径由 [`Builder::append_data`] 写入时设置
+pub(crate) fn header(
+    kind: EntryKind,
+    size: u64,
+    mode: u32,
+    mtime: u64,
+) -> Header {
+    let mut header = Header::new_gnu();
+    match kind {
+        EntryKind::File => {
+            header.set_entry_type(EntryType::Regular);
+            header.set_size(size);
+        }
+        EntryKind::Dir => {
+            header.set_entry_type(EntryType::Directory);
+            header.set_size(0);
+        }
+    }
+    header.set_mode(mode);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(mtime);
+    header
+}
+
+/// 条目头部编码后的字节数，路径超长时包含前置的 GNU 长文件名条目
+///
+/// 直接用 tokio-tar 编码一次空条目得到；头部块数与文件大小无关，
+/// 因此预先算出的归档总大小与实际发送的内容一致。
+pub(crate) async fn encoded_header_len(
+    path: &str,
+    kind: EntryKind,
+) -> io::Result<u64> {
+    let mut builder = Builder::new_non_terminated(Vec::new());
+    let mut header = header(kind, 0, 0, 0);
+    builder.append_data(&mut header, path, tokio::io::empty()).await?;
+    Ok(builder.get_ref().len() as u64)
+}
@@ -0,0 +1,306 @@
+//! 把本地目录打成 tar 流式上传：边读本地文件边写入 PUT 请求体，不在磁盘上生成临时归档。
+
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use bytes::{Bytes, BytesMut};
+use futures_util::stream;
+use reqwest::Body;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use tokio::fs::File;
+use tokio::io::{
+    AsyncRead, AsyncReadExt, AsyncWriteExt, DuplexStream, ReadBuf, Take,
+};
+use tokio::task::JoinHandle;
+use tokio_tar::Builder;
+
+use super::tar_header::{BLOCK_SIZE, EntryKind, header, padding};
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::states::reactive_core::PropertyWatcher;
+use crate::internal::states::unlock_reactive::UnlockReactiveProperty;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 归档写入端与请求体之间的管道容量，也是每次交给请求体的最大字节数
+const PIPE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// 上传进度：已交给 HTTP 请求的字节数与归档总大小
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// 已发送字节数
+    pub bytes_sent: u64,
+    /// 归档总大小（扫描目录时即可精确算出）
+    pub total: u64,
+}
+
+impl UploadProgress {
+    /// 上传百分比（0.0 ~ 100.0）
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        self.bytes_sent.min(self.total) as f64 * 100.0 / self.total as f64
+    }
+}
+
+/// 归档中的一个条目：归档内路径与元数据，文件还带有本地路径与扫描时的大小
+#[derive(Debug)]
+pub(crate) struct TarEntry {
+    pub(crate) path: String,
+    pub(crate) mode: u32,
+    pub(crate) mtime: u64,
+    /// 编码后的头部字节数，见
+    /// [`encoded_header_len`](super::tar_header::encoded_header_len)
+    pub(crate) header_len: u64,
+    pub(crate) file: Option<(PathBuf, u64)>,
+}
+
+impl TarEntry {
+    pub(crate) fn kind(&self) -> EntryKind {
+        match self.file {
+            Some(_) => EntryKind::File,
+            None => EntryKind::Dir,
+        }
+    }
+}
+
+/// 已扫描完本地目录、等待发送的 tar 上传
+///
+/// 由 [`prepare_tar_upload`](crate::local_file::prepare_tar_upload) 创建，
+/// 发送前可以通过
+/// [`watch_progress`](Self::watch_progress) 监听进度：
+///
+/// ```rust,no_run
+/// use std::path::Path;
+/// use webdav_fs::auth::WebdavAuth;
+/// use webdav_fs::local_file::prepare_tar_upload;
+///
+/// # async fn demo(auth: WebdavAuth) -> Result<(), Box<dyn std::error::Error>> {
+/// let upload =
+///     prepare_tar_upload(&auth, Path::new("photos"), "backup/photos.tar")
+///         .await?;
+/// let mut progress = upload.watch_progress();
+/// tokio::spawn(async move {
+///     while let Ok(p) = progress.changed().await {
+///         println!("{:.1}%", p.percent());
+///     }
+/// });
+/// upload.send().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TarUpload {
+    webdav_auth: WebdavAuth,
+    url: String,
+    entries: Vec<TarEntry>,
+    total: u64,
+    progress: UnlockReactiveProperty<UploadProgress>,
+}
+
+impl TarUpload {
+    pub(crate) fn new(
+        webdav_auth: &WebdavAuth,
+        url: String,
+        entries: Vec<TarEntry>,
+    ) -> Self {
+        let total = entries
+            .iter()
+            .map(|entry| {
+                let data =
+                    entry.file.as_ref().map_or(0, |(_, size)| *size);
+                entry.header_len + data + padding(data)
+            })
+            .sum::<u64>()
+            + 2 * BLOCK_SIZE;
+
+        Self {
+            webdav_auth: webdav_auth.clone(),
+            url,
+            entries,
+            total,
+            progress: UnlockReactiveProperty::new(UploadProgress {
+                bytes_sent: 0,
+                total,
+            }),
+        }
+    }
+
+    /// 目标 URL
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// 归档总大小（字节），即 PUT 请求的 `Content-Length`
+    pub fn total_size(&self) -> u64 {
+        self.total
+    }
+
+    /// 归档中的条目数（文件与目录）
+    pub fn entry_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 当前进度
+    pub fn progress(&self) -> UploadProgress {
+        self.progress.get_current().unwrap_or_default()
+    }
+
+    /// 进度监听器，发送期间每交出一块数据更新一次
+    pub fn watch_progress(&self) -> PropertyWatcher<UploadProgress> {
+        self.progress.watch()
+    }
+
+    /// 发送 PUT，返回上传的字节数
+    ///
+    /// - 文件内容在发送时才读取，按扫描时的大小截取；
+    ///   文件在此期间变短或无法读取时请求中止，返回 [`WebDavError::Request`]
+    /// - 507 表示远程空间不足，返回 [`WebDavError::InsufficientRemoteStorage`]
+    /// - 其余非 2xx 状态返回 [`WebDavError::Status`]
+    pub async fn send(self) -> Result<u64, WebDavError> {
+        let Self { webdav_auth, url, entries, total, progress } = self;
+
+        // 归档由 tokio-tar 在后台任务中写入管道，请求体从另一端读取
+        let (writer, reader) = tokio::io::duplex(PIPE_BUFFER_SIZE);
+        let state = TarStream {
+            reader,
+            archive: Some(tokio::spawn(write_archive(entries, writer))),
+            sent: 0,
+            total,
+            progress,
+        };
+        let body = Body::wrap_stream(stream::unfold(
+            state,
+            |mut state| async move {
+                let piece = state.next_piece().await?;
+                Some((piece, state))
+            },
+        ));
+
+        let request = webdav_auth
+            .client
+            .put(&url)
+            .header(CONTENT_LENGTH, total)
+            .header(CONTENT_TYPE, "application/x-tar")
+            .body(body);
+        let res = webdav_auth.send(request).await?;
+
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            return Err(WebDavError::from_write_status(
+                &url, status, body,
+            ));
+        }
+
+        Ok(total)
+    }
+}
+
+/// 按顺序写入所有条目与结尾的两个空块，完成后关闭管道
+async fn write_archive(
+    entries: Vec<TarEntry>,
+    writer: DuplexStream,
+) -> io::Result<()> {
+    let mut builder = Builder::new_non_terminated(writer);
+    for entry in entries {
+        let size = entry.file.as_ref().map_or(0, |(_, size)| *size);
+        let mut header =
+            header(entry.kind(), size, entry.mode, entry.mtime);
+        match entry.file {
+            Some((path, size)) => {
+                let file = File::open(&path).await?;
+                let data = ExactLen(file.take(size));
+                builder
+                    .append_data(&mut header, &entry.path, data)
+                    .await?;
+            }
+            None => {
+                builder
+                    .append_data(
+                        &mut header,
+                        &entry.path,
+                        tokio::io::empty(),
+                    )
+                    .await?;
+            }
+        }
+    }
+    builder.finish().await?;
+    builder.into_inner().await?.shutdown().await
+}
+
+/// 只读取扫描时记录的字节数；文件在此之前结束时返回 `UnexpectedEof`，
+/// 避免头部中的大小与实际内容不符
+struct ExactLen(Take<File>);
+
+impl AsyncRead for ExactLen {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut self.0).poll_read(cx, buf))?;
+        if buf.filled().len() == before
+            && buf.remaining() > 0
+            && self.0.limit() > 0
+        {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "文件在上传过程中变短",
+            )));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// 从管道读取归档交给请求体，并更新进度
+struct TarStream {
+    reader: DuplexStream,
+    /// 写入归档的后台任务；读到管道末尾后取出并检查其结果
+    archive: Option<JoinHandle<io::Result<()>>>,
+    sent: u64,
+    total: u64,
+    progress: UnlockReactiveProperty<UploadProgress>,
+}
+
+impl TarStream {
+    async fn next_piece(&mut self) -> Option<io::Result<Bytes>> {
+        self.archive.as_ref()?;
+
+        let mut buffer = BytesMut::with_capacity(PIPE_BUFFER_SIZE);
+        match self.reader.read_buf(&mut buffer).await {
+            Ok(0) => {}
+            Ok(read) => {
+                self.sent += read as u64;
+                let _ = self.progress.update(UploadProgress {
+                    bytes_sent: self.sent,
+                    total: self.total,
+                });
+                return Some(Ok(buffer.freeze()));
+            }
+            Err(e) => {
+                // 出错后不再产出任何数据
+                self.archive = None;
+                return Some(Err(e));
+            }
+        }
+
+        // 管道已关闭：写入任务的结果决定归档是否完整
+        let archive = self.archive.take()?;
+        let result =
+            archive.await.unwrap_or_else(|e| Err(io::Error::other(e)));
+        match result {
+            Err(e) => Some(Err(e)),
+            Ok(()) if self.sent != self.total => {
+                Some(Err(io::Error::other(format!(
+                    "归档大小与预计不符: 预计 {} 字节，实际 {} 字节",
+                    self.total, self.sent
+                ))))
+            }
+            Ok(()) => None,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! 上传计划：本地目录与远程列表的比较结果。
+
+use std::path::PathBuf;
+
+/// 判断本地文件是否需要上传的方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UploadStrategy {
+    /// 只上传远程不存在的文件
+    MissingOnly,
+    /// 远程不存在或大小不同时上传
+    SizeChanged,
+    /// 远程不存在、大小不同，或本地修改时间比远程晚时上传（默认）
+    ///
+    /// 远程没有 `getlastmodified` 时只比较大小；HTTP 日期精确到秒，
+    /// 相差不超过 1 秒视为相同
+    #[default]
+    SizeOrNewer,
+}
+
+/// 需要上传的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadReason {
+    /// 远程不存在
+    Missing,
+    /// 大小不同
+    SizeDiffers { local: u64, remote: Option<u64> },
+    /// 本地修改时间比远程晚
+    Newer,
+}
+
+/// 一个需要上传的本地文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadItem {
+    /// 相对本地根目录（也即远程根目录）的路径，以 `/` 分隔
+    pub relative_path: String,
+    /// 本地文件的完整路径
+    pub local_path: PathBuf,
+    /// 本地文件大小（字节）
+    pub size: u64,
+    pub reason: UploadReason,
+}
+
+/// [`plan_upload`](crate::local_file::plan_upload) 的结果
+///
+/// 各列表都按相对路径排序。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadPlan {
+    /// 需要在远程创建的目录（父目录在前），以 `/` 分隔
+    pub create_dirs: Vec<String>,
+    /// 需要上传的文件
+    pub uploads: Vec<UploadItem>,
+    /// 本地与远程一致、无需上传的文件数
+    pub unchanged: usize,
+    /// 本地与远程类型不一致（一边是文件、一边是目录）的路径，不会自动处理
+    pub conflicts: Vec<String>,
+}
+
+impl UploadPlan {
+    /// 需要上传的总字节数
+    pub fn total_bytes(&self) -> u64 {
+        self.uploads.iter().map(|item| item.size).sum()
+    }
+
+    /// 是否无事可做
+    pub fn is_empty(&self) -> bool {
+        self.create_dirs.is_empty() && self.uploads.is_empty()
+    }
+}
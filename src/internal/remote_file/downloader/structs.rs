@@ -1,20 +1,56 @@
+pub mod aggregate_progress;
+pub mod auth_refresh;
+pub(crate) mod bandwidth_limiter;
 pub mod byte_segments;
+pub mod checksum_algo;
+pub(crate) mod chunk_coalescer;
+pub mod chunk_write_mode;
+pub(crate) mod content_digest;
 pub mod control_command;
+pub mod delta_sync;
+pub mod download_batch;
+pub(crate) mod disk_space_guard;
 pub mod download_error;
+pub mod download_metrics;
 pub mod download_mode;
+pub mod download_progress;
 pub mod download_result;
+pub mod download_speed;
 pub mod download_status;
+pub mod download_stream;
+pub mod file_write_limiter;
+pub mod overwrite_policy;
+pub mod progress_report;
 pub mod reactive_state;
 pub mod remote_downloader;
 pub mod remote_downloader_config;
 pub mod remote_downloader_controller;
 
 // 重导出公共类型
+pub use aggregate_progress::{AggregateProgress, AggregateProgressSnapshot};
+pub use auth_refresh::{AuthRefreshFuture, AuthRefresher};
 pub use byte_segments::{ByteSegment, ByteSegments};
+pub use checksum_algo::ChecksumAlgo;
+pub use chunk_write_mode::ChunkWriteMode;
 pub use control_command::ControlCommand;
+pub use delta_sync::{
+    DEFAULT_DELTA_BLOCK_SIZE, DeltaReport, DeltaSyncConfig,
+};
+pub use download_batch::DownloadBatch;
 pub use download_error::DownloadError;
+pub use download_metrics::DownloadMetrics;
 pub use download_mode::DownloadMode;
+pub use download_progress::DownloadProgress;
 pub use download_result::DownloadResult;
+pub use download_speed::DownloadSpeed;
 pub use download_status::DownloadStatus;
+pub use download_stream::DownloadStream;
+pub use file_write_limiter::FileWriteLimiter;
+pub use overwrite_policy::OverwritePolicy;
+pub use progress_report::{AbortPredicate, ProgressReport};
 pub use remote_downloader::RemoteDownloader;
+pub use remote_downloader_config::{
+    DEFAULT_CHUNK_SIZE, DEFAULT_MAX_RETRIES, DEFAULT_RETRY_DELAY_MS,
+    DownloadConfig, RemoteDownloaderConfig,
+};
 pub use remote_downloader_controller::RemoteDownloaderController;
\ No newline at end of file
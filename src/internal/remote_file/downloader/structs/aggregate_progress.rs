@@ -0,0 +1,226 @@
+//! 多个下载任务的汇总进度。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::states::unlock_reactive::UnlockReactiveProperty;
+
+use super::download_progress::DownloadProgress;
+use super::remote_downloader_controller::RemoteDownloaderController;
+
+/// 速度采样间隔：两次采样之间不足该时长时沿用上一次的速度；
+/// 下载停滞时也按该间隔重新采样，使速度回落到 0
+const SPEED_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 汇总进度快照。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AggregateProgressSnapshot {
+    /// 所有下载任务已下载的字节数之和（包含大小未知的任务）
+    pub bytes_done: u64,
+    /// 大小已知的任务的总字节数之和，没有任何大小已知的任务时为 `None`
+    pub total: Option<u64>,
+    /// 大小已知的任务已下载的字节数之和，用于计算百分比
+    pub known_bytes_done: u64,
+    /// 汇总速度（字节/秒），按采样间隔计算；没有进行中的任务时为 0
+    pub speed: f64,
+    /// 已加入汇总的下载任务数
+    pub downloads: usize,
+    /// 尚未结束（完成、取消或出错）的下载任务数
+    pub active: usize,
+}
+
+impl AggregateProgressSnapshot {
+    /// 汇总百分比（0.0 ~ 100.0），只统计大小已知的任务；
+    /// 没有大小已知的任务时返回 `None`
+    pub fn percent(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(100.0),
+            Some(total) => Some(
+                self.known_bytes_done.min(total) as f64 * 100.0
+                    / total as f64,
+            ),
+            None => None,
+        }
+    }
+}
+
+/// 单个下载任务在汇总中的记录
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    bytes_done: u64,
+    total: Option<u64>,
+    finished: bool,
+}
+
+/// 汇总计算需要的共享状态
+#[derive(Debug)]
+struct AggregateInner {
+    entries: Vec<Entry>,
+    /// 上一次速度采样：(时间, 当时的 bytes_done)
+    last_sample: (Instant, u64),
+    speed: f64,
+}
+
+/// 多个下载任务的汇总进度。
+///
+/// 通过 [`add`](Self::add) 加入下载任务的控制器，内部监听每个任务的进度，
+/// 并把汇总后的 [`AggregateProgressSnapshot`] 发布到一个 [`UnlockReactiveProperty`]，
+/// 适合批量下载时的全局进度条。
+///
+/// 任务的总大小随进度更新：加入时未知、之后通过 HEAD 探测或响应的
+/// `Content-Length` 得知的大小同样计入。始终未知大小的任务计入
+/// `bytes_done`，但不参与百分比计算。每个任务的监听在任务结束后退出。
+#[derive(Debug, Clone)]
+pub struct AggregateProgress {
+    inner: Arc<Mutex<AggregateInner>>,
+    state: UnlockReactiveProperty<AggregateProgressSnapshot>,
+}
+
+impl Default for AggregateProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AggregateProgress {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(AggregateInner {
+                entries: Vec::new(),
+                last_sample: (Instant::now(), 0),
+                speed: 0.0,
+            })),
+            state: UnlockReactiveProperty::new(
+                AggregateProgressSnapshot::default(),
+            ),
+        }
+    }
+
+    /// 把一个下载任务加入汇总（一般在 send 之前调用；任务已结束时只记录最终进度）
+    pub fn add(&self, controller: &RemoteDownloaderController) {
+        let mut watcher = controller.watch_progress();
+        let mut terminated = controller.watch_terminated();
+        let index = {
+            let Ok(mut inner) = self.inner.lock() else { return };
+            let progress = watcher.borrow().unwrap_or_default();
+            inner.entries.push(Entry {
+                bytes_done: progress.bytes_done,
+                total: progress.total.or(controller.total_size()),
+                finished: false,
+            });
+            inner.entries.len() - 1
+        };
+        self.publish();
+
+        let aggregate = self.clone();
+        tokio::spawn(async move {
+            // 按半个采样间隔检查，避免计时误差让采样推迟一整个间隔
+            let mut sample =
+                tokio::time::interval(SPEED_SAMPLE_INTERVAL / 2);
+            loop {
+                tokio::select! {
+                    biased;
+
+                    progress = watcher.changed() => match progress {
+                        Ok(progress) => aggregate.record(index, progress),
+                        Err(_) => break,
+                    },
+                    // 下载结束或控制器被释放
+                    _ = async {
+                        let _ = terminated.wait_for(|done| *done).await;
+                    } => break,
+                    _ = sample.tick() => aggregate.publish(),
+                }
+            }
+
+            if let Some(progress) = watcher.borrow() {
+                aggregate.record(index, progress);
+            }
+            if let Ok(mut inner) = aggregate.inner.lock()
+                && let Some(entry) = inner.entries.get_mut(index)
+            {
+                entry.finished = true;
+            }
+            aggregate.publish();
+        });
+    }
+
+    /// 当前汇总快照
+    pub fn get_current(&self) -> AggregateProgressSnapshot {
+        self.state.get_current().unwrap_or_default()
+    }
+
+    /// 汇总进度的响应式属性，可通过 `watch()` 监听变化
+    pub fn state(
+        &self,
+    ) -> &UnlockReactiveProperty<AggregateProgressSnapshot> {
+        &self.state
+    }
+
+    /// 订阅汇总进度变化
+    pub fn subscribe<F>(&self, return_current_value: bool, callback: F)
+    where
+        F: Fn(&AggregateProgressSnapshot) + Send + 'static,
+    {
+        let mut watcher = self.state.watch();
+
+        tokio::spawn(async move {
+            if return_current_value && let Some(current) = watcher.borrow()
+            {
+                callback(&current);
+            }
+
+            while let Ok(snapshot) = watcher.changed().await {
+                callback(&snapshot);
+            }
+        });
+    }
+
+    /// 记录单个任务的最新进度并发布
+    fn record(&self, index: usize, progress: DownloadProgress) {
+        if let Ok(mut inner) = self.inner.lock()
+            && let Some(entry) = inner.entries.get_mut(index)
+        {
+            entry.bytes_done = progress.bytes_done;
+            entry.total = progress.total.or(entry.total);
+        }
+        self.publish();
+    }
+
+    /// 重新计算汇总值并发布
+    fn publish(&self) {
+        let snapshot = {
+            let Ok(mut inner) = self.inner.lock() else { return };
+
+            let mut snapshot = AggregateProgressSnapshot {
+                downloads: inner.entries.len(),
+                ..Default::default()
+            };
+            for entry in &inner.entries {
+                snapshot.bytes_done += entry.bytes_done;
+                snapshot.active += usize::from(!entry.finished);
+                if let Some(total) = entry.total {
+                    snapshot.total =
+                        Some(snapshot.total.unwrap_or(0) + total);
+                    snapshot.known_bytes_done += entry.bytes_done;
+                }
+            }
+
+            let (sampled_at, sampled_bytes) = inner.last_sample;
+            let elapsed = sampled_at.elapsed();
+            if snapshot.active == 0 {
+                inner.speed = 0.0;
+                inner.last_sample = (Instant::now(), snapshot.bytes_done);
+            } else if elapsed >= SPEED_SAMPLE_INTERVAL {
+                let delta =
+                    snapshot.bytes_done.saturating_sub(sampled_bytes);
+                inner.speed = delta as f64 / elapsed.as_secs_f64();
+                inner.last_sample = (Instant::now(), snapshot.bytes_done);
+            }
+            snapshot.speed = inner.speed;
+            snapshot
+        };
+
+        let _ = self.state.update(snapshot);
+    }
+}
@@ -0,0 +1,96 @@
+//! 下载途中认证失效（401/403）时的凭据刷新。
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use reqwest::{Client, StatusCode};
+use tokio::sync::Mutex as TokioMutex;
+
+use super::download_error::DownloadError;
+
+/// [`AuthRefresher`] 返回的 future
+pub type AuthRefreshFuture =
+    Pin<Box<dyn Future<Output = Result<Client, String>> + Send>>;
+
+/// `with_auth_refresh` 注册的刷新函数：返回一个带新凭据的客户端
+///
+/// 同一时刻多个分片收到 401/403 时只会调用一次，其余分片等待并复用结果。
+#[derive(Clone)]
+pub struct AuthRefresher(
+    pub(crate) Arc<dyn Fn() -> AuthRefreshFuture + Send + Sync>,
+);
+
+impl AuthRefresher {
+    pub fn new<F, Fut>(refresh: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Client, String>> + Send + 'static,
+    {
+        Self(Arc::new(move || Box::pin(refresh())))
+    }
+}
+
+impl fmt::Debug for AuthRefresher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuthRefresher(<fn>)")
+    }
+}
+
+/// 响应状态是否表示认证失效，是则返回状态码
+pub(crate) fn auth_failure(status: StatusCode) -> Option<u16> {
+    matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+        .then(|| status.as_u16())
+}
+
+/// 一次下载中各分片共享的客户端，刷新后所有分片改用新客户端
+///
+/// `generation` 每刷新一次加一，分片据此判断自己用的客户端是否已被别人刷新过。
+#[derive(Debug, Clone)]
+pub(crate) struct RefreshableClient {
+    state: Arc<TokioMutex<(Client, u64)>>,
+    refresher: Option<AuthRefresher>,
+}
+
+impl RefreshableClient {
+    pub(crate) fn new(
+        client: Client,
+        refresher: Option<AuthRefresher>,
+    ) -> Self {
+        Self { state: Arc::new(TokioMutex::new((client, 0))), refresher }
+    }
+
+    /// 当前客户端及其代数
+    pub(crate) async fn current(&self) -> (Client, u64) {
+        let state = self.state.lock().await;
+        (state.0.clone(), state.1)
+    }
+
+    /// 用第 `generation` 代客户端收到 `status`（401/403）后调用
+    ///
+    /// - 未注册刷新函数时返回 [`DownloadError::AuthExpired`]
+    /// - 已被其他分片刷新过时直接返回，调用方重新取 [`current`](Self::current)
+    /// - 刷新函数失败时返回 [`DownloadError::AuthRefreshFailed`]
+    pub(crate) async fn refresh_after(
+        &self,
+        generation: u64,
+        status: u16,
+    ) -> Result<(), DownloadError> {
+        let Some(refresher) = &self.refresher else {
+            return Err(DownloadError::AuthExpired { status });
+        };
+
+        // 持锁调用刷新函数，同时失效的其他分片在这里等待
+        let mut state = self.state.lock().await;
+        if state.1 != generation {
+            return Ok(());
+        }
+
+        let client = (refresher.0)()
+            .await
+            .map_err(DownloadError::AuthRefreshFailed)?;
+        *state = (client, generation + 1);
+        Ok(())
+    }
+}
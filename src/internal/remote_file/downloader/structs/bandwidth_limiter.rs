@@ -0,0 +1,59 @@
+//! 下载限速：按字节数预约发送时间，超出速率时 sleep 等待。
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// 下载速率限制器。
+///
+/// 每收到一块数据就把它"记账"到一条虚拟时间线上：这块数据按限速需要
+/// `len / bytes_per_sec` 秒，时间线超过当前时间的部分就是需要等待的时长。
+/// 空闲期间不会累积额度，因此任何时间窗口内的平均速率都不超过上限。
+///
+/// 分片下载时所有分片共享同一个限制器，限速作用于整个下载的总速率。
+#[derive(Debug)]
+pub(crate) struct BandwidthLimiter {
+    bytes_per_sec: u64,
+    next_free: Mutex<Option<Instant>>,
+}
+
+impl BandwidthLimiter {
+    /// 创建限制器，`bytes_per_sec` 为 0 时按 1 处理。
+    pub(crate) fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec.max(1),
+            next_free: Mutex::new(None),
+        }
+    }
+
+    /// 记录收到 `len` 字节，返回需要等待到的时间；无需等待时返回 `None`
+    pub(crate) fn reserve(&self, len: u64) -> Option<Instant> {
+        let now = Instant::now();
+        let cost = Duration::from_secs_f64(
+            len as f64 / self.bytes_per_sec as f64,
+        );
+        let Ok(mut next_free) = self.next_free.lock() else {
+            return None;
+        };
+        let start = next_free.map_or(now, |at| at.max(now));
+        let until = start + cost;
+        *next_free = Some(until);
+        (until > now).then_some(until)
+    }
+
+    /// 记录收到 `len` 字节，并等待到速率回到上限以内
+    pub(crate) async fn consume(&self, len: u64) {
+        if let Some(until) = self.reserve(len) {
+            tokio::time::sleep_until(until).await;
+        }
+    }
+}
+
+/// 等到限速截止时间；没有截止时间时永远不返回
+pub(crate) async fn throttle_elapsed(until: Option<Instant>) {
+    match until {
+        Some(until) => tokio::time::sleep_until(until).await,
+        None => std::future::pending().await,
+    }
+}
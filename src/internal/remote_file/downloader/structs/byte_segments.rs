@@ -19,7 +19,8 @@ pub struct ByteSegments {
 }
 
 impl ByteSegments {
-    /// 从已按 offset 升序且不重叠的分段列表构建。不校验连续性，由调用方保证。
+    /// 从已按 offset 升序且不重叠的分段列表构建。不校验连续性，由调用方保证，
+    /// 可用 [`is_contiguous`](Self::is_contiguous) 检查。
     pub fn new(segments: Vec<ByteSegment>) -> Self {
         let total_len = segments
             .last()
@@ -36,6 +37,23 @@ impl ByteSegments {
         self.total_len
     }
 
+    /// 按 offset 升序遍历全部分段。
+    pub fn segments(&self) -> impl Iterator<Item = &ByteSegment> {
+        self.segments.iter()
+    }
+
+    /// 各段是否从 0 开始、首尾相接地覆盖 `[0, total_len)`，没有空洞或重叠。
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = 0u64;
+        for seg in &self.segments {
+            if seg.offset != expected {
+                return false;
+            }
+            expected = seg.offset.saturating_add(seg.data.len() as u64);
+        }
+        expected == self.total_len
+    }
+
     /// 合并为连续的字节数组。
     pub fn to_bytes(&self) -> Vec<u8> {
         self.segments
@@ -46,23 +64,42 @@ impl ByteSegments {
 
     /// 按偏移读取一段：从 `offset` 起最多读 `len` 字节，返回新分配的字节。
     /// 若 `offset >= total_len` 返回空 Vec；若 `offset + len` 超出末尾则只读到末尾。
+    /// 顺序读取时优先用 [`read_into`](Self::read_into) 复用缓冲区。
     pub fn read_at(&self, offset: u64, len: usize) -> Vec<u8> {
-        if offset >= self.total_len || len == 0 {
-            return Vec::new();
-        }
-        let mut out = Vec::with_capacity(len.min((self.total_len - offset) as usize));
-        let end = offset.saturating_add(len as u64).min(self.total_len);
+        let available = self.total_len.saturating_sub(offset);
+        let mut out = vec![0u8; len.min(available as usize)];
+        let copied = self.read_into(offset, &mut out);
+        out.truncate(copied);
+        out
+    }
 
-        for seg in &self.segments {
-            let seg_end = seg.offset + seg.data.len() as u64;
-            if seg_end <= offset || seg.offset >= end {
+    /// 从 `offset` 起把数据复制到 `buf`，返回复制的字节数。
+    ///
+    /// 最多复制 `buf.len()` 字节，读到末尾或遇到分段之间的空洞时停止，
+    /// 因此返回值小于 `buf.len()` 时 `buf` 的其余部分保持不变。
+    pub fn read_into(&self, offset: u64, buf: &mut [u8]) -> usize {
+        // 第一个结束位置在 offset 之后的分段
+        let first = self.segments.partition_point(|seg| {
+            seg.offset.saturating_add(seg.data.len() as u64) <= offset
+        });
+        let mut pos = offset;
+        let mut copied = 0;
+        for seg in &self.segments[first..] {
+            if copied == buf.len() || seg.offset > pos {
+                break;
+            }
+            // 重叠输入中已被前一段完全覆盖的分段
+            if seg.offset.saturating_add(seg.data.len() as u64) <= pos {
                 continue;
             }
-            let read_start = (offset.max(seg.offset) - seg.offset) as usize;
-            let read_end = (end.min(seg_end) - seg.offset) as usize;
-            out.extend_from_slice(&seg.data[read_start..read_end]);
+            let start = (pos - seg.offset) as usize;
+            let n = (seg.data.len() - start).min(buf.len() - copied);
+            buf[copied..copied + n]
+                .copy_from_slice(&seg.data[start..start + n]);
+            copied += n;
+            pos += n as u64;
         }
-        out
+        copied
     }
 }
 
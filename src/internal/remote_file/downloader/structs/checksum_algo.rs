@@ -0,0 +1,12 @@
+//! 下载内容校验支持的摘要算法。
+
+/// [`RemoteDownloader::verify_checksum`] 使用的摘要算法
+///
+/// [`RemoteDownloader::verify_checksum`]: super::remote_downloader::RemoteDownloader::verify_checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumAlgo {
+    /// MD5，仅用于与只提供 MD5 校验值的来源比对，不具备抗碰撞能力
+    Md5,
+    /// SHA-256
+    Sha256,
+}
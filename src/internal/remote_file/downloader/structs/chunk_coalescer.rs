@@ -0,0 +1,37 @@
+//! 单线程下载的读块合并。
+
+use bytes::{Bytes, BytesMut};
+
+/// 把 `bytes_stream()` 产出的小块累积到至少 `min_bytes` 后再交给写入
+///
+/// `min_bytes` 为 0 时不合并，每个块原样返回
+#[derive(Debug)]
+pub(crate) struct ChunkCoalescer {
+    min_bytes: usize,
+    pending: BytesMut,
+}
+
+impl ChunkCoalescer {
+    pub(crate) fn new(min_bytes: usize) -> Self {
+        Self { min_bytes, pending: BytesMut::new() }
+    }
+
+    /// 收到一个块；累计达到阈值时返回需要写入的数据
+    pub(crate) fn push(&mut self, chunk: Bytes) -> Option<Bytes> {
+        // 未开启合并，或本身已经足够大且没有积压时，直接转发避免拷贝
+        if self.min_bytes == 0
+            || (self.pending.is_empty() && chunk.len() >= self.min_bytes)
+        {
+            return Some(chunk);
+        }
+
+        self.pending.extend_from_slice(&chunk);
+        (self.pending.len() >= self.min_bytes)
+            .then(|| self.pending.split().freeze())
+    }
+
+    /// 流结束时取出不足阈值的剩余数据
+    pub(crate) fn finish(&mut self) -> Option<Bytes> {
+        (!self.pending.is_empty()).then(|| self.pending.split().freeze())
+    }
+}
@@ -0,0 +1,19 @@
+//! 分片下载写入同一文件的方式。
+
+/// 分片下载时各分片任务写入文件的方式
+///
+/// - `Independent`（默认）：每个分片任务单独打开文件，拥有独立的文件偏移，
+///   定位一次后顺序写入，分片之间互不等待，吞吐更高。
+///   依赖操作系统对同一文件不同区域并发写入的正确处理，
+///   本地磁盘上的常见文件系统都满足这一点。
+/// - `SerializedMutex`：所有分片共享一个 `Arc<Mutex<File>>`，
+///   每次写入前加锁并定位，同一时间只有一个分片在写。
+///   速度较慢，但适用于网络文件系统等不能保证并发定位写入安全的场景。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChunkWriteMode {
+    /// 每个分片独立打开文件（默认）
+    #[default]
+    Independent,
+    /// 所有分片通过互斥锁共享同一个文件句柄
+    SerializedMutex,
+}
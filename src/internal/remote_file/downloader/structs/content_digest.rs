@@ -0,0 +1,108 @@
+//! 下载内容的摘要计算与校验（SHA-256 / MD5）。
+//!
+//! 分片下载的完成顺序是乱序的，逐分片哈希无法得到正确的整体摘要，
+//! 因此在全部分片完成后按偏移顺序重新扫描一遍（本地文件或排序后的内存分片）。
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+
+use super::byte_segments::ByteSegments;
+use super::checksum_algo::ChecksumAlgo;
+use super::download_error::DownloadError;
+
+/// 顺序读取本地文件时的缓冲区大小
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// 把摘要编码为小写十六进制
+pub(crate) fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 可分多次喂入数据的摘要计算器
+pub(crate) enum ContentHasher {
+    Md5(Md5),
+    Sha256(Sha256),
+}
+
+impl ContentHasher {
+    pub(crate) fn new(algo: ChecksumAlgo) -> Self {
+        match algo {
+            ChecksumAlgo::Md5 => Self::Md5(Md5::new()),
+            ChecksumAlgo::Sha256 => Self::Sha256(Sha256::new()),
+        }
+    }
+
+    pub(crate) fn algo(&self) -> ChecksumAlgo {
+        match self {
+            Self::Md5(_) => ChecksumAlgo::Md5,
+            Self::Sha256(_) => ChecksumAlgo::Sha256,
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(hasher) => hasher.update(data),
+            Self::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    /// 结束计算，返回小写十六进制摘要
+    pub(crate) fn finalize_hex(self) -> String {
+        match self {
+            Self::Md5(hasher) => to_hex(&hasher.finalize()),
+            Self::Sha256(hasher) => to_hex(&hasher.finalize()),
+        }
+    }
+}
+
+/// 按顺序读取整个本地文件计算摘要
+pub(crate) async fn digest_file(
+    path: &str,
+    algo: ChecksumAlgo,
+) -> Result<String, DownloadError> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(DownloadError::ReadLocalFile)?;
+    let mut hasher = ContentHasher::new(algo);
+    let mut buf = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .await
+            .map_err(DownloadError::ReadLocalFile)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize_hex())
+}
+
+/// 按偏移顺序计算内存分片的摘要（分片需已排序）
+pub(crate) fn digest_segments(
+    segments: &ByteSegments,
+    algo: ChecksumAlgo,
+) -> String {
+    let mut hasher = ContentHasher::new(algo);
+    for segment in segments.segments() {
+        hasher.update(&segment.data);
+    }
+    hasher.finalize_hex()
+}
+
+/// 与期望值比较（不区分大小写），不一致时返回 [`DownloadError::ChecksumMismatch`]
+pub(crate) fn verify_checksum(
+    expected: Option<&str>,
+    actual: &str,
+) -> Result<(), DownloadError> {
+    match expected {
+        Some(expected) if !expected.eq_ignore_ascii_case(actual) => {
+            Err(DownloadError::ChecksumMismatch {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,93 @@
+//! 下载过程中的磁盘剩余空间检查。
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::internal::local_file::functions::available_space::available_space;
+
+use super::download_error::DownloadError;
+
+/// 每写入多少字节重新查询一次剩余空间：4MB
+const CHECK_INTERVAL_BYTES: u64 = 4 * 1024 * 1024;
+
+/// 磁盘空间守卫：在写入前按间隔查询目标文件系统的剩余空间。
+///
+/// 所需空间 = `min_free_bytes` + 尚未下载的字节数（文件大小已知时），
+/// 剩余空间不足时返回 [`DownloadError::InsufficientDiskSpace`]。
+/// 可在分片任务间共享，任一分片触发后其余分片通过 [`Self::tripped`] 得知。
+#[derive(Debug)]
+pub(crate) struct DiskSpaceGuard {
+    dir: PathBuf,
+    min_free_bytes: u64,
+    total: Option<u64>,
+    next_check_at: AtomicU64,
+    tripped: OnceLock<(u64, u64)>,
+}
+
+impl DiskSpaceGuard {
+    pub(crate) fn new(
+        save_path: &str,
+        min_free_bytes: u64,
+        total: Option<u64>,
+    ) -> Self {
+        let dir = match Path::new(save_path).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => {
+                parent.to_path_buf()
+            }
+            _ => PathBuf::from("."),
+        };
+
+        Self {
+            dir,
+            min_free_bytes,
+            total,
+            next_check_at: AtomicU64::new(0),
+            tripped: OnceLock::new(),
+        }
+    }
+
+    /// 已下载 `bytes_done` 字节时检查剩余空间；未到检查间隔时直接返回
+    pub(crate) fn check(
+        &self,
+        bytes_done: u64,
+    ) -> Result<(), DownloadError> {
+        if let Some(&(available, required)) = self.tripped.get() {
+            return Err(DownloadError::InsufficientDiskSpace {
+                available,
+                required,
+            });
+        }
+
+        if bytes_done < self.next_check_at.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        self.next_check_at
+            .store(bytes_done + CHECK_INTERVAL_BYTES, Ordering::Relaxed);
+
+        let available = available_space(&self.dir)
+            .map_err(DownloadError::DiskSpaceQuery)?;
+        let remaining = self
+            .total
+            .map(|total| total.saturating_sub(bytes_done))
+            .unwrap_or(0);
+        let required = self.min_free_bytes.saturating_add(remaining);
+
+        if available < required {
+            let _ = self.tripped.set((available, required));
+            return Err(DownloadError::InsufficientDiskSpace {
+                available,
+                required,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 是否已经因空间不足而触发
+    pub(crate) fn tripped(&self) -> Option<DownloadError> {
+        self.tripped.get().map(|&(available, required)| {
+            DownloadError::InsufficientDiskSpace { available, required }
+        })
+    }
+}
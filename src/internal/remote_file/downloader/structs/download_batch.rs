@@ -0,0 +1,69 @@
+//! 批量下载：多个下载器共享同一个全局并发额度。
+
+use std::sync::Arc;
+
+use futures_util::future::join_all;
+use tokio::sync::Semaphore;
+
+use super::download_error::DownloadError;
+use super::download_result::DownloadResult;
+use super::remote_downloader::RemoteDownloader;
+use super::remote_downloader_controller::RemoteDownloaderController;
+
+/// 一组共享全局并发额度的下载任务。
+///
+/// 每个下载器开始前按自己可能同时发出的请求数（单线程为 1，分片为
+/// `max_chunks`）从额度中预留许可，下载结束后归还；单个文件的 `max_chunks`
+/// 超过全局上限时按上限预留，分片并发也随之降到上限，因此不会卡死。
+/// 同一时刻进行中的请求总数不超过 `global_max_concurrency`，排在后面的
+/// 文件等待许可。
+///
+/// 每个文件的下载器照常配置（保存路径、分片、限速等），send 之前可以通过
+/// [`controllers`](Self::controllers) 拿到控制器，单独暂停或取消某个文件。
+#[derive(Debug)]
+pub struct DownloadBatch {
+    downloaders: Vec<RemoteDownloader>,
+    max_concurrency: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl DownloadBatch {
+    /// 创建批量下载，`global_max_concurrency` 为 0 时按 1 处理。
+    pub fn new(
+        downloaders: Vec<RemoteDownloader>,
+        global_max_concurrency: usize,
+    ) -> Self {
+        let max_concurrency = global_max_concurrency.max(1);
+        Self {
+            downloaders,
+            max_concurrency,
+            semaphore: Arc::new(Semaphore::new(max_concurrency)),
+        }
+    }
+
+    /// 各文件的控制器，与传入的下载器一一对应、顺序相同
+    pub fn controllers(&self) -> Vec<Arc<RemoteDownloaderController>> {
+        self.downloaders.iter().map(|d| d.get_controller()).collect()
+    }
+
+    /// 下载全部文件
+    ///
+    /// 返回值与传入的下载器一一对应、顺序相同；单个文件失败或被取消
+    /// 不影响其余文件。
+    pub async fn send(
+        &self,
+    ) -> Vec<Result<DownloadResult, DownloadError>> {
+        join_all(self.downloaders.iter().map(|downloader| async move {
+            let permits = downloader
+                .get_controller()
+                .config()
+                .max_chunks
+                .clamp(1, self.max_concurrency);
+            // 信号量从不关闭，获取失败时按不限流处理
+            let _permit =
+                self.semaphore.acquire_many(permits as u32).await.ok();
+            downloader.send_with_request_cap(permits).await
+        }))
+        .await
+    }
+}
@@ -22,15 +22,27 @@ pub enum DownloadError {
     #[error("仅支持文件下载，当前为目录")]
     IsDir,
 
+    #[error("保存路径已存在文件: {0}")]
+    FileExists(String),
+
     #[error("未设置保存路径且未开启 output_bytes")]
     NoDestination,
 
     #[error("下载被取消")]
     Cancelled,
 
+    #[error("下载被 abort_if 谓词中止")]
+    AbortedByPredicate,
+
+    #[error("下载被钩子中止：{0}")]
+    HookAborted(String),
+
     #[error("下载被暂停")]
     Paused,
 
+    #[error("分片大小不能为 0")]
+    ZeroChunkSize,
+
     #[error("分片下载需要已知文件大小")]
     UnknownFileSizeForChunked,
 
@@ -65,7 +77,53 @@ pub enum DownloadError {
     #[error("多个分片下载失败: {0:?}")]
     MultipleChunksFailed(Vec<String>),
 
+    #[error("首字节超时: 请求发出后 {0:?} 内未收到任何数据")]
+    FirstByteTimeout(std::time::Duration),
+
+    #[error("认证已失效: 服务器返回 {status}")]
+    AuthExpired { status: u16 },
+
+    #[error("刷新认证失败: {0}")]
+    AuthRefreshFailed(String),
+
     #[error("服务器不支持 Range 请求")]
     RangeNotSupported,
+
+    /// `download_range` 的区间为空、超出文件大小，或服务器返回 416
+    #[error("无效的下载区间 [{start}, {end})，文件大小 {size:?}")]
+    InvalidRange { start: u64, end: u64, size: Option<u64> },
+
+    #[error("磁盘剩余空间不足: 可用 {available} 字节，至少需要 {required} 字节")]
+    InsufficientDiskSpace { available: u64, required: u64 },
+
+    #[error("SHA-256 校验失败: 期望 {expected}，实际 {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    /// 收到的字节数与预期不符；分片下载时为单个 Range 的长度，
+    /// 此时按可重试错误处理
+    #[error("下载大小不符: 期望 {expected} 字节，实际 {actual} 字节")]
+    SizeMismatch { expected: u64, actual: u64 },
+
+    /// 分片请求的响应不是与所请求区间一致的 206；属于可重试错误
+    #[error(
+        "分片响应与请求区间不符: 状态码 {status}，Content-Range {content_range:?}"
+    )]
+    UnexpectedRangeResponse {
+        status: u16,
+        content_range: Option<String>,
+    },
+
+    #[error("下载期间远程文件已变化: ETag 由 {expected} 变为 {actual}")]
+    ETagChanged { expected: String, actual: String },
+
+    /// 带 `If-Match` 的请求返回 412：远程文件已不是该 ETag 对应的版本
+    #[error("远程文件已变化: If-Match {etag} 未通过（412）")]
+    PreconditionFailed { etag: String },
+
+    #[error("读取本地文件失败: {0}")]
+    ReadLocalFile(std::io::Error),
+
+    #[error("查询磁盘剩余空间失败: {0}")]
+    DiskSpaceQuery(std::io::Error),
 }
 
@@ -0,0 +1,56 @@
+//! 下载完成后的传输统计。
+
+use std::time::Duration;
+
+/// 一次成功下载的统计快照，由 [`RemoteDownloader::send_with_metrics`] 返回。
+///
+/// [`RemoteDownloader::send_with_metrics`]: super::remote_downloader::RemoteDownloader::send_with_metrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadMetrics {
+    /// 本次实际通过网络传输的字节数（不含续传前已存在的部分）
+    pub total_bytes: u64,
+    /// 从发起请求到下载完成的耗时（包含暂停时间）
+    pub duration: Duration,
+    /// 平均速度（字节/秒），耗时为 0 时为 0
+    pub avg_bytes_per_sec: f64,
+    /// 分片失败后的重试总次数，单线程下载恒为 0
+    pub retries: usize,
+    /// 分片数，单线程下载为 1
+    pub chunks: usize,
+    /// 续传时本地已存在、无需重新下载的字节数
+    pub bytes_from_resume: u64,
+    /// 按字节顺序计算的 SHA-256（小写十六进制），
+    /// 仅在开启 `compute_sha256`、`verify_sha256` 或按 SHA-256
+    /// `verify_checksum` 时存在
+    pub sha256: Option<String>,
+}
+
+impl DownloadMetrics {
+    pub(crate) fn new(
+        total_bytes: u64,
+        duration: Duration,
+        retries: usize,
+        chunks: usize,
+        bytes_from_resume: u64,
+    ) -> Self {
+        let secs = duration.as_secs_f64();
+        let avg_bytes_per_sec =
+            if secs > 0.0 { total_bytes as f64 / secs } else { 0.0 };
+
+        Self {
+            total_bytes,
+            duration,
+            avg_bytes_per_sec,
+            retries,
+            chunks,
+            bytes_from_resume,
+            sha256: None,
+        }
+    }
+
+    /// 附上下载内容的 SHA-256
+    pub(crate) fn with_sha256(mut self, sha256: Option<String>) -> Self {
+        self.sha256 = sha256;
+        self
+    }
+}
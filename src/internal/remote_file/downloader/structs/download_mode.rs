@@ -2,5 +2,27 @@
 pub enum DownloadMode {
     SaveFile(String),
     OutputBytes,
+    /// 保存到本地文件，同时在内存中保留一份完整内容
+    SaveFileAndBytes(String),
+    /// 不保存也不累积，`send()` 返回边拉取边下载的字节流
+    Stream,
 }
 
+impl DownloadMode {
+    /// 保存路径，只输出到内存时为 `None`
+    pub(crate) fn save_path(&self) -> Option<&String> {
+        match self {
+            DownloadMode::SaveFile(path)
+            | DownloadMode::SaveFileAndBytes(path) => Some(path),
+            DownloadMode::OutputBytes | DownloadMode::Stream => None,
+        }
+    }
+
+    /// 是否需要在内存中保留下载内容
+    pub(crate) fn keeps_bytes(&self) -> bool {
+        matches!(
+            self,
+            DownloadMode::OutputBytes | DownloadMode::SaveFileAndBytes(_)
+        )
+    }
+}
@@ -0,0 +1,30 @@
+//! 下载进度快照。
+
+/// 下载进度：已下载字节数与总大小。
+///
+/// 服务器使用分块传输编码（没有 `Content-Length`）且 PROPFIND 未返回
+/// `getcontentlength` 时，`total` 为 `None`，此时没有百分比。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// 已下载字节数
+    pub bytes_done: u64,
+    /// 文件总大小，未知时为 `None`
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// 总大小是否已知
+    pub fn has_known_size(&self) -> bool {
+        self.total.is_some()
+    }
+
+    /// 下载百分比（0.0 ~ 100.0），总大小未知时返回 `None`；0 字节文件视为 100%
+    pub fn percent(&self) -> Option<f64> {
+        match self.total? {
+            0 => Some(100.0),
+            total => Some(
+                self.bytes_done.min(total) as f64 * 100.0 / total as f64,
+            ),
+        }
+    }
+}
@@ -1,4 +1,10 @@
+use std::io;
+use std::path::Path;
+
+use tokio::io::AsyncWriteExt;
+
 use super::byte_segments::ByteSegments;
+use super::download_stream::DownloadStream;
 
 /// 单次下载的结果。
 #[derive(Debug)]
@@ -9,5 +15,62 @@ pub enum DownloadResult {
     Bytes(Vec<u8>),
     /// 多线程下载得到的分段字节，按 offset 可寻址
     ByteSegments(ByteSegments),
+    /// 同时设置 `save_to` 与 `output_bytes` 时：已保存的文件路径与完整内容
+    SavedAndBytes { path: String, bytes: Vec<u8> },
+    /// `output_stream` 模式：尚未读取的字节流，见 [`DownloadStream`]
+    Stream(DownloadStream),
 }
 
+impl DownloadResult {
+    /// 把下载结果写入 `path`，返回写入的字节数
+    ///
+    /// - `Bytes`：直接写入
+    /// - `ByteSegments`：按 offset 顺序逐段写入，不会先合并成一整块内存
+    /// - `SavedToLocal`、`SavedAndBytes`：复制已保存的文件；`path` 就是该文件
+    ///   时不做任何操作
+    /// - `Stream`：不支持（读取需要独占流），返回 `ErrorKind::Unsupported`
+    pub async fn write_to(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> io::Result<u64> {
+        let path = path.as_ref();
+        match self {
+            DownloadResult::SavedToLocal(saved)
+            | DownloadResult::SavedAndBytes { path: saved, .. } => {
+                if is_same_file(Path::new(saved), path).await {
+                    return Ok(tokio::fs::metadata(saved).await?.len());
+                }
+                tokio::fs::copy(saved, path).await
+            }
+            DownloadResult::Bytes(bytes) => {
+                tokio::fs::write(path, bytes).await?;
+                Ok(bytes.len() as u64)
+            }
+            DownloadResult::ByteSegments(segments) => {
+                let mut file = tokio::fs::File::create(path).await?;
+                let mut written = 0u64;
+                for segment in segments.segments() {
+                    file.write_all(&segment.data).await?;
+                    written += segment.data.len() as u64;
+                }
+                file.flush().await?;
+                Ok(written)
+            }
+            DownloadResult::Stream(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "流式下载结果需要由调用方读取",
+            )),
+        }
+    }
+}
+
+/// 两个路径是否指向同一个已存在的文件（复制到自身会截断文件）
+async fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (
+        tokio::fs::canonicalize(a).await,
+        tokio::fs::canonicalize(b).await,
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
@@ -0,0 +1,75 @@
+//! 下载速度估计：按固定间隔采样已下载字节数，用指数加权移动平均平滑。
+
+use std::time::{Duration, Instant};
+
+/// 速度采样间隔
+pub(crate) const SPEED_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 平滑时间常数：越大越平稳、对变化的反应越慢
+const SMOOTHING_SECS: f64 = 3.0;
+
+/// 下载速度快照，约每秒更新一次
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DownloadSpeed {
+    /// 平滑后的速度（字节/秒），暂停、未开始或已结束时为 0
+    pub bytes_per_sec: f64,
+    /// 按当前速度估算的剩余时间，总大小未知或速度为 0 时为 `None`
+    pub eta: Option<Duration>,
+}
+
+/// 速度估计器：每次采样用两次采样之间的平均速度更新 EWMA
+///
+/// 权重按实际间隔计算（`1 - e^(-Δt/τ)`），采样不准时也不会放大抖动。
+/// 暂停时速度直接归零并把基准移到暂停结束的位置，恢复后从 0 开始爬升，
+/// 不会把暂停期间的时间或积压的字节算进速度。
+#[derive(Debug, Clone)]
+pub(crate) struct SpeedMeter {
+    bytes_per_sec: f64,
+    last_at: Instant,
+    last_bytes: u64,
+}
+
+impl SpeedMeter {
+    pub(crate) fn new(now: Instant, bytes_done: u64) -> Self {
+        Self { bytes_per_sec: 0.0, last_at: now, last_bytes: bytes_done }
+    }
+
+    /// 记录一次采样并返回新的速度，`idle` 表示暂停或尚未开始接收数据
+    pub(crate) fn sample(
+        &mut self,
+        now: Instant,
+        bytes_done: u64,
+        idle: bool,
+    ) -> f64 {
+        let elapsed = now.saturating_duration_since(self.last_at);
+        let delta = bytes_done.saturating_sub(self.last_bytes);
+        self.last_at = now;
+        self.last_bytes = bytes_done;
+
+        if idle {
+            self.bytes_per_sec = 0.0;
+        } else if !elapsed.is_zero() {
+            let secs = elapsed.as_secs_f64();
+            let weight = 1.0 - (-secs / SMOOTHING_SECS).exp();
+            self.bytes_per_sec +=
+                weight * (delta as f64 / secs - self.bytes_per_sec);
+        }
+        self.bytes_per_sec
+    }
+
+    /// 当前速度与按 `total` 估算的剩余时间
+    pub(crate) fn snapshot(
+        &self,
+        bytes_done: u64,
+        total: Option<u64>,
+    ) -> DownloadSpeed {
+        let eta =
+            total.filter(|_| self.bytes_per_sec > 0.0).map(|total| {
+                let remaining = total.saturating_sub(bytes_done);
+                Duration::from_secs_f64(
+                    remaining as f64 / self.bytes_per_sec,
+                )
+            });
+        DownloadSpeed { bytes_per_sec: self.bytes_per_sec, eta }
+    }
+}
@@ -1,6 +1,9 @@
 /// 下载状态（由下载器内部维护，外部只读监听）
 #[derive(Debug, Clone)]
 pub enum DownloadStatus {
+    /// 已开始但还没有收到数据：处理已有文件、探测大小、等待服务器响应等
+    Preparing,
+    /// 正在接收数据
     Running,
     Paused,
     Canceled,
@@ -0,0 +1,47 @@
+//! 流式下载结果：按消费者拉取的节奏从连接读取数据，不在内存中累积。
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_util::Stream;
+
+use super::download_error::DownloadError;
+
+/// `output_stream` 模式下 `send()` 返回的字节流
+///
+/// 按顺序产出文件内容；出错、取消或校验失败时产出一个 `Err` 后结束。
+/// 只有被拉取时才会从连接读取数据，进度、速度与 `abort_if` 随之更新。
+/// 在读完之前丢弃时视为取消，状态变为 `Canceled`。
+pub struct DownloadStream {
+    inner:
+        Pin<Box<dyn Stream<Item = Result<Bytes, DownloadError>> + Send>>,
+}
+
+impl DownloadStream {
+    pub(crate) fn new(
+        stream: impl Stream<Item = Result<Bytes, DownloadError>>
+        + Send
+        + 'static,
+    ) -> Self {
+        Self { inner: Box::pin(stream) }
+    }
+}
+
+impl Stream for DownloadStream {
+    type Item = Result<Bytes, DownloadError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl fmt::Debug for DownloadStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DownloadStream(<stream>)")
+    }
+}
@@ -0,0 +1,38 @@
+//! 磁盘写入并发限制：可在多个下载器之间共享。
+
+use std::sync::Arc;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// 磁盘写入并发限制器。
+///
+/// 与网络并发（`max_chunks`）相互独立：多个下载器共享同一个限制器时，
+/// 网络传输可以同时进行很多个，但同一时刻真正写盘的调用数不超过
+/// `max_concurrent_file_writes`，适合机械硬盘等慢速存储。
+///
+/// 限制粒度为单次写入调用，不限制同时打开的文件句柄数。
+#[derive(Debug, Clone)]
+pub struct FileWriteLimiter {
+    semaphore: Arc<Semaphore>,
+}
+
+impl FileWriteLimiter {
+    /// 创建限制器，`max_concurrent_file_writes` 为 0 时按 1 处理。
+    pub fn new(max_concurrent_file_writes: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(
+                max_concurrent_file_writes.max(1),
+            )),
+        }
+    }
+
+    /// 当前可用的写入许可数。
+    pub fn available_permits(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    /// 获取一次写入许可；信号量从不关闭，失败时返回 `None` 并按不限流处理。
+    pub(crate) async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.semaphore.acquire().await.ok()
+    }
+}
@@ -0,0 +1,102 @@
+//! 保存路径已存在文件时的处理策略。
+
+use std::path::Path;
+
+use super::download_error::DownloadError;
+use super::download_mode::DownloadMode;
+
+/// 重命名时尝试的最大序号
+const MAX_RENAME_ATTEMPTS: u32 = 10_000;
+
+/// 保存路径已存在文件时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// 覆盖已有文件（默认，与旧版本行为一致）
+    #[default]
+    Overwrite,
+    /// 返回 [`DownloadError::FileExists`]，不修改已有文件
+    Fail,
+    /// 把已有文件视为上次未完成的下载，从其末尾继续（单线程 Range 续传）
+    ///
+    /// 服务器不支持 Range 时从头下载；取消时保留已下载的部分，便于下次继续
+    Resume,
+    /// 保存为 `name (1).ext`、`name (2).ext` …中第一个不存在的路径
+    Rename,
+}
+
+/// 按策略解析出的实际保存目标
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SaveTarget {
+    /// 实际保存路径，输出到内存时为 `None`
+    pub(crate) save_path: Option<String>,
+    /// 续传起点（已有文件的长度），不续传时为 0
+    pub(crate) resume_from: u64,
+}
+
+/// 根据下载模式与策略确定保存路径和续传起点
+pub(crate) async fn resolve_save_target(
+    download_mode: &DownloadMode,
+    policy: OverwritePolicy,
+) -> Result<SaveTarget, DownloadError> {
+    let Some(path) = download_mode.save_path() else {
+        return Ok(SaveTarget::default());
+    };
+
+    let existing_len = match tokio::fs::metadata(path).await {
+        Ok(metadata) => Some(metadata.len()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(DownloadError::ReadLocalFile(e)),
+    };
+
+    let Some(existing_len) = existing_len else {
+        return Ok(SaveTarget {
+            save_path: Some(path.clone()),
+            resume_from: 0,
+        });
+    };
+
+    match policy {
+        OverwritePolicy::Overwrite => Ok(SaveTarget {
+            save_path: Some(path.clone()),
+            resume_from: 0,
+        }),
+        OverwritePolicy::Fail => {
+            Err(DownloadError::FileExists(path.clone()))
+        }
+        OverwritePolicy::Resume => Ok(SaveTarget {
+            save_path: Some(path.clone()),
+            resume_from: existing_len,
+        }),
+        OverwritePolicy::Rename => Ok(SaveTarget {
+            save_path: Some(next_free_path(path).await?),
+            resume_from: 0,
+        }),
+    }
+}
+
+/// 找到 `name (n).ext` 形式中第一个不存在的路径
+async fn next_free_path(path: &str) -> Result<String, DownloadError> {
+    let original = Path::new(path);
+    let parent = original.parent().unwrap_or(Path::new(""));
+    let stem = original
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = original
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    for n in 1..=MAX_RENAME_ATTEMPTS {
+        let candidate =
+            parent.join(format!("{} ({}){}", stem, n, extension));
+        if !tokio::fs::try_exists(&candidate)
+            .await
+            .map_err(DownloadError::ReadLocalFile)?
+        {
+            return Ok(candidate.to_string_lossy().to_string());
+        }
+    }
+
+    Err(DownloadError::FileExists(path.to_string()))
+}
@@ -0,0 +1,73 @@
+//! 每次进度更新时交给 `abort_if` 谓词的快照。
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::download_progress::DownloadProgress;
+
+/// 进度快照：在 [`DownloadProgress`] 的基础上附带耗时与平均速度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProgressReport {
+    /// 已下载字节数与总大小
+    pub progress: DownloadProgress,
+    /// 从开始下载到本次更新的耗时（包含暂停时间）
+    pub elapsed: Duration,
+    /// 平均速度（字节/秒），耗时为 0 时为 0
+    pub avg_bytes_per_sec: f64,
+}
+
+impl ProgressReport {
+    pub(crate) fn new(
+        progress: DownloadProgress,
+        elapsed: Duration,
+    ) -> Self {
+        let secs = elapsed.as_secs_f64();
+        let avg_bytes_per_sec = if secs > 0.0 {
+            progress.bytes_done as f64 / secs
+        } else {
+            0.0
+        };
+        Self { progress, elapsed, avg_bytes_per_sec }
+    }
+
+    /// 按平均速度估算的剩余时间，总大小未知或速度为 0 时返回 `None`
+    pub fn eta(&self) -> Option<Duration> {
+        let total = self.progress.total?;
+        if self.avg_bytes_per_sec <= 0.0 {
+            return None;
+        }
+        let remaining = total.saturating_sub(self.progress.bytes_done);
+        Some(Duration::from_secs_f64(
+            remaining as f64 / self.avg_bytes_per_sec,
+        ))
+    }
+}
+
+/// `abort_if` 使用的谓词，返回 `true` 时中止下载
+///
+/// 谓词在下载任务中同步调用（分片下载时可能被多个分片同时调用），
+/// 需要跨次记录状态（如"低速持续 30 秒"）时可在闭包内自行使用原子量或锁。
+#[derive(Clone)]
+pub struct AbortPredicate(
+    pub(crate) Arc<dyn Fn(&ProgressReport) -> bool + Send + Sync>,
+);
+
+impl AbortPredicate {
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&ProgressReport) -> bool + Send + Sync + 'static,
+    {
+        Self(Arc::new(predicate))
+    }
+
+    pub(crate) fn should_abort(&self, report: &ProgressReport) -> bool {
+        (self.0)(report)
+    }
+}
+
+impl fmt::Debug for AbortPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AbortPredicate(<fn>)")
+    }
+}
@@ -1,21 +1,162 @@
 use crate::internal::states::queue_reactive::QueueReactiveProperty;
+use crate::states::broadcast_reactive::BroadcastReactiveProperty;
 use crate::states::unlock_reactive::UnlockReactiveProperty;
-use std::sync::Arc;
-use tokio::sync::Notify;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use tokio::sync::{Notify, watch};
 
 use super::control_command::ControlCommand;
+use super::download_progress::DownloadProgress;
+use super::download_speed::DownloadSpeed;
 use super::download_status::DownloadStatus;
+use super::progress_report::{AbortPredicate, ProgressReport};
+use crate::internal::remote_file::downloader::traits::SharedDownloadHook;
 
 /// 下载器响应式状态
 #[derive(Debug)]
 pub struct RemoteDownloaderControllerReactiveState {
     /// 命令队列（生产者端）：外部通过 send 发送控制命令
     pub(crate) command_queue: QueueReactiveProperty<ControlCommand>,
-    /// 下载状态（只读）：内部根据命令更新，外部通过 watch 监听
-    pub download_status: UnlockReactiveProperty<DownloadStatus>,
+    /// 下载状态（只读）：内部根据命令更新
+    ///
+    /// 状态切换频率低但不能丢（快速的 `Running → Paused → Running`
+    /// 用 watch 可能只看到最后一个），因此使用保留每次变化的
+    /// [`BroadcastReactiveProperty`]；字节数与进度仍使用只保留最新值的 watch。
+    pub download_status: BroadcastReactiveProperty<DownloadStatus>,
     /// 已下载字节数（只读）：内部更新，外部通过 watch 监听
     pub downloaded_bytes: UnlockReactiveProperty<u64>,
+    /// 下载进度（只读）：已下载字节数 + 总大小（未知时为 None）
+    pub progress: UnlockReactiveProperty<DownloadProgress>,
+    /// 下载速度（只读）：下载期间约每秒更新一次，结束后归零
+    pub speed: UnlockReactiveProperty<DownloadSpeed>,
     /// 恢复通知器：用于精确唤醒暂停的任务
     pub(crate) resume_notifier: Arc<Notify>,
+    /// abort_if 谓词是否已触发（触发后通过取消流程结束下载）
+    pub(crate) aborted_by_predicate: Arc<AtomicBool>,
+    /// 钩子在 on_progress 中中止下载时给出的原因
+    pub(crate) hook_abort: Arc<OnceLock<String>>,
+    /// 下载是否已结束（完成、取消或出错），通知订阅任务退出
+    pub(crate) terminated: watch::Sender<bool>,
 }
 
+impl RemoteDownloaderControllerReactiveState {
+    /// 创建进度上报器，`total` 为本次下载的总大小（未知时为 None）
+    pub(crate) fn progress_reporter(
+        &self,
+        total: Option<u64>,
+    ) -> ProgressReporter {
+        ProgressReporter {
+            downloaded_bytes: self.downloaded_bytes.clone(),
+            progress: self.progress.clone(),
+            download_status: self.download_status.clone(),
+            first_byte: Arc::new(AtomicBool::new(false)),
+            total,
+            abort: None,
+        }
+    }
+
+    /// 创建带 abort_if 谓词与钩子 on_progress 的进度上报器，
+    /// `started_at` 用于计算耗时与速度
+    pub(crate) fn progress_reporter_with_abort(
+        &self,
+        total: Option<u64>,
+        predicate: Option<AbortPredicate>,
+        hook: Option<SharedDownloadHook>,
+        started_at: Instant,
+    ) -> ProgressReporter {
+        let mut reporter = self.progress_reporter(total);
+        if predicate.is_some() || hook.is_some() {
+            reporter.abort = Some(AbortWatch {
+                predicate,
+                hook,
+                started_at,
+                tripped: Arc::clone(&self.aborted_by_predicate),
+                hook_abort: Arc::clone(&self.hook_abort),
+                command_queue: self.command_queue.clone(),
+                resume_notifier: Arc::clone(&self.resume_notifier),
+            });
+        }
+        reporter
+    }
+}
+
+/// 进度上报时检查 abort_if 谓词与钩子的 on_progress，触发后发送取消命令
+#[derive(Debug, Clone)]
+struct AbortWatch {
+    predicate: Option<AbortPredicate>,
+    hook: Option<SharedDownloadHook>,
+    started_at: Instant,
+    tripped: Arc<AtomicBool>,
+    hook_abort: Arc<OnceLock<String>>,
+    command_queue: QueueReactiveProperty<ControlCommand>,
+    resume_notifier: Arc<Notify>,
+}
+
+impl AbortWatch {
+    fn check(&self, progress: DownloadProgress) {
+        if self.tripped.load(Ordering::SeqCst)
+            || self.hook_abort.get().is_some()
+        {
+            return;
+        }
+        let report =
+            ProgressReport::new(progress, self.started_at.elapsed());
+        if let Some(predicate) = &self.predicate
+            && predicate.should_abort(&report)
+            && !self.tripped.swap(true, Ordering::SeqCst)
+        {
+            self.cancel();
+            return;
+        }
+        if let Some(hook) = &self.hook
+            && let Err(abort) = hook.0.on_progress(&report)
+            && self.hook_abort.set(abort.reason).is_ok()
+        {
+            self.cancel();
+        }
+    }
+
+    /// 复用取消流程：分片任务有序停止、未完成的文件被删除
+    fn cancel(&self) {
+        let _ = self.command_queue.send(ControlCommand::Cancel);
+        self.resume_notifier.notify_waiters();
+    }
+}
+
+/// 进度上报器：同时更新 `downloaded_bytes` 与 `progress`，可在分片任务间 clone
+#[derive(Debug, Clone)]
+pub(crate) struct ProgressReporter {
+    downloaded_bytes: UnlockReactiveProperty<u64>,
+    progress: UnlockReactiveProperty<DownloadProgress>,
+    download_status: BroadcastReactiveProperty<DownloadStatus>,
+    /// 是否已收到第一个字节（在所有 clone 间共享）
+    first_byte: Arc<AtomicBool>,
+    total: Option<u64>,
+    abort: Option<AbortWatch>,
+}
+
+impl ProgressReporter {
+    pub(crate) fn report(&self, bytes_done: u64) {
+        let progress = DownloadProgress { bytes_done, total: self.total };
+        let _ = self.downloaded_bytes.update(bytes_done);
+        let _ = self.progress.update(progress);
+        if let Some(abort) = &self.abort {
+            abort.check(progress);
+        }
+    }
+
+    /// 收到第一个字节时把状态从 `Preparing` 切换为 `Running`，只生效一次
+    ///
+    /// 已经处于暂停或取消状态时保持不变
+    pub(crate) fn mark_first_byte(&self) {
+        if !self.first_byte.swap(true, Ordering::SeqCst)
+            && matches!(
+                self.download_status.get_current(),
+                Some(DownloadStatus::Preparing)
+            )
+        {
+            let _ = self.download_status.update(DownloadStatus::Running);
+        }
+    }
+}
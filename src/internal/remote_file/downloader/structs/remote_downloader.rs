@@ -1,13 +1,27 @@
 use crate::internal::states::queue_reactive::QueueReactiveConsumer;
 use crate::{auth::WebdavAuth, remote_file::RemoteFileData};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
+use crate::internal::auth::structs::digest_auth::DigestAuth;
+
+use super::auth_refresh::AuthRefresher;
+use super::checksum_algo::ChecksumAlgo;
+use super::chunk_write_mode::ChunkWriteMode;
 use super::control_command::ControlCommand;
 use super::download_error::DownloadError;
+use super::download_metrics::DownloadMetrics;
 use super::download_mode::DownloadMode;
 use super::download_result::DownloadResult;
+use super::file_write_limiter::FileWriteLimiter;
+use super::overwrite_policy::OverwritePolicy;
+use super::progress_report::{AbortPredicate, ProgressReport};
 use super::remote_downloader_controller::RemoteDownloaderController;
+use crate::internal::remote_file::downloader::traits::{
+    DownloadHook, SharedDownloadHook,
+};
 
 /// 远程文件下载器，不实现Clone，是因为下载器一旦开始下载，就不应该被克隆，否则会有多份下载器同时下载同一个文件，导致文件内容错误。
 #[derive(Debug)]
@@ -16,43 +30,109 @@ pub struct RemoteDownloader {
     /// 因为命令通过 mpsc 队列发送（无锁），状态通过 watch channel 读取（无锁）
     controller: Arc<RemoteDownloaderController>,
     /// 命令消费者：下载时内部消费命令队列（Mutex 包装是因为 recv 需要 &mut）
-    command_consumer: Mutex<QueueReactiveConsumer<ControlCommand>>,
+    ///
+    /// 流式模式下返回的流要持有它直到读完，因此放在 Arc 中
+    command_consumer: Arc<Mutex<QueueReactiveConsumer<ControlCommand>>>,
+    /// 是否调用过 `output_bytes`（默认模式同样输出到内存，无法从模式区分）
+    output_bytes_requested: bool,
 }
 
 impl RemoteDownloader {
     pub fn new(
         remote_file_data: Arc<RemoteFileData>,
         webdav_auth: WebdavAuth,
+    ) -> Self {
+        let url = remote_file_data.absolute_path.clone();
+        Self::with_source(
+            remote_file_data,
+            webdav_auth.client,
+            webdav_auth.digest,
+            url,
+        )
+    }
+
+    /// 指定下载客户端与 URL 创建下载器（直链下载等场景）
+    ///
+    /// `digest` 为 `Some` 时请求按 Digest 认证发送
+    pub(crate) fn with_source(
+        remote_file_data: Arc<RemoteFileData>,
+        client: reqwest::Client,
+        digest: Option<Arc<DigestAuth>>,
+        url: String,
     ) -> Self {
         let default_download_mode = DownloadMode::OutputBytes;
 
         let (controller, command_consumer) =
             RemoteDownloaderController::new(
                 remote_file_data,
-                webdav_auth,
+                client,
+                digest,
+                url,
                 default_download_mode,
             );
 
         Self {
             controller: Arc::new(controller),
-            command_consumer: Mutex::new(command_consumer),
+            command_consumer: Arc::new(Mutex::new(command_consumer)),
+            output_bytes_requested: false,
         }
     }
 
     /// 设置保存路径
     /// 注意：必须在 send() 之前调用，send() 之后配置不可变
+    ///
+    /// 与 [`output_bytes`](Self::output_bytes) 同时调用时（顺序不限）既保存
+    /// 文件又在内存中保留完整内容，返回 [`DownloadResult::SavedAndBytes`]。
     pub fn save_to(mut self, save_path: &str) -> Self {
+        let save_path = save_path.to_string();
+        let mode = if self.output_bytes_requested {
+            DownloadMode::SaveFileAndBytes(save_path)
+        } else {
+            DownloadMode::SaveFile(save_path)
+        };
         Arc::get_mut(&mut self.controller)
             .expect("Cannot configure after controller is shared")
-            .set_download_mode(DownloadMode::SaveFile(save_path.to_string()));
+            .set_download_mode(mode);
         self
     }
 
     /// 设置输出到内存
+    ///
+    /// 已设置 [`save_to`](Self::save_to) 时改为同时保存文件与输出到内存。
     pub fn output_bytes(mut self) -> Self {
+        self.output_bytes_requested = true;
+        let save_path = self.controller.config().download_mode.save_path();
+        let mode = match save_path {
+            Some(path) => DownloadMode::SaveFileAndBytes(path.clone()),
+            None => DownloadMode::OutputBytes,
+        };
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_download_mode(mode);
+        self
+    }
+
+    /// 以流的形式返回内容：`send()` 在收到响应头后返回
+    /// [`DownloadResult::Stream`]，数据在调用方拉取时才从连接读取
+    ///
+    /// 适合边下载边解压、转存等不想缓存整个文件的场景。总是使用单线程下载，
+    /// 不保存文件（与 `save_to`、`output_bytes` 互相覆盖，以最后一次为准）。
+    /// 进度、速度与 `abort_if` 随拉取更新；设置了校验时在最后一块之后校验，
+    /// 不一致时流以 [`DownloadError::ChecksumMismatch`] 结束。
+    ///
+    /// 与其他模式的区别：
+    /// - 暂停只是不再从连接读取，连接保持打开（暂停太久可能被服务器断开，
+    ///   之后流以错误结束）；调用方不拉取时同样不会读取
+    /// - 命令在拉取时处理，调用方不再拉取时取消要等到下一次拉取才生效；
+    ///   在读完之前丢弃流等同于取消
+    /// - 出错时不重试，也不能续传
+    /// - [`send_with_metrics`](Self::send_with_metrics) 返回的统计只覆盖到
+    ///   收到响应头为止
+    pub fn output_stream(mut self) -> Self {
+        self.output_bytes_requested = false;
         Arc::get_mut(&mut self.controller)
             .expect("Cannot configure after controller is shared")
-            .set_download_mode(DownloadMode::OutputBytes);
+            .set_download_mode(DownloadMode::Stream);
         self
     }
 
@@ -64,7 +144,16 @@ impl RemoteDownloader {
         self
     }
 
-    /// 设置分片大小（字节）
+    /// 设置分片大小（字节），默认 [`DEFAULT_CHUNK_SIZE`]
+    ///
+    /// 分片数为 `ceil(文件大小 / chunk_size)`，同时进行的分片数由
+    /// [`RemoteDownloader::max_chunks`] 限制。分片越大请求往返越少，
+    /// 分片越小越容易让所有并发都用上，重试时需要重传的数据也越少。
+    ///
+    /// - 为 0 时 [`send`](Self::send) 返回 [`DownloadError::ZeroChunkSize`]，
+    ///   不会发出任何请求
+    ///
+    /// [`DEFAULT_CHUNK_SIZE`]: super::remote_downloader_config::DEFAULT_CHUNK_SIZE
     pub fn chunk_size(mut self, chunk_size: u64) -> Self {
         Arc::get_mut(&mut self.controller)
             .expect("Cannot configure after controller is shared")
@@ -72,6 +161,19 @@ impl RemoteDownloader {
         self
     }
 
+    /// 服务器不支持 Range 时是否自动改用单线程下载（默认 `false`）
+    ///
+    /// 需要多个分片时，下载前先发送一次 `Range: bytes=0-0` 探测；服务器返回
+    /// `200`（忽略 Range、返回整个文件）或 `Content-Range` 与请求不符时，
+    /// 开启后改用单线程下载，否则返回 [`DownloadError::RangeNotSupported`]。
+    /// 探测收到的字节不计入进度与下载统计。
+    pub fn fallback_on_no_range(mut self, fallback: bool) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_fallback_on_no_range(fallback);
+        self
+    }
+
     /// 设置分片失败最大重试次数
     pub fn max_retries(mut self, max_retries: usize) -> Self {
         Arc::get_mut(&mut self.controller)
@@ -80,6 +182,214 @@ impl RemoteDownloader {
         self
     }
 
+    /// 设置分片重试的总时长上限
+    ///
+    /// 从分片首次失败开始计时，超过该时长后不再重试，直接返回最后一次的错误；
+    /// 与 [`max_retries`](Self::max_retries) 同时生效，先到者为准。
+    pub fn retry_deadline(mut self, retry_deadline: Duration) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_retry_deadline(retry_deadline);
+        self
+    }
+
+    /// 设置首字节超时
+    ///
+    /// 从发出请求开始计时，超过该时长仍未收到响应体的第一个字节时返回
+    /// [`DownloadError::FirstByteTimeout`]。只约束"开始传输前"的等待
+    /// （例如冷存储取回），一旦开始收到数据就不再生效。分片下载中每次
+    /// Range 请求单独计时，超时按普通失败参与重试。
+    pub fn first_byte_timeout(mut self, timeout: Duration) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_first_byte_timeout(timeout);
+        self
+    }
+
+    /// 设置请求超时
+    ///
+    /// 单个 GET 请求从发出到读完响应体的总时长上限，超时返回
+    /// [`DownloadError::Request`]。单线程下载时约束整个下载，
+    /// 分片下载时每次 Range 请求单独计时，超时按普通失败参与重试。
+    ///
+    /// [`WebdavAuth::with_timeout`](crate::auth::WebdavAuth::with_timeout)
+    /// 的请求超时不作用于下载，需要时在这里单独设置；其连接超时对下载同样生效。
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_request_timeout(timeout);
+        self
+    }
+
+    /// 限制下载速率（字节/秒），0 按 1 处理
+    ///
+    /// 每收到一块数据后按需 sleep，使平均速率不超过上限，不会空转等待。
+    /// 分片下载时各分片共享这一额度，限制的是整个下载的总速率。
+    /// 单线程下载在等待期间仍会立即响应暂停与取消。
+    pub fn throttle(mut self, bytes_per_sec: u64) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_max_bytes_per_sec(bytes_per_sec);
+        self
+    }
+
+    /// 设置最小磁盘剩余空间（字节），仅在保存到本地时生效
+    ///
+    /// 下载开始前以及写入过程中会定期查询目标文件系统的剩余空间，
+    /// 当剩余空间不足以容纳尚未下载的部分再加上 `min_free_bytes` 时，
+    /// 删除未完成的文件并返回 [`DownloadError::InsufficientDiskSpace`]。
+    pub fn min_free_bytes(mut self, min_free_bytes: u64) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_min_free_bytes(min_free_bytes);
+        self
+    }
+
+    /// 设置磁盘写入并发限制器
+    ///
+    /// 把同一个 [`FileWriteLimiter`] 交给多个下载器，即可在保持网络并发的同时
+    /// 限制同时写盘的数量。
+    pub fn file_write_limiter(mut self, limiter: FileWriteLimiter) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_file_write_limiter(limiter);
+        self
+    }
+
+    /// 设置保存路径已存在文件时的处理方式，默认 [`OverwritePolicy::Overwrite`]
+    ///
+    /// - `Fail`：返回 [`DownloadError::FileExists`]
+    /// - `Rename`：改存为 `name (1).ext` 等，实际路径见 `DownloadResult::SavedToLocal`
+    /// - `Resume`：从已有文件末尾续传（总是使用单线程下载），
+    ///   续传的字节数见 [`DownloadMetrics::bytes_from_resume`]
+    pub fn overwrite_policy(mut self, policy: OverwritePolicy) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_overwrite_policy(policy);
+        self
+    }
+
+    /// 单线程下载时把读到的数据累积到至少 `min_bytes` 再写入并更新进度
+    ///
+    /// 服务器以大量很小的帧发送数据时，可减少写入系统调用与进度回调的次数；
+    /// 剩余不足 `min_bytes` 的部分在流结束时写出。0（默认）表示不合并。
+    /// 只作用于单线程下载（包括续传），分片下载不受影响。
+    pub fn coalesce_chunks(mut self, min_bytes: usize) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_coalesce_chunks(min_bytes);
+        self
+    }
+
+    /// 设置分片下载写入文件的方式，默认 [`ChunkWriteMode::Independent`]
+    ///
+    /// 保存目标位于网络文件系统等不能保证并发定位写入安全的位置时，
+    /// 可改用 [`ChunkWriteMode::SerializedMutex`]，以速度换取安全。
+    pub fn write_mode(mut self, mode: ChunkWriteMode) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_write_mode(mode);
+        self
+    }
+
+    /// 注册下载钩子，用于观察分片下载实际发出的 Range 请求、进度与错误等
+    /// 事件，也可以在 [`DownloadHook::on_progress`] 中中止下载
+    ///
+    /// 重复调用时以最后一次为准，见 [`DownloadHook`]。
+    pub fn with_hook<H>(mut self, hook: H) -> Self
+    where
+        H: DownloadHook + 'static,
+    {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_hook(SharedDownloadHook::new(hook));
+        self
+    }
+
+    /// 注册凭据刷新函数：请求收到 401/403 时调用，用返回的客户端重试
+    ///
+    /// 用于令牌会在长时间下载途中过期的服务（如 Bearer token）。
+    /// 分片下载时只重试受影响的分片，多个分片同时失效也只刷新一次；
+    /// 同一分片刷新后仍被拒绝时返回 [`DownloadError::AuthExpired`]。
+    /// 未注册时收到 401/403 直接返回该错误，不做普通重试。
+    pub fn with_auth_refresh<F, Fut>(mut self, refresh: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<reqwest::Client, String>>
+            + Send
+            + 'static,
+    {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_auth_refresher(AuthRefresher::new(refresh));
+        self
+    }
+
+    /// 设置中止条件：每次进度更新时调用 `predicate`，返回 `true` 时中止下载
+    ///
+    /// 用于"低速持续一段时间"、"预计剩余时间过长"等自定义取消策略，
+    /// 无需自行轮询控制器。中止时走与 `cancel` 相同的清理流程，
+    /// 但返回 [`DownloadError::AbortedByPredicate`]。
+    pub fn abort_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&ProgressReport) -> bool + Send + Sync + 'static,
+    {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_abort_if(AbortPredicate::new(predicate));
+        self
+    }
+
+    /// 下载完成后计算 SHA-256，通过
+    /// [`send_with_metrics`](Self::send_with_metrics) 返回的
+    /// [`DownloadMetrics::sha256`] 读取
+    pub fn compute_sha256(mut self) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_compute_sha256(true);
+        self
+    }
+
+    /// 下载完成后校验 SHA-256（十六进制，不区分大小写）
+    ///
+    /// 分片下载会在全部分片完成后按偏移顺序重新计算摘要，结果与单线程下载一致。
+    /// 不一致时删除已保存的文件并返回 [`DownloadError::ChecksumMismatch`]。
+    pub fn verify_sha256(mut self, expected: &str) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_expected_sha256(expected.trim().to_string());
+        self
+    }
+
+    /// 下载完成后按指定算法校验摘要（十六进制，不区分大小写）
+    ///
+    /// 单线程下载边接收边计算，不会为此缓存整个文件；续传与分片下载在完成后
+    /// 按偏移顺序重新读取一遍。不一致时删除已保存的文件并返回
+    /// [`DownloadError::ChecksumMismatch`]。`ChecksumAlgo::Sha256` 等同于
+    /// [`RemoteDownloader::verify_sha256`]。
+    pub fn verify_checksum(
+        mut self,
+        algo: ChecksumAlgo,
+        expected_hex: String,
+    ) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_expected_checksum(algo, expected_hex.trim().to_string());
+        self
+    }
+
+    /// 下载完成后确认远程文件在下载期间没有变化
+    ///
+    /// 额外发送一次 HEAD，把响应的 ETag 与列举时得到的 ETag 比较（忽略引号与
+    /// 弱标记 `W/`），不一致时删除已保存的文件并返回
+    /// [`DownloadError::ETagChanged`]。列举结果或 HEAD 响应中没有 ETag 时跳过。
+    pub fn verify_etag(mut self) -> Self {
+        Arc::get_mut(&mut self.controller)
+            .expect("Cannot configure after controller is shared")
+            .set_verify_etag(true);
+        self
+    }
+
     pub fn get_controller(
         &self,
     ) -> Arc<RemoteDownloaderController> {
@@ -87,9 +397,65 @@ impl RemoteDownloader {
     }
 
     pub async fn send(&self) -> Result<DownloadResult, DownloadError> {
-        let mut consumer = self.command_consumer.lock().await;
+        let (result, _metrics) = self.send_with_metrics().await?;
+        Ok(result)
+    }
+
+    /// 与 [`send`](Self::send) 相同，同时返回本次下载的 [`DownloadMetrics`]
+    pub async fn send_with_metrics(
+        &self,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
+        self.run(None).await
+    }
+
+    /// 只下载 `[start, end)` 这一段，返回恰好这段内容的
+    /// [`DownloadResult::Bytes`]（如读取 ZIP 末尾的中央目录）
+    ///
+    /// 只发送一次 `Range: bytes=start-(end-1)` 请求，忽略保存路径、分片与
+    /// 校验等配置，也不更新进度与状态；`request_timeout` 仍然生效。
+    ///
+    /// - 区间为空（`start >= end`），或文件大小已知且 `end` 超出大小时返回
+    ///   [`DownloadError::InvalidRange`]，不会发出请求
+    /// - 文件大小未知时不做上限检查：区间越过文件末尾时只返回到末尾为止的
+    ///   字节，`start` 也在末尾之后时服务器返回 416，同样是 `InvalidRange`
+    /// - 服务器忽略 Range（返回 200）或返回的区间起点不符时返回
+    ///   [`DownloadError::RangeNotSupported`]
+    /// - 文件大小已知而收到的字节数与区间长度不符时返回
+    ///   [`DownloadError::SizeMismatch`]
+    pub async fn download_range(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<DownloadResult, DownloadError> {
+        let bytes = self.controller.download_range(start, end).await?;
+        Ok(DownloadResult::Bytes(bytes))
+    }
+
+    /// 与 [`send`](Self::send) 相同，但分片下载同时发出的请求数不超过
+    /// `request_cap`（供 [`DownloadBatch`] 分配全局额度）
+    ///
+    /// [`DownloadBatch`]: super::download_batch::DownloadBatch
+    pub(crate) async fn send_with_request_cap(
+        &self,
+        request_cap: usize,
+    ) -> Result<DownloadResult, DownloadError> {
+        let (result, _metrics) = self.run(Some(request_cap)).await?;
+        Ok(result)
+    }
+
+    async fn run(
+        &self,
+        request_cap: Option<usize>,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
+        let mut consumer =
+            Arc::clone(&self.command_consumer).lock_owned().await;
+        let mode = &self.controller.config().download_mode;
+        if matches!(mode, DownloadMode::Stream) {
+            // 流持有 consumer，读完或丢弃之前再次 send 会在这里等待
+            return self.get_controller().open_stream(consumer).await;
+        }
         // controller 是 Arc<RemoteDownloaderController>，不需要锁
         // download() 只需要 &self，pause/resume/cancel 通过 mpsc 队列发送（无锁）
-        self.controller.download(&mut consumer).await
+        self.controller.download(&mut consumer, request_cap).await
     }
 }
@@ -1,4 +1,12 @@
+use std::time::Duration;
+
+use super::auth_refresh::AuthRefresher;
+use super::chunk_write_mode::ChunkWriteMode;
 use super::download_mode::DownloadMode;
+use super::file_write_limiter::FileWriteLimiter;
+use super::overwrite_policy::OverwritePolicy;
+use super::progress_report::AbortPredicate;
+use crate::internal::remote_file::downloader::traits::SharedDownloadHook;
 
 /// 默认分片大小：1MB
 pub const DEFAULT_CHUNK_SIZE: u64 = 1024 * 1024;
@@ -9,6 +17,8 @@ pub const DEFAULT_MAX_RETRIES: usize = 3;
 /// 默认重试延迟（毫秒）
 pub const DEFAULT_RETRY_DELAY_MS: u64 = 1000;
 
+/// 下载器配置，由 [`RemoteDownloader`](super::remote_downloader::RemoteDownloader)
+/// 的 builder 方法填充，可通过 `RemoteDownloaderController::config` 读取
 #[derive(Debug, Clone)]
 pub struct RemoteDownloaderConfig {
     pub download_mode: DownloadMode,
@@ -20,6 +30,41 @@ pub struct RemoteDownloaderConfig {
     pub max_retries: usize,
     /// 重试延迟（毫秒）
     pub retry_delay_ms: u64,
+    /// 单个分片重试的总时长上限（从首次失败开始计时），`None` 表示只按次数限制
+    pub retry_deadline: Option<Duration>,
+    /// 请求发出后等待第一个字节的时长上限，`None` 表示不限制
+    pub first_byte_timeout: Option<Duration>,
+    /// 单个 GET 请求从发出到读完响应体的总时长上限，`None` 表示不限制
+    pub request_timeout: Option<Duration>,
+    /// 下载速率上限（字节/秒），分片下载时为所有分片的总速率；`None` 表示不限速
+    pub max_bytes_per_sec: Option<u64>,
+    /// 保存到本地时要求保留的最小磁盘剩余空间（字节），`None` 表示不检查
+    pub min_free_bytes: Option<u64>,
+    /// 磁盘写入并发限制（可在多个下载器间共享），`None` 表示不限制
+    pub file_write_limiter: Option<FileWriteLimiter>,
+    /// 下载完成后计算 SHA-256 并写入 `DownloadMetrics::sha256`
+    pub compute_sha256: bool,
+    /// 期望的 SHA-256（十六进制，不区分大小写），不一致时下载失败
+    pub expected_sha256: Option<String>,
+    /// 期望的 MD5（十六进制，不区分大小写），不一致时下载失败
+    pub expected_md5: Option<String>,
+    /// 下载完成后用 HEAD 重新读取 ETag，与列举时的 ETag 不一致时下载失败
+    pub verify_etag: bool,
+    /// 每次进度更新时调用，返回 `true` 时中止下载
+    pub abort_if: Option<AbortPredicate>,
+    /// 服务器不支持 Range 时改用单线程下载，为 `false` 时返回
+    /// `DownloadError::RangeNotSupported`
+    pub fallback_on_no_range: bool,
+    /// 保存路径已存在文件时的处理方式
+    pub overwrite_policy: OverwritePolicy,
+    /// 单线程下载时累积到该字节数再写入并更新进度，0 表示不合并
+    pub coalesce_chunks: usize,
+    /// 分片下载时各分片写入文件的方式
+    pub write_mode: ChunkWriteMode,
+    /// 观察下载事件的钩子，`None` 表示未注册
+    pub hook: Option<SharedDownloadHook>,
+    /// 收到 401/403 时刷新凭据，`None` 表示直接返回 `AuthExpired`
+    pub auth_refresher: Option<AuthRefresher>,
 }
 
 impl Default for RemoteDownloaderConfig {
@@ -30,7 +75,26 @@ impl Default for RemoteDownloaderConfig {
             chunk_size: DEFAULT_CHUNK_SIZE,
             max_retries: DEFAULT_MAX_RETRIES,
             retry_delay_ms: DEFAULT_RETRY_DELAY_MS,
+            retry_deadline: None,
+            first_byte_timeout: None,
+            request_timeout: None,
+            max_bytes_per_sec: None,
+            min_free_bytes: None,
+            file_write_limiter: None,
+            compute_sha256: false,
+            expected_sha256: None,
+            expected_md5: None,
+            verify_etag: false,
+            abort_if: None,
+            fallback_on_no_range: false,
+            overwrite_policy: OverwritePolicy::Overwrite,
+            coalesce_chunks: 0,
+            write_mode: ChunkWriteMode::Independent,
+            hook: None,
+            auth_refresher: None,
         }
     }
 }
 
+/// [`RemoteDownloaderConfig`] 的简称
+pub type DownloadConfig = RemoteDownloaderConfig;
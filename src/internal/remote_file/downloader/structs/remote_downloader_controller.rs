@@ -2,67 +2,243 @@ use crate::internal::states::queue_reactive::{
     QueueReactiveConsumer, QueueReactiveProperty,
 };
 use crate::{
-    auth::WebdavAuth, remote_file::RemoteFileData,
-    states::unlock_reactive::UnlockReactiveProperty,
+    remote_file::RemoteFileData,
+    states::broadcast_reactive::BroadcastReactiveProperty,
+    states::reactive_core::ReactivePropertyError,
+    states::unlock_reactive::{PropertyWatcher, UnlockReactiveProperty},
 };
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-
-use futures_util::StreamExt;
-use reqwest::header::RANGE;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt, stream};
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_RANGE, ETAG, RANGE};
 use tokio::fs::File;
 use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::Mutex as TokioMutex;
-use tokio::sync::Notify;
+use tokio::sync::{Notify, OwnedMutexGuard};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+use crate::internal::auth::structs::digest_auth::{
+    DigestAuth, send_with_digest,
+};
 
+use super::auth_refresh::{AuthRefresher, RefreshableClient, auth_failure};
+use super::bandwidth_limiter::{BandwidthLimiter, throttle_elapsed};
 use super::byte_segments::{ByteSegment, ByteSegments};
+use super::chunk_coalescer::ChunkCoalescer;
+use super::chunk_write_mode::ChunkWriteMode;
+use crate::internal::remote_file::downloader::traits::SharedDownloadHook;
+use super::checksum_algo::ChecksumAlgo;
+use super::content_digest::{
+    ContentHasher, digest_file, digest_segments, verify_checksum,
+};
 use super::control_command::ControlCommand;
+use super::disk_space_guard::DiskSpaceGuard;
 use super::download_error::DownloadError;
+use super::download_metrics::DownloadMetrics;
 use super::download_mode::DownloadMode;
+use super::download_progress::DownloadProgress;
 use super::download_result::DownloadResult;
+use super::download_speed::{
+    DownloadSpeed, SPEED_SAMPLE_INTERVAL, SpeedMeter,
+};
 use super::download_status::DownloadStatus;
-use super::reactive_state::RemoteDownloaderControllerReactiveState;
+use super::download_stream::DownloadStream;
+use super::file_write_limiter::FileWriteLimiter;
+use super::overwrite_policy::{
+    OverwritePolicy, SaveTarget, resolve_save_target,
+};
+use super::progress_report::AbortPredicate;
+use super::reactive_state::{
+    ProgressReporter, RemoteDownloaderControllerReactiveState,
+};
 use super::remote_downloader_config::RemoteDownloaderConfig;
 
+/// output_bytes 模式下各分片的数据，按 offset 索引
+///
+/// 每个分片只在整次尝试成功后写入一次；即使同一个 offset 被重复写入，
+/// 也只会保留最后一份，最终的 [`ByteSegments`] 不会出现重复或重叠的段。
+type SegmentStore = Arc<TokioMutex<BTreeMap<u64, Vec<u8>>>>;
+
+/// 分片任务句柄：(分片序号, 任务)
+type ChunkHandle = (usize, JoinHandle<Result<(), DownloadError>>);
+
+/// 取消时等待分片任务写完当前数据块的宽限期
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// 分片任务共享的上下文，所有字段都是可以廉价 clone 的共享句柄
+#[derive(Clone)]
+struct ChunkTaskContext {
+    /// 收到 401/403 并刷新凭据后，所有分片改用新的客户端
+    client: RefreshableClient,
+    digest: Option<Arc<DigestAuth>>,
+    url: String,
+    /// `SerializedMutex` 模式下共享的文件句柄
+    file: Option<Arc<TokioMutex<File>>>,
+    /// `Independent` 模式下每个分片自行打开的文件路径
+    independent_path: Option<String>,
+    output_bytes: bool,
+    segments: SegmentStore,
+    bytes_counter: Arc<AtomicU64>,
+    retry_counter: Arc<AtomicUsize>,
+    progress: ProgressReporter,
+    cancelled: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    resume_notifier: Arc<Notify>,
+    file_write_limiter: Option<FileWriteLimiter>,
+    disk_space_guard: Option<Arc<DiskSpaceGuard>>,
+    max_retries: usize,
+    retry_delay_ms: u64,
+    retry_deadline: Option<Duration>,
+    first_byte_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    bandwidth_limiter: Option<Arc<BandwidthLimiter>>,
+    hook: Option<SharedDownloadHook>,
+}
+
+/// 首字节超时的截止时间，从请求发出前开始计时
+#[derive(Debug, Clone, Copy)]
+struct FirstByteDeadline {
+    timeout: Duration,
+    at: tokio::time::Instant,
+}
+
+impl FirstByteDeadline {
+    fn start(timeout: Option<Duration>) -> Option<Self> {
+        timeout.map(|timeout| Self {
+            timeout,
+            at: tokio::time::Instant::now() + timeout,
+        })
+    }
+}
+
+/// 等到首字节截止时间后返回超时错误；没有截止时间时永远不返回
+async fn first_byte_expired(
+    deadline: Option<FirstByteDeadline>,
+) -> DownloadError {
+    match deadline {
+        Some(deadline) => {
+            tokio::time::sleep_until(deadline.at).await;
+            DownloadError::FirstByteTimeout(deadline.timeout)
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// 设置了请求超时时附加到请求上
+fn with_request_timeout(
+    request: reqwest::RequestBuilder,
+    timeout: Option<Duration>,
+) -> reqwest::RequestBuilder {
+    match timeout {
+        Some(timeout) => request.timeout(timeout),
+        None => request,
+    }
+}
+
+/// 去掉 ETag 的弱标记 `W/` 与引号，便于比较
+fn normalize_etag(etag: &str) -> &str {
+    let etag = etag.trim();
+    etag.strip_prefix("W/").unwrap_or(etag).trim_matches('"')
+}
+
+/// 单次 Range 请求的结果，交给下载钩子的 `on_range_complete`
+#[derive(Debug, Default)]
+struct RangeAttempt {
+    status: Option<u16>,
+    bytes: u64,
+}
+
 #[derive(Debug)]
 pub struct RemoteDownloaderController {
     file_data: Arc<RemoteFileData>,
-    webdav_auth: WebdavAuth,
+    /// 发起下载请求的客户端：WebDAV 下载时带认证，直链下载时不带
+    client: reqwest::Client,
+    /// Digest 认证的凭据与质询，Basic 认证与直链下载时为 `None`
+    digest: Option<Arc<DigestAuth>>,
+    /// 实际下载的 URL：默认是文件的 absolute_path，直链下载时为外部 URL
+    url: String,
     config: RemoteDownloaderConfig,
     reactive_state: RemoteDownloaderControllerReactiveState,
+    /// PROPFIND 未给出大小时，下载前通过 HEAD 探测到的大小
+    ///
+    /// `file_data` 由多个下载器共享，不能原地修改，探测结果缓存在控制器上
+    probed_size: OnceLock<u64>,
 }
 
 /// 内部实现
 impl RemoteDownloaderController {
     pub(crate) fn new(
         file_data: Arc<RemoteFileData>,
-        webdav_auth: WebdavAuth,
+        client: reqwest::Client,
+        digest: Option<Arc<DigestAuth>>,
+        url: String,
         download_mode: DownloadMode,
     ) -> (Self, QueueReactiveConsumer<ControlCommand>) {
         // 创建命令队列
         let (command_queue, command_consumer) =
             QueueReactiveProperty::new();
+        let total = file_data.size;
 
         let controller = Self {
             file_data,
-            webdav_auth,
+            client,
+            digest,
+            url,
             config: RemoteDownloaderConfig {
                 download_mode,
                 ..Default::default()
             },
             reactive_state: RemoteDownloaderControllerReactiveState {
                 command_queue,
-                download_status: UnlockReactiveProperty::new(
-                    DownloadStatus::Running,
+                download_status: BroadcastReactiveProperty::new(
+                    DownloadStatus::Preparing,
                 ),
                 downloaded_bytes: UnlockReactiveProperty::new(0),
+                progress: UnlockReactiveProperty::new(DownloadProgress {
+                    bytes_done: 0,
+                    total,
+                }),
+                speed: UnlockReactiveProperty::new(
+                    DownloadSpeed::default(),
+                ),
                 resume_notifier: Arc::new(Notify::new()),
+                aborted_by_predicate: Arc::new(AtomicBool::new(false)),
+                hook_abort: Arc::new(OnceLock::new()),
+                terminated: tokio::sync::watch::channel(false).0,
             },
+            probed_size: OnceLock::new(),
         };
 
         (controller, command_consumer)
     }
 
+    /// 下载进度的监听器（供汇总进度等内部组件使用）
+    pub(crate) fn watch_progress(
+        &self,
+    ) -> PropertyWatcher<DownloadProgress> {
+        self.reactive_state.progress.watch()
+    }
+
+    /// 下载是否已结束的监听器，结束（完成、取消或出错）后值为 `true`
+    pub(crate) fn watch_terminated(
+        &self,
+    ) -> tokio::sync::watch::Receiver<bool> {
+        self.reactive_state.terminated.subscribe()
+    }
+
+    /// 远程文件大小：优先 PROPFIND 的结果，其次 HEAD 探测的结果，都没有时为 `None`
+    pub(crate) fn total_size(&self) -> Option<u64> {
+        self.file_data.size.or_else(|| self.probed_size.get().copied())
+    }
+
     pub(crate) fn set_download_mode(
         &mut self,
         download_mode: DownloadMode,
@@ -81,6 +257,85 @@ impl RemoteDownloaderController {
     pub(crate) fn set_max_retries(&mut self, max_retries: usize) {
         self.config.max_retries = max_retries;
     }
+
+    pub(crate) fn set_retry_deadline(&mut self, retry_deadline: Duration) {
+        self.config.retry_deadline = Some(retry_deadline);
+    }
+
+    pub(crate) fn set_first_byte_timeout(&mut self, timeout: Duration) {
+        self.config.first_byte_timeout = Some(timeout);
+    }
+
+    pub(crate) fn set_request_timeout(&mut self, timeout: Duration) {
+        self.config.request_timeout = Some(timeout);
+    }
+
+    pub(crate) fn set_fallback_on_no_range(&mut self, fallback: bool) {
+        self.config.fallback_on_no_range = fallback;
+    }
+
+    pub(crate) fn set_max_bytes_per_sec(&mut self, bytes_per_sec: u64) {
+        self.config.max_bytes_per_sec = Some(bytes_per_sec);
+    }
+
+    pub(crate) fn set_min_free_bytes(&mut self, min_free_bytes: u64) {
+        self.config.min_free_bytes = Some(min_free_bytes);
+    }
+
+    pub(crate) fn set_file_write_limiter(
+        &mut self,
+        limiter: FileWriteLimiter,
+    ) {
+        self.config.file_write_limiter = Some(limiter);
+    }
+
+    pub(crate) fn set_overwrite_policy(&mut self, policy: OverwritePolicy) {
+        self.config.overwrite_policy = policy;
+    }
+
+    pub(crate) fn set_hook(&mut self, hook: SharedDownloadHook) {
+        self.config.hook = Some(hook);
+    }
+
+    pub(crate) fn set_auth_refresher(&mut self, refresher: AuthRefresher) {
+        self.config.auth_refresher = Some(refresher);
+    }
+
+    pub(crate) fn set_write_mode(&mut self, mode: ChunkWriteMode) {
+        self.config.write_mode = mode;
+    }
+
+    pub(crate) fn set_coalesce_chunks(&mut self, min_bytes: usize) {
+        self.config.coalesce_chunks = min_bytes;
+    }
+
+    pub(crate) fn set_abort_if(&mut self, predicate: AbortPredicate) {
+        self.config.abort_if = Some(predicate);
+    }
+
+    pub(crate) fn set_compute_sha256(&mut self, compute: bool) {
+        self.config.compute_sha256 = compute;
+    }
+
+    pub(crate) fn set_expected_sha256(&mut self, expected: String) {
+        self.config.compute_sha256 = true;
+        self.config.expected_sha256 = Some(expected);
+    }
+
+    pub(crate) fn set_expected_checksum(
+        &mut self,
+        algo: ChecksumAlgo,
+        expected: String,
+    ) {
+        match algo {
+            ChecksumAlgo::Sha256 => self.set_expected_sha256(expected),
+            ChecksumAlgo::Md5 => self.config.expected_md5 = Some(expected),
+        }
+    }
+
+    pub(crate) fn set_verify_etag(&mut self, verify_etag: bool) {
+        self.config.verify_etag = verify_etag;
+    }
 }
 
 /// 外部接口：通过命令队列发送控制命令
@@ -111,6 +366,47 @@ impl RemoteDownloaderController {
         self.reactive_state.downloaded_bytes.get_current().unwrap_or(0)
     }
 
+    /// 当前下载配置（builder 设置的结果，send 之后不再变化）
+    pub fn config(&self) -> &RemoteDownloaderConfig {
+        &self.config
+    }
+
+    /// 获取当前下载进度（已下载字节数与总大小）
+    pub fn progress(&self) -> DownloadProgress {
+        self.reactive_state.progress.get_current().unwrap_or_default()
+    }
+
+    /// 派生一个跟随下载进度变化的只读属性，值为 `f(&进度)`
+    ///
+    /// 例如 `project_progress(|p| p.percent().unwrap_or(0.0))` 得到百分比
+    /// 属性，可以直接交给 UI 监听；对派生属性的写入不会影响下载器。
+    /// 详见 [`UnlockReactiveProperty::project`]。
+    pub fn project_progress<U, F>(&self, f: F) -> UnlockReactiveProperty<U>
+    where
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&DownloadProgress) -> U + Send + 'static,
+    {
+        self.reactive_state.progress.project(f)
+    }
+
+    /// 获取当前下载速度与剩余时间估计（约每秒更新一次）
+    ///
+    /// 暂停期间速度为 0，恢复后重新从 0 平滑爬升；需要持续监听时使用
+    /// [`subscribe_speed`](Self::subscribe_speed)。
+    pub fn speed(&self) -> DownloadSpeed {
+        self.reactive_state.speed.get_current().unwrap_or_default()
+    }
+
+    /// 下载前即可判断文件大小是否已知
+    ///
+    /// 大小未知时没有百分比。配置了分片下载时，下载前会先发 HEAD 请求探测
+    /// `Content-Length`，探测成功后按分片下载，否则回退为单线程下载；
+    /// 单线程下载开始后，若响应带有 `Content-Length`，[`progress`](Self::progress)
+    /// 中的 `total` 仍会被补上。
+    pub fn has_known_size(&self) -> bool {
+        self.total_size().is_some()
+    }
+
     /// 获取当前下载状态
     pub fn get_download_status(&self) -> Option<DownloadStatus> {
         self.reactive_state.download_status.get_current()
@@ -120,25 +416,270 @@ impl RemoteDownloaderController {
 /// 下载逻辑：内部消费命令队列，驱动状态变化
 impl RemoteDownloaderController {
     /// 启动下载，consumer 由外部传入（因为 consumer 需要 &mut）
+    ///
+    /// `request_cap` 限制分片下载同时发出的请求数（批量下载时分到的额度），
+    /// 为 `None` 时只受 `max_chunks` 限制
     pub(crate) async fn download(
         &self,
         consumer: &mut QueueReactiveConsumer<ControlCommand>,
-    ) -> Result<DownloadResult, DownloadError> {
+        request_cap: Option<usize>,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
         let max_chunks = self.config.max_chunks;
 
-        if max_chunks <= 1 {
-            self.single_thread_download(consumer).await
-        } else {
-            self.chunked_download(consumer).await
+        // 按 overwrite_policy 处理已存在的文件
+        let target = resolve_save_target(
+            &self.config.download_mode,
+            self.config.overwrite_policy,
+        )
+        .await;
+
+        // PROPFIND 没有给出大小时，分片下载前先用 HEAD 探测一次
+        if max_chunks > 1
+            && !self.file_data.is_dir
+            && matches!(&target, Ok(t) if t.resume_from == 0)
+            && self.total_size().is_none()
+            && let Some(size) = self.probe_size().await
+        {
+            let _ = self.probed_size.set(size);
+        }
+
+        // 下载期间周期性地采样速度，下载结束时采样随之停止
+        let dispatch = self.dispatch(consumer, target, request_cap);
+        let result = tokio::select! {
+            biased;
+
+            result = dispatch => result,
+            never = self.sample_speed() => match never {},
+        };
+        let _ = self.reactive_state.speed.update(DownloadSpeed::default());
+
+        // 无论成功、取消还是出错，都通知订阅任务退出
+        self.reactive_state.terminated.send_replace(true);
+
+        let result = result.map_err(|e| self.abort_error(e));
+        if let Err(e) = &result {
+            self.notify_error(e);
+        }
+        result
+    }
+
+    /// abort_if 与钩子的 on_progress 通过取消流程结束下载，
+    /// 这里把取消换成更明确的错误
+    fn abort_error(&self, err: DownloadError) -> DownloadError {
+        let state = &self.reactive_state;
+        match err {
+            DownloadError::Cancelled => {
+                if let Some(reason) = state.hook_abort.get() {
+                    DownloadError::HookAborted(reason.clone())
+                } else if state.aborted_by_predicate.load(Ordering::SeqCst)
+                {
+                    DownloadError::AbortedByPredicate
+                } else {
+                    DownloadError::Cancelled
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// 下载以错误结束时通知钩子
+    fn notify_error(&self, err: &DownloadError) {
+        if let Some(hook) = &self.config.hook {
+            hook.0.on_error(err);
+        }
+    }
+
+    /// 分片下载前用 `Range: bytes=0-0` 确认服务器支持 Range
+    ///
+    /// 返回 200，或 206 带的 `Content-Range` 不是 `bytes 0-0/<大小>` 时视为
+    /// 不支持。只有一个分片时整个文件就是唯一的 Range，无需探测；请求失败
+    /// 或返回其他状态时交给分片任务按原有逻辑处理。不读取响应体，探测的
+    /// 字节不计入进度。
+    async fn range_supported(&self, total: u64) -> bool {
+        if total <= self.config.chunk_size {
+            return true;
+        }
+        let request = with_request_timeout(
+            self.client.get(&self.url).header(RANGE, "bytes=0-0"),
+            self.config.request_timeout,
+        );
+        let Ok(resp) =
+            send_with_digest(self.digest.as_deref(), request).await
+        else {
+            return true;
+        };
+        match resp.status() {
+            StatusCode::OK => false,
+            StatusCode::PARTIAL_CONTENT => {
+                let Some(range) = resp.headers().get(CONTENT_RANGE) else {
+                    return true;
+                };
+                range
+                    .to_str()
+                    .ok()
+                    .and_then(|v| v.strip_prefix("bytes 0-0/"))
+                    .is_some_and(|size| {
+                        size == "*" || size.parse() == Ok(total)
+                    })
+            }
+            _ => true,
         }
     }
 
+    /// 按保存目标与配置选择单线程或分片下载
+    async fn dispatch(
+        &self,
+        consumer: &mut QueueReactiveConsumer<ControlCommand>,
+        target: Result<SaveTarget, DownloadError>,
+        request_cap: Option<usize>,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
+        // 0 会让分片循环无法前进；无论最终是否分片都拒绝，避免配置错误被掩盖
+        if self.config.chunk_size == 0 {
+            return Err(DownloadError::ZeroChunkSize);
+        }
+
+        // 文件大小未知（例如服务器使用分块传输编码）时无法切分 Range，
+        // 自动回退到单线程下载；续传也走单线程
+        match target {
+            Ok(target)
+                if target.resume_from > 0
+                    || self.config.max_chunks <= 1
+                    || !self.has_known_size() =>
+            {
+                self.single_thread_download(consumer, target).await
+            }
+            Ok(target) => {
+                let save_path = target.save_path.clone();
+                let chunked = self.chunked_download(
+                    consumer,
+                    save_path,
+                    request_cap,
+                );
+                match chunked.await {
+                    Err(DownloadError::RangeNotSupported)
+                        if self.config.fallback_on_no_range =>
+                    {
+                        self.single_thread_download(consumer, target).await
+                    }
+                    result => result,
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 每隔 [`SPEED_SAMPLE_INTERVAL`] 采样一次进度并更新速度，不会自行结束
+    ///
+    /// 还没收到数据（`Preparing`）时与暂停同样处理，续传时本地已有的字节
+    /// 不会被算进速度。
+    async fn sample_speed(&self) -> Infallible {
+        let mut interval = tokio::time::interval(SPEED_SAMPLE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // 第一次 tick 立即返回
+        interval.tick().await;
+        let mut meter =
+            SpeedMeter::new(Instant::now(), self.progress().bytes_done);
+
+        loop {
+            interval.tick().await;
+            let progress = self.progress();
+            let idle = matches!(
+                self.get_download_status(),
+                Some(DownloadStatus::Paused | DownloadStatus::Preparing)
+            );
+            meter.sample(Instant::now(), progress.bytes_done, idle);
+            let speed =
+                meter.snapshot(progress.bytes_done, progress.total);
+            let _ = self.reactive_state.speed.update(speed);
+        }
+    }
+
+    /// 只下载 `[start, end)`：发送一次 Range 请求并返回这段字节
+    ///
+    /// 不经过进度、状态与控制命令，也不重试。
+    pub(crate) async fn download_range(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u8>, DownloadError> {
+        if self.file_data.is_dir {
+            return Err(DownloadError::IsDir);
+        }
+        let size = self.total_size();
+        let invalid = DownloadError::InvalidRange { start, end, size };
+        if start >= end || size.is_some_and(|size| end > size) {
+            return Err(invalid);
+        }
+
+        let request = with_request_timeout(
+            self.client
+                .get(&self.url)
+                .header(RANGE, format!("bytes={}-{}", start, end - 1)),
+            self.config.request_timeout,
+        );
+        let resp =
+            send_with_digest(self.digest.as_deref(), request).await?;
+        if let Some(status) = auth_failure(resp.status()) {
+            return Err(DownloadError::AuthExpired { status });
+        }
+        if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            return Err(invalid);
+        }
+        let resp = resp.error_for_status()?;
+
+        // 200 表示服务器忽略了 Range；Content-Range 的起点也必须一致
+        let range_start = resp
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("bytes "))
+            .and_then(|v| v.split_once('-'))
+            .map(|(first, _)| first.trim().parse::<u64>());
+        if resp.status() != StatusCode::PARTIAL_CONTENT
+            || range_start.is_some_and(|first| first != Ok(start))
+        {
+            return Err(DownloadError::RangeNotSupported);
+        }
+
+        let bytes = resp.bytes().await?.to_vec();
+        let actual = bytes.len() as u64;
+        // 大小未知时区间可能越过文件末尾，此时服务器只返回到末尾为止
+        let expected = end - start;
+        if actual > expected || (size.is_some() && actual != expected) {
+            return Err(DownloadError::SizeMismatch { expected, actual });
+        }
+        Ok(bytes)
+    }
+
+    /// 发送 HEAD 请求读取 `Content-Length`，失败或没有该头时返回 `None`
+    async fn probe_size(&self) -> Option<u64> {
+        let request = with_request_timeout(
+            self.client.head(&self.url),
+            self.config.request_timeout,
+        );
+        let resp = send_with_digest(self.digest.as_deref(), request)
+            .await
+            .ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        resp.headers()
+            .get(CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
     /// 辅助方法：等待恢复或取消
+    ///
+    /// 返回 `Err(Cancelled)` 时只设置取消标志与状态，文件由调用方在分片任务
+    /// 全部停止后再清理
     async fn wait_for_resume_or_cancel(
         &self,
         consumer: &mut QueueReactiveConsumer<ControlCommand>,
         cancelled: &Arc<AtomicBool>,
-        save_path: &Option<String>,
     ) -> Result<(), DownloadError> {
         loop {
             tokio::select! {
@@ -155,7 +696,6 @@ impl RemoteDownloaderController {
                                 cancelled.store(true, Ordering::SeqCst);
                                 let _ = self.reactive_state.download_status
                                     .update(DownloadStatus::Canceled);
-                                Self::cleanup_file(save_path).await;
                                 return Err(DownloadError::Cancelled);
                             }
                             ControlCommand::Pause => continue,
@@ -173,7 +713,6 @@ impl RemoteDownloaderController {
                             cancelled.store(true, Ordering::SeqCst);
                             let _ = self.reactive_state.download_status
                                 .update(DownloadStatus::Canceled);
-                            Self::cleanup_file(save_path).await;
                             return Err(DownloadError::Cancelled);
                         }
                         Some(ControlCommand::Pause) => continue,
@@ -183,6 +722,111 @@ impl RemoteDownloaderController {
         }
     }
 
+    /// 辅助方法：按配置创建磁盘空间守卫（仅保存到本地时）
+    fn disk_space_guard(
+        &self,
+        save_path: &Option<String>,
+    ) -> Option<Arc<DiskSpaceGuard>> {
+        let min_free_bytes = self.config.min_free_bytes?;
+        let save_path = save_path.as_deref()?;
+        Some(Arc::new(DiskSpaceGuard::new(
+            save_path,
+            min_free_bytes,
+            self.total_size(),
+        )))
+    }
+
+    /// 辅助方法：有序关闭分片任务
+    ///
+    /// 设置取消标志并唤醒暂停中的任务，然后等待剩余任务写完当前数据块后退出
+    /// （最多等待 [`CANCEL_GRACE_PERIOD`]），超时仍未结束的任务会被强制中止。
+    /// 这样调用方删除文件时不会有任务仍在写入。
+    async fn drain_chunk_tasks(
+        &self,
+        cancelled: &AtomicBool,
+        handles: &mut [ChunkHandle],
+    ) {
+        cancelled.store(true, Ordering::SeqCst);
+        self.reactive_state.resume_notifier.notify_waiters();
+
+        let drain = async {
+            for (_, handle) in handles.iter_mut() {
+                let _ = handle.await;
+            }
+        };
+        if tokio::time::timeout(CANCEL_GRACE_PERIOD, drain).await.is_err() {
+            for (_, handle) in handles.iter_mut() {
+                handle.abort();
+            }
+        }
+    }
+
+    /// 需要计算的摘要算法：SHA-256（统计或校验）与 MD5（仅校验）
+    fn checksum_algos(&self) -> Vec<ChecksumAlgo> {
+        let mut algos = Vec::new();
+        if self.config.compute_sha256 {
+            algos.push(ChecksumAlgo::Sha256);
+        }
+        if self.config.expected_md5.is_some() {
+            algos.push(ChecksumAlgo::Md5);
+        }
+        algos
+    }
+
+    /// 与期望值逐一比较，返回 SHA-256（供下载统计使用）
+    fn verify_checksums(
+        &self,
+        digests: &[(ChecksumAlgo, String)],
+    ) -> Result<Option<String>, DownloadError> {
+        let mut sha256 = None;
+        for (algo, actual) in digests {
+            let expected = match algo {
+                ChecksumAlgo::Sha256 => &self.config.expected_sha256,
+                ChecksumAlgo::Md5 => &self.config.expected_md5,
+            };
+            verify_checksum(expected.as_deref(), actual)?;
+            if *algo == ChecksumAlgo::Sha256 {
+                sha256 = Some(actual.clone());
+            }
+        }
+        Ok(sha256)
+    }
+
+    /// 开启 `verify_etag` 时用 HEAD 重新读取 ETag 并与列举时的值比较；
+    /// 列举结果或响应中没有 ETag（服务器不支持）时跳过
+    async fn verify_etag_unchanged(&self) -> Result<(), DownloadError> {
+        let Some(expected) = self
+            .file_data
+            .etag
+            .as_deref()
+            .filter(|_| self.config.verify_etag)
+        else {
+            return Ok(());
+        };
+        let request = with_request_timeout(
+            self.client.head(&self.url),
+            self.config.request_timeout,
+        );
+        let resp =
+            send_with_digest(self.digest.as_deref(), request).await?;
+        if !resp.status().is_success() {
+            return Ok(());
+        }
+        let Some(actual) =
+            resp.headers().get(ETAG).and_then(|v| v.to_str().ok())
+        else {
+            return Ok(());
+        };
+        if normalize_etag(actual) == normalize_etag(expected) {
+            Ok(())
+        } else {
+            Err(DownloadError::ETagChanged {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            })
+        }
+    }
+
     /// 辅助方法：清理临时文件
     async fn cleanup_file(save_path: &Option<String>) {
         if let Some(p) = save_path {
@@ -190,57 +834,170 @@ impl RemoteDownloaderController {
         }
     }
 
+    /// 发起整个文件的 GET 请求（`resume_from > 0` 时带 Range），返回响应
+    /// 与尚未收到首字节时仍需遵守的截止时间
+    ///
+    /// 收到 401/403 时按 auth_refresher 刷新凭据后重发一次
+    async fn send_get(
+        &self,
+        resume_from: u64,
+    ) -> Result<
+        (reqwest::Response, Option<FirstByteDeadline>),
+        DownloadError,
+    > {
+        let client = RefreshableClient::new(
+            self.client.clone(),
+            self.config.auth_refresher.clone(),
+        );
+        let mut auth_refreshed = false;
+        loop {
+            let (http, generation) = client.current().await;
+            let mut request = with_request_timeout(
+                http.get(&self.url),
+                self.config.request_timeout,
+            );
+            if resume_from > 0 {
+                request =
+                    request.header(RANGE, format!("bytes={}-", resume_from));
+            }
+            let deadline =
+                FirstByteDeadline::start(self.config.first_byte_timeout);
+            let send = send_with_digest(self.digest.as_deref(), request);
+            let resp = tokio::select! {
+                resp = send => resp?,
+                e = first_byte_expired(deadline) => return Err(e),
+            };
+            match auth_failure(resp.status()) {
+                Some(status) if !auth_refreshed => {
+                    auth_refreshed = true;
+                    client.refresh_after(generation, status).await?;
+                }
+                Some(status) => {
+                    return Err(DownloadError::AuthExpired { status });
+                }
+                None => return Ok((resp, deadline)),
+            }
+        }
+    }
+
     pub(crate) async fn single_thread_download(
         &self,
         consumer: &mut QueueReactiveConsumer<ControlCommand>,
-    ) -> Result<DownloadResult, DownloadError> {
+        target: SaveTarget,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
         // 检查是否为目录
         if self.file_data.is_dir {
             return Err(DownloadError::IsDir);
         }
 
         // 解析下载模式
-        let save_path = match &self.config.download_mode {
-            DownloadMode::SaveFile(path) => Some(path.clone()),
-            DownloadMode::OutputBytes => None,
-        };
-        let output_bytes = matches!(
-            self.config.download_mode,
-            DownloadMode::OutputBytes
-        );
+        let SaveTarget { save_path, mut resume_from } = target;
+        // 本地文件比远程还大，说明不是同一次下载，从头开始
+        if self.total_size().is_some_and(|size| resume_from > size) {
+            resume_from = 0;
+        }
+        let output_bytes = self.config.download_mode.keeps_bytes();
 
         if save_path.is_none() && !output_bytes {
             return Err(DownloadError::NoDestination);
         }
 
         // 初始化进度
-        let _ = self.reactive_state.downloaded_bytes.update(0);
+        self.reactive_state
+            .progress_reporter(self.total_size())
+            .report(resume_from);
         let _ = self
             .reactive_state
             .download_status
-            .update(DownloadStatus::Running);
-
-        // 发起 HTTP GET 请求
-        let resp = self
-            .webdav_auth
-            .client
-            .get(&self.file_data.absolute_path)
-            .send()
-            .await?;
+            .update(DownloadStatus::Preparing);
+
+        // 下载前先检查一次磁盘空间
+        let disk_space_guard = self.disk_space_guard(&save_path);
+        if let Some(guard) = &disk_space_guard {
+            guard.check(resume_from)?;
+        }
+
+        let started_at = Instant::now();
+
+        // 已有文件就是完整文件，无需再请求
+        if resume_from > 0 && self.total_size() == Some(resume_from) {
+            return self
+                .finish_single_thread(
+                    &save_path,
+                    Vec::new(),
+                    Vec::new(),
+                    resume_from,
+                    resume_from,
+                    started_at,
+                )
+                .await;
+        }
+
+        let (resp, mut first_byte_deadline) =
+            self.send_get(resume_from).await?;
+        // 在打开文件之前检查状态，错误响应（503、416 等）不能覆盖已有的部分文件
+        let resp = resp.error_for_status()?;
+
+        // 服务器忽略 Range、返回完整内容（200）时只能从头下载
+        if resume_from > 0 {
+            match resp.status() {
+                StatusCode::PARTIAL_CONTENT => {}
+                StatusCode::OK => resume_from = 0,
+                _ => return Err(DownloadError::RangeNotSupported),
+            }
+        }
+
+        // 总大小：优先使用 PROPFIND 得到的大小，其次使用响应的 Content-Length；
+        // 分块传输编码的响应两者都可能没有，此时进度中的 total 为 None
+        let progress = self.reactive_state.progress_reporter_with_abort(
+            self.total_size()
+                .or(resp.content_length().map(|len| len + resume_from)),
+            self.config.abort_if.clone(),
+            self.config.hook.clone(),
+            started_at,
+        );
+        progress.report(resume_from);
 
         let mut stream = resp.bytes_stream();
-        let mut bytes_done: u64 = 0;
+        let mut bytes_done: u64 = resume_from;
         let mut out_bytes: Vec<u8> = Vec::new();
 
-        // 打开文件（如果需要保存到本地）
-        let mut file: Option<File> = if let Some(ref p) = save_path {
-            Some(File::create(p).await.map_err(DownloadError::CreateFile)?)
-        } else {
-            None
+        // 打开文件（如果需要保存到本地），续传时追加写入
+        let mut file: Option<File> = match &save_path {
+            Some(p) if resume_from > 0 => Some(
+                tokio::fs::OpenOptions::new()
+                    .append(true)
+                    .open(p)
+                    .await
+                    .map_err(DownloadError::CreateFile)?,
+            ),
+            Some(p) => {
+                Some(File::create(p).await.map_err(DownloadError::CreateFile)?)
+            }
+            None => None,
         };
 
-        // 流式下载循环
-        loop {
+        // 单线程按顺序接收数据，可以边下载边计算摘要；
+        // 续传时前半部分不经过这里，完成后再整体计算
+        let mut hashers: Vec<ContentHasher> = match resume_from {
+            0 => self
+                .checksum_algos()
+                .into_iter()
+                .map(ContentHasher::new)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut coalescer = ChunkCoalescer::new(self.config.coalesce_chunks);
+
+        // 限速：超出速率时在到期前不再读取数据，期间命令照常处理
+        let limiter =
+            self.config.max_bytes_per_sec.map(BandwidthLimiter::new);
+        let mut throttle_until = None;
+
+        // 流式下载循环：命令与数据流在同一个 select! 中等待，
+        // 即使 stream.next() 卡在慢速分块上，取消也会立即生效
+        let stream_result: Result<(), DownloadError> = 'download: loop {
             // 使用 select! 同时处理命令和数据流
             tokio::select! {
                 // 优先处理命令（biased 确保命令优先级）
@@ -273,7 +1030,7 @@ impl RemoteDownloaderController {
                                                         .reactive_state
                                                         .download_status
                                                         .update(DownloadStatus::Canceled);
-                                                    return Err(DownloadError::Cancelled);
+                                                    break 'download Err(DownloadError::Cancelled);
                                                 }
                                                 ControlCommand::Pause => continue,
                                             }
@@ -293,7 +1050,7 @@ impl RemoteDownloaderController {
                                                     .reactive_state
                                                     .download_status
                                                     .update(DownloadStatus::Canceled);
-                                                return Err(DownloadError::Cancelled);
+                                                break 'download Err(DownloadError::Cancelled);
                                             }
                                             Some(ControlCommand::Pause) => continue,
                                         }
@@ -306,41 +1063,93 @@ impl RemoteDownloaderController {
                                 .reactive_state
                                 .download_status
                                 .update(DownloadStatus::Canceled);
-                            return Err(DownloadError::Cancelled);
+                            break 'download Err(DownloadError::Cancelled);
                         }
                         Some(ControlCommand::Resume) => {} // 已在运行中，忽略
-                        None => return Err(DownloadError::Cancelled), // 队列关闭
+                        None => break 'download Err(DownloadError::Cancelled), // 队列关闭
                     }
                 }
 
-                // 读取下一块数据
-                chunk_result = stream.next() => {
-                    match chunk_result {
-                        Some(Ok(chunk)) => {
-                            let len = chunk.len() as u64;
-                            bytes_done += len;
+                // 首字节超时（收到第一块数据后不再生效）
+                e = first_byte_expired(first_byte_deadline) => {
+                    break 'download Err(e);
+                }
 
-                            if let Some(f) = file.as_mut() {
-                                f.write_all(&chunk)
-                                    .await
-                                    .map_err(DownloadError::WriteFile)?;
-                            }
-                            if output_bytes {
-                                out_bytes.extend_from_slice(&chunk);
-                            }
+                // 限速等待结束
+                _ = throttle_elapsed(throttle_until) => {
+                    throttle_until = None;
+                }
 
-                            let _ = self
-                                .reactive_state
-                                .downloaded_bytes
-                                .update(bytes_done);
+                // 读取下一块数据
+                chunk_result = stream.next(),
+                    if throttle_until.is_none() =>
+                {
+                    // 合并小块：未达到阈值时先不写入，流结束时写出剩余部分
+                    let (block, eof) = match chunk_result {
+                        Some(Ok(chunk)) => {
+                            first_byte_deadline = None;
+                            (coalescer.push(chunk), false)
                         }
                         Some(Err(e)) => {
+                            // 等待已提交的写入完成，续传按文件长度计算断点
+                            if let Some(f) = file.as_mut() {
+                                let _ = f.flush().await;
+                            }
                             return Err(DownloadError::Request(e));
                         }
-                        None => break, // 流结束，下载完成
+                        None => (coalescer.finish(), true),
+                    };
+
+                    if let Some(chunk) = block {
+                        let len = chunk.len() as u64;
+                        progress.mark_first_byte();
+
+                        if let Some(guard) = &disk_space_guard
+                            && let Err(e) = guard.check(bytes_done)
+                        {
+                            drop(file.take());
+                            Self::cleanup_file(&save_path).await;
+                            return Err(e);
+                        }
+                        bytes_done += len;
+
+                        if let Some(f) = file.as_mut() {
+                            let _permit = match &self.config.file_write_limiter {
+                                Some(limiter) => limiter.acquire().await,
+                                None => None,
+                            };
+                            f.write_all(&chunk)
+                                .await
+                                .map_err(DownloadError::WriteFile)?;
+                        }
+                        if output_bytes {
+                            out_bytes.extend_from_slice(&chunk);
+                        }
+                        for hasher in hashers.iter_mut() {
+                            hasher.update(&chunk);
+                        }
+
+                        progress.report(bytes_done);
+                        throttle_until =
+                            limiter.as_ref().and_then(|l| l.reserve(len));
+                    }
+
+                    if eof {
+                        break 'download Ok(()); // 流结束，下载完成
                     }
                 }
             }
+        };
+
+        // 取消时释放文件句柄并删除未完成的文件（Resume 策略保留，便于下次续传）
+        if let Err(e) = stream_result {
+            if let Some(mut f) = file.take() {
+                let _ = f.flush().await;
+            }
+            if self.config.overwrite_policy != OverwritePolicy::Resume {
+                Self::cleanup_file(&save_path).await;
+            }
+            return Err(e);
         }
 
         // 刷新文件缓冲区
@@ -348,20 +1157,174 @@ impl RemoteDownloaderController {
             f.flush().await.map_err(DownloadError::FlushFile)?;
         }
 
+        let digests = hashers
+            .into_iter()
+            .map(|hasher| (hasher.algo(), hasher.finalize_hex()))
+            .collect();
+        self.finish_single_thread(
+            &save_path,
+            digests,
+            out_bytes,
+            bytes_done,
+            resume_from,
+            started_at,
+        )
+        .await
+    }
+
+    /// 单线程下载收尾：校验摘要、更新状态并组装结果
+    async fn finish_single_thread(
+        &self,
+        save_path: &Option<String>,
+        digests: Vec<(ChecksumAlgo, String)>,
+        out_bytes: Vec<u8>,
+        bytes_done: u64,
+        resume_from: u64,
+        started_at: Instant,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
+        // 文件大小已知时校验收到的总字节数（Resume 策略保留文件，便于下次续传）
+        if let Some(expected) = self.total_size()
+            && bytes_done != expected
+        {
+            if self.config.overwrite_policy != OverwritePolicy::Resume {
+                Self::cleanup_file(save_path).await;
+            }
+            return Err(DownloadError::SizeMismatch {
+                expected,
+                actual: bytes_done,
+            });
+        }
+
+        // 续传时边下载边计算的摘要不完整，按文件重新计算
+        let digests = match save_path {
+            Some(path) if digests.is_empty() => {
+                let mut digests = Vec::new();
+                for algo in self.checksum_algos() {
+                    digests.push((algo, digest_file(path, algo).await?));
+                }
+                digests
+            }
+            _ => digests,
+        };
+        let sha256 = match self.verify_checksums(&digests) {
+            Ok(sha256) => sha256,
+            Err(e) => {
+                Self::cleanup_file(save_path).await;
+                return Err(e);
+            }
+        };
+        if let Err(e) = self.verify_etag_unchanged().await {
+            Self::cleanup_file(save_path).await;
+            return Err(e);
+        }
+
         // 更新状态为完成
         let _ = self
             .reactive_state
             .download_status
             .update(DownloadStatus::Finished);
 
+        let metrics = DownloadMetrics::new(
+            bytes_done - resume_from,
+            started_at.elapsed(),
+            0,
+            1,
+            resume_from,
+        )
+        .with_sha256(sha256);
+
         // 返回结果
-        if output_bytes {
-            Ok(DownloadResult::Bytes(out_bytes))
-        } else {
-            Ok(DownloadResult::SavedToLocal(
-                save_path.unwrap_or_default(),
-            ))
+        let keeps_bytes = self.config.download_mode.keeps_bytes();
+        let result = match save_path {
+            // 续传时内存中只有新下载的部分，完整内容从文件读取
+            Some(path) if keeps_bytes && resume_from > 0 => {
+                DownloadResult::SavedAndBytes {
+                    path: path.clone(),
+                    bytes: tokio::fs::read(path)
+                        .await
+                        .map_err(DownloadError::ReadLocalFile)?,
+                }
+            }
+            Some(path) if keeps_bytes => DownloadResult::SavedAndBytes {
+                path: path.clone(),
+                bytes: out_bytes,
+            },
+            Some(path) => DownloadResult::SavedToLocal(path.clone()),
+            None => DownloadResult::Bytes(out_bytes),
+        };
+        Ok((result, metrics))
+    }
+
+    /// `output_stream` 模式：收到响应头后返回字节流，由消费者拉取数据
+    ///
+    /// 返回的统计只覆盖到收到响应头为止；速度采样在后台进行，流结束或被
+    /// 丢弃时停止。
+    pub(crate) async fn open_stream(
+        self: Arc<Self>,
+        consumer: OwnedMutexGuard<QueueReactiveConsumer<ControlCommand>>,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
+        if self.file_data.is_dir {
+            return Err(DownloadError::IsDir);
         }
+
+        self.reactive_state.progress_reporter(self.total_size()).report(0);
+        let _ = self
+            .reactive_state
+            .download_status
+            .update(DownloadStatus::Preparing);
+        let started_at = Instant::now();
+
+        let opened = self.send_get(0).await.and_then(|(resp, deadline)| {
+            Ok((resp.error_for_status()?, deadline))
+        });
+        let (resp, first_byte_deadline) = match opened {
+            Ok(opened) => opened,
+            Err(e) => {
+                self.reactive_state.terminated.send_replace(true);
+                self.notify_error(&e);
+                return Err(e);
+            }
+        };
+
+        let progress = self.reactive_state.progress_reporter_with_abort(
+            self.total_size().or(resp.content_length()),
+            self.config.abort_if.clone(),
+            self.config.hook.clone(),
+            started_at,
+        );
+        let sampler = tokio::spawn({
+            let controller = Arc::clone(&self);
+            async move { controller.sample_speed().await }
+        });
+        let body = StreamingBody {
+            hashers: self
+                .checksum_algos()
+                .into_iter()
+                .map(ContentHasher::new)
+                .collect(),
+            limiter: self
+                .config
+                .max_bytes_per_sec
+                .map(BandwidthLimiter::new),
+            controller: self,
+            consumer,
+            stream: Box::pin(resp.bytes_stream()),
+            progress,
+            first_byte_deadline,
+            throttle_until: None,
+            bytes_done: 0,
+            started_at,
+            sampler,
+            finished: false,
+        };
+        let stream = stream::unfold(body, |mut body| async move {
+            let chunk = body.next_chunk().await?;
+            Some((chunk, body))
+        });
+
+        let metrics =
+            DownloadMetrics::new(0, started_at.elapsed(), 0, 1, 0);
+        Ok((DownloadResult::Stream(DownloadStream::new(stream)), metrics))
     }
 
     /// 多线程分片下载（改进版）
@@ -375,7 +1338,9 @@ impl RemoteDownloaderController {
     pub(crate) async fn chunked_download(
         &self,
         consumer: &mut QueueReactiveConsumer<ControlCommand>,
-    ) -> Result<DownloadResult, DownloadError> {
+        save_path: Option<String>,
+        request_cap: Option<usize>,
+    ) -> Result<(DownloadResult, DownloadMetrics), DownloadError> {
         use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
         use tokio::sync::Semaphore;
 
@@ -386,30 +1351,40 @@ impl RemoteDownloaderController {
 
         // 检查文件大小是否已知
         let total = self
-            .file_data
-            .size
+            .total_size()
             .ok_or(DownloadError::UnknownFileSizeForChunked)?;
 
-        // 解析下载模式
-        let save_path = match &self.config.download_mode {
-            DownloadMode::SaveFile(path) => Some(path.clone()),
-            DownloadMode::OutputBytes => None,
-        };
-        let output_bytes = matches!(
-            self.config.download_mode,
-            DownloadMode::OutputBytes
-        );
+        // 解析下载模式（保存路径已按 overwrite_policy 解析）
+        let output_bytes = self.config.download_mode.keeps_bytes();
 
         if save_path.is_none() && !output_bytes {
             return Err(DownloadError::NoDestination);
         }
 
         // 初始化进度
-        let _ = self.reactive_state.downloaded_bytes.update(0);
+        let started_at = Instant::now();
+        let progress = self.reactive_state.progress_reporter_with_abort(
+            Some(total),
+            self.config.abort_if.clone(),
+            self.config.hook.clone(),
+            started_at,
+        );
+        progress.report(0);
         let _ = self
             .reactive_state
             .download_status
-            .update(DownloadStatus::Running);
+            .update(DownloadStatus::Preparing);
+
+        // 下载前先检查一次磁盘空间
+        let disk_space_guard = self.disk_space_guard(&save_path);
+        if let Some(guard) = &disk_space_guard {
+            guard.check(0)?;
+        }
+
+        // 确认服务器支持 Range，否则由调用方决定是否回退到单线程下载
+        if !self.range_supported(total).await {
+            return Err(DownloadError::RangeNotSupported);
+        }
 
         // 创建文件并预分配空间（如果需要保存）
         let file: Option<Arc<TokioMutex<File>>> = if let Some(ref p) = save_path {
@@ -422,15 +1397,20 @@ impl RemoteDownloaderController {
         };
 
         // 分片数据存储（用于 output_bytes 模式）
-        let segments: Arc<TokioMutex<Vec<(u64, Vec<u8>)>>> =
-            Arc::new(TokioMutex::new(Vec::new()));
+        let segments: SegmentStore =
+            Arc::new(TokioMutex::new(BTreeMap::new()));
 
-        // 并发控制
-        let max_concurrent = self.config.max_chunks.max(2);
+        // 并发控制：批量下载时不超过分到的请求额度
+        let max_concurrent = match request_cap {
+            Some(cap) => self.config.max_chunks.max(2).min(cap.max(1)),
+            None => self.config.max_chunks.max(2),
+        };
         let semaphore = Arc::new(Semaphore::new(max_concurrent));
 
         // 全局进度计数器
         let bytes_done = Arc::new(AtomicU64::new(0));
+        // 重试次数计数器（用于下载统计）
+        let retries = Arc::new(AtomicUsize::new(0));
 
         // 取消标志（用于通知所有任务停止）
         let cancelled = Arc::new(AtomicBool::new(false));
@@ -439,50 +1419,67 @@ impl RemoteDownloaderController {
 
         // 配置参数
         let chunk_size = self.config.chunk_size;
-        let max_retries = self.config.max_retries;
-        let retry_delay_ms = self.config.retry_delay_ms;
+
+        // 所有分片任务共享的上下文
+        let (shared_file, independent_path) = match self.config.write_mode {
+            ChunkWriteMode::SerializedMutex => (file.clone(), None),
+            ChunkWriteMode::Independent => (None, save_path.clone()),
+        };
+        let context = ChunkTaskContext {
+            client: RefreshableClient::new(
+                self.client.clone(),
+                self.config.auth_refresher.clone(),
+            ),
+            digest: self.digest.clone(),
+            url: self.url.clone(),
+            file: shared_file,
+            independent_path,
+            output_bytes,
+            segments: Arc::clone(&segments),
+            bytes_counter: Arc::clone(&bytes_done),
+            retry_counter: Arc::clone(&retries),
+            progress,
+            cancelled: Arc::clone(&cancelled),
+            paused: Arc::clone(&paused),
+            resume_notifier: Arc::clone(&self.reactive_state.resume_notifier),
+            file_write_limiter: self.config.file_write_limiter.clone(),
+            disk_space_guard: disk_space_guard.clone(),
+            max_retries: self.config.max_retries,
+            retry_delay_ms: self.config.retry_delay_ms,
+            retry_deadline: self.config.retry_deadline,
+            first_byte_timeout: self.config.first_byte_timeout,
+            request_timeout: self.config.request_timeout,
+            bandwidth_limiter: self
+                .config
+                .max_bytes_per_sec
+                .map(|rate| Arc::new(BandwidthLimiter::new(rate))),
+            hook: self.config.hook.clone(),
+        };
 
         // 生成分片任务
+        // 0 字节文件：没有可请求的 Range（`bytes=0--1` 非法），不会生成任何分片任务，
+        // 直接进入下方收尾逻辑，得到空文件或空的 ByteSegments
         let mut range_start = 0u64;
-        let mut handles = Vec::new();
+        let mut handles: Vec<ChunkHandle> = Vec::new();
         let mut chunk_index = 0usize;
 
         while range_start < total {
             let range_end = (range_start + chunk_size).min(total);
 
             // 克隆需要的数据
-            let client = self.webdav_auth.client.clone();
-            let url = self.file_data.absolute_path.clone();
-            let file_clone = file.clone();
+            let task_context = context.clone();
             let sem = Arc::clone(&semaphore);
-            let bytes_counter = Arc::clone(&bytes_done);
-            let progress_state = self.reactive_state.downloaded_bytes.clone();
-            let segments_clone = Arc::clone(&segments);
-            let cancelled_clone = Arc::clone(&cancelled);
-            let paused_clone = Arc::clone(&paused);
-            let resume_notifier_clone = Arc::clone(&self.reactive_state.resume_notifier);
             let offset = range_start;
             let current_chunk_index = chunk_index;
 
             // Spawn 分片下载任务
             let handle = tokio::spawn(async move {
                 Self::download_chunk(
-                    client,
-                    url,
+                    task_context,
+                    sem,
                     offset,
                     range_end,
-                    file_clone,
-                    output_bytes,
-                    segments_clone,
-                    sem,
-                    bytes_counter,
-                    progress_state,
-                    cancelled_clone,
-                    paused_clone,
-                    resume_notifier_clone,
                     current_chunk_index,
-                    max_retries,
-                    retry_delay_ms,
                 ).await
             });
 
@@ -493,10 +1490,14 @@ impl RemoteDownloaderController {
 
         // 收集错误
         let mut errors: Vec<String> = Vec::new();
+        // 认证失效（且无法刷新）或分片大小不符时直接返回该错误，而不是分片失败列表
+        let mut fatal_error: Option<DownloadError> = None;
 
         // 等待所有分片任务完成，同时监听控制命令
-        for (idx, handle) in handles {
-            tokio::pin!(handle);
+        let mut current = 0usize;
+        while current < handles.len() {
+            let idx = handles[current].0;
+            let mut cancel_requested = false;
 
             loop {
                 tokio::select! {
@@ -508,29 +1509,42 @@ impl RemoteDownloaderController {
                                 paused.store(true, Ordering::SeqCst);
                                 let _ = self.reactive_state.download_status
                                     .update(DownloadStatus::Paused);
-                                self.wait_for_resume_or_cancel(
-                                    consumer, &cancelled, &save_path,
-                                ).await?;
+                                if self
+                                    .wait_for_resume_or_cancel(consumer, &cancelled)
+                                    .await
+                                    .is_err()
+                                {
+                                    cancel_requested = true;
+                                    break;
+                                }
                                 paused.store(false, Ordering::SeqCst);
                                 // 恢复后继续 loop，等待 handle 完成
                                 continue;
                             }
                             Some(ControlCommand::Cancel) => {
-                                cancelled.store(true, Ordering::SeqCst);
                                 let _ = self.reactive_state.download_status
                                     .update(DownloadStatus::Canceled);
-                                Self::cleanup_file(&save_path).await;
-                                return Err(DownloadError::Cancelled);
+                                cancel_requested = true;
+                                break;
                             }
                             Some(ControlCommand::Resume) => continue,
-                            None => return Err(DownloadError::Cancelled),
+                            None => {
+                                cancel_requested = true;
+                                break;
+                            }
                         }
                     }
 
-                    result = &mut handle => {
+                    result = &mut handles[current].1 => {
                         match result {
                             Ok(Ok(())) => {}
                             Ok(Err(DownloadError::Cancelled)) => {}
+                            Ok(Err(
+                                e @ (DownloadError::AuthExpired { .. }
+                                | DownloadError::AuthRefreshFailed(_)),
+                            )) => {
+                                fatal_error.get_or_insert(e);
+                            }
                             Ok(Err(e)) => {
                                 errors.push(format!("分片 {}: {}", idx, e));
                             }
@@ -542,6 +1556,30 @@ impl RemoteDownloaderController {
                     }
                 }
             }
+
+            if cancel_requested {
+                // 有序关闭：先等分片任务停止写入，再释放句柄并删除文件
+                self.drain_chunk_tasks(&cancelled, &mut handles[current..])
+                    .await;
+                drop(context);
+                drop(file);
+                Self::cleanup_file(&save_path).await;
+                return Err(DownloadError::Cancelled);
+            }
+            current += 1;
+        }
+
+        // 磁盘空间不足：返回明确的错误，而不是分片失败列表
+        if let Some(e) = disk_space_guard.as_ref().and_then(|g| g.tripped()) {
+            drop(file);
+            Self::cleanup_file(&save_path).await;
+            return Err(e);
+        }
+
+        if let Some(e) = fatal_error {
+            drop(file);
+            Self::cleanup_file(&save_path).await;
+            return Err(e);
         }
 
         // 检查是否有错误
@@ -559,49 +1597,77 @@ impl RemoteDownloaderController {
             file_guard.flush().await.map_err(DownloadError::FlushFile)?;
         }
 
+        // 组装结果
+        let result = if output_bytes {
+            // BTreeMap 已按偏移量排序，直接构建 ByteSegments
+            let raw_segments = std::mem::take(&mut *segments.lock().await);
+            let byte_segments: Vec<ByteSegment> = raw_segments
+                .into_iter()
+                .map(|(offset, data)| ByteSegment { offset, data })
+                .collect();
+            let segments = ByteSegments::new(byte_segments);
+            match &save_path {
+                Some(path) => DownloadResult::SavedAndBytes {
+                    path: path.clone(),
+                    bytes: segments.to_bytes(),
+                },
+                None => DownloadResult::ByteSegments(segments),
+            }
+        } else {
+            DownloadResult::SavedToLocal(save_path.clone().unwrap_or_default())
+        };
+
+        // 分片乱序完成，摘要必须在全部完成后按偏移顺序重新计算
+        let mut digests = Vec::new();
+        for algo in self.checksum_algos() {
+            let digest = match (&result, &save_path) {
+                (DownloadResult::ByteSegments(segments), _) => {
+                    digest_segments(segments, algo)
+                }
+                (_, Some(path)) => digest_file(path, algo).await?,
+                _ => continue,
+            };
+            digests.push((algo, digest));
+        }
+        let sha256 = match self.verify_checksums(&digests) {
+            Ok(sha256) => sha256,
+            Err(e) => {
+                drop(file);
+                Self::cleanup_file(&save_path).await;
+                return Err(e);
+            }
+        };
+        if let Err(e) = self.verify_etag_unchanged().await {
+            drop(file);
+            Self::cleanup_file(&save_path).await;
+            return Err(e);
+        }
+
         // 更新状态为完成
         let _ = self
             .reactive_state
             .download_status
             .update(DownloadStatus::Finished);
 
-        // 返回结果
-        if output_bytes {
-            // 按偏移量排序并构建 ByteSegments
-            let mut raw_segments = segments.lock().await;
-            raw_segments.sort_by_key(|(offset, _)| *offset);
-            let byte_segments: Vec<ByteSegment> = raw_segments
-                .drain(..)
-                .map(|(offset, data)| ByteSegment { offset, data })
-                .collect();
-            Ok(DownloadResult::ByteSegments(ByteSegments::new(
-                byte_segments,
-            )))
-        } else {
-            Ok(DownloadResult::SavedToLocal(
-                save_path.unwrap_or_default(),
-            ))
-        }
+        let metrics = DownloadMetrics::new(
+            bytes_done.load(Ordering::Relaxed),
+            started_at.elapsed(),
+            retries.load(Ordering::Relaxed),
+            chunk_index,
+            0,
+        )
+        .with_sha256(sha256);
+
+        Ok((result, metrics))
     }
 
     /// 下载单个分片（带重试和取消支持）
     async fn download_chunk(
-        client: reqwest::Client,
-        url: String,
+        ctx: ChunkTaskContext,
+        semaphore: Arc<tokio::sync::Semaphore>,
         range_start: u64,
         range_end: u64,
-        file: Option<Arc<TokioMutex<File>>>,
-        output_bytes: bool,
-        segments: Arc<TokioMutex<Vec<(u64, Vec<u8>)>>>,
-        semaphore: Arc<tokio::sync::Semaphore>,
-        bytes_counter: Arc<AtomicU64>,
-        progress_state: crate::states::unlock_reactive::UnlockReactiveProperty<u64>,
-        cancelled: Arc<AtomicBool>,
-        paused: Arc<AtomicBool>,
-        resume_notifier: Arc<Notify>,
         chunk_index: usize,
-        max_retries: usize,
-        retry_delay_ms: u64,
     ) -> Result<(), DownloadError> {
         // 获取信号量许可
         let _permit = semaphore.acquire().await.map_err(|_| {
@@ -610,42 +1676,87 @@ impl RemoteDownloaderController {
 
         let range_header = format!("bytes={}-{}", range_start, range_end - 1);
         let mut retries = 0;
+        // 首次失败的时间，用于 retry_deadline 计时
+        let mut first_failure_at: Option<Instant> = None;
+        // 本分片是否已经因 401/403 刷新过凭据
+        let mut auth_refreshed = false;
 
         loop {
             // 检查是否被取消
-            if cancelled.load(Ordering::SeqCst) {
+            if ctx.cancelled.load(Ordering::SeqCst) {
                 return Err(DownloadError::Cancelled);
             }
 
             // 等待暂停结束（使用 Notify 精确唤醒）
-            while paused.load(Ordering::SeqCst) {
-                if cancelled.load(Ordering::SeqCst) {
+            while ctx.paused.load(Ordering::SeqCst) {
+                if ctx.cancelled.load(Ordering::SeqCst) {
                     return Err(DownloadError::Cancelled);
                 }
-                resume_notifier.notified().await;
+                ctx.resume_notifier.notified().await;
             }
 
             // 尝试下载
-            match Self::download_chunk_inner(
+            if let Some(hook) = &ctx.hook {
+                hook.0.on_range_request(
+                    range_start,
+                    range_end - 1,
+                    retries + 1,
+                );
+            }
+            let mut attempt = RangeAttempt::default();
+            let (client, generation) = ctx.client.current().await;
+            let result = Self::download_chunk_inner(
+                &ctx,
                 &client,
-                &url,
                 &range_header,
                 range_start,
-                file.clone(),
-                output_bytes,
-                segments.clone(),
-                bytes_counter.clone(),
-                progress_state.clone(),
-                cancelled.clone(),
-                paused.clone(),
-                resume_notifier.clone(),
-            ).await {
+                range_end - range_start,
+                &mut attempt,
+            )
+            .await;
+            if let Some(hook) = &ctx.hook {
+                hook.0.on_range_complete(
+                    range_start,
+                    range_end - 1,
+                    attempt.bytes,
+                    attempt.status,
+                );
+            }
+
+            match result {
                 Ok(()) => return Ok(()),
+                // 磁盘空间不足时重试没有意义，通知其余分片一起停止
+                Err(e @ DownloadError::InsufficientDiskSpace { .. }) => {
+                    ctx.cancelled.store(true, Ordering::SeqCst);
+                    return Err(e);
+                }
+                // 认证失效：刷新（或复用其他分片刷新的结果）后重试，不计入重试次数；
+                // 无法刷新或刷新后仍被拒绝时通知其余分片一起停止
+                Err(DownloadError::AuthExpired { status }) => {
+                    let refreshed = if auth_refreshed {
+                        Err(DownloadError::AuthExpired { status })
+                    } else {
+                        auth_refreshed = true;
+                        ctx.client.refresh_after(generation, status).await
+                    };
+                    if let Err(e) = refreshed {
+                        ctx.cancelled.store(true, Ordering::SeqCst);
+                        return Err(e);
+                    }
+                }
                 Err(e) => {
+                    // 失败的尝试写入的字节会被重试覆盖，不计入进度
+                    ctx.bytes_counter
+                        .fetch_sub(attempt.bytes, Ordering::Relaxed);
                     retries += 1;
                     let last_error = e.to_string();
+                    let failing_for =
+                        first_failure_at.get_or_insert_with(Instant::now).elapsed();
+                    let deadline_exceeded = ctx
+                        .retry_deadline
+                        .is_some_and(|deadline| failing_for >= deadline);
 
-                    if retries > max_retries {
+                    if retries > ctx.max_retries || deadline_exceeded {
                         return Err(DownloadError::ChunkFailed {
                             chunk_index,
                             retries,
@@ -653,8 +1764,14 @@ impl RemoteDownloaderController {
                         });
                     }
 
-                    // 等待后重试
-                    tokio::time::sleep(tokio::time::Duration::from_millis(retry_delay_ms)).await;
+                    ctx.retry_counter.fetch_add(1, Ordering::Relaxed);
+
+                    // 等待后重试（不超过 retry_deadline 的剩余时间）
+                    let mut delay = Duration::from_millis(ctx.retry_delay_ms);
+                    if let Some(deadline) = ctx.retry_deadline {
+                        delay = delay.min(deadline.saturating_sub(failing_for));
+                    }
+                    tokio::time::sleep(delay).await;
                 }
             }
         }
@@ -662,50 +1779,127 @@ impl RemoteDownloaderController {
 
     /// 分片下载内部实现（单次尝试）
     async fn download_chunk_inner(
+        ctx: &ChunkTaskContext,
         client: &reqwest::Client,
-        url: &str,
         range_header: &str,
         offset: u64,
-        file: Option<Arc<TokioMutex<File>>>,
-        output_bytes: bool,
-        segments: Arc<TokioMutex<Vec<(u64, Vec<u8>)>>>,
-        bytes_counter: Arc<AtomicU64>,
-        progress_state: crate::states::unlock_reactive::UnlockReactiveProperty<u64>,
-        cancelled: Arc<AtomicBool>,
-        paused: Arc<AtomicBool>,
-        resume_notifier: Arc<Notify>,
+        expected_len: u64,
+        attempt: &mut RangeAttempt,
     ) -> Result<(), DownloadError> {
         // 发起 Range 请求
-        let resp = client
-            .get(url)
-            .header(RANGE, range_header)
-            .send()
-            .await?;
+        let mut first_byte_deadline =
+            FirstByteDeadline::start(ctx.first_byte_timeout);
+        let request = with_request_timeout(
+            client.get(&ctx.url).header(RANGE, range_header),
+            ctx.request_timeout,
+        );
+        let send = send_with_digest(ctx.digest.as_deref(), request);
+        let resp = tokio::select! {
+            resp = send => resp?,
+            e = first_byte_expired(first_byte_deadline) => return Err(e),
+        };
+        attempt.status = Some(resp.status().as_u16());
+        if let Some(status) = auth_failure(resp.status()) {
+            return Err(DownloadError::AuthExpired { status });
+        }
+        // 写入之前确认服务器确实返回了所请求的区间：
+        // 200 会把整个文件写到分片偏移处，区间不符会写错位置
+        let resp = resp.error_for_status()?;
+        let content_range = resp
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let expected_range =
+            format!("{}-{}", offset, offset + expected_len - 1);
+        let range_matches = content_range
+            .as_deref()
+            .and_then(|v| v.strip_prefix("bytes "))
+            .and_then(|v| v.split_once('/'))
+            .is_some_and(|(range, _)| range.trim() == expected_range);
+        if resp.status() != StatusCode::PARTIAL_CONTENT || !range_matches {
+            return Err(DownloadError::UnexpectedRangeResponse {
+                status: resp.status().as_u16(),
+                content_range,
+            });
+        }
 
         let mut stream = resp.bytes_stream();
         let mut chunk_data = Vec::new();
         let mut file_offset = offset;
 
+        // Independent 模式：单独打开文件，定位一次后顺序写入
+        let mut own_file = match &ctx.independent_path {
+            Some(path) => {
+                let mut f = tokio::fs::OpenOptions::new()
+                    .write(true)
+                    .open(path)
+                    .await
+                    .map_err(DownloadError::CreateFile)?;
+                f.seek(std::io::SeekFrom::Start(offset))
+                    .await
+                    .map_err(DownloadError::SeekFile)?;
+                Some(f)
+            }
+            None => None,
+        };
+
         // 流式读取分片数据
-        while let Some(chunk_result) = stream.next().await {
+        loop {
+            let chunk_result = tokio::select! {
+                next = stream.next() => match next {
+                    Some(chunk_result) => chunk_result,
+                    None => break,
+                },
+                e = first_byte_expired(first_byte_deadline) => {
+                    return Err(e);
+                }
+            };
+            first_byte_deadline = None;
+
             // 检查取消
-            if cancelled.load(Ordering::SeqCst) {
+            if ctx.cancelled.load(Ordering::SeqCst) {
                 return Err(DownloadError::Cancelled);
             }
 
             // 等待暂停结束（使用 Notify 精确唤醒）
-            while paused.load(Ordering::SeqCst) {
-                if cancelled.load(Ordering::SeqCst) {
+            while ctx.paused.load(Ordering::SeqCst) {
+                if ctx.cancelled.load(Ordering::SeqCst) {
                     return Err(DownloadError::Cancelled);
                 }
-                resume_notifier.notified().await;
+                ctx.resume_notifier.notified().await;
             }
 
             let chunk = chunk_result?;
             let len = chunk.len() as u64;
+            // 超出区间的部分会覆盖相邻分片，写入前拒绝
+            if attempt.bytes + len > expected_len {
+                return Err(DownloadError::SizeMismatch {
+                    expected: expected_len,
+                    actual: attempt.bytes + len,
+                });
+            }
+            attempt.bytes += len;
+            ctx.progress.mark_first_byte();
 
-            // 写入文件（使用互斥锁保护）
-            if let Some(ref f) = file {
+            if let Some(guard) = &ctx.disk_space_guard {
+                guard.check(ctx.bytes_counter.load(Ordering::Relaxed))?;
+            }
+
+            // 写入文件：独立句柄直接顺序写入，共享句柄加锁后定位再写
+            if let Some(f) = own_file.as_mut() {
+                let _permit = match &ctx.file_write_limiter {
+                    Some(limiter) => limiter.acquire().await,
+                    None => None,
+                };
+                f.write_all(&chunk)
+                    .await
+                    .map_err(DownloadError::WriteFile)?;
+            } else if let Some(ref f) = ctx.file {
+                let _permit = match &ctx.file_write_limiter {
+                    Some(limiter) => limiter.acquire().await,
+                    None => None,
+                };
                 let mut file_guard = f.lock().await;
                 file_guard.seek(std::io::SeekFrom::Start(file_offset))
                     .await
@@ -717,20 +1911,36 @@ impl RemoteDownloaderController {
             }
 
             // 保存到内存（如果需要）
-            if output_bytes {
+            if ctx.output_bytes {
                 chunk_data.extend_from_slice(&chunk);
             }
 
             // 更新全局进度
-            let current = bytes_counter.fetch_add(len, Ordering::Relaxed) + len;
-            let _ = progress_state.update(current);
+            let current = ctx.bytes_counter.fetch_add(len, Ordering::Relaxed) + len;
+            ctx.progress.report(current);
+
+            // 限速：所有分片共享同一个限制器
+            if let Some(limiter) = &ctx.bandwidth_limiter {
+                limiter.consume(len).await;
+            }
 
             file_offset += len;
         }
 
-        // 保存分片数据
-        if output_bytes {
-            segments.lock().await.push((offset, chunk_data));
+        if attempt.bytes != expected_len {
+            return Err(DownloadError::SizeMismatch {
+                expected: expected_len,
+                actual: attempt.bytes,
+            });
+        }
+
+        if let Some(mut f) = own_file {
+            f.flush().await.map_err(DownloadError::FlushFile)?;
+        }
+
+        // 保存分片数据：失败的尝试在此之前已经返回，不会留下残缺的段
+        if ctx.output_bytes {
+            ctx.segments.lock().await.insert(offset, chunk_data);
         }
 
         Ok(())
@@ -738,32 +1948,59 @@ impl RemoteDownloaderController {
 }
 
 /// 响应式属性订阅：外部监听状态变化
+///
+/// 每个 `subscribe_*` 都会启动一个后台任务并返回其 `JoinHandle`。
+/// 下载结束（完成、取消或出错）后，任务会先把最后一次变化（如 `Canceled`）
+/// 交给回调，然后自行退出，因此 `send()` 返回后可以直接 `.await` 这些句柄。
 impl RemoteDownloaderController {
     /// 订阅下载状态变化
+    ///
+    /// 与其他订阅不同，状态的每一次切换都会按顺序交给回调，
+    /// 回调较慢时也不会只看到最后一个状态（见
+    /// [`BroadcastReactiveProperty`]）。
     pub fn subscribe_download_status<F>(
         &self,
         return_current_value: bool,
         callback: F,
-    ) where
+    ) -> JoinHandle<()>
+    where
         F: Fn(&DownloadStatus) + Send + 'static,
     {
-        let mut watcher = self.reactive_state.download_status.watch();
+        let status = &self.reactive_state.download_status;
+        // 先订阅再读当前值，避免两者之间的切换被漏掉
+        let mut transitions = status.subscribe();
+        let current = return_current_value
+            .then(|| status.get_current())
+            .flatten();
+        let mut terminated = self.reactive_state.terminated.subscribe();
 
         tokio::spawn(async move {
-            if return_current_value {
-                if let Some(current) = watcher.borrow() {
-                    callback(&current);
-                }
+            if let Some(current) = current {
+                callback(&current);
             }
 
-            // 然后监听后续变化
             loop {
-                match watcher.changed().await {
-                    Ok(status) => callback(&status),
-                    Err(_) => break,
+                tokio::select! {
+                    biased;
+
+                    status = transitions.recv() => match status {
+                        Ok(status) => callback(&status),
+                        // 落后时跳过丢失的部分，继续接收
+                        Err(ReactivePropertyError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    },
+                    _ = async {
+                        let _ = terminated.wait_for(|done| *done).await;
+                    } => {
+                        // 结束前把缓冲区中剩余的切换全部交给回调
+                        while let Some(status) = transitions.try_recv() {
+                            callback(&status);
+                        }
+                        break;
+                    }
                 }
             }
-        });
+        })
     }
 
     /// 订阅已下载字节数变化
@@ -771,44 +2008,283 @@ impl RemoteDownloaderController {
         &self,
         return_current_value: bool,
         callback: F,
-    ) where
+    ) -> JoinHandle<()>
+    where
         F: Fn(u64) + Send + 'static,
     {
-        let mut watcher = self.reactive_state.downloaded_bytes.watch();
+        self.spawn_subscriber(
+            self.reactive_state.downloaded_bytes.watch(),
+            return_current_value,
+            callback,
+        )
+    }
 
-        tokio::spawn(async move {
-            if return_current_value {
-                // 先发送当前值
-                if let Some(current) = watcher.borrow() {
-                    callback(current);
+    /// 按百分比步进订阅下载进度
+    ///
+    /// 仅当整数百分比前进了 `step_percent`（如每 5%）时调用回调，回调参数为
+    /// 已达到的百分比；到达 100% 时总会触发一次。`step_percent` 为 0 时按 1 处理。
+    ///
+    /// 百分比基于 [`DownloadProgress::percent`] 计算，总大小未知时不会触发回调。
+    pub fn subscribe_progress_steps<F>(
+        &self,
+        step_percent: u8,
+        callback: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn(u8) + Send + 'static,
+    {
+        let step = step_percent.clamp(1, 100);
+        let mut last_emitted = 0u8;
+
+        self.spawn_subscriber(
+            self.reactive_state.progress.watch(),
+            false,
+            move |progress: DownloadProgress| {
+                let Some(percent) = progress.percent() else { return };
+                let percent = percent.floor() as u8;
+                // 对齐到步进，100% 不受步进限制
+                let reached =
+                    if percent >= 100 { 100 } else { percent / step * step };
+
+                if reached > last_emitted {
+                    last_emitted = reached;
+                    callback(reached);
                 }
-            }
+            },
+        )
+    }
 
-            loop {
-                match watcher.changed().await {
-                    Ok(bytes) => callback(bytes),
-                    Err(_) => break,
-                }
-            }
-        });
+    /// 订阅下载进度变化（包含总大小，便于计算百分比）
+    pub fn subscribe_progress<F>(
+        &self,
+        return_current_value: bool,
+        callback: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn(&DownloadProgress) + Send + 'static,
+    {
+        self.spawn_subscriber(
+            self.reactive_state.progress.watch(),
+            return_current_value,
+            move |progress| callback(&progress),
+        )
+    }
+
+    /// 订阅下载速度变化（约每秒一次，下载结束时以速度 0 收尾）
+    pub fn subscribe_speed<F>(
+        &self,
+        return_current_value: bool,
+        callback: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn(&DownloadSpeed) + Send + 'static,
+    {
+        self.spawn_subscriber(
+            self.reactive_state.speed.watch(),
+            return_current_value,
+            move |speed| callback(&speed),
+        )
     }
 
     /// 订阅命令队列（外部可以监听最近一条命令）
-    pub fn subscribe_commands<F>(&self, callback: F)
+    pub fn subscribe_commands<F>(&self, callback: F) -> JoinHandle<()>
     where
         F: Fn(&ControlCommand) + Send + 'static,
     {
-        let mut watcher = self.reactive_state.command_queue.watch();
+        self.spawn_subscriber(
+            self.reactive_state.command_queue.watch(),
+            false,
+            move |cmd: Option<ControlCommand>| {
+                if let Some(cmd) = cmd {
+                    callback(&cmd);
+                }
+            },
+        )
+    }
+
+    /// 启动订阅任务：转发属性变化，直到下载结束或属性被释放
+    fn spawn_subscriber<T, F>(
+        &self,
+        mut watcher: PropertyWatcher<T>,
+        return_current_value: bool,
+        mut on_value: F,
+    ) -> JoinHandle<()>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnMut(T) + Send + 'static,
+    {
+        let mut terminated = self.reactive_state.terminated.subscribe();
 
         tokio::spawn(async move {
+            if return_current_value
+                && let Some(current) = watcher.borrow()
+            {
+                on_value(current);
+            }
+
             loop {
-                match watcher.changed().await {
-                    Ok(Some(cmd)) => callback(&cmd),
-                    Ok(None) => {}
-                    Err(_) => break,
+                tokio::select! {
+                    biased;
+
+                    value = watcher.changed() => match value {
+                        Ok(value) => on_value(value),
+                        Err(_) => break,
+                    },
+                    _ = async {
+                        // Ref 不能跨 await 持有，这里只等待信号
+                        let _ = terminated.wait_for(|done| *done).await;
+                    } => {
+                        // 结束前把最后一次变化（终止状态）交给回调
+                        if watcher.has_changed()
+                            && let Ok(value) = watcher.changed().await
+                        {
+                            on_value(value);
+                        }
+                        break;
+                    }
                 }
             }
-        });
+        })
     }
 }
 
+
+/// `output_stream` 模式下流的内部状态：持有控制器与命令队列，
+/// 每次被拉取时处理命令并读取下一块数据
+struct StreamingBody {
+    controller: Arc<RemoteDownloaderController>,
+    consumer: OwnedMutexGuard<QueueReactiveConsumer<ControlCommand>>,
+    stream: Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>,
+    progress: ProgressReporter,
+    first_byte_deadline: Option<FirstByteDeadline>,
+    hashers: Vec<ContentHasher>,
+    limiter: Option<BandwidthLimiter>,
+    throttle_until: Option<tokio::time::Instant>,
+    bytes_done: u64,
+    started_at: Instant,
+    sampler: JoinHandle<Infallible>,
+    /// 已读完或已出错，之后不再产出任何数据
+    finished: bool,
+}
+
+impl StreamingBody {
+    async fn next_chunk(
+        &mut self,
+    ) -> Option<Result<Bytes, DownloadError>> {
+        if self.finished {
+            return None;
+        }
+        let chunk = match self.read_chunk().await {
+            Ok(Some(chunk)) => return Some(Ok(chunk)),
+            Ok(None) => None,
+            Err(e) => Some(Err(self.controller.abort_error(e))),
+        };
+        self.finished = true;
+        self.stop_background();
+        if let Some(Err(e)) = &chunk {
+            self.controller.notify_error(e);
+        }
+        chunk
+    }
+
+    async fn read_chunk(
+        &mut self,
+    ) -> Result<Option<Bytes>, DownloadError> {
+        let state = &self.controller.reactive_state;
+        loop {
+            tokio::select! {
+                biased;
+
+                cmd = self.consumer.recv() => match cmd {
+                    Some(ControlCommand::Pause) => {
+                        let _ = state
+                            .download_status
+                            .update(DownloadStatus::Paused);
+                        let cancelled = Arc::new(AtomicBool::new(false));
+                        self.controller
+                            .wait_for_resume_or_cancel(
+                                &mut self.consumer,
+                                &cancelled,
+                            )
+                            .await?;
+                    }
+                    Some(ControlCommand::Resume) => {}
+                    Some(ControlCommand::Cancel) | None => {
+                        let _ = state
+                            .download_status
+                            .update(DownloadStatus::Canceled);
+                        return Err(DownloadError::Cancelled);
+                    }
+                },
+
+                e = first_byte_expired(self.first_byte_deadline) => {
+                    return Err(e);
+                }
+
+                _ = throttle_elapsed(self.throttle_until) => {
+                    self.throttle_until = None;
+                }
+
+                chunk = self.stream.next(),
+                    if self.throttle_until.is_none() =>
+                {
+                    let chunk = match chunk {
+                        Some(chunk) => chunk?,
+                        None => break,
+                    };
+                    self.first_byte_deadline = None;
+                    self.progress.mark_first_byte();
+                    let len = chunk.len() as u64;
+                    self.bytes_done += len;
+                    for hasher in self.hashers.iter_mut() {
+                        hasher.update(&chunk);
+                    }
+                    self.progress.report(self.bytes_done);
+                    self.throttle_until =
+                        self.limiter.as_ref().and_then(|l| l.reserve(len));
+                    return Ok(Some(chunk));
+                }
+            }
+        }
+
+        // 流结束：校验大小、摘要与 ETag 后标记完成
+        let digests = std::mem::take(&mut self.hashers)
+            .into_iter()
+            .map(|hasher| (hasher.algo(), hasher.finalize_hex()))
+            .collect();
+        self.controller
+            .finish_single_thread(
+                &None,
+                digests,
+                Vec::new(),
+                self.bytes_done,
+                0,
+                self.started_at,
+            )
+            .await?;
+        Ok(None)
+    }
+
+    /// 停止速度采样并通知订阅任务退出
+    fn stop_background(&self) {
+        self.sampler.abort();
+        let state = &self.controller.reactive_state;
+        let _ = state.speed.update(DownloadSpeed::default());
+        state.terminated.send_replace(true);
+    }
+}
+
+impl Drop for StreamingBody {
+    /// 读完之前被丢弃时按取消处理
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self
+                .controller
+                .reactive_state
+                .download_status
+                .update(DownloadStatus::Canceled);
+            self.stop_background();
+            self.controller.notify_error(&DownloadError::Cancelled);
+        }
+    }
+}
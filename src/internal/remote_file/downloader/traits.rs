@@ -1 +1,4 @@
-pub mod download;
\ No newline at end of file
+pub mod download;
+
+// 重导出公共 trait
+pub use download::{DownloadHook, HookAbort, SharedDownloadHook};
@@ -0,0 +1,86 @@
+//! 下载钩子：观察下载过程中的事件。
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::internal::remote_file::downloader::structs::{
+    DownloadError, ProgressReport,
+};
+
+/// 下载钩子，通过 `RemoteDownloader::with_hook` 注册
+///
+/// 所有方法都有空的默认实现，只需实现关心的事件。钩子在下载任务中同步调用
+/// （分片下载时可能被多个分片同时调用），不要在其中做耗时操作。
+/// 未注册钩子时不会产生任何额外开销。
+pub trait DownloadHook: Send + Sync {
+    /// 分片下载即将发送一个 Range 请求
+    ///
+    /// `start`、`end` 与请求头 `Range: bytes=start-end` 一致（闭区间），
+    /// `attempt` 从 1 开始，重试时递增。
+    fn on_range_request(&self, _start: u64, _end: u64, _attempt: usize) {}
+
+    /// 一次 Range 请求结束（无论成功还是失败）
+    ///
+    /// `bytes` 为本次请求实际收到的字节数；`status` 为响应的 HTTP 状态码，
+    /// 连接失败等没有收到响应的情况为 `None`。
+    fn on_range_complete(
+        &self,
+        _start: u64,
+        _end: u64,
+        _bytes: u64,
+        _status: Option<u16>,
+    ) {
+    }
+
+    /// 下载以错误结束（包括取消与 `abort_if` 中止），每次下载最多调用一次
+    ///
+    /// 单线程与分片下载都会调用，在未完成的文件按策略清理之后、`send`
+    /// 返回之前；流式下载在流产出错误时调用，读完之前丢弃流时以
+    /// [`DownloadError::Cancelled`] 调用。可在这里释放下载前获取的锁等资源。
+    /// 成功完成的下载不会调用。
+    fn on_error(&self, _err: &DownloadError) {}
+
+    /// 每次进度更新时调用，返回 `Err` 时中止下载
+    ///
+    /// 与 `abort_if` 相同，中止走取消流程（分片任务有序停止、未完成的文件
+    /// 被删除），但返回 [`DownloadError::HookAborted`]，带上 [`HookAbort`]
+    /// 中的原因。默认总是继续。
+    fn on_progress(
+        &self,
+        _report: &ProgressReport,
+    ) -> Result<(), HookAbort> {
+        Ok(())
+    }
+}
+
+/// 钩子在 [`DownloadHook::on_progress`] 中要求中止下载
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookAbort {
+    /// 中止原因，原样放入 [`DownloadError::HookAborted`]
+    pub reason: String,
+}
+
+impl HookAbort {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self { reason: reason.into() }
+    }
+}
+
+/// 已注册的下载钩子，可在分片任务间 clone 共享
+#[derive(Clone)]
+pub struct SharedDownloadHook(pub(crate) Arc<dyn DownloadHook>);
+
+impl SharedDownloadHook {
+    pub fn new<H>(hook: H) -> Self
+    where
+        H: DownloadHook + 'static,
+    {
+        Self(Arc::new(hook))
+    }
+}
+
+impl fmt::Debug for SharedDownloadHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedDownloadHook(<dyn DownloadHook>)")
+    }
+}
@@ -1,2 +1,8 @@
+pub mod folder_view;
+pub mod listing_cache;
+pub mod listing_cancel;
+pub mod listing_diff;
+pub mod recursion_policy;
 pub mod remote_file_data;
-pub mod remote_file;
\ No newline at end of file
+pub mod remote_file;
+pub mod sanitize_policy;
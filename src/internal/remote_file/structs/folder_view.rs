@@ -0,0 +1,49 @@
+use url::Url;
+
+use crate::remote_file::RemoteFileData;
+use crate::webdav::functions::normalize_webdav_path;
+use crate::webdav::structs::MultiStatus;
+use crate::webdav::traits::ToRemoteFileData;
+
+/// 一个目录及其直接子项，来自一次 Depth: 1 的 PROPFIND
+///
+/// 目录自身通过与请求地址比较 href（规范化后）来识别，而不是假定它是第一项，
+/// 因此不受服务器返回顺序影响。适合文件浏览器同时展示当前目录信息与内容。
+#[derive(Debug, Clone)]
+pub struct FolderView {
+    /// 目录自身
+    pub folder: RemoteFileData,
+    /// 直接子项，按服务器返回的顺序
+    pub children: Vec<RemoteFileData>,
+}
+
+impl FolderView {
+    /// 从 multistatus 构建，`requested_url` 为发送 PROPFIND 的地址
+    ///
+    /// 响应中找不到与请求地址对应的目录时返回错误
+    pub fn from_multi_status(
+        multi_status: MultiStatus,
+        base_url: &Url,
+        requested_url: &str,
+    ) -> Result<Self, String> {
+        let requested = normalize_webdav_path(requested_url);
+
+        let mut folder = None;
+        let mut children = Vec::new();
+        for data in multi_status.to_all_remote_file_data(base_url) {
+            let is_container = folder.is_none()
+                && normalize_webdav_path(&data.absolute_path) == requested;
+            if is_container {
+                folder = Some(data);
+            } else {
+                children.push(data);
+            }
+        }
+
+        let folder = folder.ok_or_else(|| {
+            format!("响应中没有目录自身: {}", requested_url)
+        })?;
+
+        Ok(Self { folder, children })
+    }
+}
@@ -0,0 +1,102 @@
+//! 目录列表的读穿透缓存。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::remote_file::RemoteFile;
+use crate::webdav::functions::collection_url;
+
+/// 目录列表缓存，配合 [`get_remote_files_cached`](crate::get_remote_files_cached) 使用
+///
+/// - 在 `ttl` 内再次读取同一目录时直接返回缓存，不发请求
+/// - 超过 `ttl` 后用目录的 ETag 发送 `If-None-Match` 的 PROPFIND 重新验证，
+///   服务器返回 304 时继续使用缓存，否则替换为新结果
+/// - 依赖目录 ETag 随内容变化；目录没有 ETag 时过期后直接重新读取
+///
+/// 可以在多个任务间共享（内部加锁），不同认证/服务器请使用不同的缓存。
+#[derive(Debug)]
+pub struct ListingCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CachedListing>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedListing {
+    files: Vec<RemoteFile>,
+    etag: Option<String>,
+    fetched_at: Instant,
+}
+
+/// 查询缓存的结果
+pub(crate) enum CacheLookup {
+    /// 在 ttl 内，可直接使用
+    Fresh(Vec<RemoteFile>),
+    /// 已过期，带着目录 ETag（如有）去重新验证
+    Stale(Option<String>),
+    /// 没有缓存
+    Miss,
+}
+
+impl ListingCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// 缓存的有效期
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// 丢弃某个目录的缓存，`absolute_url` 的尾斜杠可以省略
+    pub fn invalidate(&self, absolute_url: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(&collection_url(absolute_url));
+        }
+    }
+
+    /// 清空全部缓存
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+
+    pub(crate) fn lookup(&self, url: &str) -> CacheLookup {
+        let Ok(entries) = self.entries.lock() else {
+            return CacheLookup::Miss;
+        };
+        match entries.get(url) {
+            Some(entry) if entry.fetched_at.elapsed() < self.ttl => {
+                CacheLookup::Fresh(entry.files.clone())
+            }
+            Some(entry) => CacheLookup::Stale(entry.etag.clone()),
+            None => CacheLookup::Miss,
+        }
+    }
+
+    /// 服务器确认缓存仍然有效：重新计时并返回缓存的列表
+    pub(crate) fn revalidated(
+        &self,
+        url: &str,
+    ) -> Option<Vec<RemoteFile>> {
+        let mut entries = self.entries.lock().ok()?;
+        let entry = entries.get_mut(url)?;
+        entry.fetched_at = Instant::now();
+        Some(entry.files.clone())
+    }
+
+    pub(crate) fn store(
+        &self,
+        url: &str,
+        files: Vec<RemoteFile>,
+        etag: Option<String>,
+    ) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(
+                url.to_string(),
+                CachedListing { files, etag, fetched_at: Instant::now() },
+            );
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! 可取消的目录列举：取消令牌与部分结果。
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+use super::remote_file::RemoteFile;
+
+/// 取消令牌，clone 后共享同一个取消状态
+///
+/// 在任意线程调用 [`cancel`](Self::cancel) 后，正在等待该令牌的列举会丢弃
+/// 尚未完成的 PROPFIND（reqwest 在请求 future 被 drop 时中止请求），
+/// 并返回已经解析出的条目。
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    cancelled: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self { cancelled: Arc::new(watch::channel(false).0) }
+    }
+
+    /// 发出取消，重复调用无副作用
+    pub fn cancel(&self) {
+        self.cancelled.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    /// 等待取消发生；已取消时立即返回
+    pub async fn cancelled(&self) {
+        let mut receiver = self.cancelled.subscribe();
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+/// 可能被取消的列举结果
+#[derive(Debug, Clone)]
+pub struct PartialListing {
+    /// 已经列举到的条目，顺序与完整列举时一致（只是更短）
+    pub collected: Vec<RemoteFile>,
+    /// 是否因取消而提前结束
+    pub cancelled: bool,
+}
@@ -0,0 +1,71 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::remote_file::RemoteFileData;
+use crate::webdav::functions::normalize_webdav_path;
+
+/// 两次列举结果的差异，由 [`diff_listings`] 生成
+///
+/// 资源按规范化后的 `relative_root_path` 对应，是否修改由
+/// [`RemoteFileData::changed_since`] 判断。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListingDiff {
+    /// 只在新列表中出现的资源，按新列表的顺序
+    pub added: Vec<RemoteFileData>,
+    /// 只在旧列表中出现的资源，按旧列表的顺序
+    pub removed: Vec<RemoteFileData>,
+    /// 两边都有但内容已变化的资源（取新列表中的值），按新列表的顺序
+    pub modified: Vec<RemoteFileData>,
+}
+
+impl ListingDiff {
+    /// 两次列举之间没有任何变化
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+    }
+}
+
+/// 比较同一目录（或同一棵目录树）的两次列举结果
+///
+/// 适合同步工具判断需要下载、删除或重新下载的资源。同一列表中路径重复时
+/// 以第一次出现的为准。
+pub fn diff_listings(
+    old: &[RemoteFileData],
+    new: &[RemoteFileData],
+) -> ListingDiff {
+    let key = |data: &RemoteFileData| {
+        normalize_webdav_path(&data.relative_root_path)
+    };
+
+    let mut old_by_path = HashMap::with_capacity(old.len());
+    for data in old {
+        old_by_path.entry(key(data)).or_insert(data);
+    }
+
+    let mut diff = ListingDiff::default();
+    let mut seen = HashSet::with_capacity(new.len());
+    for data in new {
+        let path = key(data);
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        match old_by_path.get(&path) {
+            None => diff.added.push(data.clone()),
+            Some(previous) if data.changed_since(previous) => {
+                diff.modified.push(data.clone())
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut removed = HashSet::new();
+    for data in old {
+        let path = key(data);
+        if !seen.contains(&path) && removed.insert(path) {
+            diff.removed.push(data.clone());
+        }
+    }
+
+    diff
+}
@@ -0,0 +1,123 @@
+//! 递归列举目录时的策略。
+
+/// 递归时用什么判断一个目录已经访问过
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DedupKey {
+    /// 按规范化后的路径去重（默认）
+    #[default]
+    Path,
+    /// 按目录的 ETag 去重，适合会复用/改写 href 的服务器；
+    /// 没有 ETag 的目录退回按路径去重
+    Etag,
+}
+
+/// 按文件名匹配的通配符模式：`*` 匹配任意多个字符，`?` 匹配单个字符
+///
+/// 只匹配名称本身（不含路径），区分大小写，例如 `.DS_Store`、`*.tmp`、`~$*`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamePattern(String);
+
+impl NamePattern {
+    pub fn new(pattern: &str) -> Self {
+        Self(pattern.to_string())
+    }
+
+    /// 模式原文
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 名称是否与模式匹配
+    pub fn matches(&self, name: &str) -> bool {
+        let pattern: Vec<char> = self.0.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+
+        let (mut p, mut n) = (0, 0);
+        // 最近一个 `*` 的位置，以及它当前吞掉字符后名称的位置
+        let mut backtrack: Option<(usize, usize)> = None;
+
+        while n < name.len() {
+            match pattern.get(p) {
+                Some('*') => {
+                    backtrack = Some((p, n));
+                    p += 1;
+                }
+                Some(&c) if c == '?' || c == name[n] => {
+                    p += 1;
+                    n += 1;
+                }
+                _ => match backtrack {
+                    // 让上一个 `*` 多吞一个字符后重试
+                    Some((star, matched)) => {
+                        p = star + 1;
+                        n = matched + 1;
+                        backtrack = Some((star, matched + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        pattern[p..].iter().all(|&c| c == '*')
+    }
+}
+
+impl From<&str> for NamePattern {
+    fn from(pattern: &str) -> Self {
+        Self::new(pattern)
+    }
+}
+
+/// 递归列举策略
+///
+/// ```rust,no_run
+/// use webdav_fs::remote_file::{DedupKey, NamePattern, RecursionPolicy};
+///
+/// let policy = RecursionPolicy {
+///     max_depth: Some(3),
+///     visited_dedup_by: DedupKey::Etag,
+///     include_hidden: false,
+///     exclude: vec![NamePattern::new("Thumbs.db"), "*.tmp".into()],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecursionPolicy {
+    /// 最大递归深度：`Some(1)` 只列举直接子项，`None` 不限制
+    pub max_depth: Option<usize>,
+    /// 是否进入共享/挂载目录
+    ///
+    /// 子目录的 href 不在父目录路径之下时（服务器端链接、共享挂载，
+    /// 或指回上层的目录），视为共享目录。为 `false` 时仍会返回该目录本身，
+    /// 但不会进入其中。
+    pub follow_shares: bool,
+    /// 已访问目录的去重方式
+    pub visited_dedup_by: DedupKey,
+    /// 是否包含以 `.` 开头的隐藏文件与目录；为 `false` 时隐藏目录也不会进入
+    pub include_hidden: bool,
+    /// 名称匹配任一模式的条目不会返回，匹配的目录也不会进入
+    pub exclude: Vec<NamePattern>,
+}
+
+impl RecursionPolicy {
+    /// 按名称判断条目是否保留（隐藏文件与排除模式）
+    pub fn keeps(&self, name: &str) -> bool {
+        let name = name.trim_end_matches('/');
+        if !self.include_hidden && name.starts_with('.') {
+            return false;
+        }
+        !self.exclude.iter().any(|pattern| pattern.matches(name))
+    }
+}
+
+impl Default for RecursionPolicy {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            follow_shares: false,
+            visited_dedup_by: DedupKey::Path,
+            include_hidden: true,
+            exclude: Vec::new(),
+        }
+    }
+}
@@ -1,12 +1,23 @@
 use std::sync::Arc;
 
+use reqwest::Client;
+use url::Url;
+
 use crate::{
     auth::structs::webdav_auth::WebdavAuth,
     remote_file::RemoteFileData,
-    webdav::{structs::MultiStatus, traits::ToRemoteFileData},
+    webdav::{
+        errors::WebDavError,
+        functions::{get_allowed_methods, stat},
+        structs::{AllowedMethods, MultiStatus},
+        traits::ToRemoteFileData,
+    },
 };
 
-use crate::internal::remote_file::downloader::structs::RemoteDownloader;
+use crate::internal::remote_file::downloader::structs::{
+    DeltaReport, DeltaSyncConfig, DownloadError, RemoteDownloader,
+    delta_sync::run_delta_sync,
+};
 
 #[derive(Debug, Clone)]
 pub struct RemoteFile {
@@ -15,13 +26,16 @@ pub struct RemoteFile {
 }
 
 impl RemoteFile {
+    /// 从 multistatus 构建文件列表，`requested_url` 为发送 PROPFIND 的地址
+    ///
+    /// 规则见 [`ToRemoteFileData::to_remote_file_data`]。
     pub fn from_multi_status(
         webdav_auth: &WebdavAuth,
         multi_status: MultiStatus,
+        requested_url: &str,
     ) -> Result<Vec<Self>, String> {
         let resources = multi_status
-            .to_remote_file_data(&webdav_auth.base_url)
-            .map_err(|e| e)?;
+            .to_remote_file_data(&webdav_auth.base_url, requested_url)?;
 
         let files = resources
             .iter()
@@ -34,12 +48,104 @@ impl RemoteFile {
         Ok(files)
     }
 
+    /// 文件大小：PROPFIND 已给出时直接返回，否则通过
+    /// [`stat`](crate::webdav::functions::stat) 查询
+    ///
+    /// - 使用 `auth` 上配置的 [`StatMethod`](crate::auth::StatMethod)，
+    ///   HEAD 时读取 `Content-Length`，PROPFIND 时读取 `getcontentlength`
+    /// - 两种方法都失败时为 [`WebDavError::Status`] 等
+    /// - 成功但没有可解析的大小时为 [`WebDavError::UnknownSize`]
+    /// - 结果不会写回 `self.data`
+    pub async fn size_or_fetch(
+        &self,
+        auth: &WebdavAuth,
+    ) -> Result<u64, WebDavError> {
+        if let Some(size) = self.data.size {
+            return Ok(size);
+        }
+
+        stat(auth, &self.data.absolute_path).await?.size.ok_or_else(|| {
+            WebDavError::UnknownSize(self.data.absolute_path.clone())
+        })
+    }
+
+    /// 对本资源发送 OPTIONS，把允许的方法写入 `data.allowed_methods` 并返回
+    ///
+    /// 服务器没有返回 `Allow` 头时结果为 `None`。`data` 被其他 `RemoteFile`
+    /// 共享时会先复制一份，不影响其他副本。
+    pub async fn fetch_allowed_methods(
+        &mut self,
+    ) -> Result<Option<&AllowedMethods>, WebDavError> {
+        let allowed = get_allowed_methods(
+            &self.webdav_auth,
+            &self.data.absolute_path,
+        )
+        .await?;
+
+        let data = Arc::make_mut(&mut self.data);
+        data.allowed_methods = allowed;
+        Ok(data.allowed_methods.as_ref())
+    }
+
     /// 构建一个空的下载器
     pub fn build_downloader(&self) -> RemoteDownloader {
         RemoteDownloader::new(self.data.clone(), self.webdav_auth.clone())
     }
 
+    /// 从外部直链（如服务商签发的临时下载地址）下载本文件
+    ///
+    /// 使用与普通下载相同的单线程/分片/进度机制，返回的下载器可以继续链式配置。
+    /// 请求使用一个不带任何默认请求头的独立客户端，WebDAV 的认证信息
+    /// 不会发送到外部主机。文件名、大小等信息仍取自 `self.data`。
+    ///
+    /// - 仅支持 http/https 地址
+    pub fn download_from_url(
+        &self,
+        url: &str,
+    ) -> Result<RemoteDownloader, String> {
+        let parsed = Url::parse(url).map_err(|e| e.to_string())?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!("不支持的直链协议: {}", parsed.scheme()));
+        }
+
+        let client = Client::builder()
+            .http1_only()
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(RemoteDownloader::with_source(
+            self.data.clone(),
+            client,
+            None,
+            parsed.to_string(),
+        ))
+    }
+
+    /// 实验性：增量同步到已有的本地副本
+    ///
+    /// 按 `config.block_size` 分块发送 Range 请求，只覆盖与本地不一致的块；
+    /// `config.known_etag` 与远程 ETag 相同且大小一致时直接跳过。
+    /// 详见 [`DeltaSyncConfig`] 与 [`DeltaReport`]。
+    pub async fn delta_sync(
+        &self,
+        local_path: &str,
+        config: DeltaSyncConfig,
+    ) -> Result<DeltaReport, DownloadError> {
+        run_delta_sync(
+            &self.webdav_auth,
+            &self.data.absolute_path,
+            &self.data,
+            local_path,
+            &config,
+        )
+        .await
+    }
+
     /// 创建下载器（便捷方法）
+    ///
+    /// 与 [`RemoteFile::build_downloader`] 返回同一种 [`RemoteDownloader`]，
+    /// 区别只是使用传入的认证而不是列举时的认证；暂停/恢复/取消、下载钩子、
+    /// 分片大小与续传等配置两者完全相同。
     pub fn download(&self, auth: WebdavAuth) -> RemoteDownloader {
         RemoteDownloader::new(self.data.clone(), auth)
     }
@@ -1,7 +1,22 @@
 use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
 use url::Url;
 
-#[derive(Debug, Clone)]
+use crate::internal::webdav::capabilities::AllowedMethods;
+use crate::webdav::functions::normalize_webdav_path;
+
+/// 一次列举得到的单个远程资源的信息
+///
+/// 可以用 serde 序列化（例如把目录列表以 JSON 缓存到磁盘，下次启动时
+/// 与新的列举结果比较）。格式是稳定的：
+///
+/// - 字段名与结构体字段名相同（snake_case）
+/// - `base_url` 为完整 URL 字符串（以 `/` 结尾）
+/// - `last_modified`、`creation_date` 为 RFC 3339 字符串，保留原时区偏移
+/// - `allowed_methods` 为方法名数组，没有值的 `Option` 字段为 `null`
+/// - 反序列化时缺少的 `Option` 字段视为 `None`、缺少的 `privileges`
+///   视为空列表；之后新增的字段同样可以缺省，旧的缓存仍然可以读取
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RemoteFileData {
     pub base_url: Url,
     pub relative_root_path: String, // 文件的相对路径（相对根目录）
@@ -10,8 +25,66 @@ pub struct RemoteFileData {
     pub is_dir: bool,               // 是否目录
     pub size: Option<u64>,          // 文件大小（字节）
     pub last_modified: Option<DateTime<FixedOffset>>, // 原始时间
+    pub creation_date: Option<DateTime<FixedOffset>>, // 创建时间
     pub mime: Option<String>,       // MIME 类型
     pub owner: Option<String>,      // 所有者
     pub etag: Option<String>,       // 清理后的 ETag
-    pub privileges: Vec<String>,    // 权限列表
+    pub raw_etag: Option<String>,   // 服务器原样返回的 ETag（含引号与 W/）
+    #[serde(default)]
+    pub privileges: Vec<String>, // 权限列表
+    /// 资源允许的方法（OPTIONS 的 `Allow` 头），未查询或服务器未返回时为 `None`，
+    /// 见 [`RemoteFile::fetch_allowed_methods`](crate::remote_file::RemoteFile::fetch_allowed_methods)
+    pub allowed_methods: Option<AllowedMethods>,
+}
+
+impl RemoteFileData {
+    /// 用于 `If-Match` / `If-None-Match` 请求头的 ETag
+    ///
+    /// 条件请求必须原样回传服务器给出的格式（引号、弱标记 `W/`），
+    /// 否则部分服务器会返回 412。没有原始值时退回到给清理后的值加引号。
+    pub fn conditional_etag(&self) -> Option<String> {
+        self.raw_etag
+            .clone()
+            .or_else(|| self.etag.as_ref().map(|e| format!("\"{}\"", e)))
+    }
+
+    /// 与另一次列举中的同一资源相比，内容是否可能已经变化
+    ///
+    /// 两边都有 ETag 时只比较 ETag；否则依次比较修改时间与大小，
+    /// 任意一项不同（包括一边有值一边没有）即视为变化。
+    /// 文件与目录互相替换时总是视为变化。
+    pub fn changed_since(&self, other: &Self) -> bool {
+        if self.is_dir != other.is_dir {
+            return true;
+        }
+        if let (Some(etag), Some(other_etag)) = (&self.etag, &other.etag) {
+            return etag != other_etag;
+        }
+        self.last_modified != other.last_modified
+            || self.size != other.size
+    }
 }
+
+/// 比较除 `base_url`、`absolute_path` 与 `raw_etag` 之外的所有字段
+///
+/// `relative_root_path` 按 [`normalize_webdav_path`] 规范化后比较
+/// （百分号编码、重复或尾部斜杠的差异不影响结果），因此同一资源从不同
+/// 地址列举（如经过反向代理）时仍然相等。ETag 只比较清理后的值。
+impl PartialEq for RemoteFileData {
+    fn eq(&self, other: &Self) -> bool {
+        normalize_webdav_path(&self.relative_root_path)
+            == normalize_webdav_path(&other.relative_root_path)
+            && self.name == other.name
+            && self.is_dir == other.is_dir
+            && self.size == other.size
+            && self.last_modified == other.last_modified
+            && self.creation_date == other.creation_date
+            && self.mime == other.mime
+            && self.owner == other.owner
+            && self.etag == other.etag
+            && self.privileges == other.privileges
+            && self.allowed_methods == other.allowed_methods
+    }
+}
+
+impl Eq for RemoteFileData {}
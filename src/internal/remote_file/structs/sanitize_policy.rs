@@ -0,0 +1,251 @@
+//! 把远程文件名映射为本地文件系统可用的名称。
+
+use std::collections::{HashMap, HashSet};
+
+/// Windows 不允许出现在文件名中的字符（另有 0x00-0x1F 控制字符）
+const INVALID_CHARS: &[char] =
+    &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Windows 保留的设备名，不区分大小写，带扩展名（如 `con.txt`）同样保留
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5",
+    "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2", "LPT3", "LPT4",
+    "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 远程名称到本地名称的转换策略
+///
+/// 远程服务器上合法的名称在本地不一定合法（例如 Linux 上的 `a:b` 在 Windows 上
+/// 无法创建）。默认按 Windows 的规则处理，在其他系统上也能得到可移植的名称：
+///
+/// ```rust
+/// use webdav_fs::remote_file::SanitizePolicy;
+///
+/// let policy = SanitizePolicy::default();
+/// assert_eq!(policy.sanitize("report: 2024?.txt"), "report_ 2024_.txt");
+/// assert_eq!(policy.sanitize("CON.txt"), "CON_.txt");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizePolicy {
+    /// 非法字符（含控制字符）替换为该字符，`None` 表示保留原样
+    pub replace_invalid: Option<char>,
+    /// 名称的最大字节数（UTF-8），超出时截断主名并保留扩展名，`None` 表示不截断
+    pub max_name_bytes: Option<usize>,
+    /// 是否处理 Windows 保留名（`CON`、`COM1` 等），处理方式为在主名后追加 `_`
+    pub handle_reserved: bool,
+}
+
+impl Default for SanitizePolicy {
+    fn default() -> Self {
+        Self {
+            replace_invalid: Some('_'),
+            max_name_bytes: Some(255),
+            handle_reserved: true,
+        }
+    }
+}
+
+impl SanitizePolicy {
+    /// 不做任何转换的策略
+    pub fn keep_all() -> Self {
+        Self {
+            replace_invalid: None,
+            max_name_bytes: None,
+            handle_reserved: false,
+        }
+    }
+
+    /// 转换单个名称（不含路径分隔符的一级）
+    ///
+    /// 替换非法字符时也会处理结尾的 `.` 和空格（Windows 会静默去掉它们）；
+    /// 转换后为空或为 `.`/`..` 时返回替换字符（默认 `_`）。
+    pub fn sanitize(&self, name: &str) -> String {
+        let mut out = match self.replace_invalid {
+            Some(replacement) => replace_invalid(name, replacement),
+            None => name.to_string(),
+        };
+
+        if self.handle_reserved && is_reserved(&out) {
+            // 在第一个 `.` 之前插入，`nul.tar.gz` 得到 `nul_.tar.gz`
+            let index = out.find('.').unwrap_or(out.len());
+            out.insert(index, '_');
+        }
+
+        if let Some(max) = self.max_name_bytes {
+            out = truncate_name(&out, max);
+        }
+
+        if out.is_empty() || out == "." || out == ".." {
+            return self.replace_invalid.unwrap_or('_').to_string();
+        }
+        out
+    }
+}
+
+/// 一条发生了变化的名称映射：远程相对路径 → 本地相对路径（均以 `/` 分隔）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameMapping {
+    pub remote: String,
+    pub local: String,
+}
+
+/// 按 [`SanitizePolicy`] 为一整棵远程目录树分配本地路径，并记录映射
+///
+/// - 同一个远程目录总是映射到同一个本地目录；
+/// - 两个远程名称转换后落到同一本地名称时（包括只有大小写不同，
+///   Windows 与 macOS 默认不区分大小写），后到的名称追加 `~1`、`~2` 等，
+///   主名会再次截断以保证追加后仍不超过 `max_name_bytes`；
+/// - [`mappings`](Self::mappings) 只包含发生了变化的路径，便于调用方对账。
+#[derive(Debug, Clone, Default)]
+pub struct LocalNameMap {
+    policy: SanitizePolicy,
+    /// 远程相对路径 → 本地相对路径
+    assigned: HashMap<String, String>,
+    /// 已占用的本地路径（小写）
+    taken: HashSet<String>,
+    changes: Vec<NameMapping>,
+}
+
+impl LocalNameMap {
+    pub fn new(policy: SanitizePolicy) -> Self {
+        Self { policy, ..Self::default() }
+    }
+
+    /// 为远程相对路径（如 `docs/a:b.txt`）分配本地相对路径
+    ///
+    /// 对同一远程路径重复调用返回同一结果。
+    pub fn local_path(&mut self, remote_path: &str) -> String {
+        let mut remote_prefix = String::new();
+        let mut local_prefix = String::new();
+
+        for component in remote_path.split('/').filter(|c| !c.is_empty()) {
+            remote_prefix = join(&remote_prefix, component);
+            if let Some(local) = self.assigned.get(&remote_prefix) {
+                local_prefix = local.clone();
+                continue;
+            }
+
+            let name = self.policy.sanitize(component);
+            let local = self.unique(&local_prefix, &name);
+            let changed = local != join(&local_prefix, component);
+
+            self.taken.insert(local.to_lowercase());
+            self.assigned.insert(remote_prefix.clone(), local.clone());
+            if changed {
+                self.changes.push(NameMapping {
+                    remote: remote_prefix.clone(),
+                    local: local.clone(),
+                });
+            }
+            local_prefix = local;
+        }
+
+        local_prefix
+    }
+
+    /// 发生了变化的映射，按分配顺序排列
+    pub fn mappings(&self) -> &[NameMapping] {
+        &self.changes
+    }
+
+    /// 在 `parent` 下为 `name` 找一个未被占用的本地路径
+    fn unique(&self, parent: &str, name: &str) -> String {
+        let candidate = join(parent, name);
+        if !self.taken.contains(&candidate.to_lowercase()) {
+            return candidate;
+        }
+
+        let max = self.policy.max_name_bytes;
+        (1..)
+            .map(|n| {
+                join(parent, &with_suffix(name, &format!("~{}", n), max))
+            })
+            .find(|path| !self.taken.contains(&path.to_lowercase()))
+            .unwrap_or(candidate)
+    }
+}
+
+fn join(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", parent, name)
+    }
+}
+
+/// 替换非法字符与控制字符，并替换结尾的 `.` 与空格
+fn replace_invalid(name: &str, replacement: char) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || INVALID_CHARS.contains(&c) {
+                replacement
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    let trimmed = out.trim_end_matches(['.', ' ']).len();
+    if trimmed < out.len() {
+        let tail = out.len() - trimmed;
+        out.truncate(trimmed);
+        out.extend(std::iter::repeat_n(replacement, tail));
+    }
+    out
+}
+
+/// 是否为 Windows 保留名：比较第一个 `.` 之前的部分
+fn is_reserved(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+}
+
+/// 拆分主名与扩展名（扩展名含 `.`）；以 `.` 开头的隐藏文件视为没有扩展名
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(index) if index > 0 => name.split_at(index),
+        _ => (name, ""),
+    }
+}
+
+/// 在主名后追加 `suffix`，必要时截断主名使结果不超过 `max` 字节
+fn with_suffix(name: &str, suffix: &str, max: Option<usize>) -> String {
+    let (stem, ext) = split_extension(name);
+    let Some(max) = max else {
+        return format!("{}{}{}", stem, suffix, ext);
+    };
+    // 与 truncate_name 相同：扩展名本身放不下时不再特殊对待
+    let (stem, ext) = if suffix.len() + ext.len() < max {
+        (stem, ext)
+    } else {
+        (name, "")
+    };
+
+    let mut end =
+        stem.len().min(max.saturating_sub(suffix.len() + ext.len()));
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}{}", &stem[..end], suffix, ext)
+}
+
+/// 截断到 `max` 字节以内，优先保留扩展名，并保证落在字符边界上
+fn truncate_name(name: &str, max: usize) -> String {
+    if name.len() <= max {
+        return name.to_string();
+    }
+
+    let (stem, ext) = split_extension(name);
+    // 扩展名本身过长时不再特殊对待
+    let (stem, ext) =
+        if ext.len() < max { (stem, ext) } else { (name, "") };
+
+    let mut end = max - ext.len();
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &stem[..end], ext)
+}
@@ -1,4 +1,5 @@
 pub mod reactive_core;
 pub mod unlock_reactive;
 pub mod lock_reactive;
+pub mod broadcast_reactive;
 pub(crate) mod queue_reactive;
\ No newline at end of file
@@ -0,0 +1,131 @@
+//! # BroadcastReactiveProperty
+//!
+//! 保留每一次变化的响应式属性容器：在 [`UnlockReactiveProperty`] 的基础上
+//! 额外通过 [`tokio::sync::broadcast`] 发送每个新值。
+//!
+//! ## 与 UnlockReactiveProperty 的区别
+//! - `UnlockReactiveProperty`（watch）：只保留最新值，慢速监听者会错过中间值。
+//!   适合进度、字节数这类只关心"现在是多少"的高频数据。
+//! - `BroadcastReactiveProperty`：每个订阅者有一个容量有限的缓冲区，
+//!   按顺序收到每一次变化（例如 `Running → Paused → Running` 一个都不少）。
+//!   代价是每次更新都要 clone 一份值放进缓冲区；订阅者落后超过容量时，
+//!   最旧的值被丢弃，[`TransitionWatcher::recv`] 返回
+//!   [`ReactivePropertyError::Lagged`] 告知丢了多少个。
+//!   适合状态切换这类低频但不能丢的数据。
+//!
+//! ## 使用示例
+//! ```rust,no_run
+//! use webdav_fs::states::broadcast_reactive::BroadcastReactiveProperty;
+//!
+//! # async fn example() {
+//! let prop = BroadcastReactiveProperty::new(0);
+//! let mut transitions = prop.subscribe();
+//! prop.update(1).unwrap();
+//! prop.update(2).unwrap();
+//! assert_eq!(transitions.recv().await.unwrap(), 1);
+//! assert_eq!(transitions.recv().await.unwrap(), 2);
+//! # }
+//! ```
+//!
+//! [`UnlockReactiveProperty`]: super::unlock_reactive::UnlockReactiveProperty
+
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+use super::reactive_core::{
+    PropertyWatcher, ReactiveProperty, ReactivePropertyError,
+};
+
+/// 每个订阅者默认缓冲的变化数
+pub const DEFAULT_TRANSITION_BUFFER: usize = 64;
+
+/// 保留每一次变化的响应式属性容器。
+///
+/// 读取当前值与 [`watch`](Self::watch) 的行为和 `UnlockReactiveProperty` 相同；
+/// 需要完整的变化序列时使用 [`subscribe`](Self::subscribe)。
+#[derive(Clone, Debug)]
+pub struct BroadcastReactiveProperty<T: Clone + Send + Sync> {
+    latest: ReactiveProperty<T>,
+    sender: broadcast::Sender<T>,
+}
+
+impl<T> BroadcastReactiveProperty<T>
+where
+    T: Clone + Send + Sync,
+{
+    /// 创建属性，每个订阅者缓冲 [`DEFAULT_TRANSITION_BUFFER`] 个变化。
+    pub fn new(value: T) -> Self {
+        Self::with_capacity(value, DEFAULT_TRANSITION_BUFFER)
+    }
+
+    /// 创建属性，每个订阅者缓冲 `capacity` 个变化（为 0 时按 1 处理）。
+    pub fn with_capacity(value: T, capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { latest: ReactiveProperty::new(value), sender }
+    }
+
+    /// 更新属性的值：最新值立即可读，同时发送给所有订阅者。
+    pub fn update(
+        &self,
+        new_value: T,
+    ) -> Result<&Self, ReactivePropertyError> {
+        self.latest.update(new_value.clone())?;
+        // 没有订阅者时发送失败，不影响最新值
+        let _ = self.sender.send(new_value);
+        Ok(self)
+    }
+
+    /// 获取当前属性值的快照（会 clone）。
+    pub fn get_current(&self) -> Option<T> {
+        self.latest.get_current()
+    }
+
+    /// 只关心最新值的监听器，与 `UnlockReactiveProperty::watch` 相同。
+    pub fn watch(&self) -> PropertyWatcher<T> {
+        self.latest.watch()
+    }
+
+    /// 订阅此后的每一次变化。
+    pub fn subscribe(&self) -> TransitionWatcher<T> {
+        TransitionWatcher { receiver: self.sender.subscribe() }
+    }
+}
+
+/// 按顺序接收每一次变化的订阅者。
+#[derive(Debug)]
+pub struct TransitionWatcher<T> {
+    receiver: broadcast::Receiver<T>,
+}
+
+impl<T> TransitionWatcher<T>
+where
+    T: Clone,
+{
+    /// 等待下一个变化。
+    ///
+    /// - 落后超过缓冲容量时返回 [`ReactivePropertyError::Lagged`]，
+    ///   之后可以继续调用，从仍在缓冲区中的最旧值开始接收
+    /// - 属性的所有副本都已释放且缓冲区为空时返回
+    ///   [`ReactivePropertyError::Destroyed`]
+    pub async fn recv(&mut self) -> Result<T, ReactivePropertyError> {
+        self.receiver.recv().await.map_err(|e| match e {
+            RecvError::Lagged(skipped) => {
+                ReactivePropertyError::Lagged(skipped)
+            }
+            RecvError::Closed => ReactivePropertyError::Destroyed,
+        })
+    }
+
+    /// 非阻塞地取出下一个已缓冲的变化，缓冲区为空时返回 `None`。
+    ///
+    /// 落后的部分会被跳过，直接返回仍在缓冲区中的最旧值。
+    pub fn try_recv(&mut self) -> Option<T> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(value) => return Some(value),
+                Err(TryRecvError::Lagged(_)) => continue,
+                Err(_) => return None,
+            }
+        }
+    }
+}
@@ -41,7 +41,7 @@
 //! ```
 
 use std::sync::Arc;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
 use tokio::sync::{Mutex, Notify, watch};
 
 use crate::states::reactive_core::{Inner as CoreInner, PropertyWatcher};
@@ -53,7 +53,6 @@ struct Inner<T> {
     value: Mutex<Option<T>>,
     notify: Notify,
     sender: watch::Sender<Option<T>>,
-    is_dropped: AtomicBool,
 }
 
 /// 带条件等待能力的响应式属性容器。
@@ -78,7 +77,6 @@ where
                 value: Mutex::new(Some(value)),
                 notify: Notify::new(),
                 sender,
-                is_dropped: AtomicBool::new(false),
             }),
         }
     }
@@ -89,6 +87,7 @@ where
             Arc::new(CoreInner {
                 sender: self.inner.sender.clone(),
                 is_dropped: AtomicBool::new(false),
+                watchers: AtomicUsize::new(0),
             }),
         )
     }
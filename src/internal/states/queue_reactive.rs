@@ -4,9 +4,13 @@
 //! 
 //! ## 特性
 //! - 无锁设计（基于 mpsc::unbounded_channel）
+//! - 可选有界队列（[`QueueReactiveProperty::bounded`]，基于 mpsc::channel），
+//!   队列满时生产者等待，形成背压
 //! - 严格 FIFO 顺序
 //! - 生产者可以有多个（Clone sender），消费者只有一个
 //! - 仅库内部使用（`pub(crate)`）
+//! - 没有 `watch()` 监听器时，消费端不为订阅 clone 消息
+//!   （生产端照常更新，`watch()` 总能看到最近发送的消息）
 //!
 //! ## 使用场景
 //! - 控制命令传递（如下载器的 pause/resume/cancel）
@@ -32,16 +36,37 @@ pub(crate) struct QueueReactiveProperty<T: Clone + Send + Sync + 'static> {
     state: ReactiveProperty<Option<T>>,
 }
 
+/// 有界微队列响应式属性（生产者端），由 [`QueueReactiveProperty::bounded`] 创建
+///
+/// 可以 Clone，多个生产者共享同一个容量。
+/// 队列已满时 `send` 挂起，直到消费者取走消息，
+/// 适合快速生产者配慢速消费者、需要限制内存占用的场景。
+// 目前库内还没有使用有界队列的地方，只有测试使用
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Clone, Debug)]
+pub(crate) struct BoundedQueueReactiveProperty<T: Clone + Send + Sync + 'static> {
+    sender: mpsc::Sender<T>,
+    state: ReactiveProperty<Option<T>>,
+}
+
 /// 微队列消费者
 ///
 /// 不可 Clone，只能有一个消费者。
 /// 消费者独占接收端，按 FIFO 顺序消费消息。
 #[derive(Debug)]
 pub(crate) struct QueueReactiveConsumer<T: Clone + Send + Sync + 'static> {
-    receiver: mpsc::UnboundedReceiver<T>,
+    receiver: QueueReceiver<T>,
     state: ReactiveProperty<Option<T>>,
 }
 
+/// 消费者的接收端：无界或有界
+#[derive(Debug)]
+enum QueueReceiver<T> {
+    Unbounded(mpsc::UnboundedReceiver<T>),
+    #[cfg_attr(not(test), allow(dead_code))]
+    Bounded(mpsc::Receiver<T>),
+}
+
 impl<T> QueueReactiveProperty<T>
 where
     T: Clone + Send + Sync + 'static,
@@ -60,12 +85,37 @@ where
         };
         
         let consumer = QueueReactiveConsumer {
-            receiver,
+            receiver: QueueReceiver::Unbounded(receiver),
             state,
         };
         
         (producer, consumer)
     }
+
+    /// 创建一个容量为 `capacity` 的有界微队列（为 0 时按 1 处理）
+    ///
+    /// 返回 (生产者, 消费者) 元组。队列中积压的消息达到容量后，
+    /// 生产者的 `send` 会挂起等待，内存占用不会随积压无限增长。
+    /// 需要非阻塞发送的场景（如控制命令）请使用 [`new`](Self::new)。
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn bounded(
+        capacity: usize,
+    ) -> (BoundedQueueReactiveProperty<T>, QueueReactiveConsumer<T>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let state = ReactiveProperty::new(None);
+
+        let producer = BoundedQueueReactiveProperty {
+            sender,
+            state: state.clone(),
+        };
+
+        let consumer = QueueReactiveConsumer {
+            receiver: QueueReceiver::Bounded(receiver),
+            state,
+        };
+
+        (producer, consumer)
+    }
     
     /// 发送消息到队列
     /// 
@@ -87,6 +137,24 @@ where
     }
 }
 
+#[cfg_attr(not(test), allow(dead_code))]
+impl<T> BoundedQueueReactiveProperty<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// 发送消息到队列
+    ///
+    /// 队列已满时挂起，直到有空位。
+    /// 如果接收端已关闭，返回 `Err(T)`。
+    pub(crate) async fn send(&self, value: T) -> Result<(), T> {
+        self.sender.send(value.clone()).await.map_err(|e| e.0)?;
+
+        // 进入队列后再更新响应式属性（用于外部订阅）
+        let _ = self.state.update(Some(value));
+        Ok(())
+    }
+}
+
 impl<T> QueueReactiveConsumer<T>
 where
     T: Clone + Send + Sync + 'static,
@@ -96,26 +164,71 @@ where
     /// 如果队列为空，会挂起等待。
     /// 如果发送端全部关闭，返回 `None`。
     pub(crate) async fn recv(&mut self) -> Option<T> {
-        let value = self.receiver.recv().await;
+        let value = match &mut self.receiver {
+            QueueReceiver::Unbounded(receiver) => receiver.recv().await,
+            QueueReceiver::Bounded(receiver) => receiver.recv().await,
+        };
         
         // 更新响应式属性
-        if let Some(ref v) = value {
+        if let Some(ref v) = value
+            && self.state.has_watchers()
+        {
             let _ = self.state.update(Some(v.clone()));
         }
         
         value
     }
     
+    /// 批量接收消息，追加到 `buf` 末尾，返回本次接收的条数
+    ///
+    /// 队列为空时先挂起等待第一条消息，之后只取走已经在队列中的消息，
+    /// 最多 `max` 条，不会为凑满一批而继续等待。批内保持 FIFO 顺序。
+    /// 适合高吞吐场景分摊每次 `recv().await` 的开销。
+    ///
+    /// - 发送端全部关闭且队列已空时返回 0
+    /// - `max` 为 0 时不等待，直接返回 0
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) async fn recv_many(
+        &mut self,
+        buf: &mut Vec<T>,
+        max: usize,
+    ) -> usize {
+        let count = match &mut self.receiver {
+            QueueReceiver::Unbounded(receiver) => {
+                receiver.recv_many(buf, max).await
+            }
+            QueueReceiver::Bounded(receiver) => {
+                receiver.recv_many(buf, max).await
+            }
+        };
+
+        // 响应式属性只反映最后一条消息
+        if count > 0
+            && self.state.has_watchers()
+            && let Some(last) = buf.last()
+        {
+            let _ = self.state.update(Some(last.clone()));
+        }
+
+        count
+    }
+
     /// 尝试非阻塞接收消息
     /// 
     /// 如果队列为空，立即返回 `None`。
     pub(crate) fn try_recv(&mut self) -> Option<T> {
-        match self.receiver.try_recv() {
-            Ok(value) => {
-                let _ = self.state.update(Some(value.clone()));
+        let value = match &mut self.receiver {
+            QueueReceiver::Unbounded(receiver) => receiver.try_recv().ok(),
+            QueueReceiver::Bounded(receiver) => receiver.try_recv().ok(),
+        };
+        match value {
+            Some(value) => {
+                if self.state.has_watchers() {
+                    let _ = self.state.update(Some(value.clone()));
+                }
                 Some(value)
             }
-            Err(_) => None,
+            None => None,
         }
     }
 }
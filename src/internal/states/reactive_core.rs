@@ -6,7 +6,7 @@
 //! 本模块**不对外导出**，仅供 `states` 子模块内部复用。
 
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use thiserror::Error;
 use tokio::sync::watch;
 use tokio::sync::watch::Ref;
@@ -25,6 +25,10 @@ pub enum ReactivePropertyError {
     #[error("属性已被销毁")]
     Destroyed,
 
+    /// 订阅者落后太多，期间有这么多个变化被丢弃
+    #[error("订阅者落后，丢失了 {0} 个变化")]
+    Lagged(u64),
+
     /// watch 通道接收失败
     #[error("接收失败: {0}")]
     RecvError(#[from] RecvError),
@@ -37,6 +41,17 @@ pub enum ReactivePropertyError {
 pub(crate) struct Inner<T> {
     pub(crate) sender: watch::Sender<Option<T>>,
     pub(crate) is_dropped: AtomicBool,
+    /// 存活的 [`PropertyWatcher`] 数量（不含各句柄自己的缓存接收端）
+    pub(crate) watchers: AtomicUsize,
+}
+
+impl<T> Inner<T> {
+    /// 标记为已销毁并通知监听者，重复调用时不再通知
+    fn close(&self) {
+        if !self.is_dropped.swap(true, Ordering::Relaxed) {
+            let _ = self.sender.send(None);
+        }
+    }
 }
 
 impl<T> Drop for Inner<T> {
@@ -67,6 +82,7 @@ where
             inner: Arc::new(Inner {
                 sender,
                 is_dropped: AtomicBool::new(false),
+                watchers: AtomicUsize::new(0),
             }),
             cache_receiver,
         }
@@ -86,6 +102,30 @@ where
         }
     }
 
+    /// 仅当新值与当前值不同时才更新并通知监听者。
+    ///
+    /// 适合反复设置同一个值的场景（如进度条重复上报相同的字节数），
+    /// 值未变化时监听者不会被唤醒。比较与写入在同一次加锁中完成。
+    pub fn update_if_changed(
+        &self,
+        new_value: T,
+    ) -> Result<&Self, ReactivePropertyError>
+    where
+        T: PartialEq,
+    {
+        if self.inner.is_dropped.load(Ordering::Relaxed) {
+            return Ok(self);
+        }
+        self.inner.sender.send_if_modified(|current| {
+            if current.as_ref() == Some(&new_value) {
+                return false;
+            }
+            *current = Some(new_value);
+            true
+        });
+        Ok(self)
+    }
+
     /// 使用闭包更新属性的部分字段。
     pub fn update_field<F, R>(
         &self,
@@ -183,12 +223,158 @@ where
         self.get_current().as_ref().map(f)
     }
 
+    /// 异步等待属性值满足条件，返回满足条件时的值。
+    ///
+    /// 先检查当前值，满足时立即返回；否则每次值变化后重新检查。
+    ///
+    /// ⚠️ 基于 watch 通道，快速连续的更新会被合并，**中间值可能被跳过**。
+    /// 条件应当描述“处于某种状态”（如 `已下载 >= 总大小`），而不是
+    /// “发生过某次变化”（如 `已下载 == 某个中间值`），否则可能永远等不到。
+    /// 需要不丢失任何状态的等待请使用
+    /// [`LockReactiveProperty::wait_until`](super::lock_reactive::LockReactiveProperty::wait_until)。
+    ///
+    /// # 返回值
+    /// - `Ok(value)`: 满足条件的值。
+    /// - `Err(ReactivePropertyError::Destroyed)`: 属性已被销毁。
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// use webdav_fs::states::unlock_reactive::UnlockReactiveProperty;
+    ///
+    /// # async fn example() {
+    /// let prop = UnlockReactiveProperty::new(0u64);
+    ///
+    /// let p = prop.clone();
+    /// tokio::spawn(async move {
+    ///     for done in (0..=100).step_by(10) {
+    ///         p.update(done).unwrap();
+    ///     }
+    /// });
+    ///
+    /// let done = prop.wait_until(|done| *done >= 100).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn wait_until<F>(
+        &self,
+        mut predicate: F,
+    ) -> Result<T, ReactivePropertyError>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let mut receiver = self.inner.sender.subscribe();
+        let value = receiver
+            .wait_for(|value| value.as_ref().is_none_or(&mut predicate))
+            .await
+            .map_err(|_| ReactivePropertyError::Destroyed)?;
+        value.clone().ok_or(ReactivePropertyError::Destroyed)
+    }
+
+    /// 主动关闭属性，通知所有监听者“不会再有更新”。
+    ///
+    /// 不需要 drop 任何句柄：所有 clone 共享同一状态，关闭后
+    /// 监听者的 [`changed`](PropertyWatcher::changed) 返回
+    /// [`ReactivePropertyError::WatcherClosed`]，`wait_until` 返回
+    /// [`ReactivePropertyError::Destroyed`]，`get_current` 返回 `None`，
+    /// 之后的 `update` 等更新被忽略。
+    ///
+    /// 幂等：重复调用不会再次通知。
+    pub fn close(&self) {
+        self.inner.close();
+    }
+
+    /// 是否已经通过 [`close`](Self::close) 关闭
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_dropped.load(Ordering::Relaxed)
+    }
+
+    /// 派生一个只跟随本属性变化的新属性，值为 `f(&当前值)`。
+    ///
+    /// 后台任务订阅本属性，每次变化后把 `f` 的结果写入派生属性；与
+    /// watch 一样，快速连续的变化可能被合并。本属性被关闭或销毁时派生
+    /// 属性随之关闭；派生属性的所有句柄与监听器都释放后任务自动退出。
+    /// 派生任务不持有本属性，不会延长它的生命周期。
+    ///
+    /// 需要在 tokio 运行时中调用。对派生属性的写入不会影响本属性。
+    ///
+    /// # 示例
+    /// ```rust,no_run
+    /// use webdav_fs::states::unlock_reactive::UnlockReactiveProperty;
+    ///
+    /// # async fn example() {
+    /// let bytes = UnlockReactiveProperty::new(0u64);
+    /// let percent = bytes.project(|done| *done as f64 / 10.0);
+    ///
+    /// bytes.update(1_000).unwrap();
+    /// let done = percent.wait_until(|p| *p >= 100.0).await.unwrap();
+    /// # }
+    /// ```
+    pub fn project<U, F>(&self, f: F) -> ReactiveProperty<U>
+    where
+        T: 'static,
+        U: Clone + Send + Sync + 'static,
+        F: Fn(&T) -> U + Send + 'static,
+    {
+        let mut source = self.inner.sender.subscribe();
+        let initial = source.borrow_and_update().as_ref().map(&f);
+        let is_closed = initial.is_none();
+
+        let (sender, cache_receiver) = watch::channel(initial);
+        let derived = ReactiveProperty {
+            inner: Arc::new(Inner {
+                sender,
+                is_dropped: AtomicBool::new(is_closed),
+                watchers: AtomicUsize::new(0),
+            }),
+            cache_receiver,
+        };
+        if is_closed {
+            return derived;
+        }
+
+        // 只持有 Inner 而不持有接收端，句柄全部释放后 closed() 返回
+        let target = Arc::clone(&derived.inner);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    changed = source.changed() => {
+                        let value = match changed {
+                            Ok(()) => {
+                                source.borrow_and_update().as_ref().map(&f)
+                            }
+                            Err(_) => None,
+                        };
+                        // 派生属性已被主动关闭
+                        if target.is_dropped.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        match value {
+                            Some(value) => {
+                                let _ = target.sender.send(Some(value));
+                            }
+                            None => {
+                                target.close();
+                                break;
+                            }
+                        }
+                    }
+                    _ = target.sender.closed() => break,
+                }
+            }
+        });
+        derived
+    }
+
     /// 创建一个监听器，用于异步监听属性值的变化。
     pub fn watch(&self) -> PropertyWatcher<T> {
-        PropertyWatcher {
-            receiver: self.inner.sender.subscribe(),
-            inner: Arc::clone(&self.inner),
-        }
+        PropertyWatcher::new(
+            self.inner.sender.subscribe(),
+            Arc::clone(&self.inner),
+        )
+    }
+
+    /// 当前是否有存活的监听器，供内部在无人监听时跳过 clone
+    pub(crate) fn has_watchers(&self) -> bool {
+        self.inner.watchers.load(Ordering::Relaxed) > 0
     }
 }
 
@@ -197,16 +383,22 @@ where
 /// 属性监听器，用于异步接收属性值的变化。
 pub struct PropertyWatcher<T> {
     receiver: watch::Receiver<Option<T>>,
-    #[allow(dead_code)]
     inner: Arc<Inner<T>>,
 }
 
+impl<T> Drop for PropertyWatcher<T> {
+    fn drop(&mut self) {
+        self.inner.watchers.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl<T> PropertyWatcher<T>
 where
     T: Clone + Send + Sync,
 {
     /// 创建一个新的监听器（供内部使用）
     pub(crate) fn new(receiver: watch::Receiver<Option<T>>, inner: Arc<Inner<T>>) -> Self {
+        inner.watchers.fetch_add(1, Ordering::Relaxed);
         Self { receiver, inner }
     }
 
@@ -219,6 +411,11 @@ where
         }
     }
 
+    /// 是否有尚未通过 [`changed`](Self::changed) 取走的新值。
+    pub fn has_changed(&self) -> bool {
+        self.receiver.has_changed().unwrap_or(false)
+    }
+
     /// 同步获取当前值的克隆。
     pub fn borrow(&self) -> Option<T> {
         self.receiver.borrow().clone()
@@ -1,3 +1,8 @@
+pub mod capabilities;
+pub mod lock;
+pub mod quota;
 pub mod raw_xml;
+pub mod stat_info;
 pub mod functions;
-pub mod enums;
\ No newline at end of file
+pub mod enums;
+pub mod webdav_error;
\ No newline at end of file
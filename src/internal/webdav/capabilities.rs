@@ -0,0 +1,153 @@
+//! 服务器能力：由 OPTIONS 响应的 `DAV` 与 `Allow` 头解析得到。
+
+use serde::{Deserialize, Serialize};
+
+use crate::internal::webdav::enums::DavClass;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 服务器声明的 WebDAV 能力
+///
+/// 用于在调用前判断服务器是否支持某项操作（例如锁需要 class 2），
+/// 不支持时提前返回 [`WebDavError::Unsupported`]，
+/// 而不是发出请求后再处理难以理解的 501/405。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `DAV` 头中的合规等级与扩展
+    pub classes: Vec<DavClass>,
+    /// `Allow` 头中的方法（统一为大写）
+    pub allowed_methods: Vec<String>,
+}
+
+impl Capabilities {
+    /// 由 `DAV` 与 `Allow` 头的值构造（同名头有多个时传入多个值）
+    pub fn from_headers<'a>(
+        dav: impl IntoIterator<Item = &'a str>,
+        allow: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let mut classes: Vec<DavClass> = Vec::new();
+        for class in dav.into_iter().flat_map(parse_dav_header) {
+            if !classes.contains(&class) {
+                classes.push(class);
+            }
+        }
+
+        let allowed_methods = AllowedMethods::from_headers(allow).methods;
+
+        Self { classes, allowed_methods }
+    }
+
+    /// `Allow` 头中的方法；服务器未返回 `Allow` 头时为 `None`
+    pub fn allowed(&self) -> Option<AllowedMethods> {
+        if self.allowed_methods.is_empty() {
+            return None;
+        }
+        Some(AllowedMethods { methods: self.allowed_methods.clone() })
+    }
+
+    /// 服务器是否声明了该合规等级或扩展
+    ///
+    /// class 2、3 都以 class 1 为前提，声明更高等级时也视为支持 class 1
+    pub fn supports(&self, class: &DavClass) -> bool {
+        match class {
+            DavClass::Class1 => self.classes.iter().any(|c| {
+                matches!(
+                    c,
+                    DavClass::Class1 | DavClass::Class2 | DavClass::Class3
+                )
+            }),
+            _ => self.classes.contains(class),
+        }
+    }
+
+    /// `Allow` 头是否包含该方法（不区分大小写）
+    ///
+    /// 服务器未返回 `Allow` 头时无法判断，视为允许
+    pub fn allows(&self, method: &str) -> bool {
+        self.allowed_methods.is_empty()
+            || self
+                .allowed_methods
+                .iter()
+                .any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    /// 要求服务器支持某项能力，不支持时返回 [`WebDavError::Unsupported`]
+    pub fn require(&self, class: &DavClass) -> Result<(), WebDavError> {
+        if self.supports(class) {
+            Ok(())
+        } else {
+            Err(WebDavError::Unsupported(class.to_string()))
+        }
+    }
+}
+
+/// 某个资源允许的 HTTP 方法，由该资源 OPTIONS 响应的 `Allow` 头解析得到
+///
+/// 与 [`Capabilities::allows`] 不同，这里只认明确列出的方法：
+/// 未列出即视为不允许，适合 UI 据此禁用不可用的操作。
+///
+/// 序列化为方法名数组，如 `["GET","PUT"]`。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AllowedMethods {
+    /// 方法名（统一为大写，去重，保持服务器给出的顺序）
+    pub methods: Vec<String>,
+}
+
+impl AllowedMethods {
+    /// 由 `Allow` 头的值构造（同名头有多个时传入多个值）
+    pub fn from_headers<'a>(
+        allow: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let mut methods: Vec<String> = Vec::new();
+        for method in allow.into_iter().flat_map(split_tokens) {
+            let method = method.to_ascii_uppercase();
+            if !methods.contains(&method) {
+                methods.push(method);
+            }
+        }
+        Self { methods }
+    }
+
+    /// 是否允许该方法（不区分大小写）
+    pub fn contains(&self, method: &str) -> bool {
+        self.methods.iter().any(|m| m.eq_ignore_ascii_case(method))
+    }
+
+    pub fn can_get(&self) -> bool {
+        self.contains("GET")
+    }
+
+    pub fn can_put(&self) -> bool {
+        self.contains("PUT")
+    }
+
+    pub fn can_delete(&self) -> bool {
+        self.contains("DELETE")
+    }
+
+    pub fn can_move(&self) -> bool {
+        self.contains("MOVE")
+    }
+
+    pub fn can_copy(&self) -> bool {
+        self.contains("COPY")
+    }
+
+    pub fn can_mkcol(&self) -> bool {
+        self.contains("MKCOL")
+    }
+
+    pub fn can_lock(&self) -> bool {
+        self.contains("LOCK")
+    }
+}
+
+/// 解析 `DAV` 头，例如 `1, 2, 3, extended-mkcol, <http://apache.org/dav/propset/fs/1>`
+pub fn parse_dav_header(value: &str) -> Vec<DavClass> {
+    split_tokens(value).map(DavClass::from_token).collect()
+}
+
+/// 按逗号拆分头部的值，去掉空白与空项
+fn split_tokens(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|token| !token.is_empty())
+}
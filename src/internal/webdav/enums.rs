@@ -1,15 +1,33 @@
+use std::fmt;
+
 use reqwest::Method;
 
 pub enum WebDavMethod {
     PROPFIND,
+    MKCOL,
+    LOCK,
+    UNLOCK,
+    MOVE,
+    COPY,
+    PROPPATCH,
 }
 
-impl WebDavMethod {
-    pub fn to_string(&self) -> String {
-        match self {
-            WebDavMethod::PROPFIND => "PROPFIND".to_string(),
-        }
+impl fmt::Display for WebDavMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            WebDavMethod::PROPFIND => "PROPFIND",
+            WebDavMethod::MKCOL => "MKCOL",
+            WebDavMethod::LOCK => "LOCK",
+            WebDavMethod::UNLOCK => "UNLOCK",
+            WebDavMethod::MOVE => "MOVE",
+            WebDavMethod::COPY => "COPY",
+            WebDavMethod::PROPPATCH => "PROPPATCH",
+        };
+        f.write_str(name)
     }
+}
+
+impl WebDavMethod {
 
     pub fn to_head_method(&self) -> Result<Method, String> {
         let method =
@@ -17,7 +35,52 @@ impl WebDavMethod {
                 .map_err(|e| e.to_string())?;
 
         match self {
-            WebDavMethod::PROPFIND => Ok(method),
+            WebDavMethod::PROPFIND
+            | WebDavMethod::MKCOL
+            | WebDavMethod::LOCK
+            | WebDavMethod::UNLOCK
+            | WebDavMethod::MOVE
+            | WebDavMethod::COPY
+            | WebDavMethod::PROPPATCH => Ok(method),
+        }
+    }
+}
+
+/// `DAV` 响应头中声明的合规等级或扩展
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DavClass {
+    /// class 1：基本的 WebDAV 操作
+    Class1,
+    /// class 2：支持 LOCK / UNLOCK
+    Class2,
+    /// class 3：RFC 4918 修订后的语义
+    Class3,
+    /// 其他扩展，例如 `extended-mkcol`、`access-control`
+    Extension(String),
+}
+
+impl DavClass {
+    /// 由 `DAV` 头中的单个值解析，扩展名统一为小写（`<...>` 形式的 URI 保持原样）
+    pub fn from_token(token: &str) -> Self {
+        match token.trim() {
+            "1" => DavClass::Class1,
+            "2" => DavClass::Class2,
+            "3" => DavClass::Class3,
+            other if other.starts_with('<') => {
+                DavClass::Extension(other.to_string())
+            }
+            other => DavClass::Extension(other.to_ascii_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for DavClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DavClass::Class1 => f.write_str("DAV class 1"),
+            DavClass::Class2 => f.write_str("DAV class 2"),
+            DavClass::Class3 => f.write_str("DAV class 3"),
+            DavClass::Extension(name) => f.write_str(name),
         }
     }
 }
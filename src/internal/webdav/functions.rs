@@ -1 +1,12 @@
+pub mod copy_move;
+pub mod delete;
+pub mod ensure_collection_path;
+pub mod get_capabilities;
 pub mod get_folders_raw_data;
+pub mod get_quota;
+pub mod lock;
+pub mod mkcol;
+pub mod normalize_webdav_path;
+pub mod parse_multistatus;
+pub mod proppatch;
+pub mod stat;
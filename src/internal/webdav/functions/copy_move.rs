@@ -0,0 +1,83 @@
+use reqwest::StatusCode;
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::enums::WebDavMethod;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 移动（重命名）远程资源（MOVE）
+///
+/// - `destination_url` 放入 `Destination` 请求头，必须是已编码的绝对 URL
+/// - `overwrite` 对应 `Overwrite: T` / `Overwrite: F`
+/// - 201 Created（新建）与 204 No Content（覆盖）等 2xx 视为成功
+/// - 412 Precondition Failed 表示不允许覆盖而目标已存在，返回
+///   [`WebDavError::DestinationExists`]
+/// - 207 Multi-Status 表示目录中部分资源失败，与其余非 2xx 状态一样返回
+///   [`WebDavError::Status`]（507 返回 [`WebDavError::InsufficientRemoteStorage`]）
+pub async fn move_resource(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    destination_url: &str,
+    overwrite: bool,
+) -> Result<(), WebDavError> {
+    transfer(
+        webdav_auth,
+        WebDavMethod::MOVE,
+        absolute_url,
+        destination_url,
+        overwrite,
+    )
+    .await
+}
+
+/// 复制远程资源（COPY），目录按 `Depth: infinity` 整体复制
+///
+/// 参数与返回值同 [`move_resource`]。
+pub async fn copy_resource(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    destination_url: &str,
+    overwrite: bool,
+) -> Result<(), WebDavError> {
+    transfer(
+        webdav_auth,
+        WebDavMethod::COPY,
+        absolute_url,
+        destination_url,
+        overwrite,
+    )
+    .await
+}
+
+async fn transfer(
+    webdav_auth: &WebdavAuth,
+    method: WebDavMethod,
+    absolute_url: &str,
+    destination_url: &str,
+    overwrite: bool,
+) -> Result<(), WebDavError> {
+    let method =
+        method.to_head_method().map_err(WebDavError::InvalidRequest)?;
+
+    let request = webdav_auth
+        .client
+        .request(method, absolute_url)
+        .header("Destination", destination_url)
+        .header("Overwrite", if overwrite { "T" } else { "F" });
+    let res = webdav_auth.send(request).await?;
+
+    let status = res.status();
+
+    if status.is_success() && status != StatusCode::MULTI_STATUS {
+        return Ok(());
+    }
+
+    if status == StatusCode::PRECONDITION_FAILED {
+        return Err(WebDavError::DestinationExists(
+            destination_url.to_string(),
+        ));
+    }
+
+    let body = res.text().await.unwrap_or_default();
+
+    Err(WebDavError::from_write_status(absolute_url, status, body))
+}
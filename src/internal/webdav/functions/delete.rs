@@ -0,0 +1,42 @@
+use reqwest::StatusCode;
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::functions::lock::lock_if_header;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 删除单个远程文件或目录（DELETE）
+///
+/// - 200/204 等 2xx 视为成功
+/// - 207 Multi-Status 表示删除目录时其中部分资源失败，返回 [`WebDavError::Status`]，
+///   `body` 为服务器返回的 multistatus
+/// - 其余状态（如 404、423 Locked）返回 [`WebDavError::Status`]
+pub async fn delete(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<(), WebDavError> {
+    delete_with_lock(webdav_auth, absolute_url, None).await
+}
+
+/// 同 [`delete`]，`lock_token` 不为空时携带 `If: (<token>)`，
+/// 用于删除自己锁定的资源（不带令牌时服务器返回 423 Locked）
+pub async fn delete_with_lock(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    lock_token: Option<&str>,
+) -> Result<(), WebDavError> {
+    let mut request = webdav_auth.client.delete(absolute_url);
+    if let Some(token) = lock_token {
+        request = request.header("If", lock_if_header(token));
+    }
+    let res = webdav_auth.send(request).await?;
+
+    let status = res.status();
+
+    if status.is_success() && status != StatusCode::MULTI_STATUS {
+        return Ok(());
+    }
+
+    let body = res.text().await.unwrap_or_default();
+
+    Err(WebDavError::Status { status: status.as_u16(), body })
+}
@@ -0,0 +1,82 @@
+use url::Url;
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::enums::Depth;
+use crate::internal::webdav::functions::get_folders_raw_data::get_folders_raw_data;
+use crate::internal::webdav::functions::mkcol::mkcol;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 逐级确保远程目录存在（相当于 `mkdir -p`）
+///
+/// `path` 基于 webdav_auth 中的 base_url，例如 `a/b/c` 会依次对
+/// `a/`、`a/b/`、`a/b/c/` 发起 MKCOL：
+///
+/// - 已存在的目录（405）会被跳过，因此可以重复调用
+/// - 某一级已存在但是文件时，立即返回 [`WebDavError::NotADirectory`]
+/// - 路径中不允许出现 `..`
+pub async fn ensure_collection_path(
+    webdav_auth: &WebdavAuth,
+    path: &str,
+) -> Result<(), WebDavError> {
+    let components: Vec<&str> =
+        path.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+
+    if components.contains(&"..") {
+        return Err(WebDavError::InvalidRequest(format!(
+            "路径中不允许出现 '..': {}",
+            path
+        )));
+    }
+
+    let mut current_url: Url = (*webdav_auth.base_url).clone();
+
+    for component in components {
+        push_collection_segment(&mut current_url, component)?;
+
+        match mkcol(webdav_auth, current_url.as_str()).await {
+            Ok(()) => {}
+            Err(WebDavError::AlreadyExists(url)) => {
+                if !is_collection(webdav_auth, &url).await? {
+                    return Err(WebDavError::NotADirectory(url));
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// 在 URL 末尾追加一级目录（自动百分号编码，并保留尾部斜杠）
+fn push_collection_segment(
+    url: &mut Url,
+    segment: &str,
+) -> Result<(), WebDavError> {
+    let url_text = url.to_string();
+    let mut segments = url.path_segments_mut().map_err(|_| {
+        WebDavError::InvalidRequest(format!("无法拼接路径: {}", url_text))
+    })?;
+    segments.pop_if_empty().push(segment).push("");
+    Ok(())
+}
+
+/// 通过 Depth: 0 的 PROPFIND 判断已存在的资源是否为目录
+async fn is_collection(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<bool, WebDavError> {
+    let multi_status =
+        get_folders_raw_data(webdav_auth, absolute_url, &Depth::Zero)
+            .await?;
+
+    let is_dir = multi_status.responses.first().is_some_and(|response| {
+        response.propstats.iter().any(|ps| {
+            ps.prop
+                .resource_type
+                .as_ref()
+                .is_some_and(|rt| rt.is_collection.is_some())
+        })
+    });
+
+    Ok(is_dir)
+}
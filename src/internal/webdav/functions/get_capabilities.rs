@@ -0,0 +1,65 @@
+use reqwest::Method;
+use reqwest::header::ALLOW;
+
+use crate::auth::structs::request_options::StatMethod;
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::capabilities::{AllowedMethods, Capabilities};
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 发送 OPTIONS 请求，读取服务器声明的 WebDAV 能力
+///
+/// - 2xx 视为成功，解析 `DAV` 与 `Allow` 头
+/// - 成功但没有 `DAV` 头时返回空的等级列表（普通 HTTP 服务器）
+/// - 其余状态返回 [`WebDavError::Status`]
+/// - `Allow` 头只声明了 HEAD 与 PROPFIND 中的一种时，记录为
+///   [`StatMethod::Auto`] 优先使用的方法
+pub async fn get_capabilities(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<Capabilities, WebDavError> {
+    let request =
+        webdav_auth.client.request(Method::OPTIONS, absolute_url);
+    let res = webdav_auth.send(request).await?;
+
+    let status = res.status();
+
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+        return Err(WebDavError::Status { status: status.as_u16(), body });
+    }
+
+    let headers = res.headers();
+    let dav = headers
+        .get_all("DAV")
+        .iter()
+        .filter_map(|value| value.to_str().ok());
+    let allow = headers
+        .get_all(ALLOW)
+        .iter()
+        .filter_map(|value| value.to_str().ok());
+
+    let capabilities = Capabilities::from_headers(dav, allow);
+    if let Some(allowed) = capabilities.allowed() {
+        match (allowed.contains("HEAD"), allowed.contains("PROPFIND")) {
+            (true, false) => {
+                webdav_auth.learn_stat_method(StatMethod::Head)
+            }
+            (false, true) => {
+                webdav_auth.learn_stat_method(StatMethod::Propfind)
+            }
+            _ => {}
+        }
+    }
+
+    Ok(capabilities)
+}
+
+/// 对单个资源发送 OPTIONS，读取它允许的方法
+///
+/// 服务器没有返回 `Allow` 头时为 `Ok(None)`，其余行为与 [`get_capabilities`] 相同
+pub async fn get_allowed_methods(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<Option<AllowedMethods>, WebDavError> {
+    Ok(get_capabilities(webdav_auth, absolute_url).await?.allowed())
+}
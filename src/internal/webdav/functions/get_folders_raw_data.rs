@@ -1,9 +1,13 @@
-use quick_xml::de::from_str;
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::StatusCode;
+use reqwest::header::{
+    ACCEPT, CONTENT_TYPE, HeaderMap, HeaderValue, IF_NONE_MATCH, SERVER,
+};
 
 use crate::auth::structs::webdav_auth::WebdavAuth;
 use crate::internal::webdav::enums::{Depth, WebDavMethod};
-use crate::webdav::structs::MultiStatus;
+use crate::internal::webdav::webdav_error::WebDavError;
+use super::parse_multistatus::parse_multistatus;
+use crate::webdav::structs::{MultiStatus, PropStat, Response};
 
 /// 内部使用的PROPFIND请求体
 const _PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
@@ -12,47 +16,211 @@ const _PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
 </D:propfind>"#;
 
 /// 获取原始webdav文件夹数据
+///
+/// - 207 Multi-Status 与 200 OK（部分不规范的服务器）都会解析响应体
+/// - 响应体读取受 [`RequestOptions::read_timeout`](crate::auth::RequestOptions)
+///   限制，超时返回 [`WebDavError::ResponseReadTimeout`]；成功状态但响应体为空时
+///   返回 [`WebDavError::EmptyBody`]
+/// - 成功状态但返回的是 HTML 页面（`Content-Type` 为 `text/html` 或内容以
+///   `<!DOCTYPE html`/`<html` 开头）时返回 [`WebDavError::NotWebDav`]
+/// - 按 [`WebdavAuth::server_quirks`] 调整请求：服务器禁用了
+///   `Depth: infinity` 时直接返回 [`WebDavError::Unsupported`]；
+///   第一次请求后根据响应的 `Server` 头识别服务器
+/// - 若 multistatus 中每个资源都只报告了失败状态，返回
+///   [`WebDavError::MultiStatusFailed`]，而不是一个空的成功列表
 pub(crate) async fn get_folders_raw_data(
     webdav_auth: &WebdavAuth,
     absolute_url: &str,
     depth: &Depth,
-) -> Result<MultiStatus, String> {
+) -> Result<MultiStatus, WebDavError> {
+    get_props_raw_data(webdav_auth, absolute_url, depth, _PROPFIND_BODY)
+        .await
+}
+
+/// 用指定的请求体发送 PROPFIND，用于请求 `allprop` 不包含的属性
+///
+/// 其余行为与 [`get_folders_raw_data`] 相同。
+pub(crate) async fn get_props_raw_data(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    depth: &Depth,
+    body: &'static str,
+) -> Result<MultiStatus, WebDavError> {
+    propfind(webdav_auth, absolute_url, depth, None, body).await?.ok_or(
+        WebDavError::Status {
+            status: StatusCode::NOT_MODIFIED.as_u16(),
+            body: String::new(),
+        },
+    )
+}
+
+/// 带 `If-None-Match` 的 PROPFIND，用于重新验证缓存的目录列表
+///
+/// 服务器返回 304 时得到 `Ok(None)`，表示 `etag` 仍然有效；
+/// 不支持条件 PROPFIND 的服务器会忽略该请求头，直接返回新的结果。
+/// 其余行为与 [`get_folders_raw_data`] 相同。
+pub(crate) async fn get_folders_raw_data_if_none_match(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    depth: &Depth,
+    etag: &str,
+) -> Result<Option<MultiStatus>, WebDavError> {
+    propfind(webdav_auth, absolute_url, depth, Some(etag), _PROPFIND_BODY)
+        .await
+}
+
+/// 发送 PROPFIND 并解析结果；带 `if_none_match` 且服务器返回 304 时为 `None`
+async fn propfind(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    depth: &Depth,
+    if_none_match: Option<&str>,
+    body: &'static str,
+) -> Result<Option<MultiStatus>, WebDavError> {
+    let quirks = webdav_auth.server_quirks();
+    if quirks.no_depth_infinity && matches!(depth, Depth::Infinity) {
+        return Err(WebDavError::Unsupported("Depth: infinity".to_string()));
+    }
+
     // 组装请求头
     let mut headers = HeaderMap::new();
     headers
         .insert(CONTENT_TYPE, HeaderValue::from_static("application/xml"));
     headers.insert("Depth", HeaderValue::from_static(depth.as_str()));
-    headers.insert("Accept", HeaderValue::from_static("application/xml"));
+    let accept = match &quirks.accept {
+        Some(accept) => HeaderValue::from_str(accept)
+            .map_err(|e| WebDavError::InvalidRequest(e.to_string()))?,
+        None => HeaderValue::from_static("application/xml"),
+    };
+    headers.insert(ACCEPT, accept);
+    if webdav_auth.request_options.prefer_minimal {
+        headers.insert("Prefer", HeaderValue::from_static("return=minimal"));
+        headers.insert("Brief", HeaderValue::from_static("t"));
+    }
+    if let Some(etag) = if_none_match {
+        let value = HeaderValue::from_str(etag)
+            .map_err(|e| WebDavError::InvalidRequest(e.to_string()))?;
+        headers.insert(IF_NONE_MATCH, value);
+    }
 
     let method = WebDavMethod::PROPFIND
         .to_head_method()
-        .map_err(|e| e.to_string())?;
+        .map_err(WebDavError::InvalidRequest)?;
 
     let http_client = &webdav_auth.client;
 
     // 发送 PROPFIND 到基准目录（已保证有尾部斜杠）
-    let res = http_client
+    let request = http_client
         .request(method, absolute_url)
         .headers(headers)
-        .body(_PROPFIND_BODY)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+        .body(body);
+    let res = webdav_auth.send(request).await?;
 
     let status = res.status();
+    if let Some(server) =
+        res.headers().get(SERVER).and_then(|value| value.to_str().ok())
+    {
+        webdav_auth.detect_quirks(server);
+    }
+    if if_none_match.is_some() && status == StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+    let content_type = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let read_timeout =
+        webdav_auth.request_options.effective_read_timeout();
+    let xml_text = tokio::time::timeout(read_timeout, res.text())
+        .await
+        .map_err(|_| WebDavError::ResponseReadTimeout(read_timeout))??;
 
-    let xml_text = res.text().await.map_err(|e| e.to_string())?;
+    if !is_propfind_success(status) {
+        return Err(WebDavError::Status {
+            status: status.as_u16(),
+            body: xml_text,
+        });
+    }
+
+    if xml_text.trim().is_empty() {
+        return Err(WebDavError::EmptyBody(status.as_u16()));
+    }
 
-    if !status.is_success() && status.as_u16() != 207 {
-        return Err(format!(
-            "状态解析异常 {status}: {xml}",
-            status = status,
-            xml = xml_text
-        ));
+    if looks_like_html(content_type.as_deref(), &xml_text) {
+        return Err(WebDavError::NotWebDav {
+            hint: format!(
+                "{} 返回了 HTML 页面（状态码 {}），请检查地址是否为 WebDAV \
+                 端点，以及认证是否被网关拦截",
+                absolute_url,
+                status.as_u16()
+            ),
+        });
     }
 
-    let multi_status: MultiStatus =
-        from_str(&xml_text).map_err(|e| e.to_string())?;
+    let multi_status = parse_multistatus(&xml_text)?;
+
+    check_multi_status(&multi_status)?;
+
+    Ok(Some(multi_status))
+}
+
+/// PROPFIND 的 HTTP 状态是否可以进入解析阶段：207 为标准响应，200 等 2xx 做兼容
+fn is_propfind_success(status: StatusCode) -> bool {
+    status == StatusCode::MULTI_STATUS || status.is_success()
+}
+
+/// 响应是否为 HTML 页面：先看 `Content-Type`，再看正文开头
+fn looks_like_html(content_type: Option<&str>, body: &str) -> bool {
+    let html_type = content_type.is_some_and(|value| {
+        let mime = value.split(';').next().unwrap_or_default().trim();
+        mime.eq_ignore_ascii_case("text/html")
+            || mime.eq_ignore_ascii_case("application/xhtml+xml")
+    });
+    if html_type {
+        return true;
+    }
+
+    let head: String = body.trim_start().chars().take(16).collect();
+    let head = head.to_ascii_lowercase();
+    head.starts_with("<!doctype html") || head.starts_with("<html")
+}
+
+/// 检查 multistatus 是否真的包含成功的资源
+///
+/// 空的 multistatus 视为成功（例如空目录）；只要有一个资源带有 2xx 的 propstat
+/// 也视为成功。仅当所有资源都只有失败状态时返回错误。
+fn check_multi_status(multi_status: &MultiStatus) -> Result<(), WebDavError> {
+    if multi_status.responses.is_empty() {
+        return Ok(());
+    }
+
+    let any_success = multi_status.responses.iter().any(|response| {
+        response
+            .propstats
+            .iter()
+            .any(PropStat::is_success)
+    });
+
+    if any_success {
+        return Ok(());
+    }
+
+    let failures = multi_status
+        .responses
+        .iter()
+        .map(|response| (response.href.clone(), failure_status(response)))
+        .collect();
+
+    Err(WebDavError::MultiStatusFailed { failures })
+}
 
-    Ok(multi_status)
+/// 取出一个失败资源的状态行：优先资源级 `<D:status>`，否则取第一个 propstat 的状态
+fn failure_status(response: &Response) -> String {
+    response
+        .status
+        .clone()
+        .or_else(|| response.propstats.first().map(|ps| ps.status.clone()))
+        .unwrap_or_default()
 }
@@ -0,0 +1,46 @@
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::enums::Depth;
+use crate::internal::webdav::quota::Quota;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+use super::get_folders_raw_data::get_props_raw_data;
+
+/// 配额属性不在 `allprop` 的结果中，需要显式请求
+const QUOTA_PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:propfind xmlns:D="DAV:">
+  <D:prop>
+    <D:quota-used-bytes/>
+    <D:quota-available-bytes/>
+  </D:prop>
+</D:propfind>"#;
+
+/// 查询资源所在空间的配额（`Depth: 0` 的 PROPFIND）
+///
+/// - 服务器没有返回两个配额属性（不支持 RFC 4331，对应 propstat 为 404），
+///   或返回负数（不限额、未计算）时返回 [`WebDavError::Unsupported`]
+/// - 其余失败见 [`get_folders_raw_data`](super::get_folders_raw_data::get_folders_raw_data)
+pub async fn get_quota(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<Quota, WebDavError> {
+    let unsupported = || {
+        WebDavError::Unsupported(format!(
+            "{} 没有提供 quota-used-bytes / quota-available-bytes",
+            absolute_url
+        ))
+    };
+
+    let multi_status = get_props_raw_data(
+        webdav_auth,
+        absolute_url,
+        &Depth::Zero,
+        QUOTA_PROPFIND_BODY,
+    )
+    .await
+    .map_err(|e| match e {
+        WebDavError::MultiStatusFailed { .. } => unsupported(),
+        e => e,
+    })?;
+
+    Quota::from_multi_status(&multi_status).ok_or_else(unsupported)
+}
@@ -0,0 +1,153 @@
+use reqwest::header::{CONTENT_TYPE, HeaderValue};
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::enums::WebDavMethod;
+use crate::internal::webdav::lock::{
+    LockInfo, parse_lock_body, parse_timeout, timeout_header,
+};
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 请求独占写锁的 LOCK 请求体
+const LOCK_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:lockinfo xmlns:D="DAV:">
+  <D:lockscope><D:exclusive/></D:lockscope>
+  <D:locktype><D:write/></D:locktype>
+</D:lockinfo>"#;
+
+/// 对单个资源加独占写锁（LOCK，`Depth: 0`）
+///
+/// - `timeout_secs` 通过 `Timeout: Second-N` 请求，服务器可能给出更短的有效期，
+///   以返回的 [`LockInfo::timeout`] 为准
+/// - 令牌优先取 `Lock-Token` 响应头，其次取响应体中的 `locktoken`
+/// - 507 表示远程空间不足，返回 [`WebDavError::InsufficientRemoteStorage`]
+/// - 423 Locked 等其余非 2xx 状态返回 [`WebDavError::Status`]
+pub async fn lock(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    timeout_secs: u64,
+) -> Result<LockInfo, WebDavError> {
+    let method = WebDavMethod::LOCK
+        .to_head_method()
+        .map_err(WebDavError::InvalidRequest)?;
+
+    let request = webdav_auth
+        .client
+        .request(method, absolute_url)
+        .header(CONTENT_TYPE, HeaderValue::from_static("application/xml"))
+        .header("Depth", "0")
+        .header("Timeout", timeout_header(timeout_secs))
+        .body(LOCK_BODY);
+    let res = webdav_auth.send(request).await?;
+
+    let status = res.status();
+    let header_token = res
+        .headers()
+        .get("Lock-Token")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| strip_angle_brackets(value).to_string());
+    let header_timeout = res
+        .headers()
+        .get("Timeout")
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_timeout);
+    let body = res.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        return Err(WebDavError::from_write_status(
+            absolute_url,
+            status,
+            body,
+        ));
+    }
+
+    let (body_token, body_timeout) = parse_lock_body(&body);
+    let token = header_token.or(body_token).ok_or_else(|| {
+        WebDavError::Parse("LOCK 响应中没有锁令牌".to_string())
+    })?;
+
+    Ok(LockInfo { token, timeout: body_timeout.or(header_timeout) })
+}
+
+/// 续期已有的锁：不带请求体重新发送 LOCK，并携带 `If: (<token>)`
+///
+/// - 返回续期后的有效期；服务器未返回时沿用请求的 `timeout_secs`
+/// - 锁已过期或令牌无效时服务器通常返回 412，对应 [`WebDavError::Status`]
+pub async fn refresh_lock(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    token: &str,
+    timeout_secs: u64,
+) -> Result<LockInfo, WebDavError> {
+    let method = WebDavMethod::LOCK
+        .to_head_method()
+        .map_err(WebDavError::InvalidRequest)?;
+
+    let request = webdav_auth
+        .client
+        .request(method, absolute_url)
+        .header("If", lock_if_header(token))
+        .header("Timeout", timeout_header(timeout_secs));
+    let res = webdav_auth.send(request).await?;
+
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        return Err(WebDavError::from_write_status(
+            absolute_url,
+            status,
+            body,
+        ));
+    }
+
+    let (_, timeout) = parse_lock_body(&body);
+    Ok(LockInfo {
+        token: strip_angle_brackets(token).to_string(),
+        timeout: timeout
+            .or(Some(std::time::Duration::from_secs(timeout_secs))),
+    })
+}
+
+/// 释放锁（UNLOCK，`Lock-Token: <token>`）
+///
+/// - 204 No Content 等 2xx 视为成功
+/// - 其余状态（如 409 令牌不匹配）返回 [`WebDavError::Status`]
+pub async fn unlock(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    token: &str,
+) -> Result<(), WebDavError> {
+    let method = WebDavMethod::UNLOCK
+        .to_head_method()
+        .map_err(WebDavError::InvalidRequest)?;
+
+    let lock_token = format!("<{}>", strip_angle_brackets(token));
+    let request = webdav_auth
+        .client
+        .request(method, absolute_url)
+        .header("Lock-Token", lock_token);
+    let res = webdav_auth.send(request).await?;
+
+    let status = res.status();
+
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let body = res.text().await.unwrap_or_default();
+
+    Err(WebDavError::Status { status: status.as_u16(), body })
+}
+
+/// 携带锁令牌的 `If` 请求头值：`(<token>)`
+///
+/// 写入被锁定的资源（PUT、DELETE 等）时需要带上，令牌两侧的 `<` `>`
+/// 可有可无。
+pub fn lock_if_header(token: &str) -> String {
+    format!("(<{}>)", strip_angle_brackets(token))
+}
+
+/// 令牌两侧的 `<` `>` 可有可无，统一去掉
+fn strip_angle_brackets(token: &str) -> &str {
+    token.trim().trim_start_matches('<').trim_end_matches('>')
+}
@@ -0,0 +1,46 @@
+use reqwest::StatusCode;
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::enums::WebDavMethod;
+use crate::internal::webdav::functions::normalize_webdav_path::collection_url;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 创建单个远程目录（MKCOL）
+///
+/// - 201 Created 等 2xx 视为成功
+/// - 405 Method Not Allowed 表示目标已存在，返回 [`WebDavError::AlreadyExists`]
+/// - 507 表示远程空间不足，返回 [`WebDavError::InsufficientRemoteStorage`]
+/// - 其余状态（如 409 父目录不存在）返回 [`WebDavError::Status`]
+/// - [`ServerQuirks::force_trailing_slash`](crate::auth::ServerQuirks) 开启时
+///   自动补上尾部斜杠
+pub async fn mkcol(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<(), WebDavError> {
+    let method = WebDavMethod::MKCOL
+        .to_head_method()
+        .map_err(WebDavError::InvalidRequest)?;
+
+    let url = if webdav_auth.server_quirks().force_trailing_slash {
+        collection_url(absolute_url)
+    } else {
+        absolute_url.to_string()
+    };
+
+    let res =
+        webdav_auth.send(webdav_auth.client.request(method, &url)).await?;
+
+    let status = res.status();
+
+    if status.is_success() {
+        return Ok(());
+    }
+
+    if status == StatusCode::METHOD_NOT_ALLOWED {
+        return Err(WebDavError::AlreadyExists(url));
+    }
+
+    let body = res.text().await.unwrap_or_default();
+
+    Err(WebDavError::from_write_status(&url, status, body))
+}
@@ -0,0 +1,68 @@
+use percent_encoding::percent_decode_str;
+
+/// 规范化 WebDAV 路径，用于比较不同服务器返回的 href
+///
+/// 同一个资源在不同服务器上可能返回 `http://host/dav/a%20b/`、
+/// `/dav/a b`、`/dav//a%20b` 等形式，规范化后都为 `/dav/a b`：
+///
+/// - 完整 URL 只保留路径部分，丢弃查询串与片段
+/// - 解码百分号编码
+/// - 合并重复的 `/`，并保证以 `/` 开头
+/// - 去掉尾部 `/`（根路径除外），目录与文件统一为无尾斜杠的形式；
+///   需要区分类型时由调用方结合 `is_dir` 自行补回
+pub fn normalize_webdav_path(path: &str) -> String {
+    let path = strip_origin(path);
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let decoded = percent_decode_str(path).decode_utf8_lossy();
+
+    let mut normalized = String::with_capacity(decoded.len() + 1);
+    for segment in decoded.split('/').filter(|s| !s.is_empty()) {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+
+    if normalized.is_empty() {
+        normalized.push('/');
+    }
+
+    normalized
+}
+
+/// 把已知是目录的 URL 补成以 `/` 结尾的形式，查询串与片段保持不变
+///
+/// 对不带尾斜杠的目录发 PROPFIND 时，部分服务器会 301 重定向到带斜杠的地址，
+/// 而 HTTP 客户端跟随 301 时会把 PROPFIND 改成 GET，导致列举失败；
+/// 另一些服务器则直接返回该目录自身的单条记录。请求前补上尾斜杠可以避免这两种情况。
+pub fn collection_url(url: &str) -> String {
+    let split_at = url.find(['?', '#']).unwrap_or(url.len());
+    let (path, suffix) = url.split_at(split_at);
+    if path.ends_with('/') {
+        url.to_string()
+    } else {
+        format!("{}/{}", path, suffix)
+    }
+}
+
+/// 两个 href 规范化后是否指向同一资源（区分大小写）
+pub fn same_webdav_path(a: &str, b: &str) -> bool {
+    normalize_webdav_path(a) == normalize_webdav_path(b)
+}
+
+/// 两个 href 规范化后是否指向同一资源（不区分大小写）
+///
+/// 适用于 Windows/IIS 等大小写不敏感的服务器
+pub fn same_webdav_path_ignore_case(a: &str, b: &str) -> bool {
+    normalize_webdav_path(a).to_lowercase()
+        == normalize_webdav_path(b).to_lowercase()
+}
+
+/// 去掉 `scheme://host[:port]` 前缀，只保留路径
+fn strip_origin(path: &str) -> &str {
+    match path.find("://") {
+        Some(scheme_end) => {
+            let rest = &path[scheme_end + 3..];
+            rest.find('/').map(|i| &rest[i..]).unwrap_or("/")
+        }
+        None => path,
+    }
+}
@@ -0,0 +1,112 @@
+use quick_xml::Reader;
+use quick_xml::de::from_str;
+use quick_xml::events::Event;
+use serde::Deserialize;
+use url::Url;
+
+use crate::internal::webdav::webdav_error::WebDavError;
+use crate::remote_file::RemoteFileData;
+use crate::webdav::structs::{
+    MultiStatus, Response, ResponseParseWarning, ResponseWarningReason,
+};
+use crate::webdav::traits::ToRemoteFileData;
+
+/// 把 PROPFIND 返回的 XML 文本解析为 [`MultiStatus`]
+///
+/// 与 [`get_folders_raw_data`](super::get_folders_raw_data::get_folders_raw_data)
+/// 内部使用的解析逻辑相同，适合离线处理或自行发送请求后复用本库的解析。
+///
+/// 元素按本地名匹配，`D:`、`lp1:` 等命名空间前缀不影响解析；单个资源
+/// 格式不对（缺少 `href`、属性重复等）时跳过该资源并记入
+/// [`MultiStatus::skipped`]，其余资源照常返回。
+///
+/// - 空响应体返回 [`WebDavError::EmptyBody`]（状态码记为 207）
+/// - XML 不合法，或没有任何资源能解析成功时返回 [`WebDavError::Parse`]
+pub fn parse_multistatus(xml: &str) -> Result<MultiStatus, WebDavError> {
+    if xml.trim().is_empty() {
+        return Err(WebDavError::EmptyBody(207));
+    }
+
+    from_str(xml).or_else(|e| {
+        parse_each_response(xml)
+            .ok_or_else(|| WebDavError::Parse(e.to_string()))
+    })
+}
+
+/// 整体解析失败时的回退：逐个解析顶层的 `<response>`，跳过解析失败的资源
+///
+/// XML 本身不完整，或一个资源都没有解析成功时返回 `None`。
+fn parse_each_response(xml: &str) -> Option<MultiStatus> {
+    let mut reader = Reader::from_str(xml);
+    let mut depth = 0usize;
+    let mut responses = Vec::new();
+    let mut skipped = Vec::new();
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event().ok()? {
+            Event::Start(e)
+                if depth == 1
+                    && e.local_name().as_ref() == b"response" =>
+            {
+                reader.read_to_end(e.name()).ok()?;
+                let end = reader.buffer_position() as usize;
+                let text = xml.get(start..end)?;
+                match from_str::<Response>(text) {
+                    Ok(response) => responses.push(response),
+                    Err(e) => skipped.push(ResponseParseWarning {
+                        href: from_str::<HrefOnly>(text)
+                            .ok()
+                            .and_then(|r| r.href),
+                        reason: ResponseWarningReason::Malformed(
+                            e.to_string(),
+                        ),
+                    }),
+                }
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    (!responses.is_empty()).then_some(MultiStatus { responses, skipped })
+}
+
+/// 只取 `<href>`，用于给格式不对的资源标注来源
+#[derive(Deserialize)]
+struct HrefOnly {
+    href: Option<String>,
+}
+
+/// 把 [`MultiStatus`] 转换为文件列表
+///
+/// `base_url` 用于把 href 拼接成完整地址，通常是
+/// [`WebdavAuth::base_url`](crate::auth::WebdavAuth)。
+/// `requested_url` 为发送 PROPFIND 的地址（完整 URL 或绝对路径）。
+/// 与列举目录时的规则一致：href 与请求地址相同的目录（请求的目录自身）
+/// 被丢弃，没有 2xx propstat 的资源会被跳过。
+pub fn multistatus_to_files(
+    multi_status: MultiStatus,
+    base_url: &Url,
+    requested_url: &str,
+) -> Result<Vec<RemoteFileData>, WebDavError> {
+    multi_status
+        .to_remote_file_data(base_url, requested_url)
+        .map_err(WebDavError::Parse)
+}
+
+/// 与 [`multistatus_to_files`] 相同，同时返回被跳过的资源及原因
+///
+/// 可以据此区分“空目录”（两者都为空）与“服务器返回的资源都无法使用”。
+pub fn multistatus_to_files_with_warnings(
+    multi_status: MultiStatus,
+    base_url: &Url,
+    requested_url: &str,
+) -> Result<(Vec<RemoteFileData>, Vec<ResponseParseWarning>), WebDavError>
+{
+    multi_status
+        .to_remote_file_data_with_warnings(base_url, requested_url)
+        .map_err(WebDavError::Parse)
+}
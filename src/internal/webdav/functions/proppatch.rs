@@ -0,0 +1,132 @@
+use quick_xml::escape::escape;
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_TYPE, HeaderValue};
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::enums::WebDavMethod;
+use crate::internal::webdav::functions::parse_multistatus::parse_multistatus;
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 设置单个自定义（dead）属性（PROPPATCH）
+///
+/// 属性以 `{namespace}name` 标识；`value` 作为文本内容写入，`<`、`&`、
+/// 引号等会被转义，`namespace` 同样转义后放入 `xmlns` 属性。
+///
+/// - `name` 不是合法的 XML 名称（空、含空白或冒号等）时返回
+///   [`WebDavError::InvalidRequest`]，不会发出请求
+/// - 207 Multi-Status 中该属性的 propstat 不是 2xx（如 403 受保护的属性、
+///   507 空间不足）时返回 [`WebDavError::PropertyFailed`]，带上
+///   `{namespace}name` 与服务器给出的状态行；响应中找不到任何成功的
+///   propstat 时同样视为失败
+/// - 服务器直接返回 200/204 等 2xx（不带 multistatus）时视为成功
+/// - 其余非 2xx 状态返回 [`WebDavError::Status`]（507 返回
+///   [`WebDavError::InsufficientRemoteStorage`]）
+pub async fn proppatch(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    namespace: &str,
+    name: &str,
+    value: &str,
+) -> Result<(), WebDavError> {
+    let body = proppatch_body(namespace, name, value)?;
+    let method = WebDavMethod::PROPPATCH
+        .to_head_method()
+        .map_err(WebDavError::InvalidRequest)?;
+
+    let request = webdav_auth
+        .client
+        .request(method, absolute_url)
+        .header(CONTENT_TYPE, HeaderValue::from_static("application/xml"))
+        .body(body);
+    let res = webdav_auth.send(request).await?;
+
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+
+    if !status.is_success() {
+        return Err(WebDavError::from_write_status(
+            absolute_url,
+            status,
+            body,
+        ));
+    }
+    if status != StatusCode::MULTI_STATUS {
+        return Ok(());
+    }
+
+    let property = format!("{{{}}}{}", namespace, name);
+    let multi_status = parse_multistatus(&body)?;
+    let mut confirmed = false;
+    for response in &multi_status.responses {
+        if let Some(status) = &response.status
+            && !response.status_code().is_some_and(is_success)
+        {
+            return Err(WebDavError::PropertyFailed {
+                property,
+                status: status.clone(),
+            });
+        }
+        for propstat in &response.propstats {
+            if !propstat.is_success() {
+                return Err(WebDavError::PropertyFailed {
+                    property,
+                    status: propstat.status.clone(),
+                });
+            }
+            confirmed = true;
+        }
+    }
+
+    if !confirmed {
+        return Err(WebDavError::PropertyFailed {
+            property,
+            status: "响应中没有该属性的状态".to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn is_success(code: u16) -> bool {
+    (200..=299).contains(&code)
+}
+
+/// 组装 `<D:propertyupdate><D:set>` 请求体，属性放在 `P:` 前缀下
+/// （命名空间为空时不带前缀，并声明 `xmlns=""`）
+pub(crate) fn proppatch_body(
+    namespace: &str,
+    name: &str,
+    value: &str,
+) -> Result<String, WebDavError> {
+    if !is_xml_name(name) {
+        return Err(WebDavError::InvalidRequest(format!(
+            "属性名不是合法的 XML 名称: {:?}",
+            name
+        )));
+    }
+
+    let element = if namespace.is_empty() {
+        format!(r#"<{0} xmlns="">{1}</{0}>"#, name, escape(value))
+    } else {
+        format!(
+            r#"<P:{0} xmlns:P="{1}">{2}</P:{0}>"#,
+            name,
+            escape(namespace),
+            escape(value)
+        )
+    };
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:propertyupdate xmlns:D="DAV:">
+  <D:set><D:prop>{}</D:prop></D:set>
+</D:propertyupdate>"#,
+        element
+    ))
+}
+
+/// 不带前缀的 XML 名称：字母或 `_` 开头，其后为字母、数字、`-`、`.`、`_`
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '.' | '_'))
+}
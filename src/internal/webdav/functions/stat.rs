@@ -0,0 +1,167 @@
+use chrono::DateTime;
+use reqwest::header::{
+    ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_TYPE, ETAG, LAST_MODIFIED,
+};
+
+use crate::auth::structs::request_options::StatMethod;
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::enums::Depth;
+use crate::internal::webdav::functions::get_folders_raw_data::get_folders_raw_data;
+use crate::internal::webdav::stat_info::{RemoteHead, StatInfo};
+use crate::internal::webdav::webdav_error::WebDavError;
+use crate::webdav::traits::ToRemoteFileData;
+
+/// 查询单个资源的基本属性
+///
+/// 先用 [`WebdavAuth::preferred_stat_method`] 给出的方法；返回 400/404/405/501
+/// 或（PROPFIND 时）响应不是可用的 multistatus 时，改用另一种方法再试一次。
+/// 两次都失败时返回后一次的错误，资源不存在时通常为 404 的
+/// [`WebDavError::Status`]。在 [`StatMethod::Auto`] 下，改用的方法成功后
+/// 会被记住，之后优先使用。
+pub async fn stat(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<StatInfo, WebDavError> {
+    let preferred = webdav_auth.preferred_stat_method();
+
+    match stat_with(webdav_auth, absolute_url, preferred).await {
+        Err(e) if should_fall_back(&e) => {
+            let fallback = match preferred {
+                StatMethod::Propfind => StatMethod::Head,
+                _ => StatMethod::Propfind,
+            };
+            let info =
+                stat_with(webdav_auth, absolute_url, fallback).await?;
+            webdav_auth.learn_stat_method(fallback);
+            Ok(info)
+        }
+        result => result,
+    }
+}
+
+/// 资源是否存在
+///
+/// 行为同 [`stat`]；最终结果为 404 时返回 `Ok(false)`，其余错误原样返回
+pub async fn exists(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<bool, WebDavError> {
+    match stat(webdav_auth, absolute_url).await {
+        Ok(_) => Ok(true),
+        Err(WebDavError::Status { status: 404, .. }) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// 优先的方法失败后是否值得换另一种方法
+fn should_fall_back(error: &WebDavError) -> bool {
+    match error {
+        WebDavError::Status { status, .. } => {
+            matches!(status, 400 | 404 | 405 | 501)
+        }
+        WebDavError::NotWebDav { .. }
+        | WebDavError::EmptyBody(_)
+        | WebDavError::Unsupported(_) => true,
+        _ => false,
+    }
+}
+
+async fn stat_with(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+    method: StatMethod,
+) -> Result<StatInfo, WebDavError> {
+    match method {
+        StatMethod::Propfind => {
+            stat_propfind(webdav_auth, absolute_url).await
+        }
+        _ => stat_head(webdav_auth, absolute_url).await,
+    }
+}
+
+/// 发送 HEAD，只解析响应头，不读取响应体
+///
+/// 与 [`stat`] 不同，不会改用 PROPFIND 重试：非 2xx 状态直接返回
+/// [`WebDavError::Status`]（资源不存在为 404，服务器不支持 HEAD 通常为
+/// 405 或 501）。
+pub async fn head(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<RemoteHead, WebDavError> {
+    let res =
+        webdav_auth.send(webdav_auth.client.head(absolute_url)).await?;
+    let status = res.status();
+    if !status.is_success() {
+        return Err(WebDavError::Status {
+            status: status.as_u16(),
+            body: String::new(),
+        });
+    }
+
+    let headers = res.headers();
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+
+    let raw_etag = header(ETAG).map(str::to_string);
+    Ok(RemoteHead {
+        size: header(CONTENT_LENGTH).and_then(|value| value.parse().ok()),
+        mime: header(CONTENT_TYPE).map(str::to_string),
+        etag: raw_etag
+            .as_deref()
+            .map(|value| value.trim_matches('"').to_string()),
+        raw_etag,
+        last_modified: header(LAST_MODIFIED)
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok()),
+        accepts_ranges: header(ACCEPT_RANGES).is_some_and(|value| {
+            value.split(',').any(|unit| unit.trim() == "bytes")
+        }),
+    })
+}
+
+async fn stat_head(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<StatInfo, WebDavError> {
+    let head = head(webdav_auth, absolute_url).await?;
+    Ok(StatInfo {
+        is_dir: None,
+        size: head.size,
+        last_modified: head.last_modified,
+        mime: head.mime,
+        etag: head.etag,
+        method: StatMethod::Head,
+    })
+}
+
+async fn stat_propfind(
+    webdav_auth: &WebdavAuth,
+    absolute_url: &str,
+) -> Result<StatInfo, WebDavError> {
+    let multi_status =
+        get_folders_raw_data(webdav_auth, absolute_url, &Depth::Zero)
+            .await?;
+
+    let data = multi_status
+        .to_all_remote_file_data(&webdav_auth.base_url)
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            WebDavError::Parse(format!(
+                "{} 的 PROPFIND 响应中没有可用的资源",
+                absolute_url
+            ))
+        })?;
+
+    Ok(StatInfo {
+        is_dir: Some(data.is_dir),
+        size: data.size,
+        last_modified: data.last_modified,
+        mime: data.mime,
+        etag: data.etag,
+        method: StatMethod::Propfind,
+    })
+}
@@ -0,0 +1,190 @@
+//! WebDAV 锁：LOCK 响应的解析与自动续期的 [`LockGuard`]。
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use crate::auth::structs::webdav_auth::WebdavAuth;
+use crate::internal::webdav::functions::lock::{
+    lock, refresh_lock, unlock,
+};
+use crate::internal::webdav::webdav_error::WebDavError;
+
+/// 一把已获得的锁
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockInfo {
+    /// 锁令牌（不含尖括号），如 `opaquelocktoken:...`
+    pub token: String,
+    /// 服务器给出的有效期，`None` 表示 `Infinite` 或服务器未返回
+    pub timeout: Option<Duration>,
+}
+
+/// `Timeout` 请求头的值：`Second-N`
+pub(crate) fn timeout_header(timeout_secs: u64) -> String {
+    format!("Second-{}", timeout_secs)
+}
+
+/// 解析 `Timeout` 的值：`Second-3600` 得到 3600 秒，`Infinite` 等得到 `None`
+///
+/// 多个候选值（如 `Infinite, Second-4100000000`）时取第一个可识别的有限值
+pub fn parse_timeout(value: &str) -> Option<Duration> {
+    value.split(',').find_map(|token| {
+        let (unit, secs) = token.trim().split_once('-')?;
+        if !unit.eq_ignore_ascii_case("Second") {
+            return None;
+        }
+        secs.trim().parse().ok().map(Duration::from_secs)
+    })
+}
+
+/// LOCK 响应体 `<D:prop><D:lockdiscovery>...` 中用到的部分
+#[derive(Debug, Deserialize)]
+struct LockProp {
+    lockdiscovery: LockDiscovery,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockDiscovery {
+    #[serde(rename = "activelock", default)]
+    active_locks: Vec<ActiveLock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActiveLock {
+    #[serde(default)]
+    timeout: Option<String>,
+    #[serde(default)]
+    locktoken: Option<LockTokenHref>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockTokenHref {
+    href: String,
+}
+
+/// 从 LOCK 响应体中取出 (令牌, 有效期)；响应体不是预期的 XML 时字段为 `None`
+pub(crate) fn parse_lock_body(
+    xml: &str,
+) -> (Option<String>, Option<Duration>) {
+    let Ok(prop) = quick_xml::de::from_str::<LockProp>(xml) else {
+        return (None, None);
+    };
+    let Some(active) = prop.lockdiscovery.active_locks.into_iter().next()
+    else {
+        return (None, None);
+    };
+
+    let token = active.locktoken.map(|href| href.href.trim().to_string());
+    let timeout = active.timeout.as_deref().and_then(parse_timeout);
+    (token, timeout)
+}
+
+/// 持有期间自动续期、释放时自动解锁的锁
+///
+/// 由 [`LockGuard::acquire`] 获得。后台任务每隔 `refresh_every` 发送一次
+/// [`refresh_lock`]；drop 时停止续期并在后台发送 UNLOCK（尽力而为，
+/// 需要在 tokio 运行时中 drop）。需要确认解锁结果时使用 [`release`](Self::release)。
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use webdav_fs::auth::WebdavAuth;
+/// use webdav_fs::webdav::structs::LockGuard;
+///
+/// # async fn demo(auth: WebdavAuth) -> Result<(), Box<dyn std::error::Error>> {
+/// let url = "https://example.com/dav/report.docx";
+/// let guard =
+///     LockGuard::acquire(&auth, url, 300, Duration::from_secs(120)).await?;
+/// // ... 长时间编辑 ...
+/// guard.release().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct LockGuard {
+    info: LockInfo,
+    url: String,
+    webdav_auth: WebdavAuth,
+    lost: Arc<AtomicBool>,
+    refresher: Option<JoinHandle<()>>,
+}
+
+impl LockGuard {
+    /// 对 `absolute_url` 加锁，并每隔 `refresh_every` 续期为 `timeout_secs` 秒
+    pub async fn acquire(
+        webdav_auth: &WebdavAuth,
+        absolute_url: &str,
+        timeout_secs: u64,
+        refresh_every: Duration,
+    ) -> Result<Self, WebDavError> {
+        let info = lock(webdav_auth, absolute_url, timeout_secs).await?;
+        let lost = Arc::new(AtomicBool::new(false));
+
+        let refresher = {
+            let auth = webdav_auth.clone();
+            let url = absolute_url.to_string();
+            let token = info.token.clone();
+            let lost = Arc::clone(&lost);
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(refresh_every).await;
+                    let result =
+                        refresh_lock(&auth, &url, &token, timeout_secs)
+                            .await;
+                    lost.store(result.is_err(), Ordering::SeqCst);
+                }
+            })
+        };
+
+        Ok(Self {
+            info,
+            url: absolute_url.to_string(),
+            webdav_auth: webdav_auth.clone(),
+            lost,
+            refresher: Some(refresher),
+        })
+    }
+
+    /// 加锁时得到的令牌与有效期
+    pub fn info(&self) -> &LockInfo {
+        &self.info
+    }
+
+    /// 锁令牌，用于在其他请求中携带 `If: (<token>)`
+    pub fn token(&self) -> &str {
+        &self.info.token
+    }
+
+    /// 最近一次续期是否失败（锁可能已过期或被服务器回收）
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::SeqCst)
+    }
+
+    /// 停止续期并解锁，返回 UNLOCK 的结果
+    pub async fn release(mut self) -> Result<(), WebDavError> {
+        if let Some(refresher) = self.refresher.take() {
+            refresher.abort();
+        }
+        unlock(&self.webdav_auth, &self.url, &self.info.token).await
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // 已经 release 过
+        let Some(refresher) = self.refresher.take() else { return };
+        refresher.abort();
+
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let auth = self.webdav_auth.clone();
+        let url = std::mem::take(&mut self.url);
+        let token = std::mem::take(&mut self.info.token);
+        runtime.spawn(async move {
+            let _ = unlock(&auth, &url, &token).await;
+        });
+    }
+}
@@ -0,0 +1,42 @@
+//! 远程存储配额：RFC 4331 的 `quota-used-bytes` / `quota-available-bytes`。
+
+use crate::internal::webdav::raw_xml::raw_file::MultiStatus;
+
+/// [`get_quota`](crate::webdav::functions::get_quota) 的结果，单位为字节
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    /// 已使用的空间
+    pub used: u64,
+    /// 剩余可用的空间
+    pub available: u64,
+}
+
+impl Quota {
+    /// 已使用空间占总空间（已用 + 可用）的百分比，范围 0.0 ~ 100.0
+    ///
+    /// 总空间为 0 时返回 0.0。
+    pub fn percent_used(&self) -> f64 {
+        let total = self.used as f64 + self.available as f64;
+        if total == 0.0 {
+            return 0.0;
+        }
+        self.used as f64 / total * 100.0
+    }
+
+    /// 从 `Depth: 0` 的 PROPFIND 结果中取出配额
+    ///
+    /// 只看第一个资源（请求的目标本身）的 2xx propstat；两个属性缺一不可，
+    /// 缺失或为负数（如 Nextcloud 用 `-3` 表示不限额）时返回 `None`。
+    pub fn from_multi_status(multi_status: &MultiStatus) -> Option<Self> {
+        let response = multi_status.responses.first()?;
+        let mut used = None;
+        let mut available = None;
+        for propstat in
+            response.propstats.iter().filter(|ps| ps.is_success())
+        {
+            used = used.or(propstat.prop.quota_used_bytes);
+            available = available.or(propstat.prop.quota_available_bytes);
+        }
+        Some(Self { used: used?, available: available? })
+    }
+}
@@ -1,2 +1,3 @@
 pub mod raw_file;
-pub mod impl_multi_status;
\ No newline at end of file
+pub mod impl_multi_status;
+pub mod parse_warning;
\ No newline at end of file
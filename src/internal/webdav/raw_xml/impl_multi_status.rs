@@ -1,32 +1,55 @@
-use crate::{remote_file::RemoteFileData, webdav::structs::{CurrentUserPrivilegeSet, MultiStatus, Prop, PropStat, Response}};
+use crate::internal::webdav::functions::normalize_webdav_path::normalize_webdav_path;
+use crate::{
+    remote_file::RemoteFileData,
+    webdav::structs::{
+        CurrentUserPrivilegeSet, MultiStatus, Prop, PropStat, Response,
+        ResponseParseWarning, ResponseWarningReason,
+    },
+};
 use reqwest::Url;
 
 pub trait ToRemoteFileData {
+    /// 转换为文件列表，`requested_url` 为发送 PROPFIND 的地址
+    ///
+    /// 与请求地址 href 相同（规范化后比较）的目录是被列举的目录自身，
+    /// 不出现在结果中；请求的是文件时该文件照常返回。不依赖服务器返回的
+    /// 顺序，也不会误删 `Depth: 0` 时唯一的一项。
+    ///
+    /// 被跳过的资源（没有 2xx propstat、格式不对）不会出现在结果中，
+    /// 需要区分“空目录”与“全部无法解析”时使用
+    /// [`to_remote_file_data_with_warnings`](Self::to_remote_file_data_with_warnings)。
     fn to_remote_file_data(
         self,
         base_url: &Url,
-    ) -> Result<Vec<RemoteFileData>, String>;
-}
+        requested_url: &str,
+    ) -> Result<Vec<RemoteFileData>, String>
+    where
+        Self: Sized,
+    {
+        self.to_remote_file_data_with_warnings(base_url, requested_url)
+            .map(|(files, _)| files)
+    }
 
-fn take_ok_propstat(propstats: Vec<PropStat>) -> Option<PropStat> {
-    // 从 propstats 中拿到第一个 HTTP 状态是 2xx 的 PropStat（直接 move 出来）
-    propstats.into_iter().find(|ps| {
-        ps.status
-            .split_whitespace()
-            .find_map(|t| t.parse::<u16>().ok())
-            .map(|code| (200..=299).contains(&code))
-            .unwrap_or(false)
-    })
+    /// 与 [`to_remote_file_data`](Self::to_remote_file_data) 相同，
+    /// 同时返回每个被跳过的资源及原因
+    fn to_remote_file_data_with_warnings(
+        self,
+        base_url: &Url,
+        requested_url: &str,
+    ) -> Result<(Vec<RemoteFileData>, Vec<ResponseParseWarning>), String>;
+
+    /// 转换全部资源，不按位置丢弃任何一项
+    fn to_all_remote_file_data(self, base_url: &Url) -> Vec<RemoteFileData>;
 }
 
 fn decode_name(display_name: Option<String>, href: &str) -> String {
     // 如果服务端给了 display_name 就直接用（move），否则从 href 末尾提取文件名并 URL 解码
     display_name.unwrap_or_else(|| {
-        percent_encoding::percent_decode_str(
-            href.trim_end_matches('/').rsplit('/').next().unwrap_or(""),
-        )
-        .decode_utf8_lossy()
-        .to_string()
+        normalize_webdav_path(href)
+            .rsplit('/')
+            .next()
+            .unwrap_or("")
+            .to_string()
     })
 }
 
@@ -62,79 +85,119 @@ fn extract_privileges(
     }
 }
 
+fn raw_etag(raw: &Option<String>) -> Option<String> {
+    // 仅去掉 XML 中的首尾空白，引号与 W/ 前缀原样保留
+    raw.as_ref().map(|s| s.trim().to_string())
+}
+
 fn clean_etag(raw: Option<String>) -> Option<String> {
     // 去掉 ETag 的首尾引号以及多余空格
     raw.map(|s| s.trim().trim_matches('"').to_string())
 }
 
 impl ToRemoteFileData for MultiStatus {
-    fn to_remote_file_data(
+    fn to_remote_file_data_with_warnings(
         self,
         base_url: &Url,
-    ) -> Result<Vec<RemoteFileData>, String> {
-        let mut resources = Vec::new();
-
-        let mut iter = self.responses.into_iter();
-
-        if iter.len() > 1 {
-            iter.next(); // 丢掉第一个，因为第一个属于无用数据
-        }
+        requested_url: &str,
+    ) -> Result<(Vec<RemoteFileData>, Vec<ResponseParseWarning>), String>
+    {
+        let requested = normalize_webdav_path(requested_url);
+        let mut warnings = self.skipped;
 
         // 消耗 multi_status.responses 中的每个 Response
-        // 跳过第一项，一般第一项都属于请求的路径本身，属于脏数据
-        for Response { href, propstats } in iter {
-            // 挑选出第一个 2xx PropStat（消耗 propstats 避免 clone）
-            let ok_ps = match take_ok_propstat(propstats) {
-                Some(ps) => ps,
-                None => continue, // 没有 2xx 状态就跳过
-            };
-
-            // 解构 PropStat，move 出 prop
-            let PropStat { prop, .. } = ok_ps;
-
-            // 再解构 Prop，move 出需要的字段
-            let Prop {
-                resource_type,
-                content_length: size,
-                last_modified,
-                content_type: mime,
-                display_name,
-                owner,
-                etag,
-                current_user_privilege_set,
-                ..
-            } = prop;
-
-            // 提前计算 name（因为等下 href 要被 move 进结构体）
-            let name = decode_name(display_name, &href);
-
-            // 判断是否目录
-            let is_dir = resource_type
-                .as_ref()
-                .and_then(|rt| rt.is_collection.as_ref())
-                .is_some();
-
-            let absolute_path = base_url
-                .join(&href)
-                .map(|u| u.to_string())
-                .unwrap_or_else(|_| href.clone());
-
-            // 构造最终 FriendlyResource，绝大部分字段直接 move
-            resources.push(RemoteFileData {
-                base_url: base_url.clone(),
-                relative_root_path: href, // move
-                absolute_path,
-                name, // 已提前生成
-                is_dir,
-                size,
-                last_modified, // move
-                mime,          // move
-                owner,         // move
-                etag: clean_etag(etag),
-                privileges: extract_privileges(current_user_privilege_set),
-            });
+        let mut files = Vec::new();
+        for response in self.responses {
+            match to_resource(response, base_url) {
+                // 被列举的目录自身，不属于列举结果
+                Ok(file)
+                    if file.is_dir
+                        && normalize_webdav_path(&file.absolute_path)
+                            == requested => {}
+                Ok(file) => files.push(file),
+                Err(warning) => warnings.push(warning),
+            }
         }
+        Ok((files, warnings))
+    }
 
-        Ok(resources)
+    fn to_all_remote_file_data(self, base_url: &Url) -> Vec<RemoteFileData> {
+        self.responses
+            .into_iter()
+            .filter_map(|response| to_resource(response, base_url).ok())
+            .collect()
     }
 }
+
+/// 把单个 Response 转换为 RemoteFileData，没有 2xx propstat 时返回跳过原因
+fn to_resource(
+    response: Response,
+    base_url: &Url,
+) -> Result<RemoteFileData, ResponseParseWarning> {
+    let Response { href, mut propstats, status } = response;
+
+    // 挑选出第一个 2xx PropStat（直接 move 出来，避免 clone）
+    let Some(index) = propstats.iter().position(PropStat::is_success)
+    else {
+        let statuses = status
+            .into_iter()
+            .chain(propstats.into_iter().map(|ps| ps.status))
+            .collect();
+        return Err(ResponseParseWarning {
+            href: Some(href),
+            reason: ResponseWarningReason::NoSuccessfulPropstat {
+                statuses,
+            },
+        });
+    };
+    let ok_ps = propstats.swap_remove(index);
+
+    // 解构 PropStat，move 出 prop
+    let PropStat { prop, .. } = ok_ps;
+
+    // 再解构 Prop，move 出需要的字段
+    let Prop {
+        resource_type,
+        content_length: size,
+        last_modified,
+        creation_date,
+        content_type: mime,
+        display_name,
+        owner,
+        etag,
+        current_user_privilege_set,
+        ..
+    } = prop;
+
+    // 提前计算 name（因为等下 href 要被 move 进结构体）
+    let name = decode_name(display_name, &href);
+
+    // 判断是否目录
+    let is_dir = resource_type
+        .as_ref()
+        .and_then(|rt| rt.is_collection.as_ref())
+        .is_some();
+
+    let absolute_path = base_url
+        .join(&href)
+        .map(|u| u.to_string())
+        .unwrap_or_else(|_| href.clone());
+
+    // 构造最终 FriendlyResource，绝大部分字段直接 move
+    Ok(RemoteFileData {
+        base_url: base_url.clone(),
+        relative_root_path: href, // move
+        absolute_path,
+        name, // 已提前生成
+        is_dir,
+        size,
+        last_modified, // move
+        creation_date, // move
+        mime,          // move
+        owner,         // move
+        raw_etag: raw_etag(&etag),
+        etag: clean_etag(etag),
+        privileges: extract_privileges(current_user_privilege_set),
+        allowed_methods: None, // PROPFIND 不返回，需要单独 OPTIONS
+    })
+}
@@ -0,0 +1,37 @@
+//! 转换 multistatus 时被跳过的资源及原因。
+
+use std::fmt;
+
+/// 一个没能转换为 [`RemoteFileData`](crate::remote_file::RemoteFileData)
+/// 的 `<response>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseParseWarning {
+    /// 资源的 `<href>`；资源格式不对、连 href 都取不到时为 `None`
+    pub href: Option<String>,
+    /// 跳过的原因
+    pub reason: ResponseWarningReason,
+}
+
+/// 资源被跳过的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseWarningReason {
+    /// 没有 2xx 的 propstat，附带服务器给出的状态行
+    /// （资源级 `<status>` 与每个 propstat 的状态）
+    NoSuccessfulPropstat { statuses: Vec<String> },
+    /// `<response>` 本身无法解析（缺少 `href`/`prop`、属性重复等）
+    Malformed(String),
+}
+
+impl fmt::Display for ResponseParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let href = self.href.as_deref().unwrap_or("<未知资源>");
+        match &self.reason {
+            ResponseWarningReason::NoSuccessfulPropstat { statuses } => {
+                write!(f, "{} 没有 2xx 的 propstat: {:?}", href, statuses)
+            }
+            ResponseWarningReason::Malformed(reason) => {
+                write!(f, "{} 格式错误: {}", href, reason)
+            }
+        }
+    }
+}
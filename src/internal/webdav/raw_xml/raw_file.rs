@@ -1,6 +1,8 @@
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
+use super::parse_warning::ResponseParseWarning;
+
 /// 对应 WebDAV 响应 XML 顶层的 `<D:multistatus>` 节点
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "kebab-case")]
@@ -8,6 +10,10 @@ pub struct MultiStatus {
     /// `<D:response>` 节点列表，每个 response 表示一个资源（文件或目录）
     #[serde(rename = "response", default)]
     pub responses: Vec<Response>,
+    /// 解析时因格式不对被跳过的 `<response>`，不对应任何 XML 节点，
+    /// 见 [`parse_multistatus`](crate::webdav::functions::parse_multistatus)
+    #[serde(skip)]
+    pub skipped: Vec<ResponseParseWarning>,
 }
 
 /// 对应单个 `<D:response>` 节点
@@ -19,6 +25,9 @@ pub struct Response {
     /// `<D:propstat>`：资源属性集和对应状态码的列表
     #[serde(rename = "propstat", default)]
     pub propstats: Vec<PropStat>,
+    /// `<D:status>`：资源级别的状态（出错时服务器常用它代替 propstat）
+    #[serde(default)]
+    pub status: Option<String>,
 }
 
 /// 对应 `<D:propstat>` 节点：一个属性集 + 对应的 HTTP 状态
@@ -31,8 +40,38 @@ pub struct PropStat {
     pub status: String,
 }
 
+impl PropStat {
+    /// 解析出的 HTTP 状态码，如 "HTTP/1.1 200 OK" 得到 `Some(200)`
+    pub fn status_code(&self) -> Option<u16> {
+        parse_status_code(&self.status)
+    }
+
+    /// 状态是否为 2xx
+    pub fn is_success(&self) -> bool {
+        self.status_code().is_some_and(|code| (200..=299).contains(&code))
+    }
+}
+
+impl Response {
+    /// 资源级 `<D:status>` 的状态码，没有该节点时为 `None`
+    pub fn status_code(&self) -> Option<u16> {
+        self.status.as_deref().and_then(parse_status_code)
+    }
+}
+
+/// 从状态行（如 "HTTP/1.1 404 Not Found"）中解析出 HTTP 状态码
+pub fn parse_status_code(status_line: &str) -> Option<u16> {
+    status_line
+        .split_whitespace()
+        .find_map(|token| token.parse::<u16>().ok())
+        .filter(|code| (100..=599).contains(code))
+}
+
 /// 对应 `<D:prop>` 节点，列出资源的所有属性
-#[derive(Debug, Deserialize, Serialize, Clone)]
+///
+/// 按本地名匹配，命名空间前缀被忽略；未知属性直接跳过。带类型的属性
+/// （大小、时间、配额）为空或格式不对时为 `None`，不会让整个响应解析失败。
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct Prop {
     /// `<resourcetype>`：资源类型（文件/目录）
@@ -40,7 +79,11 @@ pub struct Prop {
     pub resource_type: Option<ResourceType>,
 
     /// `<getcontentlength>`：文件大小（字节），目录一般没有此字段
-    #[serde(rename = "getcontentlength")]
+    #[serde(
+        rename = "getcontentlength",
+        deserialize_with = "de_u64",
+        default
+    )]
     pub content_length: Option<u64>,
 
     /// `<getlastmodified>`：最后修改时间（HTTP-date 格式）
@@ -55,9 +98,13 @@ pub struct Prop {
     #[serde(rename = "getcontenttype")]
     pub content_type: Option<String>,
 
-    /// `<creationdate>`：资源创建时间（ISO8601，通常以 Z 结尾表示 UTC）
-    #[serde(rename = "creationdate")]
-    pub creation_date: Option<String>,
+    /// `<creationdate>`：资源创建时间（RFC 3339，通常以 Z 结尾表示 UTC）
+    #[serde(
+        rename = "creationdate",
+        deserialize_with = "de_rfc3339_date",
+        default
+    )]
+    pub creation_date: Option<DateTime<FixedOffset>>,
 
     /// `<getetag>`：实体标签（文件内容的标识符，可用于缓存或变更检测）
     #[serde(rename = "getetag")]
@@ -67,14 +114,48 @@ pub struct Prop {
     #[serde(rename = "displayname")]
     pub display_name: Option<String>,
 
-    /// `<owner>`：资源所有者（例如邮箱账号）
+    /// `<owner>`：资源所有者（例如邮箱账号），`<owner><href>` 的写法取 href
+    #[serde(deserialize_with = "de_owner", default)]
     pub owner: Option<String>,
 
+    /// `<quota-used-bytes>`：已使用的空间（字节，RFC 4331）
+    #[serde(
+        rename = "quota-used-bytes",
+        deserialize_with = "de_u64",
+        default
+    )]
+    pub quota_used_bytes: Option<u64>,
+
+    /// `<quota-available-bytes>`：剩余可用空间（字节，RFC 4331）
+    ///
+    /// Nextcloud 等用负数表示不限额或未计算，这类值解析为 `None`
+    #[serde(
+        rename = "quota-available-bytes",
+        deserialize_with = "de_u64",
+        default
+    )]
+    pub quota_available_bytes: Option<u64>,
+
     /// `<current-user-privilege-set>`：当前用户对该资源的权限集合
     #[serde(rename = "current-user-privilege-set")]
     pub current_user_privilege_set: Option<CurrentUserPrivilegeSet>,
 }
 
+/// 读取元素文本并用 `parse` 转换，元素为空或格式不对时得到 `None`
+fn de_lenient<'de, D, T>(
+    deserializer: D,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    Ok(s.as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .and_then(parse))
+}
+
 /// 将 HTTP-date 格式的时间解析为 `DateTime<FixedOffset>`
 fn de_http_date<'de, D>(
     deserializer: D,
@@ -82,14 +163,53 @@ fn de_http_date<'de, D>(
 where
     D: serde::Deserializer<'de>,
 {
-    let s: Option<String> = Option::deserialize(deserializer)?;
-    if let Some(s) = s {
-        DateTime::parse_from_rfc2822(&s)
-            .map(Some)
-            .map_err(serde::de::Error::custom)
-    } else {
-        Ok(None)
-    }
+    de_lenient(deserializer, |s| DateTime::parse_from_rfc2822(s).ok())
+}
+
+/// 将 RFC 3339 / ISO 8601 格式的时间解析为 `DateTime<FixedOffset>`
+///
+/// 兼容 `Z` 与 `+08:00` 两种时区写法；少数服务器省略时区，按 UTC 处理。
+fn de_rfc3339_date<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    de_lenient(deserializer, |s| {
+        DateTime::parse_from_rfc3339(s).ok().or_else(|| {
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+                .ok()
+                .map(|naive| naive.and_utc().fixed_offset())
+        })
+    })
+}
+
+/// 解析非负整数（大小、配额），负数（如表示不限额的 `-3`）同样得到 `None`
+fn de_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    de_lenient(deserializer, |s| s.parse().ok())
+}
+
+/// `<owner>` 既可能是纯文本，也可能是 `<owner><href>…</href></owner>`
+#[derive(Deserialize)]
+struct OwnerElement {
+    #[serde(rename = "$text", default)]
+    text: Option<String>,
+    #[serde(default)]
+    href: Option<String>,
+}
+
+fn de_owner<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let owner: Option<OwnerElement> = Option::deserialize(deserializer)?;
+    Ok(owner
+        .and_then(|owner| owner.href.or(owner.text))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty()))
 }
 
 /// `<resourcetype>` 节点
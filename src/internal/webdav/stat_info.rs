@@ -0,0 +1,43 @@
+//! 单个资源的基本属性：由 HEAD 响应头或 `Depth: 0` 的 PROPFIND 得到。
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::auth::structs::request_options::StatMethod;
+
+/// [`stat`](crate::webdav::functions::stat) 的结果
+///
+/// HEAD 与 PROPFIND 能给出的信息不同，拿不到的字段为 `None`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatInfo {
+    /// 是否目录；HEAD 无法判断，为 `None`
+    pub is_dir: Option<bool>,
+    /// 大小（字节）：HEAD 的 `Content-Length` 或 `getcontentlength`
+    pub size: Option<u64>,
+    /// 最后修改时间
+    pub last_modified: Option<DateTime<FixedOffset>>,
+    /// MIME 类型
+    pub mime: Option<String>,
+    /// 去掉引号的 ETag
+    pub etag: Option<String>,
+    /// 实际给出结果的方法（`Head` 或 `Propfind`）
+    pub method: StatMethod,
+}
+
+/// [`head`](crate::webdav::functions::head) 的结果：只来自 HEAD 的响应头
+///
+/// 服务器没有给出的头对应字段为 `None`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteHead {
+    /// `Content-Length`
+    pub size: Option<u64>,
+    /// `Content-Type`
+    pub mime: Option<String>,
+    /// 去掉引号的 `ETag`
+    pub etag: Option<String>,
+    /// 服务器原样返回的 `ETag`（含引号与 `W/`），用于条件请求
+    pub raw_etag: Option<String>,
+    /// `Last-Modified`
+    pub last_modified: Option<DateTime<FixedOffset>>,
+    /// `Accept-Ranges: bytes`，为 `true` 时可以分片或断点续传
+    pub accepts_ranges: bool,
+}
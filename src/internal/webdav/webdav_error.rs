@@ -0,0 +1,96 @@
+//! WebDAV 基础访问相关错误类型。
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WebDavError {
+    #[error("HTTP 请求失败: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("构造请求失败: {0}")]
+    InvalidRequest(String),
+
+    #[error("状态解析异常 {status}: {body}")]
+    Status { status: u16, body: String },
+
+    #[error("XML 解析失败: {0}")]
+    Parse(String),
+
+    /// 响应头已返回，但在超时时间内没有读完响应体
+    #[error("读取响应体超时（{0:?}）")]
+    ResponseReadTimeout(Duration),
+
+    /// 服务器返回了成功状态，但响应体为空
+    #[error("响应体为空，状态码 {0}")]
+    EmptyBody(u16),
+
+    /// 服务器返回的是 HTML 页面而不是 multistatus XML，
+    /// 通常是地址指向了网页或登录页（反向代理、认证网关等）
+    #[error("服务器返回的不是 WebDAV 响应: {hint}")]
+    NotWebDav { hint: String },
+
+    /// 服务器返回了 207/200，但其中每个资源都只带有失败状态，
+    /// 元素为 (href, 状态行)
+    #[error("服务器报告所有资源均失败: {failures:?}")]
+    MultiStatusFailed { failures: Vec<(String, String)> },
+
+    /// MKCOL 返回 405：目标位置已存在资源
+    #[error("资源已存在: {0}")]
+    AlreadyExists(String),
+
+    /// 目标资源不存在（404），用于需要区分“已经不存在”与其他失败的场景，
+    /// 如 `delete_remote`
+    #[error("资源不存在: {0}")]
+    NotFound(String),
+
+    /// MOVE/COPY 返回 412 Precondition Failed：`Overwrite: F` 时目标已存在
+    #[error("目标已存在且不允许覆盖: {0}")]
+    DestinationExists(String),
+
+    /// 路径中的某一级已存在，但它是文件而不是目录
+    #[error("路径中存在同名文件，不是目录: {0}")]
+    NotADirectory(String),
+
+    /// 服务器的 OPTIONS 响应中没有声明所需的能力
+    #[error("服务器不支持: {0}")]
+    Unsupported(String),
+
+    /// HEAD 响应没有 `Content-Length`（例如分块传输编码的动态资源）
+    #[error("无法获取文件大小: {0}")]
+    UnknownSize(String),
+
+    /// 读取要上传的本地文件或目录失败
+    #[error("读取本地文件失败: {0}")]
+    LocalIo(std::io::Error),
+
+    /// 507 Insufficient Storage：远程空间（配额）不足，写入类操作
+    /// （PUT、MKCOL、LOCK）会返回此错误，而不是 [`WebDavError::Status`]
+    #[error("远程存储空间不足: {url}")]
+    InsufficientRemoteStorage { url: String, body: String },
+
+    /// PROPPATCH 的 multistatus 中属性没有设置成功，`property` 为
+    /// `{namespace}name`，`status` 为服务器给出的状态行
+    #[error("属性设置失败 {property}: {status}")]
+    PropertyFailed { property: String, status: String },
+}
+
+impl WebDavError {
+    /// 写入类操作收到非成功状态时的错误：507 对应
+    /// [`WebDavError::InsufficientRemoteStorage`]，其余为 [`WebDavError::Status`]
+    pub(crate) fn from_write_status(
+        url: &str,
+        status: StatusCode,
+        body: String,
+    ) -> Self {
+        if status == StatusCode::INSUFFICIENT_STORAGE {
+            return Self::InsufficientRemoteStorage {
+                url: url.to_string(),
+                body,
+            };
+        }
+        Self::Status { status: status.as_u16(), body }
+    }
+}
@@ -11,6 +11,10 @@ pub use internal::entrance::remote::*;
 pub mod auth {
     use crate::internal;
     pub use internal::auth::*;
+    pub use internal::auth::structs::request_options::{
+        DEFAULT_READ_TIMEOUT, HttpProtocol, RequestOptions, StatMethod,
+    };
+    pub use internal::auth::structs::server_quirks::ServerQuirks;
     pub use internal::auth::structs::webdav_auth::WebdavAuth;
 }
 
@@ -18,7 +22,19 @@ pub mod auth {
 pub mod webdav {
     pub mod functions {
         use crate::internal;
+        pub use internal::webdav::functions::copy_move::*;
+        pub use internal::webdav::functions::delete::*;
+        pub use internal::webdav::functions::ensure_collection_path::*;
+        pub use internal::webdav::functions::get_capabilities::*;
+        #[allow(unused_imports)] // 目前只有 crate 内部使用的函数
         pub use internal::webdav::functions::get_folders_raw_data::*;
+        pub use internal::webdav::functions::get_quota::*;
+        pub use internal::webdav::functions::lock::*;
+        pub use internal::webdav::functions::mkcol::*;
+        pub use internal::webdav::functions::normalize_webdav_path::*;
+        pub use internal::webdav::functions::parse_multistatus::*;
+        pub use internal::webdav::functions::proppatch::*;
+        pub use internal::webdav::functions::stat::*;
     }
 
     pub mod enums {
@@ -26,12 +42,24 @@ pub mod webdav {
         pub use internal::webdav::enums::*;
     }
 
+    pub mod errors {
+        use crate::internal;
+        pub use internal::webdav::webdav_error::*;
+    }
+
     pub mod traits {
         pub use crate::internal::webdav::raw_xml::impl_multi_status::*;
     }
 
     pub mod structs {
+        pub use crate::internal::webdav::capabilities::*;
+        pub use crate::internal::webdav::lock::{
+            LockGuard, LockInfo, parse_timeout,
+        };
+        pub use crate::internal::webdav::quota::*;
+        pub use crate::internal::webdav::raw_xml::parse_warning::*;
         pub use crate::internal::webdav::raw_xml::raw_file::*;
+        pub use crate::internal::webdav::stat_info::*;
     }
 }
 
@@ -50,13 +78,37 @@ pub mod states {
         use crate::internal;
         pub use internal::states::unlock_reactive::*;
     }
+
+    pub mod broadcast_reactive {
+        use crate::internal;
+        pub use internal::states::broadcast_reactive::*;
+    }
 }
 
+/// 远程文件领域与下载器
+///
+/// 下载相关的公开类型都以 `webdav_fs::remote_file::*` 为稳定导入路径，
+/// 不要依赖 `internal` 下的模块路径：
+///
+/// ```rust,no_run
+/// use webdav_fs::remote_file::{
+///     DownloadConfig, DownloadMetrics, DownloadProgress, DownloadResult,
+///     RemoteDownloader, RemoteDownloaderController, RemoteFile,
+/// };
+/// ```
+///
+/// 下载器 trait（如下载钩子）同样从本模块导出。
 pub mod remote_file {
     use crate::internal;
     // 结构体模型
+    pub use internal::remote_file::structs::folder_view::*;
+    pub use internal::remote_file::structs::listing_cache::ListingCache;
+    pub use internal::remote_file::structs::listing_cancel::*;
+    pub use internal::remote_file::structs::listing_diff::*;
+    pub use internal::remote_file::structs::recursion_policy::*;
     pub use internal::remote_file::structs::remote_file::*;
     pub use internal::remote_file::structs::remote_file_data::*;
+    pub use internal::remote_file::structs::sanitize_policy::*;
     // 下载器：类型与入口（以 lib 为中心，此处统一导出）
     pub use internal::remote_file::downloader::structs::*;
     pub use internal::remote_file::downloader::traits::*;
@@ -64,5 +116,11 @@ pub mod remote_file {
 
 pub mod local_file {
     use crate::internal;
-    pub use internal::local_file::*;
+    pub use internal::local_file::functions::available_space::*;
+    pub use internal::local_file::functions::plan_upload::*;
+    pub use internal::local_file::functions::upload_directory_as_tar::*;
+    pub use internal::local_file::structs::tar_upload::{
+        TarUpload, UploadProgress,
+    };
+    pub use internal::local_file::structs::upload_plan::*;
 }
@@ -1,5 +1,38 @@
+pub mod bearer_auth;
+pub mod byte_segments;
+pub mod capabilities;
+pub mod connection_pool;
+pub mod copy_move;
+pub mod delete_many;
+pub mod delete_remote;
+pub mod digest_auth;
+pub mod download_many;
+pub mod download_speed;
 pub mod downloader;
+pub mod downloader_mock;
+pub mod ensure_collection_path;
+pub mod folder_view;
+pub mod get_folders_raw_data;
 pub mod get_remote_files;
+pub mod http_protocol;
+pub mod listing_cache;
+pub mod listing_diff;
+pub mod lock;
+pub mod multi_status;
+pub mod normalize_webdav_path;
+pub mod parse_multistatus;
+pub mod plan_upload;
+pub mod proppatch;
+pub mod proxy;
+pub mod quota;
 pub mod reactive_property;
 pub mod reactive_performance;
+pub mod recursive_listing;
+pub mod remote_files_tree;
+pub mod request_timeout;
+pub mod sanitize_policy;
+pub mod size_or_fetch;
+pub mod stat;
+pub mod tar_upload;
+pub mod upload;
 pub mod states_concurrent;
\ No newline at end of file
@@ -0,0 +1,56 @@
+//! Bearer token 认证测试：请求头、指纹比较与 Debug 脱敏。
+
+use crate::auth::WebdavAuth;
+use crate::delete_remote;
+use crate::tests::mock_server::{MockResponse, MockServer};
+
+const TOKEN: &str = "eyJhbGciOiJIUzI1NiJ9.secret-access-token";
+
+/// 测试：每个请求都带上 `Authorization: Bearer <token>`
+#[tokio::test]
+async fn sends_bearer_header() {
+    let server = MockServer::start(|_| MockResponse::new(204));
+    let auth = WebdavAuth::new_bearer(TOKEN, server.base_url())
+        .expect("创建 Bearer 认证失败");
+
+    delete_remote(&auth, "a/").await.expect("DELETE 应成功");
+
+    let expected = format!("Bearer {}", TOKEN);
+    assert_eq!(
+        server.requests()[0].header("Authorization"),
+        Some(expected.as_str())
+    );
+}
+
+/// 测试：相等比较基于 token 指纹，且与同一字符串的 Basic 认证区分
+#[test]
+fn bearer_equality_uses_token_fingerprint() {
+    let a =
+        WebdavAuth::new_bearer(TOKEN, "http://a.example/dav/").unwrap();
+    let b =
+        WebdavAuth::new_bearer(TOKEN, "http://b.example/dav/").unwrap();
+    let other =
+        WebdavAuth::new_bearer("other", "http://a.example/dav/").unwrap();
+    let basic =
+        WebdavAuth::new(TOKEN, "", "http://a.example/dav/").unwrap();
+
+    assert!(a.eq_only_token(&b));
+    assert_ne!(a, b);
+    assert!(!a.eq_only_token(&other));
+    assert!(!a.eq_only_token(&basic));
+    assert!(WebdavAuth::new_bearer("", "http://a.example/dav/").is_err());
+}
+
+/// 测试：Debug 输出中不出现 token 及其指纹
+#[test]
+fn debug_output_hides_token() {
+    let auth =
+        WebdavAuth::new_bearer(TOKEN, "http://localhost/dav/").unwrap();
+
+    let debug = format!("{:?}", auth);
+    assert!(!debug.contains(TOKEN), "❌ Debug 泄漏了 token: {}", debug);
+    assert!(!debug.contains("secret"));
+    assert!(!debug.contains(auth.encrypted_token.as_str()));
+    let pretty = format!("{:#?}", auth);
+    assert!(!pretty.contains(TOKEN));
+}
@@ -0,0 +1,75 @@
+//! ByteSegments 测试：按偏移读取、复用缓冲区读取与连续性校验。
+
+use crate::remote_file::{ByteSegment, ByteSegments};
+
+fn segments(parts: &[(u64, &[u8])]) -> ByteSegments {
+    ByteSegments::new(
+        parts
+            .iter()
+            .map(|&(offset, data)| ByteSegment {
+                offset,
+                data: data.to_vec(),
+            })
+            .collect(),
+    )
+}
+
+/// 测试：连续分段可以跨段读取，顺序读取时复用同一个缓冲区
+#[test]
+fn contiguous_segments_read_across_boundaries() {
+    let segs = segments(&[(0, b"0123"), (4, b"4567"), (8, b"89")]);
+    assert!(segs.is_contiguous());
+    assert_eq!(segs.total_len(), 10);
+    assert_eq!(segs.segments().count(), 3);
+
+    assert_eq!(segs.read_at(2, 5), b"23456");
+    assert_eq!(segs.read_at(7, 100), b"789");
+    assert!(segs.read_at(10, 5).is_empty());
+    assert!(segs.read_at(3, 0).is_empty());
+
+    let mut buf = [0u8; 3];
+    let mut read = Vec::new();
+    let mut offset = 0;
+    loop {
+        let n = segs.read_into(offset, &mut buf);
+        if n == 0 {
+            break;
+        }
+        read.extend_from_slice(&buf[..n]);
+        offset += n as u64;
+    }
+    assert_eq!(read, segs.to_bytes());
+    assert_eq!(read, b"0123456789");
+
+    assert!(ByteSegments::new(Vec::new()).is_contiguous());
+}
+
+/// 测试：有空洞或不从 0 开始时不连续，读取在空洞处停止
+#[test]
+fn gapped_segments_are_detected() {
+    let segs = segments(&[(0, b"0123"), (6, b"6789")]);
+    assert!(!segs.is_contiguous());
+    assert_eq!(segs.total_len(), 10);
+
+    let mut buf = [0xffu8; 8];
+    assert_eq!(segs.read_into(2, &mut buf), 2);
+    assert_eq!(&buf[..3], b"23\xff", "❌ 空洞之后不应写入缓冲区");
+    assert_eq!(segs.read_at(2, 6), b"23");
+    assert_eq!(segs.read_at(6, 10), b"6789");
+    assert!(segs.read_at(4, 4).is_empty());
+
+    assert!(!segments(&[(2, b"23")]).is_contiguous());
+}
+
+/// 测试：重叠分段不连续，读取时不会重复输出重叠部分
+#[test]
+fn overlapping_segments_are_detected() {
+    // 第二段完全落在第一段内，第三段与第一段部分重叠
+    let segs =
+        segments(&[(0, b"01234"), (1, b"12"), (3, b"3456"), (6, b"67")]);
+    assert!(!segs.is_contiguous());
+    assert_eq!(segs.total_len(), 8);
+
+    assert_eq!(segs.read_at(0, 10), b"01234567");
+    assert_eq!(segs.read_at(4, 3), b"456");
+}
@@ -0,0 +1,142 @@
+//! OPTIONS 能力解析测试（基于本地 MockServer）。
+
+use crate::auth::WebdavAuth;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::enums::DavClass;
+use crate::webdav::errors::WebDavError;
+use crate::tests::mock_server::mock_remote_file;
+use crate::webdav::functions::{get_allowed_methods, get_capabilities};
+use crate::webdav::structs::{
+    AllowedMethods, Capabilities, parse_dav_header,
+};
+
+/// 测试：解析数字等级、扩展名与 URI 形式的扩展
+#[test]
+fn parse_dav_header_tokens() {
+    let classes = parse_dav_header(
+        "1, 3 , Extended-MKCOL,<http://apache.org/dav/propset/fs/1>,",
+    );
+    assert_eq!(
+        classes,
+        vec![
+            DavClass::Class1,
+            DavClass::Class3,
+            DavClass::Extension("extended-mkcol".to_string()),
+            DavClass::Extension(
+                "<http://apache.org/dav/propset/fs/1>".to_string()
+            ),
+        ]
+    );
+}
+
+/// 测试：class 2 以 class 1 为前提；未声明的能力 require 返回 Unsupported
+#[test]
+fn supports_and_require() {
+    let caps = Capabilities::from_headers(["2"], ["GET, PUT", "propfind"]);
+    assert!(caps.supports(&DavClass::Class1));
+    assert!(caps.supports(&DavClass::Class2));
+    assert!(!caps.supports(&DavClass::Class3));
+    assert!(caps.allows("PROPFIND"));
+    assert!(!caps.allows("LOCK"));
+
+    let err = caps
+        .require(&DavClass::Extension("extended-mkcol".to_string()))
+        .unwrap_err();
+    assert!(
+        matches!(err, WebDavError::Unsupported(ref name) if name == "extended-mkcol")
+    );
+}
+
+/// 测试：OPTIONS 响应的多个 DAV 头合并去重
+#[tokio::test]
+async fn get_capabilities_reads_options_headers() {
+    let server = MockServer::start(|req| {
+        if req.method != "OPTIONS" {
+            return MockResponse::new(405);
+        }
+        MockResponse::new(200)
+            .header("DAV", "1, 2")
+            .header("DAV", "2, access-control")
+            .header("Allow", "OPTIONS, GET, PROPFIND, LOCK")
+    });
+    let auth =
+        WebdavAuth::new("user", "password", server.base_url()).unwrap();
+
+    let caps = get_capabilities(&auth, &server.url("dav/")).await.unwrap();
+
+    assert_eq!(
+        caps.classes,
+        vec![
+            DavClass::Class1,
+            DavClass::Class2,
+            DavClass::Extension("access-control".to_string()),
+        ]
+    );
+    assert!(caps.allows("lock"));
+    assert!(caps.require(&DavClass::Class2).is_ok());
+}
+
+/// 测试：OPTIONS 失败时返回状态错误
+#[tokio::test]
+async fn get_capabilities_status_error() {
+    let server = MockServer::start(|_| MockResponse::new(501));
+    let auth =
+        WebdavAuth::new("user", "password", server.base_url()).unwrap();
+
+    let err =
+        get_capabilities(&auth, &server.url("dav/")).await.unwrap_err();
+    assert!(matches!(err, WebDavError::Status { status: 501, .. }));
+}
+
+/// 测试：AllowedMethods 只认明确列出的方法
+#[test]
+fn allowed_methods_are_explicit() {
+    let allowed = AllowedMethods::from_headers(["get, HEAD", "Delete"]);
+    assert_eq!(allowed.methods, vec!["GET", "HEAD", "DELETE"]);
+    assert!(allowed.can_get());
+    assert!(allowed.can_delete());
+    assert!(!allowed.can_put());
+    assert!(!allowed.can_move());
+
+    // 没有 Allow 头时 Capabilities 视为全部允许，但不产生 AllowedMethods
+    let caps = Capabilities::from_headers(["1"], []);
+    assert!(caps.allows("MOVE"));
+    assert_eq!(caps.allowed(), None);
+}
+
+/// 测试：对单个资源 OPTIONS 并写入 RemoteFileData
+#[tokio::test]
+async fn fetch_allowed_methods_for_resource() {
+    let server = MockServer::start(|req| {
+        if req.path.ends_with("readonly.txt") {
+            MockResponse::new(200)
+                .header("DAV", "1")
+                .header("Allow", "OPTIONS, GET, HEAD, PROPFIND")
+        } else {
+            MockResponse::new(200).header("DAV", "1")
+        }
+    });
+
+    let mut file = mock_remote_file(&server, "readonly.txt", Some(1));
+    assert!(file.data.allowed_methods.is_none());
+
+    let allowed = file
+        .fetch_allowed_methods()
+        .await
+        .expect("OPTIONS 失败")
+        .cloned()
+        .expect("应返回 Allow");
+    assert!(allowed.can_get());
+    assert!(!allowed.can_delete());
+    assert!(!allowed.can_put());
+    assert_eq!(file.data.allowed_methods, Some(allowed));
+
+    let auth = file.webdav_auth.clone();
+    let without_allow =
+        get_allowed_methods(&auth, &server.url("other.txt")).await;
+    assert!(matches!(without_allow, Ok(None)));
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "OPTIONS");
+    assert_eq!(requests[0].path, "/readonly.txt");
+}
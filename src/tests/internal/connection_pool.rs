@@ -0,0 +1,141 @@
+//! 连接池测试：WebdavAuth::with_pool 的设置与连接复用。
+//!
+//! 共用的 mock 服务器每个请求后关闭连接，这里使用一个保持连接、
+//! 统计 TCP 连接数的简易服务器。
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::auth::WebdavAuth;
+
+/// 支持 keep-alive 的服务器：每个请求都返回 `ok`，返回 (根 URL, 连接数)
+fn keep_alive_server() -> (String, Arc<AtomicUsize>) {
+    let listener =
+        TcpListener::bind("127.0.0.1:0").expect("绑定测试端口失败");
+    let addr = listener.local_addr().expect("读取测试端口失败");
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    let counter = Arc::clone(&connections);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            counter.fetch_add(1, Ordering::SeqCst);
+            thread::spawn(move || {
+                let Ok(read_half) = stream.try_clone() else { return };
+                let mut reader = BufReader::new(read_half);
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    match reader.read_line(&mut line) {
+                        Ok(0) | Err(_) => return,
+                        // 请求头结束（GET 没有请求体）
+                        Ok(_) if line == "\r\n" => {
+                            let response = "HTTP/1.1 200 OK\r\n\
+                                Content-Length: 2\r\n\r\nok";
+                            if stream
+                                .write_all(response.as_bytes())
+                                .is_err()
+                            {
+                                return;
+                            }
+                        }
+                        Ok(_) => {}
+                    }
+                }
+            });
+        }
+    });
+
+    (format!("http://{}/", addr), connections)
+}
+
+/// 依次发送 `count` 个 GET
+async fn get_sequentially(auth: &WebdavAuth, url: &str, count: usize) {
+    for _ in 0..count {
+        let body = auth
+            .client
+            .get(url)
+            .send()
+            .await
+            .expect("请求失败")
+            .text()
+            .await
+            .expect("读取响应失败");
+        assert_eq!(body, "ok");
+    }
+}
+
+/// 测试：with_pool 记录设置，且不改变相等比较与其他选项
+#[test]
+fn with_pool_records_options() {
+    let plain = WebdavAuth::new("user", "password", "http://dav.invalid/")
+        .expect("创建测试认证失败");
+    let auth = plain
+        .clone()
+        .with_timeout(Duration::from_secs(5), Duration::from_secs(5))
+        .and_then(|auth| auth.with_pool(8, Duration::from_secs(30)))
+        .expect("设置连接池失败");
+
+    assert_eq!(auth, plain);
+    let options = auth.request_options();
+    assert_eq!(options.pool_max_idle_per_host, Some(8));
+    assert_eq!(options.pool_idle_timeout, Some(Duration::from_secs(30)));
+    assert_eq!(options.request_timeout, Some(Duration::from_secs(5)));
+}
+
+/// 测试：默认复用空闲连接；不保留空闲连接时每个请求新建连接
+#[tokio::test]
+async fn idle_connections_are_reused() {
+    let (base_url, connections) = keep_alive_server();
+    let url = format!("{}a.txt", base_url);
+    let auth = WebdavAuth::new("user", "password", &base_url)
+        .expect("创建测试认证失败");
+
+    get_sequentially(&auth, &url, 10).await;
+    assert_eq!(connections.load(Ordering::SeqCst), 1, "❌ 连接未复用");
+
+    let no_pool = auth
+        .with_pool(0, Duration::from_secs(30))
+        .expect("设置连接池失败");
+    connections.store(0, Ordering::SeqCst);
+    get_sequentially(&no_pool, &url, 10).await;
+    assert_eq!(connections.load(Ordering::SeqCst), 10);
+}
+
+/// 基准：复用连接与每次新建连接的请求耗时对比（默认忽略）
+///
+/// 运行：`cargo test connection_reuse_benchmark -- --ignored --nocapture`
+#[tokio::test]
+#[ignore = "基准测试，需要时手动运行"]
+async fn connection_reuse_benchmark() {
+    const REQUESTS: usize = 500;
+    let (base_url, connections) = keep_alive_server();
+    let url = format!("{}a.txt", base_url);
+    let pooled = WebdavAuth::new("user", "password", &base_url)
+        .and_then(|auth| auth.with_pool(8, Duration::from_secs(30)))
+        .expect("创建测试认证失败");
+    let unpooled = pooled
+        .clone()
+        .with_pool(0, Duration::from_secs(30))
+        .expect("设置连接池失败");
+
+    for (name, auth) in [("复用连接", &pooled), ("不复用连接", &unpooled)]
+    {
+        connections.store(0, Ordering::SeqCst);
+        let start = Instant::now();
+        get_sequentially(auth, &url, REQUESTS).await;
+        let elapsed = start.elapsed();
+        println!(
+            "{}: {} 个请求耗时 {:.2?}（{:.0} 次/秒），建立连接 {} 次",
+            name,
+            REQUESTS,
+            elapsed,
+            REQUESTS as f64 / elapsed.as_secs_f64(),
+            connections.load(Ordering::SeqCst)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! move_remote / copy_remote 测试（使用本地 mock 服务器）
+
+use crate::auth::WebdavAuth;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::errors::WebDavError;
+use crate::{copy_remote, move_remote};
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", &server.url("dav/"))
+        .expect("创建测试认证失败")
+}
+
+/// 测试：方法、编码后的 Destination 与 Overwrite 头
+#[tokio::test]
+async fn sends_encoded_destination_and_overwrite() {
+    let server = MockServer::start(|_| MockResponse::new(201));
+    let auth = auth(&server);
+
+    move_remote(&auth, "a.txt", "归档/b c.txt", false)
+        .await
+        .expect("MOVE 应成功");
+    copy_remote(&auth, "docs/", "backup/docs/", true)
+        .await
+        .expect("COPY 应成功");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+
+    assert_eq!(requests[0].method, "MOVE");
+    assert_eq!(requests[0].path, "/dav/a.txt");
+    assert_eq!(
+        requests[0].header("Destination"),
+        Some(server.url("dav/%E5%BD%92%E6%A1%A3/b%20c.txt").as_str())
+    );
+    assert_eq!(requests[0].header("Overwrite"), Some("F"));
+
+    assert_eq!(requests[1].method, "COPY");
+    assert_eq!(requests[1].path, "/dav/docs/");
+    assert_eq!(
+        requests[1].header("Destination"),
+        Some(server.url("dav/backup/docs/").as_str())
+    );
+    assert_eq!(requests[1].header("Overwrite"), Some("T"));
+}
+
+/// 测试：412 返回 DestinationExists，其余失败返回状态码
+#[tokio::test]
+async fn precondition_failed_is_typed() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/dav/exists.txt" => MockResponse::new(412),
+        _ => MockResponse::new(423).body("locked"),
+    });
+    let auth = auth(&server);
+
+    let result = move_remote(&auth, "exists.txt", "b.txt", false).await;
+    assert!(
+        matches!(
+            &result,
+            Err(WebDavError::DestinationExists(url))
+                if url == &server.url("dav/b.txt")
+        ),
+        "❌ 412 应返回 DestinationExists: {:?}",
+        result
+    );
+
+    let result = copy_remote(&auth, "locked.txt", "b.txt", true).await;
+    assert!(
+        matches!(result, Err(WebDavError::Status { status: 423, .. })),
+        "❌ 423 应返回 Status: {:?}",
+        result
+    );
+}
+
+/// 测试：任一端跳出 base_url 时不发送请求
+#[tokio::test]
+async fn escaping_endpoint_is_rejected() {
+    let server = MockServer::start(|_| MockResponse::new(201));
+    let auth = auth(&server);
+
+    let result = move_remote(&auth, "a.txt", "../outside.txt", true).await;
+    assert!(
+        matches!(result, Err(WebDavError::InvalidRequest(_))),
+        "❌ 目标跳出 base_url 应被拒绝: {:?}",
+        result
+    );
+    let result = copy_remote(&auth, "../outside.txt", "a.txt", true).await;
+    assert!(
+        matches!(result, Err(WebDavError::InvalidRequest(_))),
+        "❌ 源跳出 base_url 应被拒绝: {:?}",
+        result
+    );
+    assert!(server.requests().is_empty());
+}
@@ -0,0 +1,85 @@
+//! 批量删除测试（基于本地 MockServer）。
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::auth::WebdavAuth;
+use crate::delete_many;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::errors::WebDavError;
+
+fn auth_for(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url()).unwrap()
+}
+
+/// 测试：结果与输入顺序一致，单个失败不影响其余路径
+#[tokio::test]
+async fn delete_many_reports_each_path() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/dav/missing.txt" => MockResponse::new(404),
+        "/dav/locked/" => MockResponse::new(423),
+        _ => MockResponse::new(204),
+    });
+    // base_url 带子目录，`../` 会跳出 base_url
+    let auth =
+        WebdavAuth::new("user", "password", &server.url("dav/")).unwrap();
+
+    let results = delete_many(
+        &auth,
+        &["a.txt", "missing.txt", "locked/", "../outside.txt", "b.txt"],
+        2,
+    )
+    .await;
+
+    let paths: Vec<&str> =
+        results.iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec!["a.txt", "missing.txt", "locked/", "../outside.txt", "b.txt"]
+    );
+    assert!(results[0].1.is_ok());
+    assert!(matches!(
+        results[1].1,
+        Err(WebDavError::Status { status: 404, .. })
+    ));
+    assert!(matches!(
+        results[2].1,
+        Err(WebDavError::Status { status: 423, .. })
+    ));
+    assert!(matches!(results[3].1, Err(WebDavError::InvalidRequest(_))));
+    assert!(results[4].1.is_ok());
+
+    let methods: Vec<String> =
+        server.requests().into_iter().map(|r| r.method).collect();
+    assert_eq!(methods.len(), 4);
+    assert!(methods.iter().all(|m| m == "DELETE"));
+}
+
+/// 测试：同时进行的 DELETE 请求数不超过 concurrency
+#[tokio::test]
+async fn delete_many_respects_concurrency() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let server = MockServer::start({
+        let in_flight = Arc::clone(&in_flight);
+        let peak = Arc::clone(&peak);
+        move |_| {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            MockResponse::new(204)
+        }
+    });
+    let auth = auth_for(&server);
+    let paths: Vec<String> =
+        (0..8).map(|i| format!("f{}.txt", i)).collect();
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+
+    let results = delete_many(&auth, &paths, 3).await;
+
+    assert!(results.iter().all(|(_, r)| r.is_ok()));
+    assert!(peak.load(Ordering::SeqCst) <= 3);
+}
@@ -0,0 +1,129 @@
+//! delete_remote 测试：目录补尾部斜杠、404 与其他失败的区分。
+
+use crate::auth::WebdavAuth;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::tests::{TestVendor, load_account_optional};
+use crate::{delete_remote, upload_remote_file};
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败")
+}
+
+/// 只含一个资源的 PROPFIND 响应
+fn propfind_response(href: &str, is_dir: bool) -> MockResponse {
+    let resource_type = if is_dir {
+        "<d:resourcetype><d:collection/></d:resourcetype>"
+    } else {
+        "<d:resourcetype/>"
+    };
+    MockResponse::new(207).body(format!(
+        r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">
+<d:response><d:href>{}</d:href><d:propstat><d:prop>{}</d:prop>
+<d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#,
+        href, resource_type
+    ))
+}
+
+/// 测试：200 与 204 都视为成功，文件按原地址删除
+#[tokio::test]
+async fn file_is_deleted_at_its_url() {
+    for status in [200, 204] {
+        let server =
+            MockServer::start(move |req| match req.method.as_str() {
+                "PROPFIND" => propfind_response(&req.path, false),
+                _ => MockResponse::new(status),
+            });
+
+        let result = delete_remote(&auth(&server), "a.txt").await;
+
+        assert_eq!(result, Ok(()), "❌ {} 应视为成功", status);
+        let deletes: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|req| req.method == "DELETE")
+            .collect();
+        assert_eq!(deletes.len(), 1);
+        assert_eq!(deletes[0].path, "/a.txt");
+    }
+}
+
+/// 测试：目录补上尾部斜杠后删除；已带斜杠时不再查询
+#[tokio::test]
+async fn collection_gets_trailing_slash() {
+    let server = MockServer::start(|req| match req.method.as_str() {
+        "PROPFIND" => propfind_response("/docs/", true),
+        _ => MockResponse::new(204),
+    });
+
+    assert_eq!(delete_remote(&auth(&server), "docs").await, Ok(()));
+    assert_eq!(delete_remote(&auth(&server), "more/").await, Ok(()));
+
+    let requests: Vec<_> = server
+        .requests()
+        .into_iter()
+        .map(|req| (req.method, req.path))
+        .collect();
+    assert_eq!(
+        requests,
+        [
+            ("PROPFIND".to_string(), "/docs".to_string()),
+            ("DELETE".to_string(), "/docs/".to_string()),
+            ("DELETE".to_string(), "/more/".to_string()),
+        ]
+    );
+}
+
+/// 测试：404 与其他失败返回可区分的错误
+#[tokio::test]
+async fn missing_is_distinguished_from_failure() {
+    let missing = MockServer::start(|_| MockResponse::new(404));
+    let message = delete_remote(&auth(&missing), "gone.txt")
+        .await
+        .expect_err("404 不应视为成功");
+    assert!(
+        message.starts_with("资源不存在"),
+        "❌ 404 应返回资源不存在: {}",
+        message
+    );
+    // PROPFIND 已经确认不存在，不再发送 DELETE
+    assert_eq!(missing.requests().len(), 1);
+
+    // 查询不可用时仍按原地址删除
+    let locked = MockServer::start(|req| match req.method.as_str() {
+        "PROPFIND" => MockResponse::new(405),
+        _ => MockResponse::new(423).body("locked"),
+    });
+    let message = delete_remote(&auth(&locked), "a.txt")
+        .await
+        .expect_err("423 不应视为成功");
+    assert!(
+        message.contains("423") && !message.starts_with("资源不存在"),
+        "❌ 其他失败应返回状态码: {}",
+        message
+    );
+}
+
+/// 测试：真实服务器上先上传临时文件再删除（未配置账号时跳过）
+#[tokio::test]
+async fn delete_uploaded_file_on_vendor() {
+    // 生成的 env 文件未填写时变量为空，同样跳过
+    let Some(account) = load_account_optional(TestVendor::Teracloud)
+        .filter(|account| !account.url.is_empty())
+    else {
+        return;
+    };
+    let auth = account.to_webdav_auth().expect("创建认证失败");
+    let path = "webdav_fs_delete_remote_test.txt";
+
+    upload_remote_file(&auth, path, "delete me")
+        .await
+        .expect("上传临时文件失败");
+    assert_eq!(delete_remote(&auth, path).await, Ok(()));
+
+    let message = delete_remote(&auth, path)
+        .await
+        .expect_err("删除后再次删除应失败");
+    assert!(message.starts_with("资源不存在"), "❌ {}", message);
+}
@@ -0,0 +1,296 @@
+//! Digest 认证测试：RFC 7616 示例向量、质询解析，
+//! 以及只接受 Digest 的本地 mock 服务器上的握手与重发。
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use reqwest::Method;
+use reqwest::header::{HeaderMap, HeaderValue, WWW_AUTHENTICATE};
+use sha2::{Digest, Sha256};
+
+use crate::auth::WebdavAuth;
+use crate::internal::auth::structs::digest_auth::{
+    DigestAlgorithm, DigestAuth, DigestChallenge,
+};
+use crate::remote_file::{DownloadResult, RemoteFile};
+use crate::tests::mock_server::{
+    MockRequest, MockResponse, MockServer, file_response, mock_remote_file,
+};
+use crate::{delete_remote, upload_remote_file};
+
+/// RFC 7616 第 3.9.1 节示例中的质询
+fn rfc_challenge(algorithm: DigestAlgorithm) -> DigestChallenge {
+    DigestChallenge {
+        realm: "http-auth@example.org".to_string(),
+        nonce: "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v".to_string(),
+        opaque: Some(
+            "FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS".to_string(),
+        ),
+        algorithm,
+        qop_auth: true,
+        userhash: false,
+    }
+}
+
+/// 测试：按 RFC 7616 的示例计算出相同的 response
+#[test]
+fn authorization_matches_rfc_7616_examples() {
+    let digest = DigestAuth::new("Mufasa", "Circle of Life");
+    let cnonce = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    for (algorithm, response) in [
+        (DigestAlgorithm::Md5, "8ca523f5e9506fed4657c9700eebdbec"),
+        (
+            DigestAlgorithm::Sha256,
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1",
+        ),
+    ] {
+        let header = digest.authorization(
+            &rfc_challenge(algorithm),
+            &Method::GET,
+            "/dir/index.html",
+            1,
+            cnonce,
+        );
+        assert!(
+            header.contains(&format!(r#"response="{}""#, response)),
+            "❌ {:?} 的 response 不正确: {}",
+            algorithm,
+            header
+        );
+        assert!(header.contains("qop=auth, nc=00000001"));
+        assert!(header.contains(
+            r#"opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#
+        ));
+    }
+}
+
+/// 测试：质询解析支持同一个值中的多个方案、引号内的逗号与多个响应头
+#[test]
+fn challenges_are_parsed_and_strongest_is_chosen() {
+    let challenge = DigestChallenge::parse(
+        r#"Basic realm="basic", Digest realm="a, b", qop="auth,auth-int", nonce="n1", opaque="o""#,
+    )
+    .expect("应解析出 Digest 质询");
+    assert_eq!(challenge.realm, "a, b");
+    assert_eq!(challenge.nonce, "n1");
+    assert_eq!(challenge.opaque.as_deref(), Some("o"));
+    assert_eq!(challenge.algorithm, DigestAlgorithm::Md5);
+    assert!(challenge.qop_auth);
+
+    // 不支持的情况
+    assert_eq!(DigestChallenge::parse(r#"Basic realm="x""#), None);
+    assert_eq!(
+        DigestChallenge::parse(r#"Digest realm="x", qop="auth-int""#),
+        None
+    );
+    assert_eq!(
+        DigestChallenge::parse(
+            r#"Digest realm="x", nonce="n", algorithm=SHA-512-256"#
+        ),
+        None
+    );
+
+    let mut headers = HeaderMap::new();
+    for value in [
+        r#"Digest realm="x", nonce="md5", algorithm=MD5, qop="auth""#,
+        r#"Digest realm="x", nonce="sha", algorithm=SHA-256, qop="auth""#,
+    ] {
+        headers.append(WWW_AUTHENTICATE, HeaderValue::from_static(value));
+    }
+    let chosen = DigestChallenge::from_headers(&headers).unwrap();
+    assert_eq!(chosen.algorithm, DigestAlgorithm::Sha256);
+    assert_eq!(chosen.nonce, "sha");
+}
+
+/// 测试：相等比较基于确定性指纹，且与 Basic 认证区分
+#[test]
+fn digest_auth_equality_is_deterministic() {
+    let url = "http://localhost/dav/";
+    let a = WebdavAuth::new_digest("user", "password", url).unwrap();
+    let b = WebdavAuth::new_digest("user", "password", url).unwrap();
+    let other = WebdavAuth::new_digest("user", "other", url).unwrap();
+    let basic = WebdavAuth::new("user", "password", url).unwrap();
+
+    assert_eq!(a, b);
+    assert_ne!(a, other);
+    assert_ne!(a, basic);
+}
+
+const REALM: &str = "dav@example.com";
+const PASSWORD: &str = "secret";
+
+/// 只接受 SHA-256 Digest 的服务器：校验 Authorization，每隔 `rotate_every`
+/// 个成功请求更换一次 nonce（返回 `stale=true` 的 401）
+fn digest_server(
+    rotate_every: usize,
+    handler: impl Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+) -> MockServer {
+    let accepted = Arc::new(AtomicUsize::new(0));
+    MockServer::start(move |req| {
+        let generation = accepted.load(Ordering::SeqCst) / rotate_every;
+        let nonce = format!("nonce-{}", generation);
+
+        let challenge = |stale: bool| {
+            MockResponse::new(401).header(
+                "WWW-Authenticate",
+                &format!(
+                    r#"Digest realm="{}", qop="auth", algorithm=SHA-256, nonce="{}", opaque="op", stale={}"#,
+                    REALM, nonce, stale
+                ),
+            )
+        };
+
+        let Some(header) = req.header("Authorization") else {
+            return challenge(false);
+        };
+        let params = auth_params(header);
+        if params.get("nonce") != Some(&nonce) {
+            return challenge(true);
+        }
+        if !response_is_valid(&params, &req.method) {
+            return MockResponse::new(401);
+        }
+
+        accepted.fetch_add(1, Ordering::SeqCst);
+        handler(req)
+    })
+}
+
+fn auth_params(header: &str) -> std::collections::HashMap<String, String> {
+    header
+        .trim_start_matches("Digest ")
+        .split(", ")
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.trim_matches('"').to_string()))
+        .collect()
+}
+
+fn sha256_hex(data: &str) -> String {
+    format!("{:x}", Sha256::digest(data.as_bytes()))
+}
+
+fn response_is_valid(
+    params: &std::collections::HashMap<String, String>,
+    method: &str,
+) -> bool {
+    let get =
+        |key: &str| params.get(key).map(String::as_str).unwrap_or("");
+    let ha1 =
+        sha256_hex(&format!("{}:{}:{}", get("username"), REALM, PASSWORD));
+    let ha2 = sha256_hex(&format!("{}:{}", method, get("uri")));
+    let expected = sha256_hex(&format!(
+        "{}:{}:{}:{}:{}:{}",
+        ha1,
+        get("nonce"),
+        get("nc"),
+        get("cnonce"),
+        get("qop"),
+        ha2
+    ));
+    get("response") == expected && get("opaque") == "op"
+}
+
+fn digest_auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new_digest("user", PASSWORD, server.base_url())
+        .expect("创建 Digest 认证失败")
+}
+
+/// 测试：首个请求收到质询后重发，之后的请求直接带上认证并递增 nc
+#[tokio::test]
+async fn handshake_then_preemptive_requests() {
+    let server = digest_server(usize::MAX, |_| MockResponse::new(201));
+    let auth = digest_auth(&server);
+
+    upload_remote_file(&auth, "a.txt", "hello").await.expect("PUT 应成功");
+    upload_remote_file(&auth.clone(), "b.txt", "world")
+        .await
+        .expect("clone 后的 PUT 应成功");
+
+    let requests = server.requests();
+    let authorization: Vec<_> = requests
+        .iter()
+        .map(|req| req.header("Authorization").map(auth_params))
+        .collect();
+    assert_eq!(requests.len(), 3, "只有第一个请求需要握手");
+    assert!(authorization[0].is_none());
+    assert_eq!(authorization[1].as_ref().unwrap()["nc"], "00000001");
+    assert_eq!(authorization[2].as_ref().unwrap()["nc"], "00000002");
+    assert_eq!(authorization[2].as_ref().unwrap()["uri"], "/b.txt");
+    assert_eq!(requests[2].body, b"world");
+}
+
+/// 测试：nonce 过期（stale）时更新质询并重发一次
+#[tokio::test]
+async fn stale_nonce_is_refreshed() {
+    let server = digest_server(1, |_| MockResponse::new(204));
+    let auth = digest_auth(&server);
+
+    delete_remote(&auth, "a/").await.expect("第一次 DELETE 应成功");
+    delete_remote(&auth, "b/").await.expect("nonce 更换后应自动重发");
+
+    let nonces: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|req| {
+            req.header("Authorization")
+                .map(|h| auth_params(h)["nonce"].clone())
+        })
+        .collect();
+    assert_eq!(
+        nonces,
+        [
+            None,
+            Some("nonce-0".to_string()),
+            Some("nonce-0".to_string()),
+            Some("nonce-1".to_string()),
+        ]
+    );
+}
+
+/// 测试：密码错误时不会无限重发，返回 401
+#[tokio::test]
+async fn wrong_password_is_reported() {
+    let server = digest_server(usize::MAX, |_| MockResponse::new(201));
+    let auth = WebdavAuth::new_digest("user", "wrong", server.base_url())
+        .unwrap();
+
+    let message = upload_remote_file(&auth, "a.txt", "x")
+        .await
+        .expect_err("密码错误不应成功");
+    assert!(message.contains("401"), "❌ 应返回 401: {}", message);
+    assert_eq!(server.requests().len(), 2);
+}
+
+/// 测试：下载器（单线程与分片）同样使用 Digest 认证
+#[tokio::test]
+async fn downloads_use_digest() {
+    let content: Vec<u8> =
+        (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let served = content.clone();
+    let server =
+        digest_server(usize::MAX, move |req| file_response(req, &served));
+    let file = RemoteFile {
+        webdav_auth: digest_auth(&server),
+        ..mock_remote_file(&server, "data.bin", Some(content.len() as u64))
+    };
+
+    match file.build_downloader().output_bytes().send().await {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes, content),
+        other => panic!("❌ 单线程下载失败: {:?}", other),
+    }
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(64 * 1024)
+        .send()
+        .await;
+    match result {
+        Ok(DownloadResult::ByteSegments(segments)) => {
+            assert_eq!(segments.to_bytes(), content)
+        }
+        other => panic!("❌ 分片下载失败: {:?}", other),
+    }
+}
@@ -0,0 +1,196 @@
+//! get_many、download_many 与 DownloadBatch 测试
+//! （使用本地 mock 服务器）
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::remote_file::{DownloadBatch, DownloadError, DownloadResult};
+use crate::tests::mock_server::{
+    MockRequest, MockResponse, MockServer, file_response, mock_remote_file,
+};
+use crate::{download_many, get_many};
+
+/// 测试：结果与输入一一对应，单个失败不影响其余文件
+#[tokio::test]
+async fn downloads_small_files_in_order() {
+    let server = MockServer::start(|req| {
+        let name = req.path.trim_start_matches('/');
+        match name.strip_prefix("file_") {
+            Some(index) => file_response(req, index.as_bytes()),
+            None => MockResponse::new(404),
+        }
+    });
+    let mut files: Vec<_> = (0..20)
+        .map(|i| mock_remote_file(&server, &format!("file_{}", i), None))
+        .collect();
+    files.insert(5, mock_remote_file(&server, "missing", None));
+
+    let results = get_many(&files, 4).await;
+
+    assert_eq!(results.len(), 21);
+    for (i, (path, result)) in results.iter().enumerate() {
+        assert_eq!(path, &files[i].data.relative_root_path);
+        if i == 5 {
+            assert!(
+                matches!(result, Err(DownloadError::Request(_))),
+                "❌ 404 应返回 Request 错误: {:?}",
+                result
+            );
+        } else {
+            let expected = path.trim_start_matches("/file_");
+            assert_eq!(result.as_deref().ok(), Some(expected.as_bytes()));
+        }
+    }
+    assert_eq!(server.requests().len(), 21);
+}
+
+/// 记录同时处理中的 GET 数量的服务器：每个 GET 延迟 `delay` 后才响应
+fn counting_server(delay: Duration) -> (MockServer, Arc<AtomicUsize>) {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let server = MockServer::start({
+        let peak = Arc::clone(&peak);
+        move |req: &MockRequest| {
+            let name = req.path.trim_start_matches('/');
+            let Some(index) = name.strip_prefix("file_") else {
+                return MockResponse::new(404);
+            };
+            if req.method == "GET" {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                std::thread::sleep(delay);
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            }
+            file_response(req, index.repeat(1_000).as_bytes())
+        }
+    });
+    (server, peak)
+}
+
+/// 测试：同时进行的 GET 不超过 max_in_flight（HTTP/1.1 连接池下同样成立）
+#[tokio::test]
+async fn get_many_respects_max_in_flight() {
+    let (server, peak) = counting_server(Duration::from_millis(50));
+    let files: Vec<_> = (0..8)
+        .map(|i| mock_remote_file(&server, &format!("file_{}", i), None))
+        .collect();
+
+    let results = get_many(&files, 3).await;
+
+    for (i, (_, result)) in results.iter().enumerate() {
+        let expected = i.to_string().repeat(1_000);
+        assert_eq!(result.as_deref().ok(), Some(expected.as_bytes()));
+    }
+    let peak = peak.load(Ordering::SeqCst);
+    assert!(peak <= 3, "❌ 同时进行的请求超过上限: {}", peak);
+    assert!(peak >= 2, "❌ 应并发请求: {}", peak);
+}
+
+/// 测试：按输入顺序返回结果，单个失败不影响其余文件，并发不超过全局上限
+#[tokio::test]
+async fn download_many_respects_global_limit() {
+    let (server, peak) = counting_server(Duration::from_millis(50));
+    let mut files: Vec<_> = (0..8)
+        .map(|i| mock_remote_file(&server, &format!("file_{}", i), None))
+        .collect();
+    // 404 直接返回请求错误，不会被当作文件内容
+    files.insert(3, mock_remote_file(&server, "missing", Some(100)));
+    let auth = files[0].webdav_auth.clone();
+
+    let results = download_many(&auth, &files, 3).await;
+
+    assert_eq!(results.len(), 9);
+    for (i, result) in results.iter().enumerate() {
+        match result {
+            Ok(DownloadResult::Bytes(bytes)) => {
+                let name = files[i].data.name.trim_start_matches("file_");
+                assert_eq!(bytes, name.repeat(1_000).as_bytes());
+            }
+            Err(DownloadError::Request(e)) if i == 3 => {
+                assert_eq!(e.status().map(|s| s.as_u16()), Some(404))
+            }
+            other => panic!("❌ 第 {} 个结果不正确: {:?}", i, other),
+        }
+    }
+    let peak = peak.load(Ordering::SeqCst);
+    assert!(peak <= 3, "❌ 同时进行的请求超过上限: {}", peak);
+    assert!(peak >= 2, "❌ 应并发下载: {}", peak);
+}
+
+/// 测试：分片下载按 max_chunks 占用额度，超过上限时按上限预留而不会卡死
+#[tokio::test]
+async fn batch_counts_chunks_against_global_limit() {
+    let (server, peak) = counting_server(Duration::from_millis(30));
+    let downloaders = (1..=3)
+        .map(|i| {
+            mock_remote_file(&server, &format!("file_{}", i), Some(1_000))
+                .build_downloader()
+                .output_bytes()
+                .max_chunks(4)
+                .chunk_size(250)
+        })
+        .collect();
+    let batch = DownloadBatch::new(downloaders, 3);
+
+    let results = batch.send().await;
+
+    for (i, result) in results.iter().enumerate() {
+        match result {
+            Ok(DownloadResult::ByteSegments(segments)) => assert_eq!(
+                segments.to_bytes(),
+                (i + 1).to_string().repeat(1_000).as_bytes()
+            ),
+            other => panic!("❌ 第 {} 个结果不正确: {:?}", i, other),
+        }
+    }
+    let peak = peak.load(Ordering::SeqCst);
+    assert!(peak <= 3, "❌ 同时进行的请求超过上限: {}", peak);
+}
+
+/// 测试：通过返回的控制器单独取消某个文件，其余文件正常完成
+#[tokio::test]
+async fn batch_cancels_single_file() {
+    let content = vec![9u8; 4_000];
+    let server = MockServer::start({
+        let content = content.clone();
+        move |req| match req.path.as_str() {
+            // 响应头立即返回，响应体迟迟不来
+            "/slow.bin" => MockResponse::new(200)
+                .delayed_part(Duration::from_secs(10), vec![0u8; 10]),
+            _ => file_response(req, &content),
+        }
+    });
+    let downloaders = ["a.bin", "slow.bin", "b.bin"]
+        .iter()
+        .map(|name| {
+            mock_remote_file(&server, name, None).build_downloader()
+        })
+        .collect();
+    let batch = DownloadBatch::new(downloaders, 2);
+    let slow = Arc::clone(&batch.controllers()[1]);
+
+    let cancel_task = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = slow.cancel();
+    });
+    let results =
+        tokio::time::timeout(Duration::from_secs(5), batch.send())
+            .await
+            .expect("取消后批量下载应结束");
+    cancel_task.await.expect("取消任务 panic");
+
+    assert!(
+        matches!(results[1], Err(DownloadError::Cancelled)),
+        "❌ 被取消的文件应返回 Cancelled: {:?}",
+        results[1]
+    );
+    for i in [0, 2] {
+        match &results[i] {
+            Ok(DownloadResult::Bytes(bytes)) => {
+                assert_eq!(bytes, &content)
+            }
+            other => panic!("❌ 第 {} 个结果不正确: {:?}", i, other),
+        }
+    }
+}
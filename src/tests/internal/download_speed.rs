@@ -0,0 +1,111 @@
+//! 下载速度测试：EWMA 的平滑、暂停归零与恢复时不突增，
+//! 以及下载期间通过 speed / subscribe_speed 发布的速度。
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::internal::remote_file::downloader::structs::download_speed::SpeedMeter;
+use crate::remote_file::{DownloadResult, DownloadSpeed, DownloadStatus};
+use crate::tests::mock_server::{
+    MockResponse, MockServer, mock_remote_file,
+};
+
+/// 测试：匀速时收敛到实际速度，暂停时归零，恢复后从 0 爬升而不突增
+#[test]
+fn speed_meter_handles_pause_and_resume() {
+    let start = Instant::now();
+    let at = |secs: u64| start + Duration::from_secs(secs);
+    let mut meter = SpeedMeter::new(start, 0);
+
+    // 1000 B/s 持续 10 秒
+    let mut speed = 0.0;
+    for sec in 1..=10 {
+        speed = meter.sample(at(sec), sec * 1_000, false);
+    }
+    assert!((900.0..=1_000.0).contains(&speed), "❌ 未收敛: {}", speed);
+
+    let snapshot = meter.snapshot(10_000, Some(20_000));
+    let eta = snapshot.eta.expect("速度大于 0 时应有 ETA").as_secs_f64();
+    assert!((10.0..=11.2).contains(&eta), "❌ ETA 不正确: {}", eta);
+    assert_eq!(meter.snapshot(10_000, None).eta, None);
+
+    // 暂停 5 秒：速度为 0，没有 ETA
+    for sec in 11..=15 {
+        assert_eq!(meter.sample(at(sec), 10_000, true), 0.0);
+    }
+    assert_eq!(
+        meter.snapshot(10_000, Some(20_000)),
+        DownloadSpeed { bytes_per_sec: 0.0, eta: None }
+    );
+
+    // 恢复后继续 1000 B/s：不超过实际速度，逐步回升
+    let resumed = meter.sample(at(16), 11_000, false);
+    assert!(resumed > 0.0 && resumed < 1_000.0, "❌ 突增: {}", resumed);
+    let next = meter.sample(at(17), 12_000, false);
+    assert!(next > resumed && next < 1_000.0);
+}
+
+/// 测试：下载期间速度不超过服务器的发送速度，暂停时为 0，结束后归零
+#[tokio::test]
+async fn speed_is_published_during_download() {
+    let content = vec![3u8; 60_000];
+    // 每 100ms 发送 2000 字节（20KB/s），共约 3 秒
+    let server = MockServer::start({
+        let content = content.clone();
+        move |_| {
+            content.chunks(2_000).fold(
+                MockResponse::new(200),
+                |resp, part| {
+                    resp.delayed_part(Duration::from_millis(100), part)
+                },
+            )
+        }
+    });
+    let file = mock_remote_file(&server, "speed.bin", Some(60_000));
+    let downloader = file.build_downloader().output_bytes();
+    let controller = downloader.get_controller();
+
+    let samples = Arc::new(Mutex::new(Vec::new()));
+    let subscription = controller.subscribe_speed(false, {
+        let samples = Arc::clone(&samples);
+        move |speed| samples.lock().unwrap().push(*speed)
+    });
+
+    let pause_task = tokio::spawn({
+        let controller = Arc::clone(&controller);
+        async move {
+            tokio::time::sleep(Duration::from_millis(1_500)).await;
+            let _ = controller.pause();
+            // 等待至少一次暂停期间的采样
+            tokio::time::sleep(Duration::from_millis(2_200)).await;
+            let status = controller.get_download_status();
+            let paused_speed = controller.speed();
+            let _ = controller.resume();
+            (status, paused_speed)
+        }
+    });
+
+    match downloader.send().await {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes, content),
+        other => panic!("❌ 下载失败: {:?}", other),
+    }
+    let (status, paused_speed) = pause_task.await.expect("暂停任务 panic");
+    subscription.await.expect("订阅任务 panic");
+
+    assert!(matches!(status, Some(DownloadStatus::Paused)));
+    assert_eq!(paused_speed.bytes_per_sec, 0.0, "❌ 暂停期间速度应为 0");
+    assert_eq!(paused_speed.eta, None);
+
+    let samples = samples.lock().unwrap();
+    let peak = samples.iter().map(|s| s.bytes_per_sec).fold(0.0, f64::max);
+    // 暂停期间积压的数据在恢复后一次到达，也不应造成突增
+    assert!(
+        peak > 2_000.0 && peak <= 25_000.0,
+        "❌ 速度峰值不合理: {} ({:?})",
+        peak,
+        samples
+    );
+    assert!(samples.iter().any(|s| s.eta.is_some()));
+    assert_eq!(samples.last(), Some(&DownloadSpeed::default()));
+    assert_eq!(controller.speed(), DownloadSpeed::default());
+}
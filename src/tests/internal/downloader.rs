@@ -304,9 +304,12 @@ async fn test_subscribe_download_status() {
             println!("   记录到 {} 次状态变化", log.len());
             println!("   状态序列: {:?}", log);
 
-            // 验证至少有 Running 和 Finished 状态
+            // 验证依次出现 Preparing、Running 和 Finished 状态
+            let has_preparing =
+                log.first().is_some_and(|s| s.contains("Preparing"));
             let has_running = log.iter().any(|s| s.contains("Running"));
             let has_finished = log.iter().any(|s| s.contains("Finished"));
+            assert!(has_preparing, "第一个状态应该是 Preparing");
             assert!(has_running, "应该有 Running 状态");
             assert!(has_finished, "应该有 Finished 状态");
         }
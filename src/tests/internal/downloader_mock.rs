@@ -0,0 +1,2851 @@
+//! 下载器离线测试：基于本地 [`MockServer`]，无需真实 WebDAV 厂商账号。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+
+use crate::remote_file::{
+    AggregateProgress, AggregateProgressSnapshot, ChecksumAlgo,
+    ChunkWriteMode, DeltaSyncConfig, DownloadError, DownloadHook,
+    DownloadResult, DownloadStatus, DownloadStream, FileWriteLimiter,
+    HookAbort, OverwritePolicy, ProgressReport, RemoteFile,
+};
+use crate::tests::mock_server::{
+    MockResponse, MockServer, file_response, mock_remote_file, temp_path,
+};
+
+// ═══════════════════════════ 0 字节文件 ═══════════════════════════
+
+/// 测试：0 字节文件单线程下载到内存，得到空 Bytes
+#[tokio::test]
+async fn empty_file_single_thread_to_memory() {
+    let server = MockServer::serve_file(Vec::new());
+    let file = mock_remote_file(&server, "empty.bin", Some(0));
+
+    let result = file.build_downloader().output_bytes().send().await;
+
+    match result {
+        Ok(DownloadResult::Bytes(bytes)) => assert!(bytes.is_empty()),
+        other => panic!("❌ 应返回空 Bytes，实际: {:?}", other),
+    }
+}
+
+/// 测试：0 字节文件单线程下载到文件，得到空文件
+#[tokio::test]
+async fn empty_file_single_thread_to_file() {
+    let server = MockServer::serve_file(Vec::new());
+    let file = mock_remote_file(&server, "empty.bin", Some(0));
+    let save_path = temp_path("empty_single.bin");
+
+    let result = file.build_downloader().save_to(&save_path).send().await;
+
+    match result {
+        Ok(DownloadResult::SavedToLocal(path)) => {
+            let metadata =
+                tokio::fs::metadata(&path).await.expect("文件不存在");
+            assert_eq!(metadata.len(), 0);
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+        other => panic!("❌ 应返回 SavedToLocal，实际: {:?}", other),
+    }
+}
+
+/// 测试：0 字节文件分片下载到内存，得到空 ByteSegments，且不发起任何 Range 请求
+#[tokio::test]
+async fn empty_file_chunked_to_memory() {
+    let server = MockServer::serve_file(Vec::new());
+    let file = mock_remote_file(&server, "empty.bin", Some(0));
+
+    let result =
+        file.build_downloader().output_bytes().max_chunks(4).send().await;
+
+    match result {
+        Ok(DownloadResult::ByteSegments(segments)) => {
+            assert_eq!(segments.total_len(), 0);
+            assert!(segments.to_bytes().is_empty());
+        }
+        other => panic!("❌ 应返回空 ByteSegments，实际: {:?}", other),
+    }
+    assert!(server.requests().is_empty(), "0 字节文件不应发起分片请求");
+}
+
+/// 测试：0 字节文件分片下载到文件，得到空文件
+#[tokio::test]
+async fn empty_file_chunked_to_file() {
+    let server = MockServer::serve_file(Vec::new());
+    let file = mock_remote_file(&server, "empty.bin", Some(0));
+    let save_path = temp_path("empty_chunked.bin");
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .max_chunks(4)
+        .send()
+        .await;
+
+    match result {
+        Ok(DownloadResult::SavedToLocal(path)) => {
+            let metadata =
+                tokio::fs::metadata(&path).await.expect("文件不存在");
+            assert_eq!(metadata.len(), 0);
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+        other => panic!("❌ 应返回 SavedToLocal，实际: {:?}", other),
+    }
+}
+
+// ═══════════════════════════ 并发写入限制 ═══════════════════════════
+
+/// 测试：多个下载器共享同一个 FileWriteLimiter（单线程与分片），全部成功且许可全部归还
+#[tokio::test]
+async fn shared_file_write_limiter() {
+    let content: Vec<u8> =
+        (0..64 * 1024u32).map(|i| (i % 251) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let limiter = FileWriteLimiter::new(1);
+
+    let mut tasks = Vec::new();
+    for i in 0..4 {
+        let file = mock_remote_file(
+            &server,
+            "data.bin",
+            Some(content.len() as u64),
+        );
+        let save_path = temp_path(&format!("limited_{}.bin", i));
+        let mut downloader = file
+            .build_downloader()
+            .save_to(&save_path)
+            .file_write_limiter(limiter.clone());
+        if i % 2 == 1 {
+            downloader = downloader.max_chunks(4).chunk_size(8 * 1024);
+        }
+        tasks.push(tokio::spawn(async move { downloader.send().await }));
+    }
+
+    for task in tasks {
+        match task.await.expect("下载任务 panic") {
+            Ok(DownloadResult::SavedToLocal(path)) => {
+                let data =
+                    tokio::fs::read(&path).await.expect("文件不存在");
+                assert_eq!(data, content);
+                let _ = tokio::fs::remove_file(&path).await;
+            }
+            other => panic!("❌ 应返回 SavedToLocal，实际: {:?}", other),
+        }
+    }
+
+    assert_eq!(limiter.available_permits(), 1);
+}
+
+// ═══════════════════════════ 下载统计 ═══════════════════════════
+
+/// 测试：单线程下载的统计为 1 个分片、0 次重试，字节数与文件一致
+#[tokio::test]
+async fn metrics_single_thread() {
+    let content = vec![7u8; 10_000];
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "data.bin", Some(10_000));
+
+    let (result, metrics) = file
+        .build_downloader()
+        .output_bytes()
+        .send_with_metrics()
+        .await
+        .expect("下载失败");
+
+    assert!(
+        matches!(result, DownloadResult::Bytes(ref b) if b == &content)
+    );
+    assert_eq!(metrics.total_bytes, 10_000);
+    assert_eq!(metrics.chunks, 1);
+    assert_eq!(metrics.retries, 0);
+    assert_eq!(metrics.bytes_from_resume, 0);
+}
+
+/// 测试：分片下载中某个分片第一次传输中断，统计中记录 1 次重试
+#[tokio::test]
+async fn metrics_chunked_counts_retries() {
+    let content: Vec<u8> = (0..4096u32).map(|i| (i % 256) as u8).collect();
+    let first_attempt = Arc::new(AtomicBool::new(true));
+    let server = {
+        let content = content.clone();
+        MockServer::start(move |req| {
+            // 不匹配 bytes=0-0 的 Range 探测
+            let is_first_chunk = req.range() == Some((0, Some(1023)));
+            if is_first_chunk
+                && first_attempt.swap(false, Ordering::SeqCst)
+            {
+                // 声明 1024 字节但只发送 10 字节后断开
+                return MockResponse::new(206)
+                    .header("Content-Range", "bytes 0-1023/4096")
+                    .body(content[..10].to_vec())
+                    .truncated(1024);
+            }
+            file_response(req, &content)
+        })
+    };
+    let file = mock_remote_file(&server, "data.bin", Some(4096));
+
+    let (result, metrics) = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1024)
+        .send_with_metrics()
+        .await
+        .expect("下载失败");
+
+    match result {
+        DownloadResult::ByteSegments(segments) => {
+            assert_eq!(segments.to_bytes(), content);
+        }
+        other => panic!("❌ 应返回 ByteSegments，实际: {:?}", other),
+    }
+    assert_eq!(metrics.chunks, 4);
+    assert_eq!(metrics.retries, 1);
+    // 中断的尝试写入的字节不计入总量
+    assert_eq!(metrics.total_bytes, 4096);
+}
+
+// ═══════════════════════════ 重试时长上限 ═══════════════════════════
+
+/// 测试：分片持续失败时，retry_deadline 先于 max_retries 生效
+#[tokio::test]
+async fn retry_deadline_stops_retrying() {
+    let content = vec![1u8; 2048];
+    let server = {
+        let content = content.clone();
+        MockServer::start(move |req| {
+            if req.range().is_some_and(|(s, _)| s == 0) {
+                return MockResponse::new(206)
+                    .body(content[..10].to_vec())
+                    .truncated(1024);
+            }
+            file_response(req, &content)
+        })
+    };
+    let file = mock_remote_file(&server, "data.bin", Some(2048));
+
+    let started = std::time::Instant::now();
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(2)
+        .chunk_size(1024)
+        .max_retries(100)
+        .retry_deadline(Duration::from_millis(300))
+        .send()
+        .await;
+
+    match result {
+        Err(DownloadError::MultipleChunksFailed(errors)) => {
+            assert_eq!(errors.len(), 1);
+        }
+        other => panic!("❌ 应返回分片失败，实际: {:?}", other),
+    }
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "重试未按时长截止"
+    );
+    let first_chunk_requests = server
+        .requests()
+        .iter()
+        .filter(|r| r.range().is_some_and(|(s, _)| s == 0))
+        .count();
+    assert!(first_chunk_requests < 100);
+}
+
+// ═══════════════════════════ 百分比步进进度 ═══════════════════════════
+
+/// 测试：subscribe_progress_steps 只在跨过步进时触发，且以 100 结尾
+#[tokio::test]
+async fn progress_steps_are_aligned_and_finish_at_100() {
+    let server = MockServer::start(|req| {
+        let mut response = MockResponse::new(200);
+        if req.method == "GET" {
+            for _ in 0..10 {
+                response = response.delayed_part(
+                    Duration::from_millis(20),
+                    vec![0u8; 1000],
+                );
+            }
+        }
+        response
+    });
+    let file = mock_remote_file(&server, "data.bin", Some(10_000));
+    let downloader = file.build_downloader().output_bytes();
+    let steps = Arc::new(Mutex::new(Vec::new()));
+
+    {
+        let steps = Arc::clone(&steps);
+        downloader.get_controller().subscribe_progress_steps(
+            25,
+            move |p| {
+                steps.lock().unwrap().push(p);
+            },
+        );
+    }
+
+    downloader.send().await.expect("下载失败");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let steps = steps.lock().unwrap().clone();
+    assert!(!steps.is_empty());
+    assert_eq!(steps.last(), Some(&100));
+    assert!(
+        steps.windows(2).all(|w| w[0] < w[1]),
+        "应严格递增: {:?}",
+        steps
+    );
+    assert!(
+        steps.iter().all(|p| p % 25 == 0),
+        "应按步进对齐: {:?}",
+        steps
+    );
+}
+
+/// 测试：从下载进度派生的百分比属性最终到达 100.0
+#[tokio::test]
+async fn projected_percent_reaches_100() {
+    let server = MockServer::start(|req| {
+        let mut response = MockResponse::new(200);
+        if req.method == "GET" {
+            for _ in 0..5 {
+                response = response.delayed_part(
+                    Duration::from_millis(20),
+                    vec![0u8; 1000],
+                );
+            }
+        }
+        response
+    });
+    let file = mock_remote_file(&server, "data.bin", Some(5_000));
+    let downloader = file.build_downloader().output_bytes();
+    let percent = downloader
+        .get_controller()
+        .project_progress(|p| p.percent().unwrap_or(0.0));
+    assert_eq!(percent.get_current(), Some(0.0));
+
+    let waiter = tokio::spawn({
+        let percent = percent.clone();
+        async move { percent.wait_until(|p| *p >= 100.0).await }
+    });
+    downloader.send().await.expect("下载失败");
+
+    let reached = tokio::time::timeout(Duration::from_secs(5), waiter)
+        .await
+        .expect("百分比未到达 100")
+        .expect("等待任务 panic")
+        .expect("派生属性被关闭");
+    assert_eq!(reached, 100.0);
+    assert_eq!(percent.get_current(), Some(100.0));
+}
+
+// ═══════════════════════════ 磁盘剩余空间 ═══════════════════════════
+
+/// 测试：临时目录所在文件系统可以查询到剩余空间
+#[test]
+fn available_space_of_temp_dir() {
+    let available =
+        crate::local_file::available_space(&std::env::temp_dir())
+            .expect("查询剩余空间失败");
+    assert!(available > 0);
+}
+
+/// 测试：剩余空间无法满足 min_free_bytes 时，单线程与分片下载都在写入前中止并删除文件
+#[tokio::test]
+async fn insufficient_disk_space_aborts_before_writing() {
+    let server = MockServer::serve_file(vec![3u8; 4096]);
+
+    for (i, max_chunks) in [1usize, 4].into_iter().enumerate() {
+        let file = mock_remote_file(&server, "data.bin", Some(4096));
+        let save_path = temp_path(&format!("no_space_{}.bin", i));
+
+        let result = file
+            .build_downloader()
+            .save_to(&save_path)
+            .max_chunks(max_chunks)
+            .chunk_size(1024)
+            .min_free_bytes(u64::MAX)
+            .send()
+            .await;
+
+        assert!(
+            matches!(
+                result,
+                Err(DownloadError::InsufficientDiskSpace { .. })
+            ),
+            "❌ 应返回 InsufficientDiskSpace，实际: {:?}",
+            result
+        );
+        assert!(tokio::fs::metadata(&save_path).await.is_err());
+    }
+    assert!(server.requests().is_empty(), "空间不足时不应发起请求");
+}
+
+/// 测试：剩余空间充足时正常完成
+#[tokio::test]
+async fn sufficient_disk_space_downloads_normally() {
+    let content = vec![5u8; 4096];
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "data.bin", Some(4096));
+    let save_path = temp_path("enough_space.bin");
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .min_free_bytes(1)
+        .send()
+        .await;
+
+    assert!(matches!(result, Ok(DownloadResult::SavedToLocal(_))));
+    let data = tokio::fs::read(&save_path).await.expect("文件不存在");
+    assert_eq!(data, content);
+    let _ = tokio::fs::remove_file(&save_path).await;
+}
+
+// ═══════════════════════════ 汇总进度 ═══════════════════════════
+
+/// 测试：汇总进度累加所有任务的字节数，大小未知的任务不参与百分比
+#[tokio::test]
+async fn aggregate_progress_sums_downloads() {
+    let server = MockServer::serve_file(vec![9u8; 3000]);
+    // 分块传输编码、PROPFIND 也没有大小：始终未知
+    let chunked = MockServer::start(|_| {
+        MockResponse::new(200).chunked().body(vec![9u8; 3000])
+    });
+    let aggregate = AggregateProgress::new();
+
+    let files = [
+        mock_remote_file(&server, "data.bin", Some(3000)),
+        mock_remote_file(&server, "data.bin", Some(3000)),
+        mock_remote_file(&chunked, "data.bin", None),
+    ];
+    let mut downloaders = Vec::new();
+    for file in &files {
+        let downloader = file.build_downloader();
+        aggregate.add(&downloader.get_controller());
+        downloaders.push(downloader);
+    }
+
+    for downloader in &downloaders {
+        downloader.send().await.expect("下载失败");
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let snapshot = aggregate.get_current();
+    assert_eq!(snapshot.downloads, 3);
+    assert_eq!(snapshot.bytes_done, 9000);
+    assert_eq!(snapshot.total, Some(6000));
+    assert_eq!(snapshot.known_bytes_done, 6000);
+    assert_eq!(snapshot.percent(), Some(100.0));
+    assert_eq!(snapshot.active, 0);
+}
+
+/// 测试：加入时大小未知、下载时从 Content-Length 得知的大小计入汇总；
+/// 停滞时速度回落到 0，结束后没有进行中的任务
+#[tokio::test]
+async fn aggregate_progress_tracks_late_size_and_stalls() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200)
+            .body(vec![5u8; 2000])
+            .delayed_part(Duration::from_millis(2_000), vec![5u8; 1000])
+    });
+    let downloader =
+        mock_remote_file(&server, "late.bin", None).build_downloader();
+    let aggregate = AggregateProgress::new();
+    aggregate.add(&downloader.get_controller());
+    assert_eq!(aggregate.get_current().total, None);
+
+    let observer = tokio::spawn({
+        let aggregate = aggregate.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(1_700)).await;
+            aggregate.get_current()
+        }
+    });
+    downloader.send().await.expect("下载失败");
+    let stalled = observer.await.expect("观察任务 panic");
+    assert_eq!(stalled.bytes_done, 2000);
+    assert_eq!(stalled.total, Some(3000));
+    assert_eq!(stalled.active, 1);
+    assert_eq!(stalled.speed, 0.0, "❌ 停滞时速度应回落到 0");
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let snapshot = aggregate.get_current();
+    assert_eq!(snapshot.percent(), Some(100.0));
+    assert_eq!(snapshot.active, 0);
+    assert_eq!(snapshot.speed, 0.0);
+}
+
+/// 测试：全部任务大小未知时没有百分比
+#[test]
+fn aggregate_percent_without_known_sizes() {
+    let snapshot = AggregateProgressSnapshot {
+        bytes_done: 100,
+        downloads: 1,
+        ..Default::default()
+    };
+    assert_eq!(snapshot.percent(), None);
+}
+
+// ═══════════════════════════ 大小未知 ═══════════════════════════
+
+/// 测试：分块传输编码且大小未知时，分片配置自动回退为单线程，进度 total 为 None
+#[tokio::test]
+async fn unknown_size_chunked_encoding_falls_back_to_single_thread() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200)
+            .chunked()
+            .body(vec![1u8; 1000])
+            .body(vec![2u8; 500])
+    });
+    let file = mock_remote_file(&server, "stream.bin", None);
+    let downloader = file.build_downloader().output_bytes().max_chunks(4);
+    let controller = downloader.get_controller();
+
+    assert!(!controller.has_known_size());
+
+    let result = downloader.send().await;
+
+    match result {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes.len(), 1500),
+        other => {
+            panic!("❌ 应回退为单线程并返回 Bytes，实际: {:?}", other)
+        }
+    }
+    let progress = controller.progress();
+    assert_eq!(progress.bytes_done, 1500);
+    assert_eq!(progress.total, None);
+    assert_eq!(progress.percent(), None);
+    assert!(server.requests().iter().all(|r| r.header("Range").is_none()));
+}
+
+/// 测试：PROPFIND 未给出大小但响应带 Content-Length 时，进度中补上 total
+#[tokio::test]
+async fn unknown_size_uses_response_content_length() {
+    let server = MockServer::serve_file(vec![4u8; 2048]);
+    let file = mock_remote_file(&server, "data.bin", None);
+    let downloader = file.build_downloader().output_bytes();
+    let controller = downloader.get_controller();
+
+    downloader.send().await.expect("下载失败");
+
+    let progress = controller.progress();
+    assert_eq!(progress.total, Some(2048));
+    assert_eq!(progress.percent(), Some(100.0));
+}
+
+/// 测试：PROPFIND 未给出大小时，分片下载先用 HEAD 探测长度再切分 Range
+#[tokio::test]
+async fn unknown_size_is_probed_for_chunked_download() {
+    let content: Vec<u8> = (0..4096u32).map(|i| (i % 199) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "probe.bin", None);
+    let downloader = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1024);
+    let controller = downloader.get_controller();
+
+    let result = downloader.send().await;
+
+    match result {
+        Ok(DownloadResult::ByteSegments(segments)) => {
+            assert_eq!(segments.to_bytes(), content)
+        }
+        other => panic!("❌ 应按分片下载，实际: {:?}", other),
+    }
+    assert!(controller.has_known_size());
+    let requests = server.requests();
+    assert_eq!(requests.iter().filter(|r| r.method == "HEAD").count(), 1);
+    // 4 个分片加一次 `bytes=0-0` 的 Range 探测
+    assert_eq!(requests.iter().filter(|r| r.range().is_some()).count(), 5);
+}
+
+// ═══════════════════════════ 公开导出路径 ═══════════════════════════
+
+/// 测试：配置与进度类型可从 `remote_file` 直接命名，builder 结果可通过 config() 读取
+#[test]
+fn config_and_progress_are_nameable_from_remote_file() {
+    use crate::remote_file::{
+        DEFAULT_MAX_RETRIES, DownloadConfig, DownloadProgress,
+    };
+
+    let server = MockServer::serve_file(Vec::new());
+    let downloader = mock_remote_file(&server, "a.bin", Some(10))
+        .build_downloader()
+        .max_chunks(3);
+    let controller = downloader.get_controller();
+
+    let config: &DownloadConfig = controller.config();
+    assert_eq!(config.max_chunks, 3);
+    assert_eq!(config.max_retries, DEFAULT_MAX_RETRIES);
+
+    let progress: DownloadProgress = controller.progress();
+    assert_eq!(progress.total, Some(10));
+}
+
+// ═══════════════════════════ 下载状态 ═══════════════════════════
+
+/// 测试：两种下载路径都先报告 Preparing，收到数据后才进入 Running
+#[tokio::test]
+async fn status_is_preparing_until_first_byte() {
+    let server = MockServer::start(|req| {
+        // 响应头延迟发出，模拟慢速的准备阶段；
+        // 响应体分两段发送，让 Running 状态维持一段时间
+        std::thread::sleep(Duration::from_millis(100));
+        let mut response = file_response(req, &[3u8; 4096]);
+        if let Some((_, body)) = response.body_parts.pop() {
+            let (head, tail) = body.split_at(body.len() / 2);
+            response = response
+                .body(head.to_vec())
+                .delayed_part(Duration::from_millis(100), tail.to_vec());
+        }
+        response
+    });
+    let file = mock_remote_file(&server, "prepare.bin", Some(4096));
+
+    for max_chunks in [1, 2] {
+        let downloader = file
+            .build_downloader()
+            .output_bytes()
+            .max_chunks(max_chunks)
+            .chunk_size(2048);
+        let controller = downloader.get_controller();
+        assert!(matches!(
+            controller.get_download_status(),
+            Some(DownloadStatus::Preparing)
+        ));
+
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = controller.subscribe_download_status(true, {
+            let statuses = Arc::clone(&statuses);
+            move |status| {
+                if let Ok(mut s) = statuses.lock() {
+                    s.push(format!("{:?}", status));
+                }
+            }
+        });
+
+        downloader.send().await.expect("下载失败");
+        let _ = subscriber.await;
+
+        let mut statuses =
+            statuses.lock().map(|s| s.clone()).unwrap_or_default();
+        statuses.dedup();
+        assert_eq!(
+            statuses,
+            vec!["Preparing", "Running", "Finished"],
+            "❌ max_chunks = {} 的状态序列不对",
+            max_chunks
+        );
+    }
+}
+
+// ═══════════════════════════ 有序取消 ═══════════════════════════
+
+/// 测试：分片写入过程中取消，只返回 Cancelled；返回时所有分片任务已停止、文件已删除
+#[tokio::test]
+async fn cancel_during_chunked_writes_drains_tasks() {
+    let server = MockServer::start(|req| {
+        let Some((start, end)) = req.range() else {
+            return MockResponse::new(200)
+                .header("Accept-Ranges", "bytes");
+        };
+        let end = end.unwrap_or(4095);
+        let mut response = MockResponse::new(206).header(
+            "Content-Range",
+            &format!("bytes {}-{}/4096", start, end),
+        );
+        // 每个分片分 8 段慢速发送
+        let len = (end - start + 1) as usize;
+        for _ in 0..8 {
+            response = response.delayed_part(
+                Duration::from_millis(50),
+                vec![8u8; len / 8],
+            );
+        }
+        response
+    });
+    let file = mock_remote_file(&server, "slow.bin", Some(4096));
+    let save_path = temp_path("cancel_drain.bin");
+    let downloader = file
+        .build_downloader()
+        .save_to(&save_path)
+        .max_chunks(4)
+        .chunk_size(1024);
+    let controller = downloader.get_controller();
+
+    let cancel_task = tokio::spawn({
+        let controller = Arc::clone(&controller);
+        async move {
+            while controller.get_downloaded_bytes() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            let _ = controller.cancel();
+        }
+    });
+
+    let result = downloader.send().await;
+    cancel_task.await.expect("取消任务 panic");
+
+    assert!(
+        matches!(result, Err(DownloadError::Cancelled)),
+        "❌ 应只返回 Cancelled，实际: {:?}",
+        result
+    );
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "文件应已删除"
+    );
+
+    // 返回后不应再有分片任务在写入
+    let bytes_at_return = controller.get_downloaded_bytes();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(controller.get_downloaded_bytes(), bytes_at_return);
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "文件不应被重新创建"
+    );
+}
+
+/// 测试：单线程下载卡在慢速分块上时取消立即生效，并删除未完成的文件
+#[tokio::test]
+async fn cancel_single_thread_during_stalled_stream() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200)
+            .body(vec![1u8; 1024])
+            .delayed_part(Duration::from_secs(10), vec![2u8; 1024])
+    });
+    let file = mock_remote_file(&server, "stall.bin", Some(2048));
+    let save_path = temp_path("cancel_stall.bin");
+    let downloader =
+        file.build_downloader().save_to(&save_path).max_chunks(1);
+    let controller = downloader.get_controller();
+
+    let cancel_task = tokio::spawn({
+        let controller = Arc::clone(&controller);
+        async move {
+            while controller.get_downloaded_bytes() < 1024 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            let _ = controller.cancel();
+            std::time::Instant::now()
+        }
+    });
+
+    let result =
+        tokio::time::timeout(Duration::from_secs(5), downloader.send())
+            .await
+            .expect("取消后下载应立即结束");
+    let cancelled_at = cancel_task.await.expect("取消任务 panic");
+
+    assert!(
+        matches!(result, Err(DownloadError::Cancelled)),
+        "❌ 应返回 Cancelled，实际: {:?}",
+        result
+    );
+    assert!(cancelled_at.elapsed() < Duration::from_secs(1));
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "文件应已删除"
+    );
+}
+
+/// 测试：取消后所有订阅任务都会收到终止状态并自行退出
+#[tokio::test]
+async fn cancel_stops_subscriber_tasks() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200)
+            .body(vec![1u8; 1024])
+            .delayed_part(Duration::from_secs(10), vec![2u8; 1024])
+    });
+    let file = mock_remote_file(&server, "subscribers.bin", Some(2048));
+    let save_path = temp_path("cancel_subscribers.bin");
+    let downloader =
+        file.build_downloader().save_to(&save_path).max_chunks(1);
+    let controller = downloader.get_controller();
+
+    let statuses = Arc::new(Mutex::new(Vec::new()));
+    let handles = vec![
+        controller.subscribe_download_status(false, {
+            let statuses = Arc::clone(&statuses);
+            move |status| {
+                if let Ok(mut s) = statuses.lock() {
+                    s.push(status.clone());
+                }
+            }
+        }),
+        controller.subscribe_downloaded_bytes(false, |_| {}),
+        controller.subscribe_progress(false, |_| {}),
+        controller.subscribe_progress_steps(10, |_| {}),
+        controller.subscribe_commands(|_| {}),
+    ];
+
+    let cancel_task = tokio::spawn({
+        let controller = Arc::clone(&controller);
+        async move {
+            while controller.get_downloaded_bytes() < 1024 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            let _ = controller.cancel();
+        }
+    });
+
+    let result =
+        tokio::time::timeout(Duration::from_secs(5), downloader.send())
+            .await
+            .expect("取消后下载应立即结束");
+    cancel_task.await.expect("取消任务 panic");
+    assert!(matches!(result, Err(DownloadError::Cancelled)));
+
+    for handle in handles {
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("下载结束后订阅任务应退出")
+            .expect("订阅任务 panic");
+    }
+
+    let statuses = statuses.lock().map(|s| s.clone()).unwrap_or_default();
+    assert!(
+        matches!(statuses.last(), Some(DownloadStatus::Canceled)),
+        "❌ 状态订阅应收到 Canceled，实际: {:?}",
+        statuses
+    );
+}
+
+// ═══════════════════════════ 外部直链下载 ═══════════════════════════
+
+/// 测试：直链下载走外部地址，且不会把 WebDAV 的 Authorization 发给外部主机
+#[tokio::test]
+async fn download_from_url_does_not_leak_credentials() {
+    let content: Vec<u8> = (0..3000u32).map(|i| (i % 200) as u8).collect();
+    let webdav = MockServer::serve_file(content.clone());
+    let cdn = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&webdav, "data.bin", Some(3000));
+
+    for max_chunks in [1usize, 3] {
+        let result = file
+            .download_from_url(&cdn.url("signed/data.bin?token=abc"))
+            .expect("创建直链下载器失败")
+            .output_bytes()
+            .max_chunks(max_chunks)
+            .chunk_size(1024)
+            .send()
+            .await
+            .expect("下载失败");
+
+        let bytes = match result {
+            DownloadResult::Bytes(bytes) => bytes,
+            DownloadResult::ByteSegments(segments) => segments.to_bytes(),
+            other => panic!("❌ 意外的结果: {:?}", other),
+        };
+        assert_eq!(bytes, content);
+    }
+
+    assert!(webdav.requests().is_empty(), "不应请求 WebDAV 服务器");
+    let cdn_requests = cdn.requests();
+    assert!(!cdn_requests.is_empty());
+    for req in cdn_requests {
+        assert_eq!(req.path, "/signed/data.bin?token=abc");
+        assert!(
+            req.header("Authorization").is_none(),
+            "认证信息泄露到外部主机"
+        );
+    }
+}
+
+/// 测试：非 http/https 直链被拒绝
+#[test]
+fn download_from_url_rejects_other_schemes() {
+    let server = MockServer::serve_file(Vec::new());
+    let file = mock_remote_file(&server, "data.bin", Some(0));
+
+    assert!(file.download_from_url("ftp://example.com/data.bin").is_err());
+    assert!(file.download_from_url("not a url").is_err());
+}
+
+// ═══════════════════════════ 增量同步 ═══════════════════════════
+
+/// 测试：只覆盖不一致的块，本地多余部分被截断，最终内容与远程一致
+#[tokio::test]
+async fn delta_sync_rewrites_only_changed_blocks() {
+    let content: Vec<u8> =
+        (0..10_000u32).map(|i| (i % 251) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file =
+        mock_remote_file(&server, "disk.img", Some(content.len() as u64));
+    let path = temp_path("delta_changed.img");
+
+    // 本地副本：第 0 块与第 5 块被改动，末尾多出 100 字节
+    let mut local = content.clone();
+    local[10] ^= 0xff;
+    local[5_500] ^= 0xff;
+    local.extend_from_slice(&[0u8; 100]);
+    std::fs::write(&path, &local).unwrap();
+
+    let config = DeltaSyncConfig { block_size: 1_000, known_etag: None };
+    let report = file.delta_sync(&path, config).await.unwrap();
+
+    assert_eq!(report.total_blocks, 10);
+    assert_eq!(report.changed_blocks, 2);
+    assert_eq!(report.bytes_written, 2_000);
+    assert_eq!(report.bytes_fetched, content.len() as u64);
+    assert!(!report.skipped_by_etag);
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    let _ = std::fs::remove_file(&path);
+}
+
+/// 测试：ETag 未变化且大小一致时跳过，不发出任何请求
+#[tokio::test]
+async fn delta_sync_skips_when_etag_unchanged() {
+    let content = vec![7u8; 4_096];
+    let server = MockServer::serve_file(content.clone());
+    let mut file =
+        mock_remote_file(&server, "db.sqlite", Some(content.len() as u64));
+    let mut data = (*file.data).clone();
+    data.etag = Some("v1".to_string());
+    file.data = Arc::new(data);
+    let path = temp_path("delta_etag.sqlite");
+    std::fs::write(&path, &content).unwrap();
+
+    let config = DeltaSyncConfig {
+        block_size: 1_024,
+        known_etag: Some("v1".to_string()),
+    };
+    let report = file.delta_sync(&path, config).await.unwrap();
+
+    assert!(report.skipped_by_etag);
+    assert_eq!(report.changed_blocks, 0);
+    assert!(server.requests().is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+/// 测试：服务器忽略 Range 时返回 RangeNotSupported
+#[tokio::test]
+async fn delta_sync_requires_range_support() {
+    let content = vec![1u8; 2_048];
+    let body = content.clone();
+    let server = MockServer::start(move |_| {
+        MockResponse::new(200).body(body.clone())
+    });
+    let file =
+        mock_remote_file(&server, "a.bin", Some(content.len() as u64));
+    let path = temp_path("delta_norange.bin");
+
+    let result = file.delta_sync(&path, DeltaSyncConfig::default()).await;
+
+    assert!(matches!(result, Err(DownloadError::RangeNotSupported)));
+    let _ = std::fs::remove_file(&path);
+}
+
+/// 以 `etag` 作为列举时的 ETag 创建远程文件
+fn with_listed_etag(file: RemoteFile, etag: &str) -> RemoteFile {
+    let mut data = (*file.data).clone();
+    data.etag =
+        Some(etag.trim_start_matches("W/").trim_matches('"').into());
+    data.raw_etag = Some(etag.to_string());
+    RemoteFile { data: Arc::new(data), ..file }
+}
+
+/// 测试：每个块都带 If-Match；同步途中远程文件被修改（412）时返回
+/// PreconditionFailed，不会继续拼接新版本的块
+#[tokio::test]
+async fn delta_sync_stops_when_remote_changes() {
+    let content = vec![2u8; 4_000];
+    let changed = Arc::new(AtomicBool::new(false));
+    let server = {
+        let content = content.clone();
+        let changed = Arc::clone(&changed);
+        MockServer::start(move |req| {
+            // 第二个块之后远程文件被替换
+            if req.range() == Some((2_000, Some(2_999))) {
+                changed.store(true, Ordering::SeqCst);
+            }
+            if changed.load(Ordering::SeqCst)
+                || req.header("If-Match") != Some("\"v1\"")
+            {
+                return MockResponse::new(412);
+            }
+            file_response(req, &content)
+        })
+    };
+    let file = with_listed_etag(
+        mock_remote_file(&server, "live.db", Some(4_000)),
+        "\"v1\"",
+    );
+    let path = temp_path("delta_changed_remote.db");
+    std::fs::write(&path, vec![0u8; 4_000]).unwrap();
+
+    let config = DeltaSyncConfig { block_size: 1_000, known_etag: None };
+    let result = file.delta_sync(&path, config).await;
+
+    match result {
+        Err(DownloadError::PreconditionFailed { etag }) => {
+            assert_eq!(etag, "\"v1\"")
+        }
+        other => panic!("❌ 应返回 PreconditionFailed，实际: {:?}", other),
+    }
+    let local = std::fs::read(&path).unwrap();
+    assert_eq!(&local[..2_000], &content[..2_000]);
+    assert!(local[2_000..].iter().all(|&b| b == 0), "❌ 412 后不应写入");
+    assert_eq!(server.requests().len(), 3);
+    let _ = std::fs::remove_file(&path);
+}
+
+/// 测试：弱 ETag 永远不满足 If-Match，因此不附带该头
+#[tokio::test]
+async fn delta_sync_omits_if_match_for_weak_etag() {
+    let content = vec![3u8; 2_000];
+    let server = MockServer::serve_file(content.clone());
+    let file = with_listed_etag(
+        mock_remote_file(&server, "weak.bin", Some(2_000)),
+        "W/\"v1\"",
+    );
+    let path = temp_path("delta_weak.bin");
+
+    let config = DeltaSyncConfig { block_size: 1_000, known_etag: None };
+    file.delta_sync(&path, config).await.expect("❌ 应同步成功");
+
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    assert!(
+        server
+            .requests()
+            .iter()
+            .all(|req| req.header("If-Match").is_none())
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+/// 测试：块长度与请求区间不符时返回 SizeMismatch，该块不写入
+#[tokio::test]
+async fn delta_sync_rejects_short_block() {
+    let content = vec![4u8; 3_000];
+    let server = {
+        let content = content.clone();
+        MockServer::start(move |req| {
+            if req.range() == Some((1_000, Some(1_999))) {
+                return MockResponse::new(206)
+                    .header("Content-Range", "bytes 1000-1999/3000")
+                    .body(vec![4u8; 10]);
+            }
+            file_response(req, &content)
+        })
+    };
+    let file = mock_remote_file(&server, "short.bin", Some(3_000));
+    let path = temp_path("delta_short.bin");
+    std::fs::write(&path, vec![0u8; 3_000]).unwrap();
+
+    let config = DeltaSyncConfig { block_size: 1_000, known_etag: None };
+    let result = file.delta_sync(&path, config).await;
+
+    assert!(
+        matches!(
+            result,
+            Err(DownloadError::SizeMismatch {
+                expected: 1_000,
+                actual: 10
+            })
+        ),
+        "❌ 应返回 SizeMismatch，实际: {:?}",
+        result
+    );
+    let local = std::fs::read(&path).unwrap();
+    assert!(local[1_000..].iter().all(|&b| b == 0), "❌ 不应写入短块");
+    let _ = std::fs::remove_file(&path);
+}
+
+// ═══════════════════════════ SHA-256 校验 ═══════════════════════════
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 测试：分片乱序完成时，保存到本地与输出到内存得到的摘要都与整体内容一致
+#[tokio::test]
+async fn chunked_download_sha256_respects_byte_order() {
+    let content: Vec<u8> =
+        (0..50_000u32).map(|i| (i * 7 % 256) as u8).collect();
+    let expected = sha256_hex(&content);
+    let server = MockServer::serve_file(content.clone());
+    let file =
+        mock_remote_file(&server, "data.bin", Some(content.len() as u64));
+    let save_path = temp_path("sha256_chunked.bin");
+
+    let (_, metrics) = file
+        .build_downloader()
+        .save_to(&save_path)
+        .max_chunks(4)
+        .chunk_size(4_096)
+        .verify_sha256(&expected.to_uppercase())
+        .send_with_metrics()
+        .await
+        .unwrap();
+    assert_eq!(metrics.sha256.as_deref(), Some(expected.as_str()));
+    let _ = std::fs::remove_file(&save_path);
+
+    let (_, metrics) = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(4_096)
+        .compute_sha256()
+        .send_with_metrics()
+        .await
+        .unwrap();
+    assert_eq!(metrics.sha256.as_deref(), Some(expected.as_str()));
+}
+
+/// 测试：单线程下载边下载边计算摘要；未开启时 metrics 中没有摘要
+#[tokio::test]
+async fn single_thread_download_sha256() {
+    let content = b"hello sha256".to_vec();
+    let server = MockServer::serve_file(content.clone());
+    let file =
+        mock_remote_file(&server, "hello.txt", Some(content.len() as u64));
+
+    let (_, metrics) = file
+        .build_downloader()
+        .compute_sha256()
+        .send_with_metrics()
+        .await
+        .unwrap();
+    assert_eq!(metrics.sha256, Some(sha256_hex(&content)));
+
+    let (_, metrics) =
+        file.build_downloader().send_with_metrics().await.unwrap();
+    assert_eq!(metrics.sha256, None);
+}
+
+/// 测试：摘要不一致时返回 ChecksumMismatch 并删除已保存的文件
+#[tokio::test]
+async fn sha256_mismatch_removes_file() {
+    let content = vec![3u8; 10_000];
+    let server = MockServer::serve_file(content.clone());
+    let file =
+        mock_remote_file(&server, "bad.bin", Some(content.len() as u64));
+    let save_path = temp_path("sha256_mismatch.bin");
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .max_chunks(2)
+        .chunk_size(4_096)
+        .verify_sha256(&"0".repeat(64))
+        .send()
+        .await;
+
+    assert!(
+        matches!(result, Err(DownloadError::ChecksumMismatch { .. })),
+        "❌ 应返回 ChecksumMismatch，实际: {:?}",
+        result
+    );
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "文件应已删除"
+    );
+}
+
+const FOX: &[u8] = b"The quick brown fox jumps over the lazy dog";
+const FOX_MD5: &str = "9e107d9d372bb6826bd81d3542a419d6";
+const FOX_SHA256: &str =
+    "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592";
+
+/// 测试：verify_checksum 按 MD5 / SHA-256 校验，保存到本地与输出到内存均可
+#[tokio::test]
+async fn verify_checksum_accepts_known_hashes() {
+    let server = MockServer::serve_file(FOX.to_vec());
+    let file =
+        mock_remote_file(&server, "fox.txt", Some(FOX.len() as u64));
+    let save_path = temp_path("checksum_fox.txt");
+
+    for (algo, expected) in
+        [(ChecksumAlgo::Md5, FOX_MD5), (ChecksumAlgo::Sha256, FOX_SHA256)]
+    {
+        let result = file
+            .build_downloader()
+            .save_to(&save_path)
+            .verify_checksum(algo, expected.to_uppercase())
+            .send()
+            .await;
+        assert!(result.is_ok(), "❌ {:?} 校验应通过: {:?}", algo, result);
+        assert_eq!(std::fs::read(&save_path).unwrap(), FOX);
+
+        let result = file
+            .build_downloader()
+            .output_bytes()
+            .max_chunks(3)
+            .chunk_size(16)
+            .verify_checksum(algo, expected.to_string())
+            .send()
+            .await;
+        assert!(
+            result.is_ok(),
+            "❌ {:?} 分片校验应通过: {:?}",
+            algo,
+            result
+        );
+    }
+    let _ = std::fs::remove_file(&save_path);
+}
+
+/// 测试：MD5 不一致时返回 ChecksumMismatch 并删除已保存的文件
+#[tokio::test]
+async fn verify_checksum_md5_mismatch() {
+    let server = MockServer::serve_file(FOX.to_vec());
+    let file =
+        mock_remote_file(&server, "fox.txt", Some(FOX.len() as u64));
+    let save_path = temp_path("checksum_fox_bad.txt");
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .verify_checksum(ChecksumAlgo::Md5, "0".repeat(32))
+        .send()
+        .await;
+
+    assert!(
+        matches!(
+            &result,
+            Err(DownloadError::ChecksumMismatch { actual, .. })
+                if actual == FOX_MD5
+        ),
+        "❌ 应返回 ChecksumMismatch，实际: {:?}",
+        result
+    );
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "文件应已删除"
+    );
+}
+
+// ═══════════════════════════ abort_if 谓词 ═══════════════════════════
+
+/// 测试：谓词返回 true 时中止分片下载，返回 AbortedByPredicate 并删除文件
+#[tokio::test]
+async fn abort_if_stops_chunked_download() {
+    let server = MockServer::start(|req| {
+        let Some((start, end)) = req.range() else {
+            return MockResponse::new(200);
+        };
+        let end = end.unwrap_or(8191);
+        let len = (end - start + 1) as usize;
+        let mut response = MockResponse::new(206).header(
+            "Content-Range",
+            &format!("bytes {}-{}/8192", start, end),
+        );
+        for _ in 0..4 {
+            response = response.delayed_part(
+                Duration::from_millis(50),
+                vec![5u8; len / 4],
+            );
+        }
+        response
+    });
+    let file = mock_remote_file(&server, "slow.bin", Some(8192));
+    let save_path = temp_path("abort_if_chunked.bin");
+    let seen = Arc::new(Mutex::new(Vec::<ProgressReport>::new()));
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .max_chunks(2)
+        .chunk_size(4096)
+        .abort_if({
+            let seen = Arc::clone(&seen);
+            move |report| {
+                seen.lock().unwrap().push(*report);
+                report.progress.bytes_done >= 2048
+            }
+        })
+        .send()
+        .await;
+
+    assert!(
+        matches!(result, Err(DownloadError::AbortedByPredicate)),
+        "❌ 应返回 AbortedByPredicate，实际: {:?}",
+        result
+    );
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "文件应已删除"
+    );
+    let seen = seen.lock().unwrap();
+    assert!(seen.iter().all(|r| r.progress.total == Some(8192)));
+}
+
+/// 测试：谓词始终返回 false 时下载正常完成；ETA 按平均速度估算
+#[tokio::test]
+async fn abort_if_false_lets_download_finish() {
+    let content = vec![9u8; 4096];
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "ok.bin", Some(4096));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .abort_if(|_| false)
+        .send()
+        .await;
+
+    match result {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes, content),
+        other => panic!("❌ 应正常完成，实际: {:?}", other),
+    }
+
+    let report = ProgressReport {
+        progress: crate::remote_file::DownloadProgress {
+            bytes_done: 100,
+            total: Some(300),
+        },
+        elapsed: Duration::from_secs(1),
+        avg_bytes_per_sec: 100.0,
+    };
+    assert_eq!(report.eta(), Some(Duration::from_secs(2)));
+}
+
+// ═══════════════════════════ 已存在文件的处理 ═══════════════════════════
+
+/// 测试：Fail 策略返回 FileExists，且不修改已有文件
+#[tokio::test]
+async fn overwrite_policy_fail_keeps_existing_file() {
+    let server = MockServer::serve_file(vec![1u8; 100]);
+    let file = mock_remote_file(&server, "a.bin", Some(100));
+    let save_path = temp_path("overwrite_fail.bin");
+    std::fs::write(&save_path, b"keep me").unwrap();
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .overwrite_policy(OverwritePolicy::Fail)
+        .send()
+        .await;
+
+    assert!(matches!(result, Err(DownloadError::FileExists(_))));
+    assert_eq!(std::fs::read(&save_path).unwrap(), b"keep me");
+    assert!(server.requests().is_empty());
+    let _ = std::fs::remove_file(&save_path);
+}
+
+/// 测试：Rename 策略保存为 `name (1).ext`，原文件不变
+#[tokio::test]
+async fn overwrite_policy_rename_picks_free_name() {
+    let content = vec![2u8; 100];
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "a.bin", Some(100));
+    let dir = std::path::PathBuf::from(temp_path("overwrite_rename"));
+    std::fs::create_dir_all(&dir).unwrap();
+    let save_path = dir.join("report.txt");
+    std::fs::write(&save_path, b"old").unwrap();
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path.to_string_lossy())
+        .overwrite_policy(OverwritePolicy::Rename)
+        .send()
+        .await
+        .unwrap();
+
+    let renamed = dir.join("report (1).txt");
+    match result {
+        DownloadResult::SavedToLocal(path) => {
+            assert_eq!(path, renamed.to_string_lossy())
+        }
+        other => panic!("❌ 意外的结果: {:?}", other),
+    }
+    assert_eq!(std::fs::read(&save_path).unwrap(), b"old");
+    assert_eq!(std::fs::read(&renamed).unwrap(), content);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+/// 测试：Resume 策略从已有文件末尾发送 Range 请求，只下载剩余部分
+#[tokio::test]
+async fn overwrite_policy_resume_continues_partial_file() {
+    let content: Vec<u8> =
+        (0..5_000u32).map(|i| (i % 199) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "big.bin", Some(5_000));
+    let save_path = temp_path("overwrite_resume.bin");
+    std::fs::write(&save_path, &content[..1_200]).unwrap();
+
+    let (_, metrics) = file
+        .build_downloader()
+        .save_to(&save_path)
+        .max_chunks(4)
+        .overwrite_policy(OverwritePolicy::Resume)
+        .compute_sha256()
+        .send_with_metrics()
+        .await
+        .unwrap();
+
+    assert_eq!(std::fs::read(&save_path).unwrap(), content);
+    assert_eq!(metrics.bytes_from_resume, 1_200);
+    assert_eq!(metrics.total_bytes, 3_800);
+    assert_eq!(metrics.sha256, Some(sha256_hex(&content)));
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].range(), Some((1_200, None)));
+
+    // 已经完整的文件不再发请求
+    let (_, metrics) = file
+        .build_downloader()
+        .save_to(&save_path)
+        .overwrite_policy(OverwritePolicy::Resume)
+        .send_with_metrics()
+        .await
+        .unwrap();
+    assert_eq!(metrics.total_bytes, 0);
+    assert_eq!(server.requests().len(), 1);
+    let _ = std::fs::remove_file(&save_path);
+}
+
+/// 测试：服务器忽略 Range 时 Resume 退回为从头下载
+#[tokio::test]
+async fn overwrite_policy_resume_without_range_support_restarts() {
+    let content = vec![4u8; 2_000];
+    let body = content.clone();
+    let server = MockServer::start(move |_| {
+        MockResponse::new(200).body(body.clone())
+    });
+    let file = mock_remote_file(&server, "c.bin", Some(2_000));
+    let save_path = temp_path("overwrite_resume_norange.bin");
+    std::fs::write(&save_path, vec![0u8; 500]).unwrap();
+
+    let (_, metrics) = file
+        .build_downloader()
+        .save_to(&save_path)
+        .overwrite_policy(OverwritePolicy::Resume)
+        .send_with_metrics()
+        .await
+        .unwrap();
+
+    assert_eq!(std::fs::read(&save_path).unwrap(), content);
+    assert_eq!(metrics.bytes_from_resume, 0);
+    let _ = std::fs::remove_file(&save_path);
+}
+
+/// 测试：Resume 时服务器返回错误状态（503、416），部分文件保持原样且返回错误
+#[tokio::test]
+async fn overwrite_policy_resume_keeps_partial_file_on_error_status() {
+    let partial: Vec<u8> =
+        (0..1_000u32).map(|i| (i % 251) as u8).collect();
+    for status in [503u16, 416] {
+        let server = MockServer::start(move |_| {
+            MockResponse::new(status)
+                .body(b"<html>Service Unavailable</html>".to_vec())
+        });
+        let file = mock_remote_file(&server, "err.bin", Some(5_000));
+        let save_path =
+            temp_path(&format!("overwrite_resume_{}.bin", status));
+        std::fs::write(&save_path, &partial).unwrap();
+
+        let result = file
+            .build_downloader()
+            .save_to(&save_path)
+            .overwrite_policy(OverwritePolicy::Resume)
+            .send()
+            .await;
+
+        match result {
+            Err(DownloadError::Request(e)) => {
+                assert_eq!(e.status().map(|s| s.as_u16()), Some(status))
+            }
+            other => panic!("❌ {} 应返回错误: {:?}", status, other),
+        }
+        assert_eq!(std::fs::read(&save_path).unwrap(), partial);
+        assert_eq!(server.requests()[0].range(), Some((1_000, None)));
+        let _ = std::fs::remove_file(&save_path);
+    }
+}
+
+/// 测试：第一次下载到一半连接断开，Resume 策略下从断点继续，结果与完整文件一致
+#[tokio::test]
+async fn overwrite_policy_resume_after_interrupted_download() {
+    let content: Vec<u8> =
+        (0..8_000u32).map(|i| (i % 241) as u8).collect();
+    let first_attempt = Arc::new(AtomicBool::new(true));
+    let server = {
+        let content = content.clone();
+        MockServer::start(move |req| {
+            if req.range().is_none()
+                && first_attempt.swap(false, Ordering::SeqCst)
+            {
+                // 只发送前一半后断开
+                return MockResponse::new(200)
+                    .body(content[..4_000].to_vec())
+                    .truncated(8_000);
+            }
+            file_response(req, &content)
+        })
+    };
+    let file = mock_remote_file(&server, "half.bin", Some(8_000));
+    let save_path = temp_path("overwrite_resume_interrupted.bin");
+    let _ = std::fs::remove_file(&save_path);
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .overwrite_policy(OverwritePolicy::Resume)
+        .send()
+        .await;
+    assert!(
+        matches!(result, Err(DownloadError::Request(_))),
+        "❌ 第一次下载应因断线失败: {:?}",
+        result
+    );
+    let partial = std::fs::read(&save_path).expect("应保留已下载的部分");
+    assert!(!partial.is_empty() && partial.len() < content.len());
+    assert_eq!(partial, content[..partial.len()]);
+
+    let (_, metrics) = file
+        .build_downloader()
+        .save_to(&save_path)
+        .overwrite_policy(OverwritePolicy::Resume)
+        .send_with_metrics()
+        .await
+        .expect("续传失败");
+
+    assert_eq!(std::fs::read(&save_path).unwrap(), content);
+    assert_eq!(metrics.bytes_from_resume, partial.len() as u64);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].range(), Some((partial.len() as u64, None)));
+    let _ = std::fs::remove_file(&save_path);
+}
+
+// ═══════════════════════════ 读块合并 ═══════════════════════════
+
+/// 测试：合并后每次写入至少 min_bytes，流结束时写出剩余部分
+#[tokio::test]
+async fn coalesce_chunks_batches_small_frames() {
+    let server = MockServer::start(|_| {
+        let mut response = MockResponse::new(200).chunked();
+        for i in 0..10u8 {
+            response = response
+                .delayed_part(Duration::from_millis(20), vec![i; 100]);
+        }
+        response
+    });
+    let file = mock_remote_file(&server, "frames.bin", Some(1000));
+    let seen = Arc::new(Mutex::new(Vec::<u64>::new()));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .coalesce_chunks(300)
+        .abort_if({
+            let seen = Arc::clone(&seen);
+            move |report| {
+                seen.lock().unwrap().push(report.progress.bytes_done);
+                false
+            }
+        })
+        .send()
+        .await;
+
+    let expected: Vec<u8> = (0..10u8).flat_map(|i| vec![i; 100]).collect();
+    match result {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes, expected),
+        other => panic!("❌ 应返回 Bytes，实际: {:?}", other),
+    }
+
+    let mut seen = seen.lock().unwrap().clone();
+    seen.dedup();
+    assert_eq!(seen.first(), Some(&0));
+    assert_eq!(seen.last(), Some(&1000));
+    let steps: Vec<u64> = seen.windows(2).map(|w| w[1] - w[0]).collect();
+    assert!(
+        steps[..steps.len() - 1].iter().all(|&step| step >= 300),
+        "❌ 除最后一次外每次写入应不少于 300 字节，实际: {:?}",
+        seen
+    );
+}
+
+// ═══════════════════════════ 分片写入方式 ═══════════════════════════
+
+/// 测试：两种写入方式并发写入多个分片，得到的文件内容一致
+#[tokio::test]
+async fn chunked_write_modes_produce_same_file() {
+    let content: Vec<u8> =
+        (0..64 * 1024u32).map(|i| (i % 251) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file =
+        mock_remote_file(&server, "modes.bin", Some(content.len() as u64));
+
+    for (mode, name) in [
+        (ChunkWriteMode::Independent, "write_mode_independent.bin"),
+        (ChunkWriteMode::SerializedMutex, "write_mode_mutex.bin"),
+    ] {
+        let save_path = temp_path(name);
+        let result = file
+            .build_downloader()
+            .save_to(&save_path)
+            .max_chunks(4)
+            .chunk_size(4096)
+            .write_mode(mode)
+            .send()
+            .await;
+        assert!(result.is_ok(), "❌ {:?} 下载失败: {:?}", mode, result);
+
+        let saved = tokio::fs::read(&save_path).await.unwrap();
+        assert_eq!(saved, content, "❌ {:?} 写入的内容不一致", mode);
+        let _ = tokio::fs::remove_file(&save_path).await;
+    }
+}
+
+// ═══════════════════════════ 结果落盘 ═══════════════════════════
+
+/// 测试：分片下载到内存后按 offset 顺序写入文件
+#[tokio::test]
+async fn write_to_persists_byte_segments() {
+    let content: Vec<u8> = (0..10_000u32).map(|i| (i % 97) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "persist.bin", Some(10_000));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1024)
+        .send()
+        .await
+        .unwrap();
+    assert!(matches!(result, DownloadResult::ByteSegments(_)));
+
+    let save_path = temp_path("write_to_segments.bin");
+    let written = result.write_to(&save_path).await.unwrap();
+    assert_eq!(written, 10_000);
+    assert_eq!(tokio::fs::read(&save_path).await.unwrap(), content);
+
+    // 已保存的文件：写到自身不做任何操作，写到别处则复制
+    let saved = DownloadResult::SavedToLocal(save_path.clone());
+    assert_eq!(saved.write_to(&save_path).await.unwrap(), 10_000);
+    assert_eq!(tokio::fs::read(&save_path).await.unwrap(), content);
+
+    let copy_path = temp_path("write_to_copy.bin");
+    assert_eq!(saved.write_to(&copy_path).await.unwrap(), 10_000);
+    assert_eq!(tokio::fs::read(&copy_path).await.unwrap(), content);
+
+    let _ = tokio::fs::remove_file(&save_path).await;
+    let _ = tokio::fs::remove_file(&copy_path).await;
+}
+
+// ═══════════════════════════ 下载钩子 ═══════════════════════════
+
+/// 一次 Range 请求的结果：(start, end, bytes, status)
+type RangeCompletion = (u64, u64, u64, Option<u16>);
+
+/// 记录 Range 请求事件的钩子
+#[derive(Default)]
+struct RangeLog {
+    requests: Mutex<Vec<(u64, u64, usize)>>,
+    completions: Mutex<Vec<RangeCompletion>>,
+    errors: Mutex<Vec<String>>,
+}
+
+impl DownloadHook for Arc<RangeLog> {
+    fn on_range_request(&self, start: u64, end: u64, attempt: usize) {
+        self.requests.lock().unwrap().push((start, end, attempt));
+    }
+
+    fn on_range_complete(
+        &self,
+        start: u64,
+        end: u64,
+        bytes: u64,
+        status: Option<u16>,
+    ) {
+        self.completions
+            .lock()
+            .unwrap()
+            .push((start, end, bytes, status));
+    }
+
+    fn on_error(&self, err: &DownloadError) {
+        self.errors.lock().unwrap().push(format!("{:?}", err));
+    }
+}
+
+/// 测试：钩子看到每个 Range 请求（含重试）及其结果
+#[tokio::test]
+async fn hook_observes_range_requests() {
+    let content = vec![6u8; 4096];
+    let first_attempt = Arc::new(AtomicBool::new(true));
+    let server = MockServer::start({
+        let content = content.clone();
+        let first_attempt = Arc::clone(&first_attempt);
+        move |req| {
+            // 第二个分片的第一次请求中途断开
+            if req.range() == Some((2048, Some(4095)))
+                && first_attempt.swap(false, Ordering::SeqCst)
+            {
+                return MockResponse::new(206)
+                    .header("Content-Range", "bytes 2048-4095/4096")
+                    .body(vec![6u8; 100])
+                    .truncated(2048);
+            }
+            file_response(req, &content)
+        }
+    });
+    let file = mock_remote_file(&server, "hooked.bin", Some(4096));
+    let log = Arc::new(RangeLog::default());
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(2)
+        .chunk_size(2048)
+        .max_retries(2)
+        .with_hook(Arc::clone(&log))
+        .send()
+        .await;
+    assert!(result.is_ok(), "❌ 重试后应下载成功: {:?}", result);
+
+    let mut requests = log.requests.lock().unwrap().clone();
+    requests.sort();
+    assert_eq!(
+        requests,
+        vec![(0, 2047, 1), (2048, 4095, 1), (2048, 4095, 2)]
+    );
+
+    let mut completions = log.completions.lock().unwrap().clone();
+    completions.sort();
+    assert_eq!(
+        completions,
+        vec![
+            (0, 2047, 2048, Some(206)),
+            (2048, 4095, 100, Some(206)),
+            (2048, 4095, 2048, Some(206)),
+        ]
+    );
+    assert!(log.errors.lock().unwrap().is_empty(), "❌ 成功时不应调用");
+}
+
+/// 测试：注册钩子的下载在 send 期间同样依次切换为
+/// Preparing → Running → Finished
+#[tokio::test]
+async fn hooked_download_reports_status_sequence() {
+    let server = MockServer::serve_file(vec![2u8; 4096]);
+    let file = mock_remote_file(&server, "hooked_status.bin", Some(4096));
+    let downloader = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(2)
+        .chunk_size(2048)
+        .with_hook(Arc::new(RangeLog::default()));
+    let controller = downloader.get_controller();
+
+    let statuses = Arc::new(Mutex::new(Vec::new()));
+    let subscription = controller.subscribe_download_status(false, {
+        let statuses = Arc::clone(&statuses);
+        move |status| statuses.lock().unwrap().push(status.clone())
+    });
+
+    downloader.send().await.expect("下载失败");
+    subscription.await.expect("订阅任务 panic");
+
+    let statuses = statuses.lock().unwrap();
+    assert!(
+        matches!(
+            statuses.as_slice(),
+            [
+                DownloadStatus::Preparing,
+                DownloadStatus::Running,
+                DownloadStatus::Finished
+            ]
+        ),
+        "❌ 状态序列不正确: {:?}",
+        statuses
+    );
+}
+
+/// 测试：单线程与分片下载出错时各调用一次 on_error
+#[tokio::test]
+async fn hook_observes_download_errors() {
+    // 单线程：只发送一半后断开
+    let server = MockServer::start(|_| {
+        MockResponse::new(200).body(vec![1u8; 500]).truncated(1_000)
+    });
+    let file = mock_remote_file(&server, "broken.bin", Some(1_000));
+    let log = Arc::new(RangeLog::default());
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .with_hook(Arc::clone(&log))
+        .send()
+        .await;
+    assert!(matches!(result, Err(DownloadError::Request(_))));
+    let errors = log.errors.lock().unwrap().clone();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("Request"), "❌ {:?}", errors);
+
+    // 分片：Range 探测之后每个分片都返回 500
+    let server = MockServer::start(|req| match req.range() {
+        Some((0, Some(0))) => MockResponse::new(206)
+            .header("Content-Range", "bytes 0-0/4096")
+            .body(vec![0u8]),
+        _ => MockResponse::new(500),
+    });
+    let file = mock_remote_file(&server, "broken.bin", Some(4096));
+    let log = Arc::new(RangeLog::default());
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(2)
+        .chunk_size(2048)
+        .max_retries(0)
+        .with_hook(Arc::clone(&log))
+        .send()
+        .await;
+    assert!(result.is_err(), "❌ 分片全部失败时应返回错误");
+    assert_eq!(log.errors.lock().unwrap().len(), 1, "❌ 应只调用一次");
+}
+
+/// 每 50ms 发送 1000 字节的服务器
+fn paced_server(content: &[u8]) -> MockServer {
+    let content = content.to_vec();
+    MockServer::start(move |_| {
+        content.chunks(1_000).fold(MockResponse::new(200), |resp, part| {
+            resp.delayed_part(Duration::from_millis(50), part)
+        })
+    })
+}
+
+/// 下载到一半时在 on_progress 中要求中止的钩子
+#[derive(Default)]
+struct HalfwayAbort {
+    reports: Mutex<Vec<u64>>,
+    errors: Mutex<Vec<String>>,
+}
+
+impl DownloadHook for Arc<HalfwayAbort> {
+    fn on_progress(
+        &self,
+        report: &ProgressReport,
+    ) -> Result<(), HookAbort> {
+        let bytes_done = report.progress.bytes_done;
+        self.reports.lock().unwrap().push(bytes_done);
+        match report.progress.total {
+            Some(total) if bytes_done * 2 >= total => {
+                Err(HookAbort::new("超出配额"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn on_error(&self, err: &DownloadError) {
+        self.errors.lock().unwrap().push(err.to_string());
+    }
+}
+
+/// 测试：钩子在 50% 时中止，返回 HookAborted 并删除未完成的文件
+#[tokio::test]
+async fn progress_hook_aborts_halfway() {
+    let content = vec![4u8; 10_000];
+    let server = paced_server(&content);
+    let file = mock_remote_file(&server, "quota.bin", Some(10_000));
+    let save_path = temp_path("hook_abort_halfway.bin");
+    let hook = Arc::new(HalfwayAbort::default());
+
+    let downloader = file
+        .build_downloader()
+        .save_to(&save_path)
+        .with_hook(Arc::clone(&hook));
+    let controller = downloader.get_controller();
+    let result = downloader.send().await;
+
+    match &result {
+        Err(DownloadError::HookAborted(reason)) => {
+            assert_eq!(reason, "超出配额")
+        }
+        other => panic!("❌ 应返回 HookAborted，实际: {:?}", other),
+    }
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "❌ 文件应已删除"
+    );
+    assert!(matches!(
+        controller.get_download_status(),
+        Some(DownloadStatus::Canceled)
+    ));
+    // 中止后不再继续读取
+    let reports = hook.reports.lock().unwrap();
+    let last = *reports.last().expect("应收到进度");
+    assert!((5_000..=6_000).contains(&last), "❌ 中止过晚: {}", last);
+    assert_eq!(
+        *hook.errors.lock().unwrap(),
+        vec!["下载被钩子中止：超出配额".to_string()]
+    );
+}
+
+// ═══════════════════════════ 首字节超时 ═══════════════════════════
+
+/// 测试：迟迟不发送第一个字节时返回 FirstByteTimeout
+#[tokio::test]
+async fn first_byte_timeout_aborts_slow_start() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200)
+            .delayed_part(Duration::from_millis(800), vec![1u8; 64])
+    });
+    let file = mock_remote_file(&server, "cold.bin", Some(64));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .first_byte_timeout(Duration::from_millis(100))
+        .send()
+        .await;
+
+    match result {
+        Err(DownloadError::FirstByteTimeout(timeout)) => {
+            assert_eq!(timeout, Duration::from_millis(100));
+        }
+        other => panic!("❌ 应返回 FirstByteTimeout，实际: {:?}", other),
+    }
+}
+
+/// 测试：开始传输后中途变慢不受首字节超时约束
+#[tokio::test]
+async fn first_byte_timeout_ignores_mid_stream_stall() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200)
+            .body(vec![1u8; 32])
+            .delayed_part(Duration::from_millis(300), vec![2u8; 32])
+    });
+    let file = mock_remote_file(&server, "stall.bin", Some(64));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .first_byte_timeout(Duration::from_millis(100))
+        .send()
+        .await;
+
+    match result {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes.len(), 64),
+        other => panic!("❌ 中途变慢不应超时，实际: {:?}", other),
+    }
+}
+
+// ═══════════════════════════ 重试与分段 ═══════════════════════════
+
+/// 测试：分片中途断开并重试后，每个 offset 只有一个分段且互不重叠
+#[tokio::test]
+async fn retried_range_yields_single_segment() {
+    let content: Vec<u8> = (0..4096u32).map(|i| (i % 251) as u8).collect();
+    let failed_once = Arc::new(AtomicBool::new(false));
+    let server = MockServer::start({
+        let content = content.clone();
+        let failed_once = Arc::clone(&failed_once);
+        move |req| {
+            // 中间的分片第一次只发送一半就断开
+            if req.range() == Some((1024, Some(2047)))
+                && !failed_once.swap(true, Ordering::SeqCst)
+            {
+                return MockResponse::new(206)
+                    .header("Content-Range", "bytes 1024-2047/4096")
+                    .body(content[1024..1536].to_vec())
+                    .truncated(1024);
+            }
+            file_response(req, &content)
+        }
+    });
+    let file = mock_remote_file(&server, "retry.bin", Some(4096));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1024)
+        .max_retries(2)
+        .send()
+        .await;
+
+    let segments = match result {
+        Ok(DownloadResult::ByteSegments(segments)) => segments,
+        other => panic!("❌ 应返回 ByteSegments，实际: {:?}", other),
+    };
+    assert!(failed_once.load(Ordering::SeqCst), "❌ 没有触发重试");
+
+    let offsets: Vec<(u64, usize)> =
+        segments.segments().map(|s| (s.offset, s.data.len())).collect();
+    assert_eq!(
+        offsets,
+        vec![(0, 1024), (1024, 1024), (2048, 1024), (3072, 1024)]
+    );
+    assert_eq!(segments.total_len(), 4096);
+    assert_eq!(segments.to_bytes(), content);
+}
+
+// ═══════════════════════════ 分片大小 ═══════════════════════════
+
+/// 测试：分片数为 ceil(大小 / chunk_size)，最后一个分片为余下部分
+#[tokio::test]
+async fn chunk_size_determines_range_count() {
+    let content: Vec<u8> =
+        (0..10_000u32).map(|i| (i % 211) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "sized.bin", Some(10_000));
+
+    for (chunk_size, expected_chunks) in
+        [(3_000, 4), (2_500, 4), (10_000, 1)]
+    {
+        let (result, metrics) = file
+            .build_downloader()
+            .output_bytes()
+            .max_chunks(8)
+            .chunk_size(chunk_size)
+            .send_with_metrics()
+            .await
+            .expect("下载失败");
+        match result {
+            DownloadResult::ByteSegments(segments) => {
+                assert_eq!(segments.to_bytes(), content)
+            }
+            other => panic!("❌ 应返回 ByteSegments，实际: {:?}", other),
+        }
+        assert_eq!(
+            metrics.chunks, expected_chunks,
+            "chunk_size={}",
+            chunk_size
+        );
+    }
+
+    // 第一个请求是 `bytes=0-0` 的 Range 探测
+    let requests = server.requests();
+    assert_eq!(requests[0].range(), Some((0, Some(0))));
+    let mut ranges: Vec<_> =
+        requests[1..5].iter().filter_map(|r| r.range()).collect();
+    ranges.sort();
+    assert_eq!(
+        ranges,
+        [
+            (0, Some(2_999)),
+            (3_000, Some(5_999)),
+            (6_000, Some(8_999)),
+            (9_000, Some(9_999)),
+        ]
+    );
+}
+
+/// 测试：chunk_size 为 0 时 send 返回 ZeroChunkSize，不发出请求
+#[tokio::test]
+async fn zero_chunk_size_is_rejected() {
+    let server = MockServer::serve_file(vec![1u8; 4_096]);
+    let file = mock_remote_file(&server, "zero.bin", Some(4_096));
+
+    for max_chunks in [1, 4] {
+        let result = file
+            .build_downloader()
+            .max_chunks(max_chunks)
+            .chunk_size(0)
+            .output_bytes()
+            .send()
+            .await;
+        assert!(
+            matches!(result, Err(DownloadError::ZeroChunkSize)),
+            "❌ 应返回 ZeroChunkSize，实际: {:?}",
+            result
+        );
+    }
+    assert!(server.requests().is_empty(), "❌ 不应发出任何请求");
+}
+
+// ═══════════════════════════ Range 探测 ═══════════════════════════
+
+/// 忽略 Range、总是返回完整文件（200）的服务器
+fn no_range_server(content: &[u8]) -> MockServer {
+    let content = content.to_vec();
+    MockServer::start(move |_| {
+        MockResponse::new(200).body(content.clone())
+    })
+}
+
+/// 测试：服务器不支持 Range 时分片下载默认返回 RangeNotSupported
+#[tokio::test]
+async fn chunked_download_without_range_support_fails() {
+    let content = vec![7u8; 4096];
+    let server = no_range_server(&content);
+    let file = mock_remote_file(&server, "norange.bin", Some(4096));
+    let save_path = temp_path("norange_chunked.bin");
+
+    let result = file
+        .build_downloader()
+        .save_to(&save_path)
+        .max_chunks(4)
+        .chunk_size(1024)
+        .send()
+        .await;
+
+    assert!(
+        matches!(result, Err(DownloadError::RangeNotSupported)),
+        "❌ 应返回 RangeNotSupported，实际: {:?}",
+        result
+    );
+    assert_eq!(server.requests().len(), 1, "探测失败后不应再请求分片");
+    assert!(!std::path::Path::new(&save_path).exists());
+}
+
+/// 测试：开启 fallback_on_no_range 后回退到单线程下载，探测字节不计入统计
+#[tokio::test]
+async fn chunked_download_falls_back_to_single_thread() {
+    let content: Vec<u8> = (0..4096u32).map(|i| (i % 13) as u8).collect();
+    let server = no_range_server(&content);
+    let file = mock_remote_file(&server, "fallback.bin", Some(4096));
+    let downloader = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1024)
+        .fallback_on_no_range(true);
+    let controller = downloader.get_controller();
+
+    let (result, metrics) =
+        downloader.send_with_metrics().await.expect("回退后应下载成功");
+
+    match result {
+        DownloadResult::Bytes(bytes) => assert_eq!(bytes, content),
+        other => panic!("❌ 回退后应返回 Bytes，实际: {:?}", other),
+    }
+    assert_eq!(metrics.chunks, 1);
+    assert_eq!(metrics.total_bytes, 4096);
+    assert_eq!(controller.progress().bytes_done, 4096);
+    assert_eq!(server.requests().len(), 2);
+}
+
+// ═══════════════════════════ 认证失效 ═══════════════════════════
+
+/// 只接受 `Authorization: Bearer fresh` 的文件服务器，其余请求返回 401
+fn bearer_server(content: Vec<u8>) -> MockServer {
+    MockServer::start(move |req| {
+        if req.header("Authorization") != Some("Bearer fresh") {
+            return MockResponse::new(401);
+        }
+        file_response(req, &content)
+    })
+}
+
+/// 带 `Authorization: Bearer <token>` 默认请求头的客户端
+fn bearer_client(token: &str) -> Result<reqwest::Client, String> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    let value = reqwest::header::HeaderValue::from_str(&format!(
+        "Bearer {}",
+        token
+    ))
+    .map_err(|e| e.to_string())?;
+    headers.insert(reqwest::header::AUTHORIZATION, value);
+    reqwest::Client::builder()
+        .http1_only()
+        .default_headers(headers)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// 测试：未注册刷新函数时，分片收到 401 直接返回 AuthExpired，不做普通重试
+#[tokio::test]
+async fn chunk_unauthorized_returns_auth_expired() {
+    let server = bearer_server(vec![7u8; 4096]);
+    let file = mock_remote_file(&server, "expired.bin", Some(4096));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(2)
+        .chunk_size(1024)
+        .max_retries(3)
+        .send()
+        .await;
+
+    assert!(
+        matches!(result, Err(DownloadError::AuthExpired { status: 401 })),
+        "❌ 应返回 AuthExpired，实际: {:?}",
+        result
+    );
+    // 每个分片最多请求一次，不会按 max_retries 重试
+    assert!(server.requests().len() <= 4);
+}
+
+/// 测试：多个分片同时收到 401 时只刷新一次，刷新后重试受影响的分片
+#[tokio::test]
+async fn chunk_auth_refresh_retries_with_new_client() {
+    let content: Vec<u8> = (0..4096u32).map(|i| (i % 253) as u8).collect();
+    let server = bearer_server(content.clone());
+    let file = mock_remote_file(&server, "refresh.bin", Some(4096));
+    let refreshes = Arc::new(Mutex::new(0usize));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1024)
+        .max_retries(0)
+        .with_auth_refresh({
+            let refreshes = Arc::clone(&refreshes);
+            move || {
+                if let Ok(mut count) = refreshes.lock() {
+                    *count += 1;
+                }
+                async { bearer_client("fresh") }
+            }
+        })
+        .send()
+        .await;
+
+    match result {
+        Ok(DownloadResult::ByteSegments(segments)) => {
+            assert_eq!(segments.to_bytes(), content)
+        }
+        other => panic!("❌ 刷新后应下载成功，实际: {:?}", other),
+    }
+    assert_eq!(*refreshes.lock().expect("锁失败"), 1, "❌ 应只刷新一次");
+}
+
+/// 测试：单线程下载收到 401 时刷新并重发；刷新后仍被拒绝则返回 AuthExpired
+#[tokio::test]
+async fn single_thread_auth_refresh() {
+    let server = bearer_server(b"hello".to_vec());
+    let file = mock_remote_file(&server, "single.bin", Some(5));
+
+    let ok = file
+        .build_downloader()
+        .output_bytes()
+        .with_auth_refresh(|| async { bearer_client("fresh") })
+        .send()
+        .await;
+    match ok {
+        Ok(DownloadResult::Bytes(bytes)) => {
+            assert_eq!(&bytes[..], b"hello")
+        }
+        other => panic!("❌ 刷新后应下载成功，实际: {:?}", other),
+    }
+
+    let rejected = file
+        .build_downloader()
+        .output_bytes()
+        .with_auth_refresh(|| async { bearer_client("stale") })
+        .send()
+        .await;
+    assert!(
+        matches!(
+            rejected,
+            Err(DownloadError::AuthExpired { status: 401 })
+        ),
+        "❌ 应返回 AuthExpired，实际: {:?}",
+        rejected
+    );
+
+    let failed = file
+        .build_downloader()
+        .output_bytes()
+        .with_auth_refresh(|| async {
+            Err("token endpoint down".to_string())
+        })
+        .send()
+        .await;
+    assert!(
+        matches!(failed, Err(DownloadError::AuthRefreshFailed(_))),
+        "❌ 应返回 AuthRefreshFailed，实际: {:?}",
+        failed
+    );
+}
+
+// ═══════════════════════════ 限速 ═══════════════════════════
+
+/// 测试：单线程与分片下载的总速率都不超过上限，内容完整
+#[tokio::test]
+async fn throttle_caps_download_rate() {
+    let content: Vec<u8> =
+        (0..20_000u32).map(|i| (i % 253) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "throttle.bin", Some(20_000));
+
+    // 20KB / 40KB/s ≈ 0.5s
+    let started = std::time::Instant::now();
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .throttle(40_000)
+        .send()
+        .await;
+    match result {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes, content),
+        other => panic!("❌ 单线程下载失败: {:?}", other),
+    }
+    let single = started.elapsed();
+    assert!(
+        single >= Duration::from_millis(400),
+        "❌ 未限速: {:?}",
+        single
+    );
+
+    // 4 个分片共享同一额度，总时长同样约 0.5s
+    let started = std::time::Instant::now();
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(5_000)
+        .throttle(40_000)
+        .send()
+        .await;
+    match result {
+        Ok(DownloadResult::ByteSegments(segments)) => {
+            assert_eq!(segments.to_bytes(), content)
+        }
+        other => panic!("❌ 分片下载失败: {:?}", other),
+    }
+    let chunked = started.elapsed();
+    assert!(
+        chunked >= Duration::from_millis(400),
+        "❌ 未限速: {:?}",
+        chunked
+    );
+}
+
+/// 测试：单线程下载在限速等待期间取消立即生效
+#[tokio::test]
+async fn cancel_while_throttled() {
+    let server = MockServer::serve_file(vec![7u8; 100_000]);
+    let file =
+        mock_remote_file(&server, "throttle_cancel.bin", Some(100_000));
+    // 1KB/s 下需要 100s
+    let downloader =
+        file.build_downloader().output_bytes().throttle(1_000);
+    let controller = downloader.get_controller();
+
+    let cancel_task = tokio::spawn({
+        let controller = Arc::clone(&controller);
+        async move {
+            while controller.get_downloaded_bytes() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            let _ = controller.cancel();
+        }
+    });
+
+    let result =
+        tokio::time::timeout(Duration::from_secs(5), downloader.send())
+            .await
+            .expect("限速等待中取消后下载应立即结束");
+    cancel_task.await.expect("取消任务 panic");
+    assert!(
+        matches!(result, Err(DownloadError::Cancelled)),
+        "❌ 应返回 Cancelled，实际: {:?}",
+        result
+    );
+}
+
+// ═══════════════════════════ 完整性校验 ═══════════════════════════
+
+/// 测试：单线程下载正常结束但字节数少于 PROPFIND 大小时返回 SizeMismatch
+#[tokio::test]
+async fn single_thread_short_body_is_size_mismatch() {
+    // 分块传输编码没有 Content-Length，reqwest 无法发现缺少的部分
+    let server = MockServer::start(|_| {
+        MockResponse::new(200).body(vec![1u8; 1_500]).chunked()
+    });
+    let file = mock_remote_file(&server, "short.bin", Some(2_000));
+    let save_path = temp_path("size_mismatch_single.bin");
+
+    let result = file.build_downloader().save_to(&save_path).send().await;
+
+    assert!(
+        matches!(
+            result,
+            Err(DownloadError::SizeMismatch {
+                expected: 2_000,
+                actual: 1_500
+            })
+        ),
+        "❌ 应返回 SizeMismatch，实际: {:?}",
+        result
+    );
+    assert!(
+        tokio::fs::metadata(&save_path).await.is_err(),
+        "文件应已删除"
+    );
+}
+
+/// 测试：响应在 Content-Length 之前断开时返回请求错误
+#[tokio::test]
+async fn single_thread_truncated_response_fails() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200).body(vec![1u8; 500]).truncated(2_000)
+    });
+    let file = mock_remote_file(&server, "truncated.bin", Some(2_000));
+
+    let result = file.build_downloader().output_bytes().send().await;
+
+    assert!(
+        matches!(result, Err(DownloadError::Request(_))),
+        "❌ 应返回 Request，实际: {:?}",
+        result
+    );
+}
+
+/// 测试：分片的 206 响应体比请求的 Range 短时按可重试错误处理，
+/// 重试成功后内容完整
+#[tokio::test]
+async fn chunked_short_range_is_retried() {
+    let content: Vec<u8> = (0..4_096).map(|i| (i % 251) as u8).collect();
+    let first_attempt = Arc::new(AtomicBool::new(true));
+    let server = {
+        let content = content.clone();
+        let first_attempt = Arc::clone(&first_attempt);
+        MockServer::start(move |req| {
+            if req.range() == Some((1_024, Some(2_047)))
+                && first_attempt.swap(false, Ordering::SeqCst)
+            {
+                return MockResponse::new(206)
+                    .header("Content-Range", "bytes 1024-2047/4096")
+                    .body(content[1_024..2_024].to_vec());
+            }
+            file_response(req, &content)
+        })
+    };
+    let file = mock_remote_file(&server, "short_range.bin", Some(4_096));
+
+    let (result, metrics) = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1_024)
+        .send_with_metrics()
+        .await
+        .expect("❌ 重试后应下载成功");
+
+    match result {
+        DownloadResult::ByteSegments(segments) => {
+            assert_eq!(segments.to_bytes(), content);
+        }
+        other => panic!("❌ 应返回 ByteSegments，实际: {:?}", other),
+    }
+    assert_eq!(metrics.retries, 1);
+    assert_eq!(metrics.total_bytes, 4_096);
+    let short_requests = server
+        .requests()
+        .iter()
+        .filter(|req| req.range() == Some((1_024, Some(2_047))))
+        .count();
+    assert_eq!(short_requests, 2);
+}
+
+/// 测试：分片请求收到 200 或与请求不符的 Content-Range 时不写入，
+/// 持续如此时相应分片失败
+#[tokio::test]
+async fn chunked_unexpected_range_response_is_not_written() {
+    let content = vec![4u8; 4_096];
+    let server = {
+        let content = content.clone();
+        MockServer::start(move |req| match req.range() {
+            // 忽略 Range，返回整个文件
+            Some((1_024, _)) => {
+                MockResponse::new(200).body(vec![9u8; 4_096])
+            }
+            // 返回错位的区间
+            Some((2_048, _)) => MockResponse::new(206)
+                .header("Content-Range", "bytes 0-1023/4096")
+                .body(vec![9u8; 1_024]),
+            _ => file_response(req, &content),
+        })
+    };
+    let file = mock_remote_file(&server, "wrong_range.bin", Some(4_096));
+    let log = Arc::new(RangeLog::default());
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1_024)
+        .max_retries(1)
+        .with_hook(Arc::clone(&log))
+        .send()
+        .await;
+
+    match result {
+        Err(DownloadError::MultipleChunksFailed(errors)) => {
+            assert_eq!(
+                errors.len(),
+                2,
+                "❌ 应有两个分片失败: {:?}",
+                errors
+            );
+            assert!(
+                errors
+                    .iter()
+                    .all(|e| e.contains("分片响应与请求区间不符"))
+            );
+        }
+        other => {
+            panic!("❌ 应返回 MultipleChunksFailed，实际: {:?}", other)
+        }
+    }
+    // 被拒绝的响应一个字节也没有读取
+    let mut rejected: Vec<_> = log
+        .completions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(start, ..)| *start == 1_024 || *start == 2_048)
+        .cloned()
+        .collect();
+    rejected.sort();
+    assert_eq!(
+        rejected,
+        vec![
+            (1_024, 2_047, 0, Some(200)),
+            (1_024, 2_047, 0, Some(200)),
+            (2_048, 3_071, 0, Some(206)),
+            (2_048, 3_071, 0, Some(206)),
+        ]
+    );
+}
+
+/// 测试：分片请求收到 503 时重试，而不是把错误页写进文件
+#[tokio::test]
+async fn chunked_server_error_is_retried() {
+    let content: Vec<u8> = (0..4_096).map(|i| (i % 13) as u8).collect();
+    let first_attempt = Arc::new(AtomicBool::new(true));
+    let server = {
+        let content = content.clone();
+        let first_attempt = Arc::clone(&first_attempt);
+        MockServer::start(move |req| {
+            if req.range() == Some((3_072, Some(4_095)))
+                && first_attempt.swap(false, Ordering::SeqCst)
+            {
+                return MockResponse::new(503).body(b"busy".to_vec());
+            }
+            file_response(req, &content)
+        })
+    };
+    let file = mock_remote_file(&server, "busy.bin", Some(4_096));
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(4)
+        .chunk_size(1_024)
+        .send()
+        .await;
+
+    match result {
+        Ok(DownloadResult::ByteSegments(segments)) => {
+            assert_eq!(segments.to_bytes(), content);
+        }
+        other => panic!("❌ 应返回 ByteSegments，实际: {:?}", other),
+    }
+}
+
+/// 测试：verify_etag 在下载后发现 ETag 变化时返回 ETagChanged，
+/// 只有弱标记或引号不同时视为未变化
+#[tokio::test]
+async fn verify_etag_detects_changed_file() {
+    let content = vec![5u8; 1_000];
+    let served = content.clone();
+    let server = MockServer::start(move |req| match req.path.as_str() {
+        "/changed.bin" => {
+            file_response(req, &served).header("ETag", "\"v2\"")
+        }
+        _ => file_response(req, &served).header("ETag", "W/\"v1\""),
+    });
+    let with_etag = |path: &str| {
+        let file = mock_remote_file(&server, path, Some(1_000));
+        let mut data = (*file.data).clone();
+        data.etag = Some("v1".to_string());
+        RemoteFile { data: Arc::new(data), ..file }
+    };
+
+    let result = with_etag("changed.bin")
+        .build_downloader()
+        .output_bytes()
+        .verify_etag()
+        .send()
+        .await;
+    assert!(
+        matches!(
+            &result,
+            Err(DownloadError::ETagChanged { expected, actual })
+                if expected == "v1" && actual == "\"v2\""
+        ),
+        "❌ 应返回 ETagChanged，实际: {:?}",
+        result
+    );
+
+    let result = with_etag("same.bin")
+        .build_downloader()
+        .output_bytes()
+        .max_chunks(2)
+        .chunk_size(500)
+        .verify_etag()
+        .send()
+        .await;
+    match result {
+        Ok(DownloadResult::ByteSegments(segments)) => {
+            assert_eq!(segments.to_bytes(), content)
+        }
+        other => panic!("❌ ETag 未变化时应成功: {:?}", other),
+    }
+    let heads = server
+        .requests()
+        .iter()
+        .filter(|req| req.method == "HEAD")
+        .count();
+    assert_eq!(heads, 2);
+}
+
+// ═══════════════════════════ 区间下载 ═══════════════════════════
+
+/// 测试：download_range 只发送一次 Range 请求，返回恰好该区间的字节
+#[tokio::test]
+async fn download_range_returns_exact_slice() {
+    let content: Vec<u8> =
+        (0..5_000u32).map(|i| (i % 241) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "archive.zip", Some(5_000));
+    let downloader = file.build_downloader().max_chunks(4);
+
+    match downloader.download_range(4_000, 5_000).await {
+        Ok(DownloadResult::Bytes(bytes)) => {
+            assert_eq!(bytes, &content[4_000..])
+        }
+        other => panic!("❌ 应返回 Bytes，实际: {:?}", other),
+    }
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].range(), Some((4_000, Some(4_999))));
+
+    // 区间为空或超出已知大小时不发请求
+    for (start, end) in [(10, 10), (20, 10), (4_000, 5_001)] {
+        let result = downloader.download_range(start, end).await;
+        assert!(
+            matches!(
+                result,
+                Err(DownloadError::InvalidRange { size: Some(5_000), .. })
+            ),
+            "❌ [{}, {}) 应返回 InvalidRange: {:?}",
+            start,
+            end,
+            result
+        );
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// 测试：大小未知时越过末尾只返回到末尾，起点也越界时为 InvalidRange
+#[tokio::test]
+async fn download_range_with_unknown_size() {
+    let server = MockServer::serve_file(b"0123456789".to_vec());
+    let file = mock_remote_file(&server, "unknown.bin", None);
+    let downloader = file.build_downloader();
+
+    match downloader.download_range(6, 100).await {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes, b"6789"),
+        other => panic!("❌ 应返回到末尾为止的字节，实际: {:?}", other),
+    }
+    let result = downloader.download_range(50, 60).await;
+    assert!(
+        matches!(
+            result,
+            Err(DownloadError::InvalidRange { size: None, .. })
+        ),
+        "❌ 416 应返回 InvalidRange: {:?}",
+        result
+    );
+}
+
+/// 测试：服务器忽略 Range 返回 200 时报错，而不是返回整个文件
+#[tokio::test]
+async fn download_range_requires_range_support() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(200).body(vec![1u8; 1_000])
+    });
+    let file = mock_remote_file(&server, "norange.zip", Some(1_000));
+
+    let result = file.build_downloader().download_range(0, 10).await;
+
+    assert!(
+        matches!(result, Err(DownloadError::RangeNotSupported)),
+        "❌ 应返回 RangeNotSupported: {:?}",
+        result
+    );
+}
+
+// ═══════════════════════ 同时保存文件与输出内存 ═══════════════════════
+
+/// 检查 `SavedAndBytes` 中的内容与磁盘上的文件都等于原始内容，然后删除文件
+async fn assert_saved_and_bytes(
+    result: Result<DownloadResult, DownloadError>,
+    save_path: &str,
+    content: &[u8],
+) {
+    match result {
+        Ok(DownloadResult::SavedAndBytes { path, bytes }) => {
+            assert_eq!(path, save_path);
+            let on_disk =
+                tokio::fs::read(&path).await.expect("读取保存的文件失败");
+            let _ = tokio::fs::remove_file(&path).await;
+            assert_eq!(bytes, content, "❌ 返回的内容不正确");
+            assert_eq!(on_disk, bytes, "❌ 返回的内容与文件不一致");
+        }
+        other => panic!("❌ 应返回 SavedAndBytes，实际: {:?}", other),
+    }
+}
+
+/// 测试：单线程下载同时保存文件并返回内容，两个设置的顺序不影响结果
+#[tokio::test]
+async fn single_thread_saves_and_returns_bytes() {
+    let content: Vec<u8> =
+        (0..50_000u32).map(|i| (i % 253) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "both.bin", Some(50_000));
+
+    let save_path = temp_path("both_single_a.bin");
+    let downloader =
+        file.build_downloader().save_to(&save_path).output_bytes();
+    let result = downloader.send().await;
+    assert_saved_and_bytes(result, &save_path, &content).await;
+
+    let save_path = temp_path("both_single_b.bin");
+    let downloader =
+        file.build_downloader().output_bytes().save_to(&save_path);
+    let result = downloader.send().await;
+    assert_saved_and_bytes(result, &save_path, &content).await;
+}
+
+/// 测试：分片下载同时保存文件并返回按顺序拼接的完整内容
+#[tokio::test]
+async fn chunked_saves_and_returns_bytes() {
+    let content: Vec<u8> =
+        (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    let server = MockServer::serve_file(content.clone());
+    let file = mock_remote_file(&server, "both.bin", Some(200_000));
+    let save_path = temp_path("both_chunked.bin");
+
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .save_to(&save_path)
+        .max_chunks(4)
+        .chunk_size(64 * 1024)
+        .send()
+        .await;
+
+    assert_saved_and_bytes(result, &save_path, &content).await;
+    // Range 探测之外还有多个分片请求
+    assert!(server.requests().len() > 2);
+}
+
+// ═══════════════════════════ 流式下载 ═══════════════════════════
+
+fn into_stream(
+    result: Result<DownloadResult, DownloadError>,
+) -> DownloadStream {
+    match result {
+        Ok(DownloadResult::Stream(stream)) => stream,
+        other => panic!("❌ 应返回 Stream，实际: {:?}", other),
+    }
+}
+
+/// 测试：流按拉取的节奏产出内容，进度随拉取更新，读完后状态为完成
+#[tokio::test]
+async fn stream_reports_progress_as_consumer_pulls() {
+    let content: Vec<u8> =
+        (0..5_000u32).map(|i| (i % 239) as u8).collect();
+    let server = paced_server(&content);
+    let file = mock_remote_file(&server, "stream.bin", Some(5_000));
+    let downloader = file
+        .build_downloader()
+        .output_stream()
+        .verify_sha256(&sha256_hex(&content));
+    let controller = downloader.get_controller();
+
+    let mut stream = into_stream(downloader.send().await);
+    let mut received = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.expect("读取流失败");
+        received.extend_from_slice(&chunk);
+        assert_eq!(
+            controller.progress().bytes_done,
+            received.len() as u64,
+            "❌ 进度应等于已拉取的字节数"
+        );
+    }
+
+    assert_eq!(received, content);
+    assert_eq!(controller.progress().total, Some(5_000));
+    assert!(matches!(
+        controller.get_download_status(),
+        Some(DownloadStatus::Finished)
+    ));
+}
+
+/// 测试：摘要不一致时流在最后一块之后以 ChecksumMismatch 结束
+#[tokio::test]
+async fn stream_ends_with_checksum_error() {
+    let server = MockServer::serve_file(vec![7u8; 3_000]);
+    let file = mock_remote_file(&server, "stream.bin", Some(3_000));
+    let result = file
+        .build_downloader()
+        .output_stream()
+        .verify_sha256(&"0".repeat(64))
+        .send()
+        .await;
+
+    let items: Vec<_> = into_stream(result).collect().await;
+    let (last, data) = items.split_last().expect("流不应为空");
+    let received: usize =
+        data.iter().map(|chunk| chunk.as_ref().unwrap().len()).sum();
+    assert_eq!(received, 3_000);
+    assert!(
+        matches!(last, Err(DownloadError::ChecksumMismatch { .. })),
+        "❌ 应以 ChecksumMismatch 结束: {:?}",
+        last
+    );
+}
+
+/// 测试：暂停期间不产出数据，取消在下一次拉取时生效，丢弃流视为取消
+#[tokio::test]
+async fn stream_pause_cancel_and_drop() {
+    let content = vec![1u8; 10_000];
+    let server = paced_server(&content);
+    let file = mock_remote_file(&server, "stream.bin", Some(10_000));
+
+    let downloader = file.build_downloader().output_stream();
+    let controller = downloader.get_controller();
+    let mut stream = into_stream(downloader.send().await);
+    stream.next().await.expect("应有数据").expect("读取失败");
+
+    controller.pause().unwrap();
+    let next =
+        tokio::time::timeout(Duration::from_millis(300), stream.next());
+    assert!(next.await.is_err(), "❌ 暂停期间不应产出数据");
+    assert!(matches!(
+        controller.get_download_status(),
+        Some(DownloadStatus::Paused)
+    ));
+
+    controller.resume().unwrap();
+    stream.next().await.expect("应有数据").expect("读取失败");
+    controller.cancel().unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(DownloadError::Cancelled))
+    ));
+    assert!(stream.next().await.is_none(), "❌ 取消后流应结束");
+
+    // 读完之前丢弃
+    let downloader = file.build_downloader().output_stream();
+    let controller = downloader.get_controller();
+    let mut stream = into_stream(downloader.send().await);
+    stream.next().await.expect("应有数据").expect("读取失败");
+    drop(stream);
+    assert!(matches!(
+        controller.get_download_status(),
+        Some(DownloadStatus::Canceled)
+    ));
+}
@@ -0,0 +1,187 @@
+//! `ensure_collection_path`（mkdir -p）测试：基于带状态的本地 MockServer。
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use crate::auth::WebdavAuth;
+use crate::create_remote_dir;
+use crate::tests::mock_server::{MockRequest, MockResponse, MockServer};
+use crate::webdav::errors::WebDavError;
+use crate::webdav::functions::ensure_collection_path;
+
+/// 模拟的远程文件系统：已有目录（以 `/` 结尾）与已有文件
+struct FakeTree {
+    dirs: HashSet<String>,
+    files: HashSet<String>,
+}
+
+fn propfind_body(href: &str, is_dir: bool) -> String {
+    let resource_type = if is_dir { "<d:collection/>" } else { "" };
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>{href}</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype>{resource_type}</d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#
+    )
+}
+
+fn handle(tree: &Mutex<FakeTree>, req: &MockRequest) -> MockResponse {
+    let Ok(mut tree) = tree.lock() else {
+        return MockResponse::new(500);
+    };
+    let file_path = req.path.trim_end_matches('/').to_string();
+
+    match req.method.as_str() {
+        "MKCOL" => {
+            if tree.dirs.contains(&req.path)
+                || tree.files.contains(&file_path)
+            {
+                return MockResponse::new(405);
+            }
+            let parent = match file_path.rsplit_once('/') {
+                Some((parent, _)) => format!("{}/", parent),
+                None => "/".to_string(),
+            };
+            if !tree.dirs.contains(&parent) {
+                return MockResponse::new(409);
+            }
+            tree.dirs.insert(req.path.clone());
+            MockResponse::new(201)
+        }
+        "PROPFIND" => {
+            if tree.dirs.contains(&req.path) {
+                MockResponse::new(207).body(propfind_body(&req.path, true))
+            } else if tree.files.contains(&file_path) {
+                MockResponse::new(207)
+                    .body(propfind_body(&file_path, false))
+            } else {
+                MockResponse::new(404)
+            }
+        }
+        _ => MockResponse::new(405),
+    }
+}
+
+fn start_tree(dirs: &[&str], files: &[&str]) -> MockServer {
+    let tree = Arc::new(Mutex::new(FakeTree {
+        dirs: dirs.iter().map(|d| d.to_string()).collect(),
+        files: files.iter().map(|f| f.to_string()).collect(),
+    }));
+    MockServer::start(move |req| handle(&tree, req))
+}
+
+fn auth_for(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", &server.url("dav/")).unwrap()
+}
+
+fn mkcol_paths(server: &MockServer) -> Vec<String> {
+    server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "MKCOL")
+        .map(|r| r.path)
+        .collect()
+}
+
+/// 测试：从零开始逐级创建所有缺失目录
+#[tokio::test]
+async fn creates_every_missing_level() {
+    let server = start_tree(&["/", "/dav/"], &[]);
+    let auth = auth_for(&server);
+
+    ensure_collection_path(&auth, "a/b/c").await.unwrap();
+
+    assert_eq!(
+        mkcol_paths(&server),
+        vec!["/dav/a/", "/dav/a/b/", "/dav/a/b/c/"]
+    );
+}
+
+/// 测试：已存在的层级（405）被跳过，重复调用依然成功
+#[tokio::test]
+async fn existing_levels_are_idempotent() {
+    let server = start_tree(&["/", "/dav/", "/dav/a/"], &[]);
+    let auth = auth_for(&server);
+
+    ensure_collection_path(&auth, "/a/b/").await.unwrap();
+    ensure_collection_path(&auth, "a/b").await.unwrap();
+
+    // 第一次：a 已存在（405）、b 新建；第二次：两级都已存在
+    assert_eq!(mkcol_paths(&server).len(), 4);
+}
+
+/// 测试：某一级是文件时返回 NotADirectory，且不再继续创建更深的层级
+#[tokio::test]
+async fn file_component_is_not_a_directory() {
+    let server = start_tree(&["/", "/dav/"], &["/dav/a"]);
+    let auth = auth_for(&server);
+
+    let result = ensure_collection_path(&auth, "a/b").await;
+
+    match result {
+        Err(WebDavError::NotADirectory(url)) => {
+            assert!(url.ends_with("/dav/a/"))
+        }
+        other => panic!("❌ 应返回 NotADirectory，实际: {:?}", other),
+    }
+    assert_eq!(mkcol_paths(&server), vec!["/dav/a/"]);
+}
+
+/// 测试：路径中包含 '..' 时直接拒绝，不发起任何请求
+#[tokio::test]
+async fn parent_component_is_rejected() {
+    let server = start_tree(&["/", "/dav/"], &[]);
+    let auth = auth_for(&server);
+
+    let result = ensure_collection_path(&auth, "a/../b").await;
+
+    assert!(matches!(result, Err(WebDavError::InvalidRequest(_))));
+    assert!(server.requests().is_empty());
+}
+
+/// 测试：MKCOL 返回 507 时映射为远程空间不足
+#[tokio::test]
+async fn insufficient_storage_is_typed() {
+    let server = MockServer::start(|_| MockResponse::new(507));
+    let auth = auth_for(&server);
+
+    let result = ensure_collection_path(&auth, "a").await;
+
+    match result {
+        Err(WebDavError::InsufficientRemoteStorage { url, .. }) => {
+            assert!(url.ends_with("/a/"))
+        }
+        other => panic!(
+            "❌ 应返回 InsufficientRemoteStorage，实际: {:?}",
+            other
+        ),
+    }
+}
+
+/// 测试：create_remote_dir 只创建一级，已存在与父目录缺失分别给出明确错误
+#[tokio::test]
+async fn create_remote_dir_is_single_level() {
+    let server = start_tree(&["/", "/dav/"], &[]);
+    let auth = auth_for(&server);
+
+    assert_eq!(create_remote_dir(&auth, "new").await, Ok(()));
+
+    let exists = create_remote_dir(&auth, "new").await;
+    let message = exists.expect_err("已存在时应返回错误");
+    assert!(message.starts_with("资源已存在"), "❌ 实际: {}", message);
+
+    let nested = create_remote_dir(&auth, "a/b").await;
+    let message = nested.expect_err("父目录缺失时应返回错误");
+    assert!(message.starts_with("父目录不存在"), "❌ 实际: {}", message);
+
+    assert_eq!(
+        mkcol_paths(&server),
+        vec!["/dav/new/", "/dav/new/", "/dav/a/b/"]
+    );
+}
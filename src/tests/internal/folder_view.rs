@@ -0,0 +1,114 @@
+//! FolderView 测试：目录自身按 href 识别，不受服务器返回顺序影响。
+
+use url::Url;
+
+use crate::auth::WebdavAuth;
+use crate::get_folder_view;
+use crate::remote_file::FolderView;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::functions::parse_multistatus;
+
+fn response(href: &str, is_dir: bool) -> String {
+    let resource_type = if is_dir {
+        "<d:resourcetype><d:collection/></d:resourcetype>"
+    } else {
+        "<d:resourcetype/>"
+    };
+    format!(
+        "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}</d:prop>\
+         <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        href, resource_type
+    )
+}
+
+fn multistatus(responses: &[String]) -> String {
+    format!(
+        r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">{}</d:multistatus>"#,
+        responses.concat()
+    )
+}
+
+fn view(xml: &str, requested_url: &str) -> Result<FolderView, String> {
+    let base_url = Url::parse("http://h/dav/").unwrap();
+    FolderView::from_multi_status(
+        parse_multistatus(xml).unwrap(),
+        &base_url,
+        requested_url,
+    )
+}
+
+fn child_names(view: &FolderView) -> Vec<&str> {
+    view.children.iter().map(|c| c.name.as_str()).collect()
+}
+
+/// 测试：目录自身在第一项（最常见的顺序）
+#[test]
+fn container_first() {
+    let xml = multistatus(&[
+        response("/dav/docs/", true),
+        response("/dav/docs/a.txt", false),
+        response("/dav/docs/sub/", true),
+    ]);
+
+    let view = view(&xml, "http://h/dav/docs/").unwrap();
+
+    assert_eq!(view.folder.name, "docs");
+    assert!(view.folder.is_dir);
+    assert_eq!(child_names(&view), vec!["a.txt", "sub"]);
+}
+
+/// 测试：目录自身排在最后、且 href 写法与请求地址不同（编码、无尾斜杠）
+#[test]
+fn container_last_with_different_href_form() {
+    let xml = multistatus(&[
+        response("/dav/my%20docs/a.txt", false),
+        response("/dav/my%20docs/b.txt", false),
+        response("/dav/my docs", true),
+    ]);
+
+    let view = view(&xml, "http://h/dav/my%20docs/").unwrap();
+
+    assert_eq!(view.folder.name, "my docs");
+    assert_eq!(child_names(&view), vec!["a.txt", "b.txt"]);
+}
+
+/// 测试：空目录只返回自身，children 为空
+#[test]
+fn empty_folder_has_no_children() {
+    let xml = multistatus(&[response("http://h/dav/empty/", true)]);
+
+    let view = view(&xml, "http://h/dav/empty/").unwrap();
+
+    assert_eq!(view.folder.name, "empty");
+    assert!(view.children.is_empty());
+}
+
+/// 测试：响应中没有目录自身时返回错误，而不是把某个子项当作目录
+#[test]
+fn missing_container_is_error() {
+    let xml = multistatus(&[
+        response("/dav/docs/a.txt", false),
+        response("/dav/docs/b.txt", false),
+    ]);
+
+    assert!(view(&xml, "http://h/dav/docs/").is_err());
+}
+
+/// 测试：入口函数补尾斜杠后请求，返回目录与子项
+#[tokio::test]
+async fn get_folder_view_from_server() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/docs/" => MockResponse::new(207).body(multistatus(&[
+            response("/docs/n.txt", false),
+            response("/docs/", true),
+        ])),
+        _ => MockResponse::new(404),
+    });
+    let auth =
+        WebdavAuth::new("user", "password", server.base_url()).unwrap();
+
+    let view = get_folder_view(&auth, "docs").await.unwrap();
+
+    assert_eq!(view.folder.name, "docs");
+    assert_eq!(child_names(&view), vec!["n.txt"]);
+}
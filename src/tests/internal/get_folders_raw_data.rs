@@ -0,0 +1,297 @@
+//! PROPFIND 原始数据获取与状态分类测试（基于本地 MockServer）。
+
+use std::time::Duration;
+
+use crate::auth::{ServerQuirks, WebdavAuth};
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::enums::Depth;
+use crate::webdav::errors::WebDavError;
+use crate::webdav::functions::get_folders_raw_data;
+
+const OK_MULTISTATUS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/a.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getcontentlength>3</d:getcontentlength></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:owner/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+const FAILED_MULTISTATUS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/locked/</d:href>
+    <d:status>HTTP/1.1 423 Locked</d:status>
+  </d:response>
+  <d:response>
+    <d:href>/dav/secret.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getetag/></d:prop>
+      <d:status>HTTP/1.1 403 Forbidden</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+fn auth_for(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url()).unwrap()
+}
+
+/// 测试：207 且包含 2xx propstat 视为成功
+#[tokio::test]
+async fn multistatus_with_success_is_ok() {
+    let server =
+        MockServer::start(|_| MockResponse::new(207).body(OK_MULTISTATUS));
+    let auth = auth_for(&server);
+
+    let multi_status =
+        get_folders_raw_data(&auth, &server.url("dav/"), &Depth::One)
+            .await
+            .unwrap();
+
+    assert_eq!(multi_status.responses.len(), 2);
+}
+
+/// 测试：不规范地返回 200 也能解析
+#[tokio::test]
+async fn non_compliant_200_is_parsed() {
+    let server =
+        MockServer::start(|_| MockResponse::new(200).body(OK_MULTISTATUS));
+    let auth = auth_for(&server);
+
+    let result =
+        get_folders_raw_data(&auth, &server.url("dav/"), &Depth::One)
+            .await;
+
+    assert!(result.is_ok(), "200 + 合法 multistatus 应视为成功");
+}
+
+/// 测试：207 中所有资源都失败时返回 MultiStatusFailed
+#[tokio::test]
+async fn multistatus_with_only_failures_is_error() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(207).body(FAILED_MULTISTATUS)
+    });
+    let auth = auth_for(&server);
+
+    let result =
+        get_folders_raw_data(&auth, &server.url("dav/"), &Depth::One)
+            .await;
+
+    match result {
+        Err(WebDavError::MultiStatusFailed { failures }) => {
+            assert_eq!(failures.len(), 2);
+            assert_eq!(failures[0].0, "/dav/locked/");
+            assert!(failures[0].1.contains("423"));
+            assert!(failures[1].1.contains("403"));
+        }
+        other => panic!("❌ 应返回 MultiStatusFailed，实际: {:?}", other),
+    }
+}
+
+/// 测试：非 2xx 状态返回 Status 错误并带上响应体
+#[tokio::test]
+async fn error_status_is_reported() {
+    let server =
+        MockServer::start(|_| MockResponse::new(401).body("unauthorized"));
+    let auth = auth_for(&server);
+
+    let result =
+        get_folders_raw_data(&auth, &server.url("dav/"), &Depth::One)
+            .await;
+
+    match result {
+        Err(WebDavError::Status { status, body }) => {
+            assert_eq!(status, 401);
+            assert_eq!(body, "unauthorized");
+        }
+        other => panic!("❌ 应返回 Status 错误，实际: {:?}", other),
+    }
+}
+
+/// 测试：prefer_minimal 时 PROPFIND 带上 Prefer 与 Brief 请求头，默认不带
+#[tokio::test]
+async fn prefer_minimal_sends_headers() {
+    let server =
+        MockServer::start(|_| MockResponse::new(207).body(OK_MULTISTATUS));
+    let url = server.url("dav/");
+
+    get_folders_raw_data(&auth_for(&server), &url, &Depth::One)
+        .await
+        .unwrap();
+    get_folders_raw_data(
+        &auth_for(&server).prefer_minimal(),
+        &url,
+        &Depth::One,
+    )
+    .await
+    .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("Prefer"), None);
+    assert_eq!(requests[0].header("Brief"), None);
+    assert_eq!(requests[1].header("Prefer"), Some("return=minimal"));
+    assert_eq!(requests[1].header("Brief"), Some("t"));
+}
+
+/// 测试：响应体迟迟发不完时按 read_timeout 返回 ResponseReadTimeout
+#[tokio::test]
+async fn slow_body_times_out() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(207)
+            .body(&OK_MULTISTATUS.as_bytes()[..20])
+            .delayed_part(
+                Duration::from_secs(3),
+                &OK_MULTISTATUS.as_bytes()[20..],
+            )
+    });
+    let auth = auth_for(&server).read_timeout(Duration::from_millis(200));
+
+    let err =
+        get_folders_raw_data(&auth, &server.url("dav/"), &Depth::One)
+            .await
+            .unwrap_err();
+
+    assert!(
+        matches!(err, WebDavError::ResponseReadTimeout(t) if t == Duration::from_millis(200))
+    );
+}
+
+/// 测试：207 但响应体为空时返回 EmptyBody，而不是 XML 解析错误
+#[tokio::test]
+async fn empty_body_is_reported() {
+    let server = MockServer::start(|_| MockResponse::new(207));
+
+    let err = get_folders_raw_data(
+        &auth_for(&server),
+        &server.url("dav/"),
+        &Depth::One,
+    )
+    .await
+    .unwrap_err();
+
+    assert!(matches!(err, WebDavError::EmptyBody(207)));
+}
+
+/// 测试：add_default_header 添加的请求头随每个请求发送，且不影响之前 clone 出的实例
+#[tokio::test]
+async fn default_header_is_sent_with_requests() {
+    let server =
+        MockServer::start(|_| MockResponse::new(207).body(OK_MULTISTATUS));
+    let url = server.url("dav/");
+    let before = auth_for(&server);
+
+    let auth = before
+        .clone()
+        .add_default_header("OCS-APIRequest", "true")
+        .unwrap();
+    assert!(
+        auth.clone()
+            .add_default_header("Authorization", "Basic x")
+            .is_err()
+    );
+    assert!(auth.clone().add_default_header("bad header", "v").is_err());
+
+    get_folders_raw_data(&auth, &url, &Depth::One).await.unwrap();
+    get_folders_raw_data(&before, &url, &Depth::One).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("OCS-APIRequest"), Some("true"));
+    assert!(requests[0].header("Authorization").is_some());
+    assert_eq!(requests[1].header("OCS-APIRequest"), None);
+}
+
+/// 测试：200 返回 HTML 登录页时给出明确提示，而不是 XML 解析错误
+#[tokio::test]
+async fn html_page_is_reported_as_not_webdav() {
+    let server = MockServer::start(|req| {
+        let response = MockResponse::new(200).body(
+            "\n  <!DOCTYPE html><html><body>Please sign in</body></html>",
+        );
+        if req.path == "/typed/" {
+            response.header("Content-Type", "text/html; charset=utf-8")
+        } else {
+            response.header("Content-Type", "application/xml")
+        }
+    });
+    let auth = auth_for(&server);
+
+    // Content-Type 为 text/html
+    let err =
+        get_folders_raw_data(&auth, &server.url("typed/"), &Depth::One)
+            .await
+            .unwrap_err();
+    assert!(matches!(err, WebDavError::NotWebDav { .. }), "{:?}", err);
+
+    // Content-Type 声称是 XML，但正文是 HTML
+    let err =
+        get_folders_raw_data(&auth, &server.url("sniffed/"), &Depth::One)
+            .await
+            .unwrap_err();
+    match err {
+        WebDavError::NotWebDav { hint } => {
+            assert!(hint.contains("/sniffed/"))
+        }
+        other => panic!("❌ 应返回 NotWebDav，实际: {:?}", other),
+    }
+}
+
+/// 测试：按 Server 头识别 IIS 后，Depth: infinity 直接返回 Unsupported
+#[tokio::test]
+async fn server_header_selects_quirks() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(207)
+            .header("Server", "Microsoft-IIS/10.0")
+            .body(OK_MULTISTATUS)
+    });
+    let auth = auth_for(&server);
+    assert_eq!(auth.server_quirks(), ServerQuirks::default());
+
+    get_folders_raw_data(&auth, &server.url("dav/"), &Depth::One)
+        .await
+        .unwrap();
+    let quirks = auth.clone().server_quirks();
+    assert!(quirks.no_depth_infinity && quirks.force_trailing_slash);
+
+    let err =
+        get_folders_raw_data(&auth, &server.url("dav/"), &Depth::Infinity)
+            .await
+            .unwrap_err();
+    assert!(matches!(err, WebDavError::Unsupported(_)), "{:?}", err);
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// 测试：手动指定的 quirks 优先于自动识别，并用于 Accept 头
+#[tokio::test]
+async fn manual_quirks_override_detection() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(207)
+            .header("Server", "Apache/2.4.57")
+            .body(OK_MULTISTATUS)
+    });
+    let quirks = ServerQuirks {
+        accept: Some("text/xml".to_string()),
+        ..Default::default()
+    };
+    let auth = auth_for(&server).quirks(quirks.clone());
+
+    get_folders_raw_data(&auth, &server.url("dav/"), &Depth::One)
+        .await
+        .unwrap();
+
+    assert_eq!(auth.server_quirks(), quirks);
+    let requests = server.requests();
+    assert_eq!(requests[0].header("Accept"), Some("text/xml"));
+}
@@ -0,0 +1,69 @@
+//! HTTP 协议版本测试：默认只用 HTTP/1.1，prefer_http2 在服务器不支持
+//! HTTP/2 时的行为（mock 服务器只支持 HTTP/1.1）。
+
+use std::time::Duration;
+
+use crate::auth::{HttpProtocol, WebdavAuth};
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::errors::WebDavError;
+use crate::webdav::functions::head;
+
+fn server() -> MockServer {
+    MockServer::start(|_| MockResponse::new(200).body("ok"))
+}
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败")
+}
+
+/// 测试：默认只用 HTTP/1.1；prefer_http2 在明文 HTTP 上仍回退到 HTTP/1.1
+#[tokio::test]
+async fn prefer_http2_falls_back_to_http1() {
+    let server = server();
+    let plain = auth(&server);
+    assert_eq!(
+        plain.request_options().http_protocol,
+        HttpProtocol::Http1Only
+    );
+
+    let auth = plain
+        .clone()
+        .with_timeout(Duration::from_secs(5), Duration::from_secs(5))
+        .and_then(|auth| auth.prefer_http2(false))
+        .expect("设置 HTTP/2 失败");
+    assert_eq!(auth, plain, "❌ 切换协议不应改变相等比较");
+    let options = auth.request_options();
+    assert_eq!(options.http_protocol, HttpProtocol::PreferHttp2);
+    assert_eq!(options.request_timeout, Some(Duration::from_secs(5)));
+
+    let info = head(&auth, &server.url("a.txt"))
+        .await
+        .expect("不支持 HTTP/2 的服务器应回退到 HTTP/1.1");
+    assert_eq!(info.size, Some(2));
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// 测试：prior knowledge 不会回退，服务器不支持 HTTP/2 时请求失败
+#[tokio::test]
+async fn http2_prior_knowledge_fails_on_http1_server() {
+    let server = server();
+    let auth = auth(&server)
+        .prefer_http2(true)
+        .and_then(|auth| {
+            auth.with_timeout(
+                Duration::from_secs(5),
+                Duration::from_secs(5),
+            )
+        })
+        .expect("设置 HTTP/2 失败");
+    assert_eq!(
+        auth.request_options().http_protocol,
+        HttpProtocol::Http2PriorKnowledge
+    );
+
+    match head(&auth, &server.url("a.txt")).await {
+        Err(WebDavError::Request(_)) => {}
+        other => panic!("❌ 应以请求错误失败: {:?}", other),
+    }
+}
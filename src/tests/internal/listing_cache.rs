@@ -0,0 +1,95 @@
+//! 目录列表缓存测试（基于本地 MockServer）。
+
+use std::time::Duration;
+
+use crate::auth::WebdavAuth;
+use crate::get_remote_files_cached;
+use crate::remote_file::ListingCache;
+use crate::tests::mock_server::{MockResponse, MockServer};
+
+const LISTING: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <d:getetag>"v1"</d:getetag>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/a.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getcontentlength>3</d:getcontentlength></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+/// 目录 ETag 为 "v1"：带匹配的 If-None-Match 时返回 304
+fn listing_server() -> MockServer {
+    MockServer::start(|req| {
+        if req.header("If-None-Match") == Some("\"v1\"") {
+            MockResponse::new(304)
+        } else {
+            MockResponse::new(207).body(LISTING)
+        }
+    })
+}
+
+fn auth_for(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url()).unwrap()
+}
+
+/// 测试：ttl 内再次读取不发请求
+#[tokio::test]
+async fn fresh_listing_is_served_from_cache() {
+    let server = listing_server();
+    let auth = auth_for(&server);
+    let cache = ListingCache::new(Duration::from_secs(60));
+
+    let first =
+        get_remote_files_cached(&auth, &cache, "dav").await.unwrap();
+    let second =
+        get_remote_files_cached(&auth, &cache, "dav/").await.unwrap();
+
+    assert_eq!(first.len(), 1);
+    assert_eq!(second.len(), 1);
+    assert_eq!(server.requests().len(), 1);
+}
+
+/// 测试：过期后带 If-None-Match 重新验证，304 时沿用缓存
+#[tokio::test]
+async fn stale_listing_is_revalidated_with_etag() {
+    let server = listing_server();
+    let auth = auth_for(&server);
+    let cache = ListingCache::new(Duration::ZERO);
+
+    get_remote_files_cached(&auth, &cache, "dav/").await.unwrap();
+    let files =
+        get_remote_files_cached(&auth, &cache, "dav/").await.unwrap();
+
+    assert_eq!(files.len(), 1);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].header("If-None-Match"), None);
+    assert_eq!(requests[1].header("If-None-Match"), Some("\"v1\""));
+}
+
+/// 测试：invalidate 后重新完整读取
+#[tokio::test]
+async fn invalidated_listing_is_fetched_again() {
+    let server = listing_server();
+    let auth = auth_for(&server);
+    let cache = ListingCache::new(Duration::from_secs(60));
+
+    get_remote_files_cached(&auth, &cache, "dav/").await.unwrap();
+    cache.invalidate(&server.url("dav"));
+    get_remote_files_cached(&auth, &cache, "dav/").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|r| r.header("If-None-Match").is_none()));
+}
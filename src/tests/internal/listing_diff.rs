@@ -0,0 +1,149 @@
+//! diff_listings 与 RemoteFileData 的比较、序列化测试
+
+use chrono::{DateTime, FixedOffset};
+use url::Url;
+
+use crate::remote_file::{RemoteFileData, diff_listings};
+use crate::webdav::structs::AllowedMethods;
+
+fn file(path: &str, size: u64, etag: Option<&str>) -> RemoteFileData {
+    RemoteFileData {
+        base_url: Url::parse("http://example.com/dav/")
+            .expect("解析 URL 失败"),
+        relative_root_path: path.to_string(),
+        absolute_path: format!("http://example.com/dav{}", path),
+        name: path.rsplit('/').next().unwrap_or(path).to_string(),
+        is_dir: false,
+        size: Some(size),
+        last_modified: Some(modified("2024-01-01T00:00:00+00:00")),
+        creation_date: None,
+        mime: None,
+        owner: None,
+        etag: etag.map(str::to_string),
+        raw_etag: etag.map(|e| format!("\"{}\"", e)),
+        privileges: Vec::new(),
+        allowed_methods: None,
+    }
+}
+
+fn modified(time: &str) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(time).expect("解析时间失败")
+}
+
+/// 测试：相等比较忽略 base_url 与路径写法，其余字段不同时不相等
+#[test]
+fn equality_ignores_base_url_and_path_form() {
+    let a = file("/docs/a.txt", 3, Some("v1"));
+    let mut b = a.clone();
+    b.base_url =
+        Url::parse("http://proxy.example.com/").expect("解析失败");
+    b.absolute_path = "http://proxy.example.com/docs/a.txt".to_string();
+    b.relative_root_path = "/docs//a%2Etxt".to_string();
+    b.raw_etag = Some("W/\"v1\"".to_string());
+    assert_eq!(a, b);
+
+    b.size = Some(4);
+    assert_ne!(a, b);
+}
+
+/// 测试：只有 ETag 变化时视为修改；ETag 相同时忽略大小与时间
+#[test]
+fn etag_only_change_is_modified() {
+    let old = file("/a.txt", 3, Some("v1"));
+    let new = file("/a.txt", 3, Some("v2"));
+    assert!(new.changed_since(&old));
+
+    let mut touched = file("/a.txt", 3, Some("v1"));
+    touched.last_modified = Some(modified("2024-06-01T00:00:00+00:00"));
+    assert!(!touched.changed_since(&old), "❌ ETag 相同时不应视为修改");
+
+    let diff = diff_listings(&[old], std::slice::from_ref(&new));
+    assert_eq!(diff.modified, vec![new]);
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+}
+
+/// 测试：没有 ETag 时按大小判断，只有大小变化同样视为修改
+#[test]
+fn size_only_change_is_modified_without_etag() {
+    let old = vec![file("/a.txt", 3, None), file("/b.txt", 5, None)];
+    let new = vec![file("/a.txt", 4, None), file("/b.txt", 5, None)];
+
+    let diff = diff_listings(&old, &new);
+    assert_eq!(diff.modified.len(), 1);
+    assert_eq!(diff.modified[0].relative_root_path, "/a.txt");
+    assert_eq!(diff.modified[0].size, Some(4));
+    assert!(!new[1].changed_since(&old[1]));
+}
+
+/// 测试：新增与删除的资源按路径识别，文件变成目录视为修改
+#[test]
+fn added_removed_and_type_change() {
+    let mut dir = file("/c/", 0, None);
+    dir.is_dir = true;
+    let old = vec![
+        file("/gone.txt", 1, Some("g")),
+        file("/same.txt", 1, Some("s")),
+        file("/c", 0, None),
+    ];
+    let new = vec![
+        file("/same.txt", 1, Some("s")),
+        file("/new.txt", 1, Some("n")),
+        dir.clone(),
+    ];
+
+    let diff = diff_listings(&old, &new);
+    assert_eq!(diff.added, vec![file("/new.txt", 1, Some("n"))]);
+    assert_eq!(diff.removed, vec![file("/gone.txt", 1, Some("g"))]);
+    assert_eq!(diff.modified, vec![dir]);
+
+    assert!(diff_listings(&new, &new).is_empty());
+}
+
+/// 测试：列表序列化为 JSON 后反序列化得到相等的值，格式与文档一致
+#[test]
+fn listing_json_round_trip() {
+    let mut dir = file("/docs/", 0, None);
+    dir.is_dir = true;
+    dir.size = None;
+    let mut doc = file("/docs/报告 1.txt", 12, Some("abc"));
+    doc.last_modified = Some(modified("2024-03-05T08:09:10+08:00"));
+    doc.privileges = vec!["read".to_string(), "write".to_string()];
+    doc.allowed_methods = Some(AllowedMethods::from_headers(["GET, PUT"]));
+    let listing = vec![dir, doc];
+
+    let json = serde_json::to_string(&listing).expect("序列化失败");
+    let value: serde_json::Value =
+        serde_json::from_str(&json).expect("解析 JSON 失败");
+    assert_eq!(value[1]["base_url"], "http://example.com/dav/");
+    assert_eq!(value[1]["last_modified"], "2024-03-05T08:09:10+08:00");
+    assert_eq!(
+        value[1]["allowed_methods"],
+        serde_json::json!(["GET", "PUT"])
+    );
+    assert!(value[0]["etag"].is_null());
+
+    let restored: Vec<RemoteFileData> =
+        serde_json::from_str(&json).expect("反序列化失败");
+    assert_eq!(restored, listing);
+    // PartialEq 不比较 base_url 与 raw_etag，这里单独确认
+    assert_eq!(restored[1].base_url, listing[1].base_url);
+    assert_eq!(restored[1].raw_etag, listing[1].raw_etag);
+}
+
+/// 测试：缺少可选字段与 privileges 的旧缓存仍然可以读取
+#[test]
+fn missing_optional_fields_deserialize() {
+    let json = r#"{
+        "base_url": "http://example.com/dav/",
+        "relative_root_path": "/a.txt",
+        "absolute_path": "http://example.com/dav/a.txt",
+        "name": "a.txt",
+        "is_dir": false
+    }"#;
+    let data: RemoteFileData =
+        serde_json::from_str(json).expect("反序列化失败");
+    assert_eq!(data.size, None);
+    assert_eq!(data.etag, None);
+    assert!(data.privileges.is_empty());
+    assert!(data.allowed_methods.is_none());
+}
@@ -0,0 +1,210 @@
+//! LOCK / 续期 / UNLOCK 与 LockGuard 测试（使用本地 mock 服务器），
+//! 以及携带锁令牌的上传与删除。
+
+use std::time::Duration;
+
+use crate::tests::mock_server::{MockRequest, MockResponse, MockServer};
+use crate::tests::{TestVendor, load_account_optional};
+use crate::webdav::functions::{lock, refresh_lock, unlock};
+use crate::webdav::structs::{LockGuard, parse_timeout};
+use crate::{
+    delete_remote, delete_remote_locked, lock_remote, unlock_remote,
+    upload_remote_file, upload_remote_file_locked,
+};
+
+use crate::auth::WebdavAuth;
+
+const TOKEN: &str = "opaquelocktoken:1234-abcd";
+
+/// 模拟支持锁的服务器：LOCK 返回令牌与 Timeout，UNLOCK 返回 204
+fn lock_server() -> MockServer {
+    MockServer::start(|req: &MockRequest| match req.method.as_str() {
+        "LOCK" => MockResponse::new(200)
+            .header("Content-Type", "application/xml")
+            .header("Lock-Token", &format!("<{}>", TOKEN))
+            .body(format!(
+                r#"<?xml version="1.0" encoding="utf-8"?>
+<D:prop xmlns:D="DAV:"><D:lockdiscovery><D:activelock>
+<D:locktype><D:write/></D:locktype>
+<D:lockscope><D:exclusive/></D:lockscope>
+<D:depth>0</D:depth>
+<D:timeout>Second-600</D:timeout>
+<D:locktoken><D:href>{}</D:href></D:locktoken>
+</D:activelock></D:lockdiscovery></D:prop>"#,
+                TOKEN
+            )),
+        "UNLOCK" => MockResponse::new(204),
+        // 写入需要带上锁令牌，否则视为被锁定
+        "PUT" | "DELETE" => {
+            let expected = format!("(<{}>)", TOKEN);
+            if req.header("If") == Some(expected.as_str()) {
+                MockResponse::new(204)
+            } else {
+                MockResponse::new(423).body("locked")
+            }
+        }
+        _ => MockResponse::new(405),
+    })
+}
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败")
+}
+
+/// 测试：Timeout 值的解析
+#[test]
+fn timeout_values_are_parsed() {
+    assert_eq!(
+        parse_timeout("Second-3600"),
+        Some(Duration::from_secs(3600))
+    );
+    assert_eq!(parse_timeout("Infinite"), None);
+    assert_eq!(
+        parse_timeout("Infinite, Second-60"),
+        Some(Duration::from_secs(60))
+    );
+}
+
+/// 测试：加锁、续期、解锁发送的请求头
+#[tokio::test]
+async fn lock_refresh_and_unlock_send_expected_headers() {
+    let server = lock_server();
+    let auth = auth(&server);
+    let url = server.url("doc.txt");
+
+    let info = lock(&auth, &url, 600).await.expect("加锁失败");
+    assert_eq!(info.token, TOKEN);
+    assert_eq!(info.timeout, Some(Duration::from_secs(600)));
+
+    let refreshed = refresh_lock(&auth, &url, &info.token, 900)
+        .await
+        .expect("续期失败");
+    assert_eq!(refreshed.token, TOKEN);
+
+    unlock(&auth, &url, &info.token).await.expect("解锁失败");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].header("Timeout"), Some("Second-600"));
+    assert_eq!(requests[0].header("Depth"), Some("0"));
+    assert!(requests[0].header("If").is_none());
+
+    assert_eq!(requests[1].method, "LOCK");
+    assert_eq!(
+        requests[1].header("If"),
+        Some(format!("(<{}>)", TOKEN).as_str())
+    );
+    assert_eq!(requests[1].header("Timeout"), Some("Second-900"));
+    assert!(requests[1].body.is_empty(), "❌ 续期不应带请求体");
+
+    assert_eq!(requests[2].method, "UNLOCK");
+    assert_eq!(
+        requests[2].header("Lock-Token"),
+        Some(format!("<{}>", TOKEN).as_str())
+    );
+}
+
+/// 测试：LockGuard 按间隔续期，drop 后自动解锁
+#[tokio::test]
+async fn lock_guard_refreshes_and_unlocks_on_drop() {
+    let server = lock_server();
+    let auth = auth(&server);
+    let url = server.url("long.txt");
+
+    let guard =
+        LockGuard::acquire(&auth, &url, 60, Duration::from_millis(50))
+            .await
+            .expect("加锁失败");
+    assert_eq!(guard.token(), TOKEN);
+
+    tokio::time::sleep(Duration::from_millis(180)).await;
+    assert!(!guard.is_lost());
+    drop(guard);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let requests = server.requests();
+    let refreshes =
+        requests.iter().filter(|r| r.header("If").is_some()).count();
+    assert!(refreshes >= 2, "❌ 续期次数过少: {}", refreshes);
+    assert_eq!(requests.last().map(|r| r.method.as_str()), Some("UNLOCK"));
+}
+
+/// 测试：release 停止续期并返回解锁结果
+#[tokio::test]
+async fn lock_guard_release_unlocks() {
+    let server = lock_server();
+    let auth = auth(&server);
+    let url = server.url("short.txt");
+
+    let guard =
+        LockGuard::acquire(&auth, &url, 60, Duration::from_secs(60))
+            .await
+            .expect("加锁失败");
+    guard.release().await.expect("解锁失败");
+
+    let methods: Vec<String> =
+        server.requests().into_iter().map(|r| r.method).collect();
+    assert_eq!(methods, vec!["LOCK", "UNLOCK"]);
+}
+
+/// 测试：入口函数加锁后，带令牌的上传与删除成功，不带令牌时返回 423
+#[tokio::test]
+async fn locked_upload_and_delete_send_if_header() {
+    let server = lock_server();
+    let auth = auth(&server);
+
+    let info = lock_remote(&auth, "doc.txt", 600).await.expect("加锁失败");
+    assert_eq!(info.token, TOKEN);
+
+    let message = upload_remote_file(&auth, "doc.txt", "plain")
+        .await
+        .expect_err("不带令牌写入被锁定的文件应失败");
+    assert!(message.contains("423"), "❌ {}", message);
+
+    upload_remote_file_locked(&auth, "doc.txt", "locked", &info.token)
+        .await
+        .expect("带令牌上传失败");
+    // 令牌两侧带 `<` `>` 时同样可用
+    let bracketed = format!("<{}>", info.token);
+    delete_remote_locked(&auth, "doc.txt", &bracketed)
+        .await
+        .expect("带令牌删除失败");
+    unlock_remote(&auth, "doc.txt", &info.token).await.expect("解锁失败");
+
+    let requests = server.requests();
+    let delete = requests
+        .iter()
+        .find(|r| r.method == "DELETE")
+        .expect("应发送 DELETE");
+    assert_eq!(
+        delete.header("If"),
+        Some(format!("(<{}>)", TOKEN).as_str())
+    );
+    assert_eq!(requests.last().map(|r| r.method.as_str()), Some("UNLOCK"));
+}
+
+/// 测试：真实服务器上加锁、带令牌覆盖、解锁后删除（未配置账号时跳过）
+#[tokio::test]
+async fn lock_upload_unlock_on_vendor() {
+    // 生成的 env 文件未填写时变量为空，同样跳过
+    let Some(account) = load_account_optional(TestVendor::Teracloud)
+        .filter(|account| !account.url.is_empty())
+    else {
+        return;
+    };
+    let auth = account.to_webdav_auth().expect("创建认证失败");
+    let path = "webdav_fs_lock_remote_test.txt";
+
+    upload_remote_file(&auth, path, "before lock")
+        .await
+        .expect("上传临时文件失败");
+    let info = lock_remote(&auth, path, 60).await.expect("加锁失败");
+    assert!(!info.token.is_empty());
+
+    upload_remote_file_locked(&auth, path, "while locked", &info.token)
+        .await
+        .expect("带令牌上传失败");
+    unlock_remote(&auth, path, &info.token).await.expect("解锁失败");
+    assert_eq!(delete_remote(&auth, path).await, Ok(()));
+}
@@ -0,0 +1,280 @@
+//! MultiStatus / PropStat 原始结构测试：状态解析等纯数据逻辑，不依赖网络。
+
+use url::Url;
+
+use crate::webdav::structs::{
+    MultiStatus, Prop, PropStat, ResponseWarningReason, parse_status_code,
+};
+use crate::webdav::traits::ToRemoteFileData;
+
+fn propstat(status: &str) -> PropStat {
+    PropStat { prop: Prop::default(), status: status.to_string() }
+}
+
+/// 测试：状态行解析出状态码，兼容不同的 HTTP 版本写法
+#[test]
+fn parses_status_lines() {
+    assert_eq!(parse_status_code("HTTP/1.1 200 OK"), Some(200));
+    assert_eq!(parse_status_code("HTTP/1.0 404 Not Found"), Some(404));
+    assert_eq!(parse_status_code("  HTTP/2 207 Multi-Status "), Some(207));
+    assert_eq!(parse_status_code("HTTP/1.1 424"), Some(424));
+}
+
+/// 测试：非法状态行得到 None，而不是把其它数字误认为状态码
+#[test]
+fn rejects_invalid_status_lines() {
+    assert_eq!(parse_status_code(""), None);
+    assert_eq!(parse_status_code("HTTP/1.1 OK"), None);
+    assert_eq!(parse_status_code("HTTP/1.1 1000 Weird"), None);
+}
+
+/// 测试：PropStat 按状态类别判断是否成功
+#[test]
+fn propstat_success_class() {
+    assert!(propstat("HTTP/1.1 200 OK").is_success());
+    assert!(propstat("HTTP/1.1 204 No Content").is_success());
+    assert!(!propstat("HTTP/1.1 404 Not Found").is_success());
+    assert!(!propstat("garbage").is_success());
+    assert_eq!(
+        propstat("HTTP/1.1 403 Forbidden").status_code(),
+        Some(403)
+    );
+}
+
+/// 测试：etag 为去引号后的值，raw_etag 保留服务器原值（含 W/）用于条件请求
+#[test]
+fn keeps_raw_etag_alongside_cleaned_one() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/a.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getetag> "abc123" </d:getetag></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/b.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getetag>W/"weak-1"</d:getetag></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let multi_status: MultiStatus = quick_xml::de::from_str(xml).unwrap();
+    let base_url = Url::parse("http://example.com/").unwrap();
+
+    let files = multi_status
+        .to_remote_file_data(&base_url, "http://example.com/dav/")
+        .unwrap();
+
+    assert_eq!(files[0].raw_etag.as_deref(), Some("\"abc123\""));
+    assert_eq!(files[0].etag.as_deref(), Some("abc123"));
+    assert_eq!(files[0].conditional_etag().as_deref(), Some("\"abc123\""));
+    assert_eq!(files[1].raw_etag.as_deref(), Some("W/\"weak-1\""));
+    assert_eq!(
+        files[1].conditional_etag().as_deref(),
+        Some("W/\"weak-1\"")
+    );
+}
+
+/// 测试：creationdate 解析为带时区的时间，兼容 Z 与偏移量写法
+#[test]
+fn parses_creation_date() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/utc.txt</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:creationdate>2024-03-01T08:30:00Z</d:creationdate>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/offset.txt</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:creationdate>2024-03-01T16:30:00.250+08:00</d:creationdate>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/none.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getcontentlength>1</d:getcontentlength></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let multi_status: MultiStatus = quick_xml::de::from_str(xml).unwrap();
+    let base_url = Url::parse("http://example.com/").unwrap();
+
+    let files = multi_status.to_all_remote_file_data(&base_url);
+
+    let utc = files[0].creation_date.expect("应解析出 Z 结尾的时间");
+    assert_eq!(utc.offset().local_minus_utc(), 0);
+    assert_eq!(utc.to_rfc3339(), "2024-03-01T08:30:00+00:00");
+
+    let offset = files[1].creation_date.expect("应解析出带偏移量的时间");
+    assert_eq!(offset.offset().local_minus_utc(), 8 * 3600);
+    assert_eq!(offset.timestamp(), utc.timestamp());
+    assert_eq!(offset.timestamp_subsec_millis(), 250);
+
+    assert_eq!(files[2].creation_date, None);
+}
+
+/// 测试：没有 2xx propstat 的资源带着 href 与状态行出现在警告中，
+/// 空目录则文件与警告都为空
+#[test]
+fn reports_responses_without_successful_propstat() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/secret.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getcontentlength/></d:prop>
+      <d:status>HTTP/1.1 403 Forbidden</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/gone.txt</d:href>
+    <d:status>HTTP/1.1 404 Not Found</d:status>
+  </d:response>
+  <d:response>
+    <d:href>/dav/a.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getcontentlength>3</d:getcontentlength></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let multi_status: MultiStatus = quick_xml::de::from_str(xml).unwrap();
+    let base_url = Url::parse("http://example.com/").unwrap();
+
+    let (files, warnings) = multi_status
+        .clone()
+        .to_remote_file_data_with_warnings(&base_url, "/dav/")
+        .unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].name, "a.txt");
+
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].href.as_deref(), Some("/dav/secret.txt"));
+    assert_eq!(
+        warnings[0].reason,
+        ResponseWarningReason::NoSuccessfulPropstat {
+            statuses: vec!["HTTP/1.1 403 Forbidden".to_string()],
+        }
+    );
+    assert_eq!(warnings[1].href.as_deref(), Some("/dav/gone.txt"));
+    assert_eq!(
+        warnings[1].reason,
+        ResponseWarningReason::NoSuccessfulPropstat {
+            statuses: vec!["HTTP/1.1 404 Not Found".to_string()],
+        }
+    );
+
+    // 不关心警告的调用方得到同样的文件列表
+    let plain =
+        multi_status.to_remote_file_data(&base_url, "/dav/").unwrap();
+    assert_eq!(plain.len(), 1);
+
+    let empty_dir = r#"<d:multistatus xmlns:d="DAV:"><d:response>
+<d:href>/dav/empty/</d:href><d:propstat><d:prop><d:resourcetype>
+<d:collection/></d:resourcetype></d:prop>
+<d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#;
+    let multi_status: MultiStatus =
+        quick_xml::de::from_str(empty_dir).unwrap();
+    let (files, warnings) = multi_status
+        .to_remote_file_data_with_warnings(&base_url, "/dav/empty/")
+        .unwrap();
+    assert!(files.is_empty(), "❌ 空目录不应返回目录自身: {:?}", files);
+    assert!(warnings.is_empty());
+}
+
+/// 测试：列举目录时按 href 丢弃目录自身，即使它不在第一项
+#[test]
+fn drops_requested_directory_by_href() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/docs/a.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getcontentlength>1</d:getcontentlength></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>http://example.com/dav/docs/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/docs/sub%20dir/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let multi_status: MultiStatus = quick_xml::de::from_str(xml).unwrap();
+    let base_url = Url::parse("http://example.com/dav/").unwrap();
+
+    let files = multi_status
+        .to_remote_file_data(&base_url, "http://example.com/dav/docs")
+        .unwrap();
+
+    let names: Vec<_> = files.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, vec!["a.txt", "sub dir"]);
+}
+
+/// 测试：对单个文件 PROPFIND（Depth: 0）时唯一的一项就是结果，不会被丢弃
+#[test]
+fn keeps_single_file_propfind_result() {
+    let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/docs/report.pdf</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:resourcetype/>
+        <d:getcontentlength>2048</d:getcontentlength>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+    let multi_status: MultiStatus = quick_xml::de::from_str(xml).unwrap();
+    let base_url = Url::parse("http://example.com/dav/").unwrap();
+
+    let files = multi_status
+        .to_remote_file_data(
+            &base_url,
+            "http://example.com/dav/docs/report.pdf",
+        )
+        .unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].name, "report.pdf");
+    assert_eq!(files[0].size, Some(2048));
+}
@@ -0,0 +1,63 @@
+//! href 规范化测试：同一逻辑资源在不同服务器上的 href 写法应规范化为同一结果。
+
+use crate::webdav::functions::{
+    collection_url, normalize_webdav_path, same_webdav_path,
+    same_webdav_path_ignore_case,
+};
+
+/// 测试：目录 href 的各种写法（完整 URL、编码、重复斜杠、尾斜杠）
+#[test]
+fn directory_hrefs_from_different_servers() {
+    let hrefs = [
+        "/dav/我的 文件/", // 未编码 + 尾斜杠
+        "/dav/%E6%88%91%E7%9A%84%20%E6%96%87%E4%BB%B6/", // Nextcloud 风格
+        "https://dav.example.com/dav/%E6%88%91%E7%9A%84%20%E6%96%87%E4%BB%B6", // 完整 URL
+        "http://127.0.0.1:8080//dav//我的%20文件//", // 重复斜杠 + 端口
+    ];
+
+    for href in hrefs {
+        assert_eq!(
+            normalize_webdav_path(href),
+            "/dav/我的 文件",
+            "{}",
+            href
+        );
+    }
+}
+
+/// 测试：文件 href 的写法差异，以及查询串与片段被丢弃
+#[test]
+fn file_hrefs_from_different_servers() {
+    assert!(same_webdav_path("/dav/a%20b.txt", "dav/a b.txt"));
+    assert!(same_webdav_path(
+        "https://host/dav/a%20b.txt?version=2",
+        "/dav/a b.txt#frag"
+    ));
+    assert!(!same_webdav_path("/dav/a.txt", "/dav/b.txt"));
+}
+
+/// 测试：根路径的各种写法都规范化为 "/"
+#[test]
+fn root_paths() {
+    for href in ["", "/", "//", "http://host", "http://host/"] {
+        assert_eq!(normalize_webdav_path(href), "/", "{:?}", href);
+    }
+}
+
+/// 测试：大小写敏感与不敏感的比较
+#[test]
+fn case_sensitivity() {
+    assert!(!same_webdav_path("/DAV/Photos/", "/dav/photos"));
+    assert!(same_webdav_path_ignore_case("/DAV/Photos/", "/dav/photos"));
+}
+
+/// 测试：目录 URL 补尾斜杠，已有斜杠时不变，查询串保留在斜杠之后
+#[test]
+fn collection_url_appends_trailing_slash() {
+    assert_eq!(collection_url("http://h/dav/docs"), "http://h/dav/docs/");
+    assert_eq!(collection_url("http://h/dav/docs/"), "http://h/dav/docs/");
+    assert_eq!(
+        collection_url("http://h/dav/docs?x=1#top"),
+        "http://h/dav/docs/?x=1#top"
+    );
+}
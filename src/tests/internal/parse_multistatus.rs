@@ -0,0 +1,358 @@
+//! 公开的 MultiStatus 解析与转换测试：使用各类服务器的真实响应样本，不依赖网络。
+
+use url::Url;
+
+use crate::webdav::errors::WebDavError;
+use crate::webdav::functions::{
+    multistatus_to_files, multistatus_to_files_with_warnings,
+    parse_multistatus,
+};
+use crate::webdav::structs::ResponseWarningReason;
+
+/// Nextcloud / ownCloud 风格：`d:` 前缀，带 `oc:` 扩展属性与 404 propstat
+const NEXTCLOUD_RESPONSE: &str = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:s="http://sabredav.org/ns" xmlns:oc="http://owncloud.org/ns" xmlns:nc="http://nextcloud.org/ns">
+  <d:response>
+    <d:href>/remote.php/dav/files/alice/Documents/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getlastmodified>Tue, 03 Sep 2024 10:15:30 GMT</d:getlastmodified>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <d:getetag>&quot;66d6e1b2a3c4f&quot;</d:getetag>
+        <oc:id>00000123ocabcdefgh</oc:id>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/files/alice/Documents/Report%202024.pdf</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getlastmodified>Mon, 02 Sep 2024 08:00:00 GMT</d:getlastmodified>
+        <d:getcontentlength>52341</d:getcontentlength>
+        <d:resourcetype/>
+        <d:getetag>&quot;a1b2c3d4e5&quot;</d:getetag>
+        <d:getcontenttype>application/pdf</d:getcontenttype>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:quota-used-bytes/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/files/alice/Documents/Archive/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getlastmodified>Sun, 01 Sep 2024 12:00:00 GMT</d:getlastmodified>
+        <d:resourcetype><d:collection/></d:resourcetype>
+        <d:getetag>&quot;66d45a00b1b2c&quot;</d:getetag>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+/// Apache mod_dav 风格：`D:` 前缀，属性分散在 `lp1`/`lp2` 命名空间
+const APACHE_RESPONSE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:ns0="DAV:">
+<D:response xmlns:lp1="DAV:" xmlns:lp2="http://apache.org/dav/props/">
+<D:href>/dav/</D:href>
+<D:propstat>
+<D:prop>
+<lp1:resourcetype><D:collection/></lp1:resourcetype>
+<lp1:getlastmodified>Wed, 04 Sep 2024 09:30:00 GMT</lp1:getlastmodified>
+</D:prop>
+<D:status>HTTP/1.1 200 OK</D:status>
+</D:propstat>
+</D:response>
+<D:response xmlns:lp1="DAV:" xmlns:lp2="http://apache.org/dav/props/">
+<D:href>/dav/notes.txt</D:href>
+<D:propstat>
+<D:prop>
+<lp1:resourcetype/>
+<lp1:getcontentlength>12</lp1:getcontentlength>
+<lp1:getetag>"c-5f1a2b3c4d5e6"</lp1:getetag>
+<lp2:executable>F</lp2:executable>
+<D:getcontenttype>text/plain</D:getcontenttype>
+</D:prop>
+<D:status>HTTP/1.1 200 OK</D:status>
+</D:propstat>
+</D:response>
+</D:multistatus>"#;
+
+/// 不带前缀的默认命名空间写法（IIS 等），单个资源（Depth: 0）
+const DEFAULT_NS_RESPONSE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<multistatus xmlns="DAV:">
+  <response>
+    <href>http://files.example.com/share/photo.jpg</href>
+    <propstat>
+      <prop>
+        <getcontentlength>2048</getcontentlength>
+        <getcontenttype>image/jpeg</getcontenttype>
+        <displayname>photo.jpg</displayname>
+        <resourcetype/>
+      </prop>
+      <status>HTTP/1.1 200 OK</status>
+    </propstat>
+  </response>
+</multistatus>"#;
+
+/// Teracloud（InfiniCLOUD）风格：`D:` 前缀，目录的 getcontentlength 为空元素，
+/// 带 creationdate、配额属性与纯文本的 owner
+const TERACLOUD_RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<D:multistatus xmlns:D="DAV:">
+  <D:response>
+    <D:href>/dav/Backup/</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:creationdate>2024-08-30T02:11:45Z</D:creationdate>
+        <D:getcontentlength></D:getcontentlength>
+        <D:getlastmodified>Fri, 30 Aug 2024 02:11:45 GMT</D:getlastmodified>
+        <D:resourcetype><D:collection/></D:resourcetype>
+        <D:quota-used-bytes>1073741824</D:quota-used-bytes>
+        <D:quota-available-bytes>20401094656</D:quota-available-bytes>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+  <D:response>
+    <D:href>/dav/Backup/db.sqlite</D:href>
+    <D:propstat>
+      <D:prop>
+        <D:creationdate>2024-08-30T02:12:01Z</D:creationdate>
+        <D:getcontentlength>4096</D:getcontentlength>
+        <D:getlastmodified>Fri, 30 Aug 2024 03:00:00 GMT</D:getlastmodified>
+        <D:getetag>"1000-61fd3a"</D:getetag>
+        <D:owner> alice </D:owner>
+        <D:resourcetype/>
+      </D:prop>
+      <D:status>HTTP/1.1 200 OK</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#;
+
+/// Apache mod_dav 的单个资源：`lp1:` 前缀、`<owner><href>` 与无法解析的时间
+const APACHE_OWNER_RESPONSE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:ns0="DAV:">
+<D:response xmlns:lp1="DAV:" xmlns:lp2="http://apache.org/dav/props/">
+<D:href>/dav/shared.doc</D:href>
+<D:propstat>
+<D:prop>
+<lp1:resourcetype/>
+<lp1:creationdate>2024-09-04T09:30:00+08:00</lp1:creationdate>
+<lp1:getcontentlength> 2048 </lp1:getcontentlength>
+<lp1:getlastmodified>not a date</lp1:getlastmodified>
+<D:owner><D:href>mailto:bob@example.com</D:href></D:owner>
+<D:supportedlock>
+<D:lockentry>
+<D:lockscope><D:exclusive/></D:lockscope>
+<D:locktype><D:write/></D:locktype>
+</D:lockentry>
+</D:supportedlock>
+<D:lockdiscovery/>
+</D:prop>
+<D:status>HTTP/1.1 200 OK</D:status>
+</D:propstat>
+</D:response>
+</D:multistatus>"#;
+
+/// 第二、三个资源格式不对（缺少 href、属性重复），第一个与最后一个正常
+const PARTLY_MALFORMED_RESPONSE: &str = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/</d:href>
+    <d:propstat>
+      <d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:propstat>
+      <d:prop><d:getcontentlength>1</d:getcontentlength></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/dup.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getetag>"a"</d:getetag><d:getetag>"b"</d:getetag></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/ok.txt</d:href>
+    <d:propstat>
+      <d:prop><d:getcontentlength>7</d:getcontentlength></d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+fn base(url: &str) -> Url {
+    Url::parse(url).unwrap()
+}
+
+/// 测试：Nextcloud 响应解析后丢弃目录自身，文件属性与目录标记正确
+#[test]
+fn nextcloud_response_round_trip() {
+    let multi_status = parse_multistatus(NEXTCLOUD_RESPONSE).unwrap();
+    assert_eq!(multi_status.responses.len(), 3);
+
+    let files = multistatus_to_files(
+        multi_status,
+        &base("https://cloud.example.com/remote.php/dav/files/alice/"),
+        "https://cloud.example.com/remote.php/dav/files/alice/Documents/",
+    )
+    .unwrap();
+
+    assert_eq!(files.len(), 2);
+    let pdf = &files[0];
+    assert_eq!(pdf.name, "Report 2024.pdf");
+    assert!(!pdf.is_dir);
+    assert_eq!(pdf.size, Some(52341));
+    assert_eq!(pdf.mime.as_deref(), Some("application/pdf"));
+    assert_eq!(pdf.etag.as_deref(), Some("a1b2c3d4e5"));
+    assert!(pdf.last_modified.is_some());
+    assert_eq!(
+        pdf.absolute_path,
+        "https://cloud.example.com/remote.php/dav/files/alice/Documents/Report%202024.pdf"
+    );
+
+    let archive = &files[1];
+    assert_eq!(archive.name, "Archive");
+    assert!(archive.is_dir);
+    assert_eq!(archive.size, None);
+}
+
+/// 测试：Apache mod_dav 的多命名空间前缀同样可以解析
+#[test]
+fn apache_response_round_trip() {
+    let multi_status = parse_multistatus(APACHE_RESPONSE).unwrap();
+    let files = multistatus_to_files(
+        multi_status,
+        &base("http://localhost/dav/"),
+        "http://localhost/dav/",
+    )
+    .unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].name, "notes.txt");
+    assert_eq!(files[0].size, Some(12));
+    assert_eq!(files[0].mime.as_deref(), Some("text/plain"));
+    assert_eq!(files[0].raw_etag.as_deref(), Some("\"c-5f1a2b3c4d5e6\""));
+}
+
+/// 测试：默认命名空间、对单个文件 PROPFIND 时该文件本身就是结果，不会被丢弃
+#[test]
+fn default_namespace_single_resource() {
+    let multi_status = parse_multistatus(DEFAULT_NS_RESPONSE).unwrap();
+    let files = multistatus_to_files(
+        multi_status,
+        &base("http://files.example.com/share/"),
+        "http://files.example.com/share/photo.jpg",
+    )
+    .unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].name, "photo.jpg");
+    assert_eq!(files[0].size, Some(2048));
+    assert_eq!(
+        files[0].absolute_path,
+        "http://files.example.com/share/photo.jpg"
+    );
+}
+
+/// 测试：空文本与非法 XML 分别得到 EmptyBody 与 Parse
+#[test]
+fn rejects_empty_and_invalid_xml() {
+    assert!(matches!(
+        parse_multistatus("  \n"),
+        Err(WebDavError::EmptyBody(_))
+    ));
+    assert!(matches!(
+        parse_multistatus("<d:multistatus xmlns:d=\"DAV:\"><d:response>"),
+        Err(WebDavError::Parse(_))
+    ));
+}
+
+/// 测试：Teracloud 的空 getcontentlength 不会让解析失败，配额与创建时间可用
+#[test]
+fn teracloud_response_round_trip() {
+    let multi_status = parse_multistatus(TERACLOUD_RESPONSE).unwrap();
+    let dir = &multi_status.responses[0].propstats[0].prop;
+    assert_eq!(dir.content_length, None);
+    assert_eq!(dir.quota_used_bytes, Some(1_073_741_824));
+    assert_eq!(dir.quota_available_bytes, Some(20_401_094_656));
+
+    let files = multistatus_to_files(
+        multi_status,
+        &base("https://example.teracloud.jp/dav/"),
+        "/dav/Backup",
+    )
+    .unwrap();
+
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].name, "db.sqlite");
+    assert_eq!(files[0].size, Some(4096));
+    assert_eq!(files[0].etag.as_deref(), Some("1000-61fd3a"));
+    assert_eq!(files[0].owner.as_deref(), Some("alice"));
+    assert!(files[0].last_modified.is_some());
+    assert_eq!(
+        files[0].creation_date.map(|t| t.to_rfc3339()).as_deref(),
+        Some("2024-08-30T02:12:01+00:00")
+    );
+}
+
+/// 测试：单个属性值无法解析时只有该字段为 None，`<owner><href>` 取 href
+#[test]
+fn apache_lenient_values_and_owner_href() {
+    let multi_status = parse_multistatus(APACHE_OWNER_RESPONSE).unwrap();
+    let files = multistatus_to_files(
+        multi_status,
+        &base("http://localhost/dav/"),
+        "http://localhost/dav/shared.doc",
+    )
+    .unwrap();
+
+    assert_eq!(files.len(), 1);
+    let file = &files[0];
+    assert_eq!(file.name, "shared.doc");
+    assert_eq!(file.size, Some(2048));
+    assert_eq!(file.last_modified, None);
+    assert_eq!(
+        file.creation_date.map(|t| t.offset().local_minus_utc()),
+        Some(8 * 3600)
+    );
+    assert_eq!(file.owner.as_deref(), Some("mailto:bob@example.com"));
+}
+
+/// 测试：格式不对的资源被跳过并记入警告，其余资源照常返回
+#[test]
+fn skips_malformed_responses() {
+    let multi_status =
+        parse_multistatus(PARTLY_MALFORMED_RESPONSE).unwrap();
+    let hrefs: Vec<_> =
+        multi_status.responses.iter().map(|r| r.href.as_str()).collect();
+    assert_eq!(hrefs, vec!["/dav/", "/dav/ok.txt"]);
+
+    let (files, warnings) = multistatus_to_files_with_warnings(
+        multi_status,
+        &base("http://localhost/dav/"),
+        "http://localhost/dav/",
+    )
+    .unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].size, Some(7));
+
+    let hrefs: Vec<_> =
+        warnings.iter().map(|w| w.href.as_deref()).collect();
+    assert_eq!(hrefs, vec![None, Some("/dav/dup.txt")]);
+    assert!(
+        warnings.iter().all(|w| matches!(
+            w.reason,
+            ResponseWarningReason::Malformed(_)
+        ))
+    );
+}
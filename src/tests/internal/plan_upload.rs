@@ -0,0 +1,187 @@
+//! plan_upload 测试
+
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Utc};
+use url::Url;
+
+use crate::local_file::{UploadReason, UploadStrategy, plan_upload};
+use crate::remote_file::RemoteFileData;
+use crate::tests::mock_server::temp_path;
+
+/// 在临时目录下创建本地树：a.txt(5)、same.txt(4)、sub/b.txt(3)、new/c.txt(1)
+fn local_tree(name: &str) -> PathBuf {
+    let root = PathBuf::from(temp_path(name));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("sub")).expect("创建目录失败");
+    fs::create_dir_all(root.join("new")).expect("创建目录失败");
+    fs::write(root.join("a.txt"), b"hello").expect("写入失败");
+    fs::write(root.join("same.txt"), b"same").expect("写入失败");
+    fs::write(root.join("sub/b.txt"), b"abc").expect("写入失败");
+    fs::write(root.join("new/c.txt"), b"c").expect("写入失败");
+    root
+}
+
+fn remote(
+    href: &str,
+    is_dir: bool,
+    size: Option<u64>,
+    modified: Option<DateTime<Utc>>,
+) -> RemoteFileData {
+    let base_url =
+        Url::parse("http://example.com/").expect("解析 URL 失败");
+    RemoteFileData {
+        absolute_path: format!("http://example.com{}", href),
+        base_url,
+        relative_root_path: href.to_string(),
+        name: href
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or(href)
+            .to_string(),
+        is_dir,
+        size,
+        last_modified: modified.map(|t| t.fixed_offset()),
+        creation_date: None,
+        mime: None,
+        owner: None,
+        etag: None,
+        raw_etag: None,
+        privileges: Vec::new(),
+        allowed_methods: None,
+    }
+}
+
+/// 测试：缺失的文件与目录、大小不同的文件被列出，相同的文件计入 unchanged
+#[test]
+fn plans_missing_and_resized_files() {
+    let root = local_tree("plan_upload_basic");
+    let future = Utc::now() + Duration::hours(1);
+    let listing = vec![
+        remote("/backup/", true, None, None),
+        remote("/backup/a.txt", false, Some(3), Some(future)),
+        remote("/backup/same.txt", false, Some(4), Some(future)),
+        remote("/backup/sub/", true, None, None),
+        // 不在 remote_root 之下，应被忽略
+        remote("/other/new/c.txt", false, Some(1), Some(future)),
+    ];
+
+    let plan = plan_upload(
+        &root,
+        "/backup/",
+        &listing,
+        UploadStrategy::default(),
+    )
+    .expect("计划失败");
+
+    assert_eq!(plan.create_dirs, vec!["new".to_string()]);
+    let paths: Vec<_> =
+        plan.uploads.iter().map(|i| i.relative_path.as_str()).collect();
+    assert_eq!(paths, vec!["a.txt", "new/c.txt", "sub/b.txt"]);
+    assert_eq!(
+        plan.uploads[0].reason,
+        UploadReason::SizeDiffers { local: 5, remote: Some(3) }
+    );
+    assert_eq!(plan.uploads[1].reason, UploadReason::Missing);
+    assert_eq!(plan.uploads[0].local_path, root.join("a.txt"));
+    assert_eq!(plan.unchanged, 1);
+    assert_eq!(plan.total_bytes(), 5 + 1 + 3);
+    assert!(plan.conflicts.is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+/// 测试：大小相同时按策略决定是否比较修改时间
+#[test]
+fn strategy_controls_mtime_comparison() {
+    let root = local_tree("plan_upload_mtime");
+    let past = Utc::now() - Duration::days(1);
+    let listing = vec![
+        remote("/a.txt", false, Some(5), Some(past)),
+        remote("/same.txt", false, Some(4), None),
+        remote("/sub/", true, None, None),
+        remote("/sub/b.txt", false, Some(1), Some(past)),
+        remote("/new/", true, None, None),
+        remote("/new/c.txt", false, Some(1), Some(past)),
+    ];
+
+    let newer =
+        plan_upload(&root, "/", &listing, UploadStrategy::SizeOrNewer)
+            .expect("计划失败");
+    let reasons: Vec<_> = newer
+        .uploads
+        .iter()
+        .map(|i| (i.relative_path.as_str(), i.reason.clone()))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            ("a.txt", UploadReason::Newer),
+            ("new/c.txt", UploadReason::Newer),
+            (
+                "sub/b.txt",
+                UploadReason::SizeDiffers { local: 3, remote: Some(1) }
+            ),
+        ]
+    );
+    // 远程没有修改时间，只比较大小
+    assert_eq!(newer.unchanged, 1);
+
+    let size =
+        plan_upload(&root, "/", &listing, UploadStrategy::SizeChanged)
+            .expect("计划失败");
+    assert_eq!(size.uploads.len(), 1);
+    assert_eq!(size.unchanged, 3);
+
+    let missing =
+        plan_upload(&root, "/", &listing, UploadStrategy::MissingOnly)
+            .expect("计划失败");
+    assert!(missing.is_empty());
+    assert_eq!(missing.unchanged, 4);
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+/// 测试：远程 href 百分号解码后比较；文件与目录类型冲突被单独列出
+#[test]
+fn decodes_hrefs_and_reports_conflicts() {
+    let root = PathBuf::from(temp_path("plan_upload_conflict"));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("我的 文档")).expect("创建目录失败");
+    fs::write(root.join("我的 文档/x.txt"), b"x").expect("写入失败");
+    fs::create_dir_all(root.join("was_file")).expect("创建目录失败");
+    fs::write(root.join("was_file/inner.txt"), b"i").expect("写入失败");
+    fs::write(root.join("was_dir"), b"d").expect("写入失败");
+
+    let listing = vec![
+        remote(
+            "/dav/%E6%88%91%E7%9A%84%20%E6%96%87%E6%A1%A3/",
+            true,
+            None,
+            None,
+        ),
+        remote(
+            "/dav/%E6%88%91%E7%9A%84%20%E6%96%87%E6%A1%A3/x.txt",
+            false,
+            Some(1),
+            None,
+        ),
+        remote("/dav/was_file", false, Some(1), None),
+        remote("/dav/was_dir/", true, None, None),
+    ];
+
+    let plan =
+        plan_upload(&root, "/dav", &listing, UploadStrategy::default())
+            .expect("计划失败");
+
+    assert!(plan.is_empty());
+    assert_eq!(plan.unchanged, 1);
+    assert_eq!(
+        plan.conflicts,
+        vec!["was_dir".to_string(), "was_file".to_string()]
+    );
+
+    let _ = fs::remove_dir_all(&root);
+}
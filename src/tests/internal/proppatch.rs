@@ -0,0 +1,124 @@
+//! PROPPATCH 设置自定义属性测试：请求体转义与 multistatus 中的逐属性状态
+
+use crate::auth::WebdavAuth;
+use crate::internal::webdav::functions::proppatch::proppatch_body;
+use crate::set_property;
+use crate::tests::mock_server::{MockRequest, MockResponse, MockServer};
+use crate::webdav::errors::WebDavError;
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败")
+}
+
+/// 返回 207，其中属性的 propstat 状态为 `status_line`
+fn multistatus(status_line: &str) -> MockResponse {
+    MockResponse::new(207).header("Content-Type", "application/xml").body(
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<D:multistatus xmlns:D="DAV:" xmlns:P="urn:example">
+  <D:response>
+    <D:href>/doc.txt</D:href>
+    <D:propstat>
+      <D:prop><P:author/></D:prop>
+      <D:status>{}</D:status>
+    </D:propstat>
+  </D:response>
+</D:multistatus>"#,
+            status_line
+        ),
+    )
+}
+
+/// 测试：值与命名空间中的特殊字符被转义，非法属性名被拒绝
+#[test]
+fn proppatch_body_escapes_value() {
+    let body = proppatch_body("urn:a&b", "author", r#"<Tom & "Jerry">"#)
+        .expect("应生成请求体");
+    assert!(body.contains(r#"<D:propertyupdate xmlns:D="DAV:">"#));
+    assert!(body.contains(
+        r#"<P:author xmlns:P="urn:a&amp;b">&lt;Tom &amp; &quot;Jerry&quot;&gt;</P:author>"#
+    ));
+
+    let body = proppatch_body("", "note", "x").unwrap();
+    assert!(body.contains(r#"<note xmlns="">x</note>"#));
+
+    for name in ["", "1st", "a b", "P:author", "a<b"] {
+        assert!(
+            matches!(
+                proppatch_body("urn:example", name, "x"),
+                Err(WebDavError::InvalidRequest(_))
+            ),
+            "❌ 应拒绝属性名 {:?}",
+            name
+        );
+    }
+}
+
+/// 测试：发送 PROPPATCH，propstat 为 2xx 时成功
+#[tokio::test]
+async fn set_property_confirms_success() {
+    let server =
+        MockServer::start(|req: &MockRequest| match req.method.as_str() {
+            "PROPPATCH" => multistatus("HTTP/1.1 200 OK"),
+            _ => MockResponse::new(405),
+        });
+
+    set_property(
+        &auth(&server),
+        "doc.txt",
+        "urn:example",
+        "author",
+        "A&B",
+    )
+    .await
+    .expect("设置属性应成功");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "PROPPATCH");
+    assert_eq!(requests[0].path, "/doc.txt");
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(body.contains(">A&amp;B</P:author>"), "❌ 请求体: {}", body);
+}
+
+/// 测试：propstat 不是 2xx 时返回该属性与状态行；其余失败映射为状态错误
+#[tokio::test]
+async fn set_property_reports_failed_property() {
+    let server =
+        MockServer::start(|_| multistatus("HTTP/1.1 403 Forbidden"));
+    let result = set_property(
+        &auth(&server),
+        "doc.txt",
+        "urn:example",
+        "author",
+        "x",
+    )
+    .await;
+    match result {
+        Err(WebDavError::PropertyFailed { property, status }) => {
+            assert_eq!(property, "{urn:example}author");
+            assert_eq!(status, "HTTP/1.1 403 Forbidden");
+        }
+        other => panic!("❌ 应返回 PropertyFailed，实际: {:?}", other),
+    }
+
+    // 没有 multistatus 的 2xx 视为成功，非 2xx 返回状态码
+    let server =
+        MockServer::start(|req: &MockRequest| match req.path.as_str() {
+            "/ok.txt" => MockResponse::new(200),
+            _ => MockResponse::new(423).body("locked"),
+        });
+    let auth = auth(&server);
+    set_property(&auth, "ok.txt", "urn:example", "author", "x")
+        .await
+        .expect("200 应视为成功");
+    let result =
+        set_property(&auth, "locked.txt", "urn:example", "author", "x")
+            .await;
+    assert!(
+        matches!(result, Err(WebDavError::Status { status: 423, .. })),
+        "❌ 应返回 423: {:?}",
+        result
+    );
+}
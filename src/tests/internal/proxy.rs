@@ -0,0 +1,87 @@
+//! 代理测试：WebdavAuth::with_proxy 让请求经过代理，代理凭据取自 URL，
+//! 代理不可达时返回连接错误。
+
+use std::net::TcpListener;
+use std::time::Duration;
+
+use base64::Engine;
+
+use crate::auth::WebdavAuth;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::errors::WebDavError;
+use crate::webdav::functions::head;
+
+/// 测试：请求以绝对 URL 发给代理，带上代理凭据且保留 WebDAV 认证头
+#[tokio::test]
+async fn requests_go_through_proxy_with_credentials() {
+    // mock 服务器充当 HTTP 代理，目标主机本身无法解析
+    let proxy = MockServer::start(|_| {
+        MockResponse::new(200)
+            .header("Content-Type", "text/plain")
+            .body("ok")
+    });
+    let proxy_url =
+        proxy.base_url().replacen("http://", "http://puser:ppass@", 1);
+    let plain = WebdavAuth::new("user", "password", "http://dav.invalid/")
+        .expect("创建测试认证失败");
+    let auth = plain
+        .clone()
+        .with_proxy(&proxy_url)
+        .expect("设置代理失败")
+        // 之后重建 client 时仍保留代理
+        .with_timeout(Duration::from_secs(5), Duration::from_secs(5))
+        .expect("设置超时失败");
+    assert_eq!(auth, plain, "❌ 设置代理不应改变相等比较");
+
+    let info = head(&auth, "http://dav.invalid/a.txt")
+        .await
+        .expect("经代理请求失败");
+    assert_eq!(info.size, Some(2));
+
+    let requests = proxy.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "http://dav.invalid/a.txt");
+    let encode = |s: &str| {
+        base64::engine::general_purpose::STANDARD.encode(s.as_bytes())
+    };
+    assert_eq!(
+        requests[0].header("Proxy-Authorization"),
+        Some(format!("Basic {}", encode("puser:ppass")).as_str())
+    );
+    assert_eq!(
+        requests[0].header("Authorization"),
+        Some(format!("Basic {}", encode("user:password")).as_str())
+    );
+}
+
+/// 测试：代理不可达时返回连接错误，不会绕过代理直连服务器
+#[tokio::test]
+async fn unreachable_proxy_is_connection_error() {
+    let server = MockServer::start(|_| MockResponse::new(200));
+    // 取一个空闲端口后立即释放，连接该端口会被拒绝
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("获取空闲端口失败")
+        .port();
+    let auth = WebdavAuth::new("user", "password", server.base_url())
+        .and_then(|auth| {
+            auth.with_proxy(&format!("http://127.0.0.1:{}", port))
+        })
+        .expect("创建测试认证失败");
+
+    match head(&auth, &server.url("a.txt")).await {
+        Err(WebDavError::Request(e)) => {
+            assert!(e.is_connect(), "❌ 应为连接错误: {:?}", e)
+        }
+        other => panic!("❌ 代理不可达时应失败: {:?}", other),
+    }
+    assert!(server.requests().is_empty(), "❌ 不应直连服务器");
+}
+
+/// 测试：无法解析的代理地址直接返回错误
+#[test]
+fn invalid_proxy_url_is_rejected() {
+    let auth = WebdavAuth::new("user", "password", "http://dav.invalid/")
+        .expect("创建测试认证失败");
+    assert!(auth.with_proxy("not a url").is_err());
+}
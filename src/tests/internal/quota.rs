@@ -0,0 +1,94 @@
+//! 配额属性测试：quota-used-bytes / quota-available-bytes 的解析与查询。
+
+use crate::auth::WebdavAuth;
+use crate::get_remote_quota;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::functions::parse_multistatus;
+use crate::webdav::structs::Quota;
+
+/// Nextcloud 对用户根目录 `Depth: 0` PROPFIND 的典型响应
+fn nextcloud_quota(used: &str, available: &str) -> String {
+    format!(
+        r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:s="http://sabredav.org/ns"
+  xmlns:oc="http://owncloud.org/ns" xmlns:nc="http://nextcloud.org/ns">
+  <d:response>
+    <d:href>/remote.php/dav/files/alice/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:quota-used-bytes>{}</d:quota-used-bytes>
+        <d:quota-available-bytes>{}</d:quota-available-bytes>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><oc:size/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>"#,
+        used, available
+    )
+}
+
+/// 测试：解析 Nextcloud 响应中的配额，并计算使用百分比
+#[test]
+fn parses_nextcloud_quota() {
+    let xml = nextcloud_quota("3221225472", "7516192768");
+    let multi_status = parse_multistatus(&xml).expect("解析失败");
+
+    let prop = &multi_status.responses[0].propstats[0].prop;
+    assert_eq!(prop.quota_used_bytes, Some(3_221_225_472));
+    assert_eq!(prop.quota_available_bytes, Some(7_516_192_768));
+
+    let quota = Quota::from_multi_status(&multi_status).expect("应有配额");
+    assert_eq!(
+        quota,
+        Quota { used: 3_221_225_472, available: 7_516_192_768 }
+    );
+    assert!((quota.percent_used() - 30.0).abs() < 1e-9);
+    assert_eq!(Quota { used: 0, available: 0 }.percent_used(), 0.0);
+}
+
+/// 测试：Nextcloud 用负数表示不限额，解析为 None 而不是让整个响应失败
+#[test]
+fn negative_quota_means_unlimited() {
+    let xml = nextcloud_quota("1024", "-3");
+    let multi_status = parse_multistatus(&xml).expect("解析失败");
+
+    let prop = &multi_status.responses[0].propstats[0].prop;
+    assert_eq!(prop.quota_used_bytes, Some(1024));
+    assert_eq!(prop.quota_available_bytes, None);
+    assert_eq!(Quota::from_multi_status(&multi_status), None);
+}
+
+/// 测试：get_remote_quota 显式请求配额属性；不限额时返回“不支持”的错误
+#[tokio::test]
+async fn get_remote_quota_requests_quota_props() {
+    let server = MockServer::start(|req| {
+        let available =
+            if req.path.contains("unlimited") { "-3" } else { "600" };
+        MockResponse::new(207)
+            .header("Content-Type", "application/xml")
+            .body(nextcloud_quota("400", available))
+    });
+    let auth = WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败");
+
+    let quota = get_remote_quota(&auth, "docs/").await.expect("查询失败");
+    assert_eq!(quota, Quota { used: 400, available: 600 });
+    assert!((quota.percent_used() - 40.0).abs() < 1e-9);
+
+    let err = get_remote_quota(&auth, "unlimited/")
+        .await
+        .expect_err("不限额时应返回错误");
+    assert!(err.starts_with("服务器不支持"), "❌ 错误信息: {}", err);
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "PROPFIND");
+    assert_eq!(requests[0].header("depth"), Some("0"));
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(body.contains("quota-used-bytes"));
+    assert!(body.contains("quota-available-bytes"));
+    assert!(!body.contains("allprop"));
+}
@@ -66,6 +66,7 @@ const QUEUE_TIMEOUT_SECS: u64 = 30;
 const QUEUE_FIFO_TEST_MESSAGE_COUNT: usize = 10_0000;
 
 /// 测试用的进度结构体（独立于下载器模块）
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 struct TestProgress {
     bytes_done: u64,
@@ -495,7 +496,7 @@ async fn queue_reactive_property_performance_benchmark() {
             let base = (producer_id * messages_per_producer) as u64;
             for i in 0..messages_per_producer {
                 let value = base + i as u64;
-                if let Err(_) = p.send(value) {
+                if p.send(value).is_err() {
                     return Err(format!(
                         "生产者 {} 发送失败: value={}",
                         producer_id, value
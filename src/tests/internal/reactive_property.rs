@@ -10,7 +10,10 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+use crate::internal::states::queue_reactive::QueueReactiveProperty;
+use crate::states::broadcast_reactive::BroadcastReactiveProperty;
 use crate::states::lock_reactive::LockReactiveProperty;
+use crate::states::reactive_core::ReactivePropertyError;
 use crate::states::unlock_reactive::UnlockReactiveProperty;
 
 // ═══════════════════════════ 功能测试 ═══════════════════════════
@@ -25,6 +28,50 @@ async fn unlock_basic_update_and_read() {
     assert_eq!(prop.get_current().unwrap(), 50);
 }
 
+/// 测试：watch 合并快速变化，broadcast 订阅者按顺序收到每一个
+#[tokio::test]
+async fn broadcast_keeps_every_transition() {
+    let prop = BroadcastReactiveProperty::new("running");
+    let mut watcher = prop.watch();
+    let mut transitions = prop.subscribe();
+
+    prop.update("paused").unwrap();
+    prop.update("running").unwrap();
+
+    // watch 只能看到最新值
+    assert_eq!(watcher.changed().await.unwrap(), "running");
+    assert!(!watcher.has_changed());
+
+    assert_eq!(transitions.recv().await.unwrap(), "paused");
+    assert_eq!(transitions.recv().await.unwrap(), "running");
+    assert_eq!(transitions.try_recv(), None);
+    assert_eq!(prop.get_current(), Some("running"));
+}
+
+/// 测试：落后超过缓冲容量时报告丢失数量，之后继续接收
+#[tokio::test]
+async fn broadcast_reports_lag() {
+    let prop = BroadcastReactiveProperty::with_capacity(0u32, 2);
+    let mut transitions = prop.subscribe();
+
+    for value in 1..=5 {
+        prop.update(value).unwrap();
+    }
+
+    assert!(matches!(
+        transitions.recv().await,
+        Err(ReactivePropertyError::Lagged(3))
+    ));
+    assert_eq!(transitions.recv().await.unwrap(), 4);
+    assert_eq!(transitions.recv().await.unwrap(), 5);
+
+    drop(prop);
+    assert!(matches!(
+        transitions.recv().await,
+        Err(ReactivePropertyError::Destroyed)
+    ));
+}
+
 #[tokio::test]
 async fn lock_basic_update_and_read() {
     let prop = LockReactiveProperty::new("hello".to_string());
@@ -46,6 +93,254 @@ async fn unlock_watch_receives_updates() {
     assert_eq!(v, 2);
 }
 
+/// 测试：update_if_changed 连续设置相同的值只通知一次
+#[tokio::test]
+async fn unlock_update_if_changed_skips_identical_values() {
+    let prop = UnlockReactiveProperty::new(0i32);
+    let mut watcher = prop.watch();
+
+    prop.update_if_changed(5).unwrap();
+    prop.update_if_changed(5).unwrap();
+    assert_eq!(watcher.changed().await.unwrap(), 5);
+    assert!(!watcher.has_changed(), "❌ 相同的值不应再次通知");
+
+    // 与当前值相同时不通知，不同时照常通知
+    prop.update_if_changed(5).unwrap();
+    assert!(!watcher.has_changed());
+    prop.update_if_changed(6).unwrap();
+    assert!(watcher.has_changed());
+    assert_eq!(watcher.changed().await.unwrap(), 6);
+
+    // update 保持原有行为：即使值相同也通知
+    prop.update(6).unwrap();
+    assert!(watcher.has_changed());
+}
+
+/// 测试：unlock 的 wait_until 在当前值已满足时立即返回
+#[tokio::test]
+async fn unlock_wait_until_already_satisfied() {
+    let prop = UnlockReactiveProperty::new(100i32);
+    let value = tokio::time::timeout(
+        Duration::from_millis(100),
+        prop.wait_until(|v| *v == 100),
+    )
+    .await
+    .expect("❌ 已满足时应立即返回")
+    .unwrap();
+    assert_eq!(value, 100);
+}
+
+/// 测试：unlock 的 wait_until 等到异步更新满足条件，返回满足时的值
+#[tokio::test]
+async fn unlock_wait_until_async_satisfied() {
+    let prop = UnlockReactiveProperty::new(0u64);
+    let p = prop.clone();
+
+    tokio::spawn(async move {
+        for done in [10, 50, 90] {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            p.update(done).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        p.update(120).unwrap();
+    });
+
+    let value = tokio::time::timeout(
+        Duration::from_secs(2),
+        prop.wait_until(|done| *done >= 100),
+    )
+    .await
+    .expect("❌ 应在更新后返回")
+    .unwrap();
+    assert_eq!(value, 120);
+}
+
+/// 测试：有界队列满时生产者挂起，消费者取走消息后继续发送
+#[tokio::test]
+async fn bounded_queue_blocks_producer_when_full() {
+    let (producer, mut consumer) = QueueReactiveProperty::<u32>::bounded(2);
+
+    producer.send(1).await.unwrap();
+    producer.send(2).await.unwrap();
+
+    // 队列已满：第三条消息在超时前无法进入队列
+    let blocked =
+        tokio::time::timeout(Duration::from_millis(50), producer.send(3))
+            .await;
+    assert!(blocked.is_err(), "❌ 队列满时 send 应挂起");
+
+    let sender = tokio::spawn({
+        let producer = producer.clone();
+        async move { producer.send(3).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!sender.is_finished(), "❌ 消费前 send 不应完成");
+
+    assert_eq!(consumer.recv().await, Some(1));
+    tokio::time::timeout(Duration::from_secs(1), sender)
+        .await
+        .expect("❌ 有空位后 send 应完成")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(consumer.recv().await, Some(2));
+    assert_eq!(consumer.try_recv(), Some(3));
+    assert_eq!(consumer.try_recv(), None);
+
+    // 接收端关闭后 send 返回原消息
+    drop(consumer);
+    assert_eq!(producer.send(4).await, Err(4));
+}
+
+/// 测试：recv_many 分批取走消息，批内与批间都保持 0..N 的顺序
+#[tokio::test]
+async fn queue_recv_many_preserves_order() {
+    const N: u64 = 10_000;
+    let (producer, mut consumer) = QueueReactiveProperty::<u64>::new();
+    let task = tokio::spawn(async move {
+        for i in 0..N {
+            producer.send(i).unwrap();
+            if i % 1_000 == 0 {
+                tokio::task::yield_now().await;
+            }
+        }
+    });
+
+    let mut received = Vec::with_capacity(N as usize);
+    let mut batches = 0;
+    loop {
+        let count = consumer.recv_many(&mut received, 256).await;
+        if count == 0 {
+            break;
+        }
+        assert!(count <= 256, "❌ 单批超过上限: {}", count);
+        batches += 1;
+    }
+    task.await.unwrap();
+
+    assert_eq!(received, (0..N).collect::<Vec<_>>());
+    assert!(batches >= N as usize / 256, "❌ 批次数异常: {}", batches);
+    assert_eq!(consumer.recv_many(&mut received, 0).await, 0);
+}
+
+/// clone 时计数的消息，用于统计队列内部的 clone 次数
+#[derive(Debug)]
+struct Counted(u64, Arc<AtomicU64>);
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        self.1.fetch_add(1, Ordering::Relaxed);
+        Self(self.0, Arc::clone(&self.1))
+    }
+}
+
+/// 测试：没有监听器时消费端不 clone 消息；有监听器时消费端照常更新
+#[tokio::test]
+async fn queue_consumer_skips_clone_without_watchers() {
+    let clones = Arc::new(AtomicU64::new(0));
+    let (producer, mut consumer) = QueueReactiveProperty::<Counted>::new();
+
+    // 生产端每条消息 clone 一次，供 watch() 看到最近发送的消息
+    for i in 0..100 {
+        producer.send(Counted(i, Arc::clone(&clones))).unwrap();
+    }
+    assert_eq!(clones.load(Ordering::Relaxed), 100);
+    let mut batch = Vec::new();
+    consumer.recv_many(&mut batch, 10).await;
+    while consumer.try_recv().is_some() {}
+    assert_eq!(
+        clones.load(Ordering::Relaxed),
+        100,
+        "❌ 无人订阅时消费端不应 clone"
+    );
+
+    let mut watcher = producer.watch();
+    producer.send(Counted(7, Arc::clone(&clones))).unwrap();
+    let latest = watcher.changed().await.unwrap();
+    assert_eq!(latest.map(|m| m.0), Some(7));
+    let before = clones.load(Ordering::Relaxed);
+    assert_eq!(consumer.recv().await.map(|m| m.0), Some(7));
+    assert!(clones.load(Ordering::Relaxed) > before);
+
+    // 监听器销毁后消费端恢复为不 clone
+    drop(watcher);
+    producer.send(Counted(8, Arc::clone(&clones))).unwrap();
+    let before = clones.load(Ordering::Relaxed);
+    assert_eq!(consumer.recv().await.map(|m| m.0), Some(8));
+    assert_eq!(clones.load(Ordering::Relaxed), before);
+}
+
+/// 测试：close 后监听者收到 WatcherClosed，即使仍有句柄的 clone 存活
+#[tokio::test]
+async fn unlock_close_notifies_watchers() {
+    let prop = UnlockReactiveProperty::new(1i32);
+    let handle = prop.clone();
+    let mut watcher = prop.watch();
+
+    let waiter = tokio::spawn({
+        let prop = prop.clone();
+        async move { prop.wait_until(|v| *v > 100).await }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    handle.close();
+    assert!(prop.is_closed());
+    assert!(matches!(
+        watcher.changed().await,
+        Err(ReactivePropertyError::WatcherClosed)
+    ));
+    assert!(matches!(
+        waiter.await.unwrap(),
+        Err(ReactivePropertyError::Destroyed)
+    ));
+
+    // 关闭后更新被忽略，重复关闭不会再次通知
+    prop.update(2).unwrap();
+    assert_eq!(prop.get_current(), None);
+    handle.close();
+    assert!(!watcher.has_changed());
+}
+
+/// 测试：派生属性跟随源属性变化，源属性关闭或销毁时随之关闭
+#[tokio::test]
+async fn unlock_project_follows_and_closes_with_source() {
+    let source = UnlockReactiveProperty::new(2i32);
+    let doubled = source.project(|v| v * 2);
+    assert_eq!(doubled.get_current(), Some(4));
+
+    source.update(5).unwrap();
+    assert_eq!(doubled.wait_until(|v| *v == 10).await.unwrap(), 10);
+
+    let mut watcher = doubled.watch();
+    source.close();
+    assert!(matches!(
+        watcher.changed().await,
+        Err(ReactivePropertyError::WatcherClosed)
+    ));
+    assert!(doubled.is_closed());
+
+    // 派生属性的句柄全部释放后，后台任务退出并释放对源的订阅
+    let source = UnlockReactiveProperty::new(0u32);
+    drop(source.project(|v| *v));
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(source.inner.sender.receiver_count(), 1);
+
+    // 源属性的最后一个句柄被释放时同样关闭
+    let source = UnlockReactiveProperty::new(1u8);
+    let label = source.project(|v| format!("#{}", v));
+    let mut watcher = label.watch();
+    drop(source);
+    assert!(matches!(
+        watcher.changed().await,
+        Err(ReactivePropertyError::WatcherClosed)
+    ));
+
+    // 对已关闭的源派生，得到的属性直接处于关闭状态
+    let closed = UnlockReactiveProperty::new(1i32);
+    closed.close();
+    assert!(closed.project(|v| *v).is_closed());
+}
+
 #[tokio::test]
 async fn lock_wait_until_already_satisfied() {
     let prop = LockReactiveProperty::new(100i32);
@@ -0,0 +1,511 @@
+//! 递归列举与 RecursionPolicy 测试（基于本地 MockServer 模拟的目录树）。
+
+use crate::auth::WebdavAuth;
+use std::time::{Duration, Instant};
+
+use crate::remote_file::{
+    CancelToken, DedupKey, NamePattern, RecursionPolicy, RemoteFile,
+    RemoteFileData,
+};
+use crate::{
+    get_remote_files_recursive, get_remote_files_recursive_cancellable,
+    walk_remote,
+};
+use crate::tests::mock_server::{MockResponse, MockServer};
+
+/// 构造一个 multistatus：第一项为目录自身，其余为子项（href 以 `/` 结尾视为目录）
+fn multistatus(
+    self_href: &str,
+    children: &[(&str, Option<&str>)],
+) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="utf-8"?><d:multistatus xmlns:d="DAV:">"#,
+    );
+    let entries =
+        std::iter::once((self_href, None)).chain(children.iter().copied());
+    for (href, etag) in entries {
+        let resource_type = if href.ends_with('/') {
+            "<d:resourcetype><d:collection/></d:resourcetype>"
+        } else {
+            "<d:resourcetype/>"
+        };
+        let etag = etag
+            .map(|e| format!("<d:getetag>\"{}\"</d:getetag>", e))
+            .unwrap_or_default();
+        xml.push_str(&format!(
+            "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}{}</d:prop>\
+             <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+            href, resource_type, etag
+        ));
+    }
+    xml.push_str("</d:multistatus>");
+    xml
+}
+
+/// 模拟的目录树：
+///
+/// ```text
+/// /root/
+///   a/          etag A
+///     deep/     etag D
+///       h.txt
+///     g.txt
+///     alias/    etag A（与 a/ 相同，模拟绑定挂载）
+///       loop.txt
+///   f.txt
+///   /shared/    etag S（href 不在 /root/ 之下，视为共享目录）
+///     s.txt
+/// ```
+fn tree_response(path: &str) -> MockResponse {
+    let body = match path {
+        "/root/" => multistatus(
+            "/root/",
+            &[
+                ("/root/a/", Some("A")),
+                ("/root/f.txt", None),
+                ("/shared/", Some("S")),
+            ],
+        ),
+        "/root/a/" => multistatus(
+            "/root/a/",
+            &[
+                ("/root/a/deep/", Some("D")),
+                ("/root/a/g.txt", None),
+                ("/root/a/alias/", Some("A")),
+            ],
+        ),
+        "/root/a/deep/" => {
+            multistatus("/root/a/deep/", &[("/root/a/deep/h.txt", None)])
+        }
+        "/root/a/alias/" => multistatus(
+            "/root/a/alias/",
+            &[("/root/a/alias/loop.txt", None)],
+        ),
+        "/shared/" => multistatus("/shared/", &[("/shared/s.txt", None)]),
+        _ => return MockResponse::new(404),
+    };
+    MockResponse::new(207).body(body)
+}
+
+fn tree_server() -> MockServer {
+    MockServer::start(|req| tree_response(&req.path))
+}
+
+fn auth_for(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url()).unwrap()
+}
+
+fn paths(files: &[RemoteFile]) -> Vec<String> {
+    let mut paths: Vec<String> =
+        files.iter().map(|f| f.data.relative_root_path.clone()).collect();
+    paths.sort();
+    paths
+}
+
+fn requested_paths(server: &MockServer) -> Vec<String> {
+    let mut paths: Vec<String> =
+        server.requests().into_iter().map(|r| r.path).collect();
+    paths.sort();
+    paths
+}
+
+/// 测试：默认策略递归整棵树，但不进入共享目录
+#[tokio::test]
+async fn default_policy_lists_tree_without_shares() {
+    let server = tree_server();
+    let auth = auth_for(&server);
+
+    let files = get_remote_files_recursive(
+        &auth,
+        "root/",
+        &RecursionPolicy::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        paths(&files),
+        vec![
+            "/root/a/",
+            "/root/a/alias/",
+            "/root/a/alias/loop.txt",
+            "/root/a/deep/",
+            "/root/a/deep/h.txt",
+            "/root/a/g.txt",
+            "/root/f.txt",
+            "/shared/",
+        ]
+    );
+    assert!(!requested_paths(&server).contains(&"/shared/".to_string()));
+}
+
+/// 测试：max_depth = 1 只列举直接子项
+#[tokio::test]
+async fn max_depth_limits_recursion() {
+    let server = tree_server();
+    let auth = auth_for(&server);
+    let policy =
+        RecursionPolicy { max_depth: Some(1), ..Default::default() };
+
+    let files =
+        get_remote_files_recursive(&auth, "root/", &policy).await.unwrap();
+
+    assert_eq!(paths(&files), vec!["/root/a/", "/root/f.txt", "/shared/"]);
+    assert_eq!(requested_paths(&server), vec!["/root/"]);
+}
+
+/// 测试：follow_shares 为 true 时进入共享目录
+#[tokio::test]
+async fn follow_shares_descends_into_shared_collections() {
+    let server = tree_server();
+    let auth = auth_for(&server);
+    let policy =
+        RecursionPolicy { follow_shares: true, ..Default::default() };
+
+    let files =
+        get_remote_files_recursive(&auth, "root/", &policy).await.unwrap();
+
+    assert!(paths(&files).contains(&"/shared/s.txt".to_string()));
+}
+
+/// 测试：按 ETag 去重时，etag 重复的目录仍会列出，但不会再进入
+#[tokio::test]
+async fn etag_dedup_skips_repeated_collections() {
+    let server = tree_server();
+    let auth = auth_for(&server);
+    let policy = RecursionPolicy {
+        visited_dedup_by: DedupKey::Etag,
+        ..Default::default()
+    };
+
+    let files =
+        get_remote_files_recursive(&auth, "root/", &policy).await.unwrap();
+    let paths = paths(&files);
+
+    assert!(paths.contains(&"/root/a/alias/".to_string()));
+    assert!(!paths.contains(&"/root/a/alias/loop.txt".to_string()));
+    assert!(paths.contains(&"/root/a/deep/h.txt".to_string()));
+    assert!(
+        !requested_paths(&server).contains(&"/root/a/alias/".to_string())
+    );
+}
+
+/// 测试：指回上级目录的链接不会导致无限递归
+#[tokio::test]
+async fn link_back_to_ancestor_is_not_revisited() {
+    let server = MockServer::start(|req| {
+        let body = match req.path.as_str() {
+            "/root/" => multistatus("/root/", &[("/root/sub/", None)]),
+            "/root/sub/" => multistatus(
+                "/root/sub/",
+                &[("/root/sub/up/", None), ("/root/sub/x.txt", None)],
+            ),
+            // up/ 实际指回 /root/sub/，服务器返回的 href 也是原目录
+            "/root/sub/up/" => {
+                multistatus("/root/sub/up/", &[("/root/sub/", None)])
+            }
+            _ => return MockResponse::new(404),
+        };
+        MockResponse::new(207).body(body)
+    });
+    let auth = auth_for(&server);
+    let policy =
+        RecursionPolicy { follow_shares: true, ..Default::default() };
+
+    let files =
+        get_remote_files_recursive(&auth, "root/", &policy).await.unwrap();
+
+    assert_eq!(
+        paths(&files),
+        vec![
+            "/root/sub/",
+            "/root/sub/",
+            "/root/sub/up/",
+            "/root/sub/x.txt"
+        ]
+    );
+    assert_eq!(
+        requested_paths(&server),
+        vec!["/root/", "/root/sub/", "/root/sub/up/"]
+    );
+}
+
+/// 测试：子目录列举失败时返回带路径的错误
+#[tokio::test]
+async fn sub_listing_error_is_reported_with_path() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/root/a/" => MockResponse::new(500).body("boom"),
+        path => tree_response(path),
+    });
+    let auth = auth_for(&server);
+
+    let err = get_remote_files_recursive(
+        &auth,
+        "root/",
+        &RecursionPolicy::default(),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.contains("/root/a/"), "unexpected error: {}", err);
+}
+
+/// 测试：目录 URL 缺少尾斜杠时先补上再请求，不会触发 301 重定向
+#[tokio::test]
+async fn directory_urls_get_trailing_slash_before_propfind() {
+    let server = MockServer::start(|req| {
+        let body = match req.path.as_str() {
+            // 不带斜杠的目录地址一律重定向，跟随后会变成 GET
+            "/docs" | "/docs/sub" => {
+                return MockResponse::new(301)
+                    .header("Location", &format!("{}/", req.path));
+            }
+            // 子目录的 href 不带尾斜杠，但 resourcetype 标明是目录
+            "/docs/" => multistatus("/docs/", &[]).replace(
+                "</d:multistatus>",
+                "<d:response><d:href>/docs/sub</d:href><d:propstat><d:prop>\
+                 <d:resourcetype><d:collection/></d:resourcetype></d:prop>\
+                 <d:status>HTTP/1.1 200 OK</d:status></d:propstat>\
+                 </d:response></d:multistatus>",
+            ),
+            "/docs/sub/" => {
+                multistatus("/docs/sub/", &[("/docs/sub/n.txt", None)])
+            }
+            _ => return MockResponse::new(404),
+        };
+        MockResponse::new(207).body(body)
+    });
+    let auth = auth_for(&server);
+
+    let files = get_remote_files_recursive(
+        &auth,
+        "docs",
+        &RecursionPolicy::default(),
+    )
+    .await
+    .unwrap();
+
+    assert!(paths(&files).contains(&"/docs/sub/n.txt".to_string()));
+    assert_eq!(requested_paths(&server), vec!["/docs/", "/docs/sub/"]);
+}
+
+/// 测试：通配符只匹配名称，`*` 可匹配空串
+#[test]
+fn name_pattern_matches_wildcards() {
+    let tmp = NamePattern::new("*.tmp");
+    assert!(tmp.matches("a.tmp"));
+    assert!(tmp.matches(".tmp"));
+    assert!(!tmp.matches("a.tmp.bak"));
+
+    let office = NamePattern::new("~$*.doc?");
+    assert!(office.matches("~$report.docx"));
+    assert!(!office.matches("report.docx"));
+
+    assert!(NamePattern::new("Thumbs.db").matches("Thumbs.db"));
+    assert!(!NamePattern::new("Thumbs.db").matches("thumbs.db"));
+    assert!(NamePattern::new("a*b*c").matches("aXbYbZc"));
+}
+
+/// 测试：排除的目录既不返回也不会被请求
+#[tokio::test]
+async fn exclude_patterns_skip_entries_and_subtrees() {
+    let server = tree_server();
+    let auth = auth_for(&server);
+    let policy = RecursionPolicy {
+        exclude: vec![NamePattern::new("de?p"), "g.*".into()],
+        ..Default::default()
+    };
+
+    let files =
+        get_remote_files_recursive(&auth, "root/", &policy).await.unwrap();
+
+    assert_eq!(
+        paths(&files),
+        vec![
+            "/root/a/",
+            "/root/a/alias/",
+            "/root/a/alias/loop.txt",
+            "/root/f.txt",
+            "/shared/",
+        ]
+    );
+    assert!(
+        !requested_paths(&server).contains(&"/root/a/deep/".to_string())
+    );
+}
+
+/// 测试：include_hidden = false 时跳过点文件与隐藏目录
+#[tokio::test]
+async fn hidden_entries_can_be_excluded() {
+    let server = MockServer::start(|req| {
+        let body = match req.path.as_str() {
+            "/root/" => multistatus(
+                "/root/",
+                &[
+                    ("/root/.git/", None),
+                    ("/root/.DS_Store", None),
+                    ("/root/keep.txt", None),
+                ],
+            ),
+            "/root/.git/" => {
+                multistatus("/root/.git/", &[("/root/.git/HEAD", None)])
+            }
+            _ => return MockResponse::new(404),
+        };
+        MockResponse::new(207).body(body)
+    });
+    let auth = auth_for(&server);
+
+    let all = get_remote_files_recursive(
+        &auth,
+        "root/",
+        &RecursionPolicy::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(all.len(), 4);
+
+    let policy =
+        RecursionPolicy { include_hidden: false, ..Default::default() };
+    let visible =
+        get_remote_files_recursive(&auth, "root/", &policy).await.unwrap();
+    assert_eq!(paths(&visible), vec!["/root/keep.txt"]);
+}
+
+/// 测试：取消后立即返回已列举的条目，丢弃进行中的慢请求
+#[tokio::test]
+async fn cancel_returns_partial_listing() {
+    let server = MockServer::start(|req| {
+        let response = tree_response(&req.path);
+        if req.path == "/root/a/" {
+            // 第二层的请求迟迟不返回
+            std::thread::sleep(Duration::from_secs(3));
+        }
+        response
+    });
+    let auth = auth_for(&server);
+    let cancel = CancelToken::new();
+
+    let canceller = {
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            cancel.cancel();
+        })
+    };
+
+    let started = Instant::now();
+    let listing = get_remote_files_recursive_cancellable(
+        &auth,
+        "root/",
+        &RecursionPolicy::default(),
+        &cancel,
+    )
+    .await
+    .unwrap();
+    let _ = canceller.await;
+
+    assert!(listing.cancelled);
+    assert!(
+        started.elapsed() < Duration::from_secs(2),
+        "❌ 取消后应立即返回"
+    );
+    assert_eq!(
+        paths(&listing.collected),
+        vec!["/root/a/", "/root/f.txt", "/shared/"]
+    );
+}
+
+/// 测试：未取消时与 get_remote_files_recursive 结果相同；预先取消时不发请求
+#[tokio::test]
+async fn cancellable_listing_without_cancel_is_complete() {
+    let server = tree_server();
+    let auth = auth_for(&server);
+    let policy = RecursionPolicy::default();
+
+    let listing = get_remote_files_recursive_cancellable(
+        &auth,
+        "root/",
+        &policy,
+        &CancelToken::new(),
+    )
+    .await
+    .unwrap();
+    let files =
+        get_remote_files_recursive(&auth, "root/", &policy).await.unwrap();
+
+    assert!(!listing.cancelled);
+    assert_eq!(paths(&listing.collected), paths(&files));
+
+    let requests_before = server.requests().len();
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let listing = get_remote_files_recursive_cancellable(
+        &auth, "root/", &policy, &cancel,
+    )
+    .await
+    .unwrap();
+    assert!(listing.cancelled);
+    assert!(listing.collected.is_empty());
+    assert_eq!(server.requests().len(), requests_before);
+}
+
+/// 测试：walk_remote 按 max_depth 限制层数，返回拍平的 RemoteFileData
+#[tokio::test]
+async fn walk_remote_limits_depth() {
+    let server = tree_server();
+    let auth = auth_for(&server);
+
+    let relative_paths = |files: Vec<RemoteFileData>| {
+        let mut paths: Vec<String> =
+            files.into_iter().map(|f| f.relative_root_path).collect();
+        paths.sort();
+        paths
+    };
+
+    assert!(walk_remote(&auth, "root/", 0).await.unwrap().is_empty());
+    assert_eq!(
+        relative_paths(walk_remote(&auth, "root", 1).await.unwrap()),
+        vec!["/root/a/", "/root/f.txt", "/shared/"]
+    );
+    assert_eq!(
+        relative_paths(walk_remote(&auth, "root/", 2).await.unwrap()),
+        vec![
+            "/root/a/",
+            "/root/a/alias/",
+            "/root/a/deep/",
+            "/root/a/g.txt",
+            "/root/f.txt",
+            "/shared/",
+        ]
+    );
+}
+
+/// 测试：目录把自身列为子项时不重复计入、也不会反复请求
+#[tokio::test]
+async fn walk_remote_survives_self_referential_href() {
+    let server = MockServer::start(|req| {
+        let body = match req.path.as_str() {
+            "/root/" => multistatus(
+                "/root/",
+                &[("/root/", None), ("/root/loop/", None)],
+            ),
+            // loop/ 的子项又指回 /root/
+            "/root/loop/" => multistatus(
+                "/root/loop/",
+                &[("/root/", None), ("/root/loop/x.txt", None)],
+            ),
+            _ => return MockResponse::new(404),
+        };
+        MockResponse::new(207).body(body)
+    });
+    let auth = auth_for(&server);
+
+    let files = walk_remote(&auth, "root/", 10).await.unwrap();
+
+    let mut names: Vec<_> =
+        files.iter().map(|f| f.relative_root_path.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["/root/", "/root/loop/", "/root/loop/x.txt"]);
+    assert_eq!(requested_paths(&server), vec!["/root/", "/root/loop/"]);
+}
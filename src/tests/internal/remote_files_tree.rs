@@ -0,0 +1,78 @@
+//! get_remote_files_tree 测试：返回直接子项，跳过目录自身。
+
+use crate::auth::WebdavAuth;
+use crate::get_remote_files_tree;
+use crate::tests::assert_test_result;
+use crate::tests::mock_server::{MockResponse, MockServer};
+
+/// 单个资源；以 `/` 结尾的 href 视为目录
+fn response(href: &str, size: u64) -> String {
+    let prop = if href.ends_with('/') {
+        "<d:resourcetype><d:collection/></d:resourcetype>".to_string()
+    } else {
+        format!(
+            "<d:resourcetype/><d:getcontentlength>{}</d:getcontentlength>",
+            size
+        )
+    };
+    format!(
+        "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}</d:prop>\
+         <d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        href, prop
+    )
+}
+
+fn multistatus(responses: &[String]) -> MockResponse {
+    MockResponse::new(207).body(format!(
+        r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">{}</d:multistatus>"#,
+        responses.concat()
+    ))
+}
+
+/// 测试：列出已知目录，子项数量、大小与类型正确，目录自身不在结果中
+#[tokio::test]
+async fn lists_immediate_children() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        // 目录自身不在第一项
+        "/dav/docs/" => multistatus(&[
+            response("/dav/docs/a.txt", 3),
+            response("/dav/docs/", 0),
+            response("/dav/docs/sub/", 0),
+            response("/dav/docs/b.bin", 1024),
+        ]),
+        _ => MockResponse::new(404),
+    });
+    let auth =
+        WebdavAuth::new("user", "password", &server.url("dav/")).unwrap();
+
+    let children = get_remote_files_tree(&auth, Some("docs")).await;
+
+    let children = children.expect("列举目录失败");
+    let ok_count = children.len();
+    assert_test_result(ok_count, 0, 3, 0, "lists_immediate_children");
+
+    let names: Vec<_> = children.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["a.txt", "sub", "b.bin"]);
+    assert!(children[1].is_dir);
+    assert_eq!(children[2].size, Some(1024));
+    assert_eq!(server.requests()[0].header("Depth"), Some("1"));
+}
+
+/// 测试：未传路径时列举 base_url
+#[tokio::test]
+async fn defaults_to_base_url() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/dav/" => multistatus(&[
+            response("/dav/", 0),
+            response("/dav/root.txt", 1),
+        ]),
+        _ => MockResponse::new(404),
+    });
+    let auth =
+        WebdavAuth::new("user", "password", &server.url("dav/")).unwrap();
+
+    let children = get_remote_files_tree(&auth, None).await.unwrap();
+
+    assert_test_result(children.len(), 0, 1, 0, "defaults_to_base_url");
+    assert_eq!(children[0].name, "root.txt");
+}
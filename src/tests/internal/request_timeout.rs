@@ -0,0 +1,96 @@
+//! 请求超时测试：WebdavAuth::with_timeout 约束 WebDAV 请求但不约束下载，
+//! 下载器的 request_timeout 单独生效。
+
+use std::time::{Duration, Instant};
+
+use crate::auth::{DEFAULT_READ_TIMEOUT, RequestOptions, WebdavAuth};
+use crate::delete_remote;
+use crate::remote_file::{DownloadError, DownloadResult, RemoteFile};
+use crate::tests::mock_server::{
+    MockResponse, MockServer, mock_remote_file,
+};
+
+const CONTENT: &[u8] = b"slow content";
+
+/// GET 立即返回响应头、`delay` 后才发送响应体；其余方法 `delay` 后才响应
+fn slow_server(delay: Duration) -> MockServer {
+    MockServer::start(move |req| match req.method.as_str() {
+        "GET" => MockResponse::new(200).delayed_part(delay, CONTENT),
+        _ => {
+            std::thread::sleep(delay);
+            MockResponse::new(204)
+        }
+    })
+}
+
+/// 测试：下载器的请求超时到期后返回 DownloadError::Request
+#[tokio::test]
+async fn download_request_timeout_is_request_error() {
+    let server = slow_server(Duration::from_secs(2));
+    let file =
+        mock_remote_file(&server, "slow.bin", Some(CONTENT.len() as u64));
+
+    let started = Instant::now();
+    let result = file
+        .build_downloader()
+        .output_bytes()
+        .request_timeout(Duration::from_millis(200))
+        .send()
+        .await;
+
+    match result {
+        Err(DownloadError::Request(e)) => {
+            assert!(e.is_timeout(), "❌ 应为超时错误: {:?}", e)
+        }
+        other => panic!("❌ 应返回 DownloadError::Request: {:?}", other),
+    }
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+/// 测试：未设置读取超时时读取响应体以请求超时为上限，两者都未设置时用默认值
+#[test]
+fn read_timeout_falls_back_to_request_timeout() {
+    let options = RequestOptions::default();
+    assert_eq!(options.effective_read_timeout(), DEFAULT_READ_TIMEOUT);
+
+    let options = RequestOptions {
+        request_timeout: Some(Duration::from_secs(300)),
+        ..Default::default()
+    };
+    assert_eq!(options.effective_read_timeout(), Duration::from_secs(300));
+
+    let options = RequestOptions {
+        read_timeout: Some(Duration::from_secs(5)),
+        ..options
+    };
+    assert_eq!(options.effective_read_timeout(), Duration::from_secs(5));
+}
+
+/// 测试：认证上的请求超时约束 WebDAV 请求，下载不受其影响
+#[tokio::test]
+async fn auth_timeout_applies_to_webdav_requests_only() {
+    let server = slow_server(Duration::from_millis(600));
+    let auth = WebdavAuth::new("user", "password", server.base_url())
+        .unwrap()
+        .with_timeout(Duration::from_secs(1), Duration::from_millis(200))
+        .expect("设置超时失败");
+    assert_eq!(
+        auth.request_options().request_timeout,
+        Some(Duration::from_millis(200))
+    );
+
+    let started = Instant::now();
+    delete_remote(&auth, "a/")
+        .await
+        .expect_err("慢速服务器上的 DELETE 应超时");
+    assert!(started.elapsed() < Duration::from_millis(600));
+
+    let file = RemoteFile {
+        webdav_auth: auth,
+        ..mock_remote_file(&server, "slow.bin", Some(CONTENT.len() as u64))
+    };
+    match file.build_downloader().output_bytes().send().await {
+        Ok(DownloadResult::Bytes(bytes)) => assert_eq!(bytes, CONTENT),
+        other => panic!("❌ 下载不应受请求超时限制: {:?}", other),
+    }
+}
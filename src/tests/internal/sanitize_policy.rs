@@ -0,0 +1,103 @@
+//! SanitizePolicy / LocalNameMap 测试
+
+use crate::remote_file::{LocalNameMap, NameMapping, SanitizePolicy};
+
+/// 测试：非法字符、控制字符与结尾的点/空格被替换
+#[test]
+fn invalid_chars_are_replaced() {
+    let policy = SanitizePolicy::default();
+
+    assert_eq!(policy.sanitize("a:b|c*d"), "a_b_c_d");
+    assert_eq!(policy.sanitize("tab\there"), "tab_here");
+    assert_eq!(policy.sanitize("ends with dot."), "ends with dot_");
+    assert_eq!(policy.sanitize("normal.txt"), "normal.txt");
+}
+
+/// 测试：保留名（含扩展名、不区分大小写）追加下划线
+#[test]
+fn reserved_names_are_suffixed() {
+    let policy = SanitizePolicy::default();
+
+    assert_eq!(policy.sanitize("CON"), "CON_");
+    assert_eq!(policy.sanitize("nul.tar.gz"), "nul_.tar.gz");
+    assert_eq!(policy.sanitize("com1.txt"), "com1_.txt");
+    assert_eq!(policy.sanitize("CONSOLE.txt"), "CONSOLE.txt");
+}
+
+/// 测试：超长名称截断到字节上限，保留扩展名且不切断多字节字符
+#[test]
+fn long_names_are_truncated_on_char_boundary() {
+    let policy = SanitizePolicy {
+        max_name_bytes: Some(10),
+        ..SanitizePolicy::default()
+    };
+
+    assert_eq!(policy.sanitize("abcdefghijkl.txt"), "abcdef.txt");
+    // "文" 占 3 字节，6 字节的主名空间只能放下两个
+    assert_eq!(policy.sanitize("文件名很长.md"), "文件.md");
+}
+
+/// 测试：keep_all 不做任何转换
+#[test]
+fn keep_all_leaves_names_untouched() {
+    let policy = SanitizePolicy::keep_all();
+
+    assert_eq!(policy.sanitize("a:b"), "a:b");
+    assert_eq!(policy.sanitize("CON"), "CON");
+}
+
+/// 测试：255 字节的名称冲突时，追加序号后仍不超过字节上限且保留扩展名
+#[test]
+fn name_map_suffix_respects_max_name_bytes() {
+    let mut map = LocalNameMap::new(SanitizePolicy::default());
+    let name = format!("{}.txt", "x".repeat(251));
+    let upper = format!("{}.txt", "X".repeat(251));
+    assert_eq!(name.len(), 255);
+
+    assert_eq!(map.local_path(&name), name);
+    let suffixed = map.local_path(&upper);
+    assert_eq!(suffixed, format!("{}~1.txt", "X".repeat(249)));
+    assert_eq!(suffixed.len(), 255);
+
+    // "é" 占 2 字节，截断点落在字符中间时向前退到字符边界
+    let accented = format!("{}a.txt", "é".repeat(125));
+    assert_eq!(accented.len(), 255);
+    assert_eq!(map.local_path(&accented), accented);
+    let suffixed = map.local_path(&format!("{}A.txt", "É".repeat(125)));
+    assert_eq!(suffixed, format!("{}~1.txt", "É".repeat(124)));
+    assert_eq!(suffixed.len(), 254);
+}
+
+/// 测试：目录映射保持一致，冲突名称追加序号，并记录变化的映射
+#[test]
+fn name_map_resolves_conflicts_and_records_changes() {
+    let mut map = LocalNameMap::new(SanitizePolicy::default());
+
+    assert_eq!(map.local_path("docs/a:b.txt"), "docs/a_b.txt");
+    assert_eq!(map.local_path("docs/a_b.txt"), "docs/a_b~1.txt");
+    // 只有大小写不同也视为冲突
+    assert_eq!(map.local_path("docs/A_B.TXT"), "docs/A_B~2.TXT");
+    assert_eq!(map.local_path("dir:1/x"), "dir_1/x");
+    assert_eq!(map.local_path("dir:1/y"), "dir_1/y");
+    // 重复调用得到同一结果
+    assert_eq!(map.local_path("docs/a:b.txt"), "docs/a_b.txt");
+
+    assert_eq!(
+        map.mappings(),
+        &[
+            NameMapping {
+                remote: "docs/a:b.txt".into(),
+                local: "docs/a_b.txt".into(),
+            },
+            NameMapping {
+                remote: "docs/a_b.txt".into(),
+                local: "docs/a_b~1.txt".into(),
+            },
+            NameMapping {
+                remote: "docs/A_B.TXT".into(),
+                local: "docs/A_B~2.TXT".into(),
+            },
+            NameMapping { remote: "dir:1".into(), local: "dir_1".into() },
+        ]
+    );
+}
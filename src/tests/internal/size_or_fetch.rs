@@ -0,0 +1,62 @@
+//! RemoteFile::size_or_fetch 测试（使用本地 mock 服务器）
+
+use crate::tests::mock_server::{
+    MockResponse, MockServer, mock_remote_file,
+};
+use crate::webdav::errors::WebDavError;
+
+/// 测试：PROPFIND 已给出大小时不发送请求
+#[tokio::test]
+async fn known_size_skips_head() {
+    let server = MockServer::serve_file(vec![0u8; 10]);
+    let file = mock_remote_file(&server, "known.bin", Some(42));
+
+    let size = file.size_or_fetch(&file.webdav_auth).await;
+
+    assert_eq!(size.ok(), Some(42));
+    assert!(server.requests().is_empty(), "❌ 不应发送任何请求");
+}
+
+/// 测试：大小未知时通过 HEAD 的 Content-Length 获取
+#[tokio::test]
+async fn unknown_size_is_fetched_with_head() {
+    let server = MockServer::serve_file(vec![0u8; 1234]);
+    let file = mock_remote_file(&server, "unknown.bin", None);
+
+    let size = file.size_or_fetch(&file.webdav_auth).await;
+
+    assert_eq!(size.ok(), Some(1234));
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "HEAD");
+}
+
+/// 测试：分块传输编码的资源没有 Content-Length，返回 UnknownSize
+#[tokio::test]
+async fn chunked_source_reports_unknown_size() {
+    let server = MockServer::start(|_| MockResponse::new(200).chunked());
+    let file = mock_remote_file(&server, "stream", None);
+
+    let result = file.size_or_fetch(&file.webdav_auth).await;
+
+    assert!(
+        matches!(result, Err(WebDavError::UnknownSize(_))),
+        "❌ 应返回 UnknownSize，实际: {:?}",
+        result
+    );
+}
+
+/// 测试：HEAD 失败时返回状态码
+#[tokio::test]
+async fn failed_head_returns_status() {
+    let server = MockServer::start(|_| MockResponse::new(404));
+    let file = mock_remote_file(&server, "missing.bin", None);
+
+    let result = file.size_or_fetch(&file.webdav_auth).await;
+
+    assert!(
+        matches!(result, Err(WebDavError::Status { status: 404, .. })),
+        "❌ 应返回 404，实际: {:?}",
+        result
+    );
+}
@@ -0,0 +1,157 @@
+//! stat / exists / head_remote 的方法选择与回退测试（使用本地 mock 服务器）
+
+use crate::auth::{StatMethod, WebdavAuth};
+use crate::head_remote;
+use crate::tests::mock_server::{MockResponse, MockServer};
+use crate::webdav::functions::{exists, get_capabilities, stat};
+
+/// 单个资源的 `Depth: 0` PROPFIND 响应
+fn single_resource(href: &str, is_dir: bool, size: Option<u64>) -> String {
+    let resource_type = if is_dir {
+        "<d:resourcetype><d:collection/></d:resourcetype>"
+    } else {
+        "<d:resourcetype/>"
+    };
+    let size = size
+        .map(|s| format!("<d:getcontentlength>{}</d:getcontentlength>", s))
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?><d:multistatus xmlns:d="DAV:">
+<d:response><d:href>{}</d:href><d:propstat><d:prop>{}{}</d:prop>
+<d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>
+</d:multistatus>"#,
+        href, resource_type, size
+    )
+}
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败")
+}
+
+fn methods(server: &MockServer) -> Vec<String> {
+    server.requests().into_iter().map(|r| r.method).collect()
+}
+
+/// 测试：目录的 HEAD 返回 404 时改用 PROPFIND，之后 Auto 直接使用 PROPFIND
+#[tokio::test]
+async fn auto_falls_back_to_propfind_and_remembers() {
+    let server = MockServer::start(|req| match req.method.as_str() {
+        "HEAD" => MockResponse::new(404),
+        "PROPFIND" => MockResponse::new(207)
+            .header("Content-Type", "application/xml")
+            .body(single_resource("/docs/", true, None)),
+        _ => MockResponse::new(500),
+    });
+    let auth = auth(&server);
+    let url = server.url("docs/");
+
+    let info = stat(&auth, &url).await.expect("stat 失败");
+    assert_eq!(info.is_dir, Some(true));
+    assert_eq!(info.method, StatMethod::Propfind);
+    assert_eq!(auth.preferred_stat_method(), StatMethod::Propfind);
+
+    assert!(exists(&auth, &url).await.expect("exists 失败"));
+    assert_eq!(methods(&server), vec!["HEAD", "PROPFIND", "PROPFIND"]);
+}
+
+/// 测试：手动指定 PROPFIND 时，PROPFIND 不可用则回退 HEAD，且不改变偏好
+#[tokio::test]
+async fn explicit_propfind_falls_back_to_head() {
+    let server = MockServer::start(|req| match req.method.as_str() {
+        "PROPFIND" => MockResponse::new(405),
+        "HEAD" => {
+            MockResponse::new(200).header("ETag", "\"abc\"").truncated(321)
+        }
+        _ => MockResponse::new(500),
+    });
+    let auth = auth(&server).stat_method(StatMethod::Propfind);
+    let url = server.url("file.bin");
+
+    let info = stat(&auth, &url).await.expect("stat 失败");
+    assert_eq!(info.method, StatMethod::Head);
+    assert_eq!(info.size, Some(321));
+    assert_eq!(info.etag.as_deref(), Some("abc"));
+    assert_eq!(info.is_dir, None);
+
+    assert_eq!(auth.preferred_stat_method(), StatMethod::Propfind);
+    assert_eq!(methods(&server), vec!["PROPFIND", "HEAD"]);
+}
+
+/// 测试：两种方法都返回 404 时 exists 为 false
+#[tokio::test]
+async fn missing_resource_does_not_exist() {
+    let server = MockServer::start(|_| MockResponse::new(404));
+    let auth = auth(&server);
+
+    let found = exists(&auth, &server.url("nope")).await;
+
+    assert!(
+        matches!(found, Ok(false)),
+        "❌ 应为 false，实际: {:?}",
+        found
+    );
+    assert_eq!(methods(&server), vec!["HEAD", "PROPFIND"]);
+}
+
+/// 测试：OPTIONS 的 Allow 头只声明 PROPFIND 时，Auto 优先使用 PROPFIND
+#[tokio::test]
+async fn capability_discovery_selects_propfind() {
+    let server = MockServer::start(|req| match req.method.as_str() {
+        "OPTIONS" => MockResponse::new(200)
+            .header("DAV", "1")
+            .header("Allow", "OPTIONS, GET, PROPFIND"),
+        "PROPFIND" => MockResponse::new(207)
+            .header("Content-Type", "application/xml")
+            .body(single_resource("/a.txt", false, Some(7))),
+        _ => MockResponse::new(500),
+    });
+    let auth = auth(&server);
+    assert_eq!(auth.preferred_stat_method(), StatMethod::Head);
+
+    get_capabilities(&auth, server.base_url())
+        .await
+        .expect("OPTIONS 失败");
+    assert_eq!(auth.preferred_stat_method(), StatMethod::Propfind);
+
+    let info = stat(&auth, &server.url("a.txt")).await.expect("stat 失败");
+    assert_eq!(info.size, Some(7));
+    assert_eq!(methods(&server), vec!["OPTIONS", "PROPFIND"]);
+}
+
+/// 测试：head_remote 只发送 HEAD，解析各响应头；404 与 405 给出明确的错误
+#[tokio::test]
+async fn head_remote_parses_headers() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/report.pdf" => MockResponse::new(200)
+            .header("Content-Type", "application/pdf")
+            .header("ETag", "W/\"r-1\"")
+            .header("Last-Modified", "Mon, 02 Sep 2024 08:00:00 GMT")
+            .header("Accept-Ranges", "bytes")
+            .truncated(52_341),
+        "/no-head" => MockResponse::new(405),
+        _ => MockResponse::new(404),
+    });
+    let auth = auth(&server);
+
+    let head = head_remote(&auth, "report.pdf").await.expect("HEAD 失败");
+    assert_eq!(head.size, Some(52_341));
+    assert_eq!(head.mime.as_deref(), Some("application/pdf"));
+    assert_eq!(head.etag.as_deref(), Some("W/\"r-1"));
+    assert_eq!(head.raw_etag.as_deref(), Some("W/\"r-1\""));
+    assert_eq!(
+        head.last_modified.map(|t| t.timestamp()),
+        Some(1_725_264_000)
+    );
+    assert!(head.accepts_ranges);
+
+    let missing = head_remote(&auth, "missing.txt").await.unwrap_err();
+    assert!(missing.starts_with("资源不存在"), "❌ {}", missing);
+
+    let unsupported = head_remote(&auth, "no-head").await.unwrap_err();
+    assert!(unsupported.contains("405"), "❌ {}", unsupported);
+    assert!(unsupported.contains("stat"), "❌ {}", unsupported);
+
+    // 不会改用 PROPFIND 重试
+    assert_eq!(methods(&server), vec!["HEAD", "HEAD", "HEAD"]);
+}
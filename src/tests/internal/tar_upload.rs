@@ -0,0 +1,177 @@
+//! upload_directory_as_tar / prepare_tar_upload 测试（使用本地 mock 服务器）
+
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::auth::WebdavAuth;
+use crate::local_file::{prepare_tar_upload, upload_directory_as_tar};
+use crate::tests::mock_server::{MockResponse, MockServer, temp_path};
+use crate::webdav::errors::WebDavError;
+
+/// 归档中的一个条目：(路径, 类型标志, 内容)
+type Entry = (String, u8, Vec<u8>);
+
+/// 用 `tar` crate 解析归档，并确认结尾是两个空块
+fn read_tar(data: &[u8]) -> Vec<Entry> {
+    assert!(data.len() >= 1024 && data.len().is_multiple_of(512));
+    assert!(
+        data[data.len() - 1024..].iter().all(|&b| b == 0),
+        "❌ 结尾应为两个空块"
+    );
+
+    let mut archive = tar::Archive::new(data);
+    archive
+        .entries()
+        .expect("❌ 归档无效")
+        .map(|entry| {
+            let mut entry = entry.expect("❌ 条目无效");
+            let path = entry.path().expect("❌ 路径无效");
+            let path = path.to_string_lossy().into_owned();
+            let typeflag = entry.header().entry_type().as_byte();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).expect("❌ 读取内容失败");
+            (path, typeflag, content)
+        })
+        .collect()
+}
+
+/// 本地目录：a.txt、empty.bin、sub/、sub/<长名称>.dat
+fn local_tree(name: &str) -> (PathBuf, String) {
+    let root = PathBuf::from(temp_path(name));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("sub")).expect("创建目录失败");
+    fs::write(root.join("a.txt"), b"hello tar").expect("写入失败");
+    fs::write(root.join("empty.bin"), b"").expect("写入失败");
+    let long = format!("{}.dat", "n".repeat(120));
+    let big: Vec<u8> = (0..70_000u32).map(|i| (i % 239) as u8).collect();
+    fs::write(root.join("sub").join(&long), big).expect("写入失败");
+    (root, long)
+}
+
+fn capture_server() -> MockServer {
+    MockServer::start(|_| MockResponse::new(201))
+}
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败")
+}
+
+/// 测试：PUT 请求体是完整的 tar，Content-Length 与预先算出的大小一致
+#[tokio::test]
+async fn streams_directory_as_tar() {
+    let (root, long) = local_tree("tar_upload_basic");
+    let server = capture_server();
+    let auth = auth(&server);
+
+    let upload = prepare_tar_upload(&auth, &root, "backup/my photos.tar")
+        .await
+        .expect("准备失败");
+    assert_eq!(upload.entry_count(), 4);
+    let total = upload.total_size();
+    let progress = upload.watch_progress();
+
+    let sent = upload.send().await.expect("上传失败");
+
+    assert_eq!(sent, total);
+    assert_eq!(progress.borrow().map(|p| p.bytes_sent), Some(total));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(request.method, "PUT");
+    assert_eq!(request.path, "/backup/my%20photos.tar");
+    assert_eq!(request.header("Content-Type"), Some("application/x-tar"));
+    assert_eq!(request.body.len() as u64, total);
+
+    let entries = read_tar(&request.body);
+    let names: Vec<(&str, u8)> =
+        entries.iter().map(|(n, t, _)| (n.as_str(), *t)).collect();
+    let long_path = format!("sub/{}", long);
+    assert_eq!(
+        names,
+        vec![
+            ("a.txt", b'0'),
+            ("empty.bin", b'0'),
+            ("sub", b'5'),
+            (long_path.as_str(), b'0'),
+        ]
+    );
+    assert_eq!(entries[0].2, b"hello tar");
+    assert!(entries[1].2.is_empty());
+    assert_eq!(entries[3].2.len(), 70_000);
+    assert_eq!(entries[3].2[1000], (1000 % 239) as u8);
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+/// 测试：文件在准备与发送之间变短时请求中止，而不是发出大小不符的归档
+#[tokio::test]
+async fn shrunk_file_aborts_upload() {
+    let (root, long) = local_tree("tar_upload_shrunk");
+    let server = capture_server();
+    let auth = auth(&server);
+
+    let upload = prepare_tar_upload(&auth, &root, "shrunk.tar")
+        .await
+        .expect("准备失败");
+    fs::write(root.join("sub").join(&long), vec![1u8; 69_990])
+        .expect("写入失败");
+
+    let result = upload.send().await;
+
+    assert!(
+        matches!(result, Err(WebDavError::Request(_))),
+        "❌ 应返回 Request 错误，实际: {:?}",
+        result
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+/// 测试：服务器拒绝时返回状态码，507 单独映射为空间不足
+#[tokio::test]
+async fn rejected_put_returns_status() {
+    let (root, _) = local_tree("tar_upload_rejected");
+    let full = MockServer::start(|_| MockResponse::new(507));
+    let forbidden = MockServer::start(|_| MockResponse::new(403));
+
+    let result =
+        upload_directory_as_tar(&auth(&full), &root, "full.tar").await;
+    assert!(
+        matches!(
+            &result,
+            Err(WebDavError::InsufficientRemoteStorage { url, .. })
+                if url.ends_with("/full.tar")
+        ),
+        "❌ 应返回 InsufficientRemoteStorage，实际: {:?}",
+        result
+    );
+
+    let result =
+        upload_directory_as_tar(&auth(&forbidden), &root, "x.tar").await;
+    assert!(
+        matches!(result, Err(WebDavError::Status { status: 403, .. })),
+        "❌ 应返回 403，实际: {:?}",
+        result
+    );
+    let _ = fs::remove_dir_all(&root);
+}
+
+/// 测试：无效的远程路径与不存在的本地目录在发送前报错
+#[tokio::test]
+async fn invalid_paths_fail_before_sending() {
+    let (root, _) = local_tree("tar_upload_invalid");
+    let server = capture_server();
+    let auth = auth(&server);
+
+    let escaped = prepare_tar_upload(&auth, &root, "../etc/x.tar").await;
+    assert!(matches!(escaped, Err(WebDavError::InvalidRequest(_))));
+
+    let missing = root.join("does-not-exist");
+    let result = prepare_tar_upload(&auth, &missing, "x.tar").await;
+    assert!(matches!(result, Err(WebDavError::LocalIo(_))));
+
+    assert!(server.requests().is_empty(), "❌ 不应发送任何请求");
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,87 @@
+//! upload_remote_file / upload_remote_file_stream 测试（使用本地 mock 服务器）
+
+use crate::auth::WebdavAuth;
+use crate::tests::mock_server::{MockResponse, MockServer, temp_path};
+use crate::{upload_remote_file, upload_remote_file_stream};
+
+fn auth(server: &MockServer) -> WebdavAuth {
+    WebdavAuth::new("user", "password", server.base_url())
+        .expect("创建测试认证失败")
+}
+
+/// 测试：201 与 204 都视为成功，请求为带完整请求体的 PUT
+#[tokio::test]
+async fn created_and_no_content_are_success() {
+    for status in [201, 204] {
+        let server = MockServer::start(move |_| MockResponse::new(status));
+
+        let result =
+            upload_remote_file(&auth(&server), "docs/a.txt", "hello")
+                .await;
+
+        assert_eq!(result, Ok(()), "❌ {} 应视为成功", status);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "PUT");
+        assert_eq!(requests[0].path, "/docs/a.txt");
+        assert_eq!(requests[0].body, b"hello");
+    }
+}
+
+/// 测试：409 父目录不存在与其他失败状态都返回错误
+#[tokio::test]
+async fn failures_are_reported() {
+    let conflict = MockServer::start(|_| MockResponse::new(409));
+    let result =
+        upload_remote_file(&auth(&conflict), "missing/a.txt", vec![1u8])
+            .await;
+    let message = result.expect_err("409 不应视为成功");
+    assert!(message.contains("409"), "❌ 错误信息应包含 409: {}", message);
+
+    let denied =
+        MockServer::start(|_| MockResponse::new(403).body("read only"));
+    let result = upload_remote_file(&auth(&denied), "a.txt", "x").await;
+    let message = result.expect_err("403 不应视为成功");
+    assert!(
+        message.contains("403") && message.contains("read only"),
+        "❌ 错误信息应包含状态码与响应体: {}",
+        message
+    );
+}
+
+/// 测试：跳出 base_url 的路径在发送前被拒绝
+#[tokio::test]
+async fn escaping_path_is_rejected() {
+    let server = MockServer::start(|_| MockResponse::new(201));
+    let auth = WebdavAuth::new(
+        "user",
+        "password",
+        &format!("{}dav/", server.base_url()),
+    )
+    .expect("创建测试认证失败");
+
+    let result = upload_remote_file(&auth, "../etc/passwd", "x").await;
+
+    assert!(result.is_err());
+    assert!(server.requests().is_empty(), "❌ 不应发送任何请求");
+}
+
+/// 测试：流式上传本地文件，带 Content-Length 且内容完整
+#[tokio::test]
+async fn streams_local_file_with_content_length() {
+    let content: Vec<u8> =
+        (0..200_000u32).map(|i| (i % 241) as u8).collect();
+    let path = temp_path("upload_stream.bin");
+    std::fs::write(&path, &content).expect("写入失败");
+    let server = MockServer::start(|_| MockResponse::new(201));
+
+    let file = tokio::fs::File::open(&path).await.expect("打开失败");
+    let result =
+        upload_remote_file_stream(&auth(&server), "big.bin", file).await;
+
+    assert_eq!(result, Ok(()));
+    let requests = server.requests();
+    assert_eq!(requests[0].header("Content-Length"), Some("200000"));
+    assert_eq!(requests[0].body, content);
+    let _ = std::fs::remove_file(&path);
+}
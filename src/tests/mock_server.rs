@@ -0,0 +1,361 @@
+//! 测试用的极简 HTTP 服务器：基于 `std::net` + 线程实现，不依赖外部 WebDAV 厂商。
+//!
+//! - 每个连接处理一个请求后即关闭（响应带 `Connection: close`）。
+//! - 通过闭包决定响应内容；[`MockServer::serve_file`] 提供支持 `Range`/`HEAD` 的静态文件。
+//! - [`MockResponse`] 支持按段延迟发送与截断连接，用于模拟慢速流和中途断线。
+
+// 测试辅助工具，并非每个测试都会用到全部能力
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::auth::WebdavAuth;
+use crate::remote_file::{RemoteFile, RemoteFileData};
+
+/// 服务器收到的一次请求。
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// 按名称（不区分大小写）读取请求头。
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// 解析 `Range: bytes=start-end` 请求头（end 为闭区间，可省略）。
+    pub fn range(&self) -> Option<(u64, Option<u64>)> {
+        let value = self.header("Range")?.strip_prefix("bytes=")?;
+        let (start, end) = value.split_once('-')?;
+        let start = start.trim().parse().ok()?;
+        let end = end.trim();
+        let end = if end.is_empty() { None } else { end.parse().ok() };
+        Some((start, end))
+    }
+}
+
+/// 服务器返回的响应。
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// 按顺序发送的响应体分段：(发送前的延迟, 数据)
+    pub body_parts: Vec<(Duration, Vec<u8>)>,
+    /// 声明的 Content-Length；为 `None` 时使用实际长度，
+    /// 大于实际长度可用来模拟连接中途断开。
+    pub content_length: Option<u64>,
+    /// 使用 `Transfer-Encoding: chunked` 发送（不带 Content-Length），
+    /// 每个响应体分段作为一个 chunk
+    pub chunked: bool,
+}
+
+impl MockResponse {
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body_parts: Vec::new(),
+            content_length: None,
+            chunked: false,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body_parts.push((Duration::ZERO, body.into()));
+        self
+    }
+
+    /// 追加一段延迟发送的响应体。
+    pub fn delayed_part(
+        mut self,
+        delay: Duration,
+        body: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.body_parts.push((delay, body.into()));
+        self
+    }
+
+    /// 改用分块传输编码发送响应体（不带 Content-Length）。
+    pub fn chunked(mut self) -> Self {
+        self.chunked = true;
+        self
+    }
+
+    /// 声明比实际更长的 Content-Length，发送完已有分段后直接断开连接。
+    pub fn truncated(mut self, declared_len: u64) -> Self {
+        self.content_length = Some(declared_len);
+        self
+    }
+}
+
+/// 运行在本地随机端口上的测试服务器。线程随测试进程结束而退出。
+pub struct MockServer {
+    base_url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// 使用自定义处理函数启动服务器。
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener =
+            TcpListener::bind("127.0.0.1:0").expect("绑定测试端口失败");
+        let addr = listener.local_addr().expect("读取测试端口失败");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler = Arc::new(handler);
+
+        {
+            let requests = Arc::clone(&requests);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { continue };
+                    let handler = Arc::clone(&handler);
+                    let requests = Arc::clone(&requests);
+                    thread::spawn(move || {
+                        handle_connection(stream, &*handler, &requests);
+                    });
+                }
+            });
+        }
+
+        Self { base_url: format!("http://{}/", addr), requests }
+    }
+
+    /// 提供一个静态文件：支持 GET（含 Range）与 HEAD，其余方法返回 405。
+    pub fn serve_file(content: Vec<u8>) -> Self {
+        Self::start(move |req| file_response(req, &content))
+    }
+
+    /// 服务器根 URL（以 `/` 结尾）。
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 拼接出某个路径的完整 URL。
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    /// 迄今收到的全部请求。
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+}
+
+/// 按静态文件语义构造响应：支持 HEAD、Range（206）与完整 GET（200）。
+pub fn file_response(req: &MockRequest, content: &[u8]) -> MockResponse {
+    let total = content.len() as u64;
+    match req.method.as_str() {
+        "HEAD" => MockResponse::new(200)
+            .header("Accept-Ranges", "bytes")
+            .truncated(total),
+        "GET" => match req.range() {
+            Some((start, end)) if start < total => {
+                let end = end
+                    .unwrap_or(total.saturating_sub(1))
+                    .min(total.saturating_sub(1));
+                let slice = &content[start as usize..=end as usize];
+                MockResponse::new(206)
+                    .header(
+                        "Content-Range",
+                        &format!("bytes {}-{}/{}", start, end, total),
+                    )
+                    .body(slice.to_vec())
+            }
+            Some(_) => MockResponse::new(416)
+                .header("Content-Range", &format!("bytes */{}", total)),
+            None => MockResponse::new(200)
+                .header("Accept-Ranges", "bytes")
+                .body(content.to_vec()),
+        },
+        _ => MockResponse::new(405),
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    handler: &(dyn Fn(&MockRequest) -> MockResponse + Send + Sync),
+    requests: &Mutex<Vec<MockRequest>>,
+) {
+    let Ok(read_half) = stream.try_clone() else { return };
+    let mut reader = BufReader::new(read_half);
+    let Some(request) = read_request(&mut reader) else { return };
+
+    if let Ok(mut log) = requests.lock() {
+        log.push(request.clone());
+    }
+
+    let response = handler(&request);
+    let _ = write_response(stream, &request, response);
+}
+
+fn read_request(reader: &mut impl BufRead) -> Option<MockRequest> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header_line = String::new();
+        reader.read_line(&mut header_line).ok()?;
+        let header_line = header_line.trim_end();
+        if header_line.is_empty() {
+            break;
+        }
+        if let Some((k, v)) = header_line.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+
+    let find = |name: &str| {
+        headers
+            .iter()
+            .find(|(k, _): &&(String, String)| {
+                k.eq_ignore_ascii_case(name)
+            })
+            .map(|(_, v)| v.clone())
+    };
+
+    let body = if find("Transfer-Encoding")
+        .is_some_and(|v| v.eq_ignore_ascii_case("chunked"))
+    {
+        read_chunked_body(reader)?
+    } else {
+        let len: usize = find("Content-Length")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0u8; len];
+        reader.read_exact(&mut body).ok()?;
+        body
+    };
+
+    Some(MockRequest { method, path, headers, body })
+}
+
+fn read_chunked_body(reader: &mut impl BufRead) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut size_line = String::new();
+        reader.read_line(&mut size_line).ok()?;
+        let size_str = size_line.trim().split(';').next()?;
+        let size = usize::from_str_radix(size_str, 16).ok()?;
+        if size == 0 {
+            // 读掉结尾的空行（忽略 trailer）
+            let mut end = String::new();
+            reader.read_line(&mut end).ok()?;
+            return Some(body);
+        }
+        let mut chunk = vec![0u8; size];
+        reader.read_exact(&mut chunk).ok()?;
+        body.extend_from_slice(&chunk);
+        let mut crlf = [0u8; 2];
+        reader.read_exact(&mut crlf).ok()?;
+    }
+}
+
+fn write_response(
+    mut stream: TcpStream,
+    request: &MockRequest,
+    response: MockResponse,
+) -> std::io::Result<()> {
+    let actual_len: usize =
+        response.body_parts.iter().map(|(_, b)| b.len()).sum();
+    let content_length =
+        response.content_length.unwrap_or(actual_len as u64);
+
+    let mut head = format!("HTTP/1.1 {} MOCK\r\n", response.status);
+    for (k, v) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    if response.chunked {
+        head.push_str("Transfer-Encoding: chunked\r\n");
+    } else {
+        head.push_str(&format!("Content-Length: {}\r\n", content_length));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.flush()?;
+
+    if request.method != "HEAD" {
+        for (delay, part) in response.body_parts {
+            if !delay.is_zero() {
+                thread::sleep(delay);
+            }
+            if response.chunked {
+                if part.is_empty() {
+                    continue; // 空 chunk 会被当作结束标记
+                }
+                stream.write_all(
+                    format!("{:x}\r\n", part.len()).as_bytes(),
+                )?;
+                stream.write_all(&part)?;
+                stream.write_all(b"\r\n")?;
+            } else {
+                stream.write_all(&part)?;
+            }
+            stream.flush()?;
+        }
+        if response.chunked {
+            stream.write_all(b"0\r\n\r\n")?;
+            stream.flush()?;
+        }
+    }
+
+    stream.shutdown(std::net::Shutdown::Both)
+}
+
+/// 构造一个指向测试服务器上某个文件的 [`RemoteFile`]。
+pub fn mock_remote_file(
+    server: &MockServer,
+    path: &str,
+    size: Option<u64>,
+) -> RemoteFile {
+    let webdav_auth =
+        WebdavAuth::new("user", "password", server.base_url())
+            .expect("创建测试认证失败");
+    let data = RemoteFileData {
+        base_url: (*webdav_auth.base_url).clone(),
+        relative_root_path: format!("/{}", path.trim_start_matches('/')),
+        absolute_path: server.url(path),
+        name: path.rsplit('/').next().unwrap_or(path).to_string(),
+        is_dir: false,
+        size,
+        last_modified: None,
+        creation_date: None,
+        mime: None,
+        owner: None,
+        etag: None,
+        raw_etag: None,
+        privileges: Vec::new(),
+        allowed_methods: None,
+    };
+    RemoteFile { data: Arc::new(data), webdav_auth }
+}
+
+/// 在系统临时目录下生成一个本测试专用的文件路径（不会创建文件）。
+pub fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("webdav_fs_test_{}_{}", std::process::id(), name))
+        .to_string_lossy()
+        .to_string()
+}
@@ -5,4 +5,7 @@ mod lib;
 #[cfg(test)]
 pub use lib::*;
 
+#[cfg(test)]
+pub mod mock_server;
+
 pub mod internal;